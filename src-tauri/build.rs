@@ -1,3 +1,89 @@
+/// Every command in `generate_handler!`. Tauri only checks app commands against capabilities
+/// once the app has a manifest; see `capabilities/` for who may call what.
+const COMMANDS: &[&str] = &[
+    "discover_site_icon",
+    "host_platform",
+    "activate_service_content",
+    "refresh_service_content",
+    "hide_all_service_content",
+    "sync_service_host_state",
+    "sync_docked_content_layout",
+    "get_http_settings",
+    "set_http_settings",
+    "get_service_settings",
+    "set_service_tls_policy",
+    "set_local_server_ports",
+    "discover_local_servers",
+    "native_chat_send",
+    "native_chat_cancel",
+    "native_chat_list_models",
+    "native_chat_list_conversations",
+    "native_chat_load_conversation",
+    "native_chat_delete_conversation",
+    "native_chat_set_api_key",
+    "native_chat_has_api_key",
+    "list_user_scripts",
+    "save_user_script",
+    "delete_user_script",
+    "read_user_script_log",
+    "get_content_filter_stats",
+    "reload_content_filters",
+    "set_service_content_blocking",
+    "get_app_theme",
+    "set_app_theme",
+    "set_service_theme",
+    "get_service_zoom",
+    "zoom_service_in",
+    "zoom_service_out",
+    "zoom_service_reset",
+    "zoom_service_set",
+    "find_in_service",
+    "open_find_in_service",
+    "stop_find_in_service",
+    "service_go_back",
+    "service_go_forward",
+    "service_go_home",
+    "get_service_navigation_state",
+    "get_service_statuses",
+    "set_service_keep_alive",
+    "get_keep_alive_statuses",
+    "set_service_login_rules",
+    "get_service_login_states",
+    "clear_service_browsing_data",
+    "open_private_service",
+    "close_private_service",
+    "export_service_cookies",
+    "import_service_cookies",
+    "list_browser_profiles",
+    "preview_browser_cookie_import",
+    "import_browser_cookies",
+    "get_app_lock_status",
+    "set_app_lock_password",
+    "set_app_lock_options",
+    "lock_app",
+    "unlock_app",
+    "report_app_activity",
+    "get_data_encryption_status",
+    "enable_data_encryption",
+    "unlock_data",
+    "recover_data",
+    "export_data_recovery_key",
+    "disable_data_encryption",
+    "get_effective_policy",
+    "get_sync_status",
+    "set_sync_settings",
+    "sync_now",
+    "get_sync_conflicts",
+    "create_backup",
+    "restore_backup",
+    "take_restored_frontend_state",
+    "report_bridge_message",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
@@ -0,0 +1,285 @@
+//! Password lock for the whole app, so chats are not readable on an unattended machine.
+//!
+//! While locked every service webview stays hidden and the frontend covers the main window
+//! with a lock screen. The password is kept only as an Argon2id hash. Input in service pages
+//! is reported over the script bridge; where there is no bridge, a focused AnyChat window
+//! counts as activity instead. Screen lock is followed through the desktop's D-Bus signals on
+//! Linux only.
+
+use std::time::{Duration, Instant};
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+use crate::secrets::{self, KdfParams};
+
+pub(crate) const APP_LOCK_EVENT: &str = "app://lock";
+pub(crate) const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Slows down guessing from the lock screen.
+pub(crate) const FAILED_UNLOCK_DELAY: Duration = Duration::from_secs(1);
+const MIN_PASSWORD_LEN: usize = 6;
+const MAX_IDLE_MINUTES: u32 = 24 * 60;
+
+/// Reports user input at most every 30 seconds.
+pub(crate) const ACTIVITY_SCRIPT: &str = r#"
+(function() {
+    if (window.top !== window || window.__anychatActivity) return;
+    var last = 0;
+    window.__anychatActivity = function() {
+        var now = Date.now();
+        if (now - last < 30000 || !window.__anychatPost) return;
+        last = now;
+        window.__anychatPost({ type: 'activity' });
+    };
+    ['keydown', 'pointerdown', 'wheel', 'touchstart'].forEach(function(type) {
+        window.addEventListener(type, window.__anychatActivity, { capture: true, passive: true });
+    });
+})();
+"#;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct AppLockSettings {
+    /// Argon2id PHC string; the lock is off without one.
+    pub password_hash: Option<String>,
+    /// Minutes without input before locking; `None` turns idle locking off.
+    pub idle_minutes: Option<u32>,
+    pub lock_on_screen_lock: bool,
+}
+
+impl AppLockSettings {
+    pub fn enabled(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_minutes
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_minutes: Option<u32>,
+    pub lock_on_screen_lock: bool,
+}
+
+pub(crate) fn validate_idle_minutes(idle_minutes: Option<u32>) -> Result<(), String> {
+    match idle_minutes {
+        Some(minutes) if minutes == 0 || minutes > MAX_IDLE_MINUTES => Err(format!(
+            "Idle lock time must be between 1 and {} minutes",
+            MAX_IDLE_MINUTES
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "The lock password needs at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn hash_password(password: &str, params: KdfParams) -> Result<String, String> {
+    validate_password(password)?;
+    let salt = SaltString::encode_b64(&secrets::random_bytes::<16>()).map_err(|e| e.to_string())?;
+    let params = argon2::Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        None,
+    )
+    .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Password hashing failed: {}", e))
+}
+
+/// Cost parameters are read back from the hash, so older hashes keep verifying.
+pub(crate) fn verify_password(password_hash: &str, password: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct LockState {
+    locked: bool,
+    last_activity: Instant,
+    /// Windows other than service hosts that were hidden by the lock, shown again on unlock.
+    hidden_windows: Vec<String>,
+}
+
+impl LockState {
+    pub fn new(now: Instant) -> Self {
+        LockState {
+            locked: false,
+            last_activity: now,
+            hidden_windows: Vec::new(),
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Returns false when already locked.
+    pub fn lock(&mut self) -> bool {
+        !std::mem::replace(&mut self.locked, true)
+    }
+
+    pub fn remember_hidden(&mut self, labels: Vec<String>) {
+        self.hidden_windows.extend(labels);
+    }
+
+    /// Returns the windows to show again.
+    pub fn unlock(&mut self, now: Instant) -> Vec<String> {
+        self.locked = false;
+        self.last_activity = now;
+        std::mem::take(&mut self.hidden_windows)
+    }
+
+    pub fn touch(&mut self, now: Instant) {
+        if !self.locked {
+            self.last_activity = now;
+        }
+    }
+
+    pub fn idle_expired(&self, now: Instant, timeout: Duration) -> bool {
+        !self.locked && now.saturating_duration_since(self.last_activity) >= timeout
+    }
+}
+
+impl Default for LockState {
+    fn default() -> Self {
+        LockState::new(Instant::now())
+    }
+}
+
+/// Locks the app when the desktop session locks, via the screen saver interfaces of GNOME and
+/// KDE/freedesktop on the session bus and logind's `Lock` on the system bus. Must run on the
+/// GTK main thread, like the resume signals.
+#[cfg(target_os = "linux")]
+pub(crate) fn watch_screen_lock(app: &tauri::AppHandle) {
+    use webkit2gtk::gio::{self, DBusSignalFlags};
+
+    for (bus, interface, member) in [
+        (
+            gio::BusType::Session,
+            "org.freedesktop.ScreenSaver",
+            "ActiveChanged",
+        ),
+        (
+            gio::BusType::Session,
+            "org.gnome.ScreenSaver",
+            "ActiveChanged",
+        ),
+        (
+            gio::BusType::System,
+            "org.freedesktop.login1.Session",
+            "Lock",
+        ),
+    ] {
+        let connection = match gio::bus_get_sync(bus, None::<&gio::Cancellable>) {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!(
+                    "[AnyChat] Cannot watch {} for screen lock: {}",
+                    interface, e
+                );
+                continue;
+            }
+        };
+        let app = app.clone();
+        connection.signal_subscribe(
+            None,
+            Some(interface),
+            Some(member),
+            None,
+            None,
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| {
+                // `Lock` has no arguments; `ActiveChanged` reports true when the screen locks.
+                if parameters.get::<(bool,)>() != Some((false,)) {
+                    crate::lock_on_screen_lock(&app);
+                }
+            },
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn watch_screen_lock(_app: &tauri::AppHandle) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        hash_password, validate_idle_minutes, verify_password, AppLockSettings, LockState,
+    };
+    use crate::secrets::KdfParams;
+    use std::time::{Duration, Instant};
+
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn passwords_are_stored_as_verifiable_hashes() {
+        assert!(hash_password("short", TEST_KDF).is_err());
+
+        let hash = hash_password("correct horse", TEST_KDF).unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(!hash.contains("correct horse"));
+        assert!(verify_password(&hash, "correct horse"));
+        assert!(!verify_password(&hash, "wrong horse"));
+        assert!(!verify_password("not a hash", "correct horse"));
+        assert_ne!(hash, hash_password("correct horse", TEST_KDF).unwrap());
+    }
+
+    #[test]
+    fn idle_time_is_counted_from_the_last_activity_while_unlocked() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(300);
+        let mut state = LockState::new(start);
+        state.touch(start + Duration::from_secs(200));
+        assert!(!state.idle_expired(start + Duration::from_secs(400), timeout));
+        assert!(state.idle_expired(start + Duration::from_secs(500), timeout));
+
+        assert!(state.lock());
+        assert!(!state.lock());
+        assert!(!state.idle_expired(start + Duration::from_secs(900), timeout));
+
+        state.remember_hidden(vec!["private-chatgpt".to_string()]);
+        let later = start + Duration::from_secs(1000);
+        assert_eq!(state.unlock(later), vec!["private-chatgpt".to_string()]);
+        assert!(!state.is_locked());
+        assert!(!state.idle_expired(later + Duration::from_secs(299), timeout));
+    }
+
+    #[test]
+    fn idle_lock_settings_are_validated() {
+        assert!(validate_idle_minutes(None).is_ok());
+        assert!(validate_idle_minutes(Some(5)).is_ok());
+        assert!(validate_idle_minutes(Some(0)).is_err());
+        assert!(validate_idle_minutes(Some(24 * 60 + 1)).is_err());
+
+        let settings = AppLockSettings {
+            idle_minutes: Some(2),
+            ..AppLockSettings::default()
+        };
+        assert!(!settings.enabled());
+        assert_eq!(settings.idle_timeout(), Some(Duration::from_secs(120)));
+    }
+}
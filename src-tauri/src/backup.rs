@@ -0,0 +1,1132 @@
+//! Single-file backups of AnyChat's data: the service registry, user scripts, archived
+//! conversations, the frontend's service list and icon cache, and optionally the webview
+//! storage directories (cookies, local storage, IndexedDB).
+//!
+//! A backup is a gzipped tar archive with a versioned `manifest.json` listing every file with
+//! its size and SHA-256. Webview files are hashed as they are streamed into the archive, so the
+//! manifest is its last entry. Data files are stored decrypted so a backup can be restored on another
+//! machine, and sealed with the current vault as they are restored. With a passphrase, which is
+//! required while data encryption is on, the whole archive is sealed with XChaCha20-Poly1305 in
+//! 64 KiB frames (the STREAM construction) under a key derived with Argon2id.
+//!
+//! Restoring happens in two steps. The archive is first extracted next to each data directory
+//! and checked against the manifest; nothing is touched if that fails. On the next start, before
+//! any webview opens, each staged directory is swapped in with a rename, and everything the
+//! backup does not cover (settings, secrets, and webview storage unless it was backed up) is
+//! carried over. The replaced directory is kept as `<dir>.before-restore` until the next restore.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{
+    aead::{
+        generic_array::GenericArray,
+        stream::{DecryptorBE32, EncryptorBE32},
+    },
+    XChaCha20Poly1305,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::secrets::KdfParams;
+use crate::storage::Vault;
+
+/// Frontend state from the last restore, picked up once by the frontend after the relaunch.
+pub(crate) const RESTORED_FRONTEND_FILE: &str = "restored_frontend.json";
+const BACKUP_FORMAT: &str = "anychat-backup";
+const BACKUP_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const FRONTEND_ENTRY: &str = "frontend.json";
+const DATA_SECTION: &str = "data";
+const WEBVIEW_SECTION: &str = "webview";
+/// Written into a staging directory once it was fully extracted and verified.
+const READY_MARKER: &str = ".restore-ready";
+const STAGING_SUFFIX: &str = ".restore";
+const PREVIOUS_SUFFIX: &str = ".before-restore";
+/// First line of a passphrase-protected backup, followed by a JSON [`SealedHeader`] line.
+const SEALED_MAGIC: &[u8] = b"ANYCHAT-SEALED-BACKUP\n";
+const SEALED_HEADER_MAX_BYTES: u64 = 1024;
+const SEALED_CHUNK_LEN: usize = 64 * 1024;
+const SEALED_TAG_LEN: usize = 16;
+/// XChaCha20's 24-byte nonce minus the STREAM counter and last-frame flag.
+const SEALED_NONCE_LEN: usize = 19;
+const SEALED_SALT_LEN: usize = 16;
+/// Limits for a restored archive, so a crafted one cannot exhaust memory or disk. Documents are
+/// held in memory; webview files are streamed to disk.
+const MAX_DOCUMENT_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_WEBVIEW_FILE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+const MAX_EXTRACTED_BYTES: u64 = 32 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupFile {
+    /// Path inside the archive.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    /// Unix time in milliseconds.
+    pub created_at: u64,
+    pub app_version: String,
+    pub includes_webview_storage: bool,
+    pub files: Vec<BackupFile>,
+}
+
+/// Where the archive's sections live on this machine.
+#[derive(Debug, Clone)]
+pub(crate) struct BackupRoots {
+    pub data_dir: PathBuf,
+    /// `None` where the webview keeps its storage outside AnyChat's directories (macOS).
+    pub webview_dir: Option<PathBuf>,
+}
+
+impl BackupRoots {
+    fn staging_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![with_suffix(&self.data_dir, STAGING_SUFFIX)];
+        if let Some(webview_dir) = self.separate_webview_dir() {
+            dirs.push(with_suffix(webview_dir, STAGING_SUFFIX));
+        }
+        dirs
+    }
+
+    /// The webview directory when it is not the data directory itself, as it is on Linux.
+    fn separate_webview_dir(&self) -> Option<&Path> {
+        self.webview_dir
+            .as_deref()
+            .filter(|webview_dir| *webview_dir != self.data_dir)
+    }
+}
+
+fn with_suffix(dir: &Path, suffix: &str) -> PathBuf {
+    let mut path = dir.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Top-level entries of the data directory that the backup replaces as a whole.
+fn is_backed_up_entry(name: &str) -> bool {
+    [
+        crate::registry::SERVICE_REGISTRY_FILE,
+        crate::user_scripts::USER_SCRIPTS_DIR,
+        crate::native_chat::CONVERSATIONS_DIR,
+    ]
+    .contains(&name)
+}
+
+/// Top-level entries AnyChat itself keeps in the data directory. Where the webview stores its
+/// data in the same directory, everything else belongs to the webview.
+fn is_app_entry(name: &str) -> bool {
+    is_backed_up_entry(name)
+        || [
+            crate::settings::SETTINGS_FILE,
+            crate::secrets::SECRETS_FILE,
+            crate::secrets::SECRETS_KEY_FILE,
+            crate::encryption::ENCRYPTION_FILE,
+            crate::sync::SYNC_STATE_FILE,
+            crate::sync::SYNC_CONFLICTS_FILE,
+            crate::content_blocking::CONTENT_FILTERS_DIR,
+            crate::user_scripts::USER_SCRIPTS_LOG,
+            crate::watchdog::WATCHDOG_LOG,
+            RESTORED_FRONTEND_FILE,
+            READY_MARKER,
+        ]
+        .contains(&name)
+        || name
+            .strip_suffix(crate::storage::ROTATED_LOG_SUFFIX)
+            .is_some_and(|log| {
+                [
+                    crate::user_scripts::USER_SCRIPTS_LOG,
+                    crate::watchdog::WATCHDOG_LOG,
+                ]
+                .contains(&log)
+            })
+        || name.ends_with(".tmp")
+}
+
+/// Regular files under `dir`, relative to `root`. Symlinks are skipped.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(root, &entry.path(), files)?;
+        } else if file_type.is_file() {
+            if let Ok(relative) = entry.path().strip_prefix(root) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn archive_path(section: &str, relative: &Path) -> String {
+    let mut path = section.to_string();
+    for component in relative.components() {
+        path.push('/');
+        path.push_str(&component.as_os_str().to_string_lossy());
+    }
+    path
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// What goes into a backup entry: documents are small and held in memory, webview files can
+/// be large and are streamed from disk.
+enum BackupSource {
+    Document(Vec<u8>),
+    File(PathBuf),
+}
+
+/// Everything that goes into a backup, as archive path and source.
+fn backup_contents(
+    roots: &BackupRoots,
+    vault: &Vault,
+    frontend: &serde_json::Value,
+    include_webview_storage: bool,
+) -> Result<Vec<(String, BackupSource)>, String> {
+    let mut contents = vec![(
+        FRONTEND_ENTRY.to_string(),
+        BackupSource::Document(serde_json::to_vec_pretty(frontend).map_err(|e| e.to_string())?),
+    )];
+
+    let mut data_files = Vec::new();
+    let registry = roots.data_dir.join(crate::registry::SERVICE_REGISTRY_FILE);
+    if registry.is_file() {
+        data_files.push(PathBuf::from(crate::registry::SERVICE_REGISTRY_FILE));
+    }
+    for dir in [
+        crate::user_scripts::USER_SCRIPTS_DIR,
+        crate::native_chat::CONVERSATIONS_DIR,
+    ] {
+        collect_files(&roots.data_dir, &roots.data_dir.join(dir), &mut data_files)?;
+    }
+    for relative in data_files {
+        if relative
+            .extension()
+            .is_none_or(|extension| extension != "json")
+        {
+            continue;
+        }
+        // Read through the vault so the archive does not depend on this machine's key.
+        let document: serde_json::Value =
+            crate::storage::read_json(vault, &roots.data_dir.join(&relative))?;
+        contents.push((
+            archive_path(DATA_SECTION, &relative),
+            BackupSource::Document(
+                serde_json::to_vec_pretty(&document).map_err(|e| e.to_string())?,
+            ),
+        ));
+    }
+
+    if include_webview_storage {
+        let webview_dir = roots
+            .webview_dir
+            .as_deref()
+            .ok_or("Webview storage cannot be backed up on this platform")?;
+        let mut webview_files = Vec::new();
+        collect_files(webview_dir, webview_dir, &mut webview_files)?;
+        for relative in webview_files {
+            let top_level = relative
+                .components()
+                .next()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if webview_dir == roots.data_dir && is_app_entry(&top_level) {
+                continue;
+            }
+            contents.push((
+                archive_path(WEBVIEW_SECTION, &relative),
+                BackupSource::File(webview_dir.join(&relative)),
+            ));
+        }
+    }
+    Ok(contents)
+}
+
+fn append_file<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+    mtime: u64,
+) -> Result<(), String> {
+    append_reader(builder, path, bytes, bytes.len() as u64, mtime)
+}
+
+fn append_reader<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    reader: impl Read,
+    size: u64,
+    mtime: u64,
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o600);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, reader)
+        .map_err(|e| format!("Failed to add {} to the backup: {}", path, e))
+}
+
+/// Hashes what passes through it and counts the bytes.
+struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(out)?;
+        self.hasher.update(&out[..read]);
+        self.size += read as u64;
+        Ok(read)
+    }
+}
+
+/// Streams a file into the archive and returns its manifest entry. The size in the tar header
+/// is taken up front, so a file that shrinks while it is read fails the backup.
+fn append_streamed_file<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    source: &Path,
+    mtime: u64,
+) -> Result<BackupFile, String> {
+    let file = std::fs::File::open(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+        .len();
+    let mut reader = HashingReader {
+        inner: file.take(size),
+        hasher: Sha256::new(),
+        size: 0,
+    };
+    append_reader(builder, path, &mut reader, size, mtime)?;
+    if reader.size != size {
+        return Err(format!(
+            "{} changed while it was being backed up; try again",
+            source.display()
+        ));
+    }
+    Ok(BackupFile {
+        path: path.to_string(),
+        size,
+        sha256: hex(&reader.hasher.finalize()),
+    })
+}
+
+/// The passphrase a backup is sealed with, and the cost of deriving its key.
+pub(crate) struct BackupPassphrase<'a> {
+    pub passphrase: &'a str,
+    pub kdf: KdfParams,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedHeader {
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+}
+
+fn sealing_error() -> std::io::Error {
+    std::io::Error::other("Failed to encrypt the backup")
+}
+
+fn unsealing_error() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "wrong passphrase or damaged backup",
+    )
+}
+
+/// Seals everything written to it in frames of [`SEALED_CHUNK_LEN`]. The last frame is always
+/// shorter, so a reader can tell it apart and notice a truncated file.
+struct SealingWriter<W: Write> {
+    inner: W,
+    encryptor: EncryptorBE32<XChaCha20Poly1305>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> SealingWriter<W> {
+    fn new(mut inner: W, passphrase: &BackupPassphrase) -> Result<Self, String> {
+        crate::encryption::validate_passphrase(passphrase.passphrase)?;
+        let salt = crate::secrets::random_bytes::<SEALED_SALT_LEN>();
+        let nonce = crate::secrets::random_bytes::<SEALED_NONCE_LEN>();
+        let key = crate::secrets::derive_key(passphrase.passphrase, &salt, passphrase.kdf)?;
+        let header = serde_json::to_vec(&SealedHeader {
+            kdf: passphrase.kdf,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+        })
+        .map_err(|e| e.to_string())?;
+        inner
+            .write_all(SEALED_MAGIC)
+            .and_then(|_| inner.write_all(&header))
+            .and_then(|_| inner.write_all(b"\n"))
+            .map_err(|e| format!("Failed to write the backup: {}", e))?;
+        Ok(SealingWriter {
+            inner,
+            encryptor: EncryptorBE32::new(&key.into(), GenericArray::from_slice(&nonce)),
+            buffer: Vec::with_capacity(SEALED_CHUNK_LEN),
+        })
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        let frame = self
+            .encryptor
+            .encrypt_last(self.buffer.as_slice())
+            .map_err(|_| sealing_error())?;
+        self.inner.write_all(&frame)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for SealingWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        let taken = bytes.len().min(SEALED_CHUNK_LEN - self.buffer.len());
+        self.buffer.extend_from_slice(&bytes[..taken]);
+        if self.buffer.len() == SEALED_CHUNK_LEN {
+            let frame = self
+                .encryptor
+                .encrypt_next(self.buffer.as_slice())
+                .map_err(|_| sealing_error())?;
+            self.inner.write_all(&frame)?;
+            self.buffer.clear();
+        }
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads what [`SealingWriter`] wrote, failing on tampered, reordered or missing frames.
+struct UnsealingReader<R: Read> {
+    inner: R,
+    /// `None` once the last frame was read.
+    decryptor: Option<DecryptorBE32<XChaCha20Poly1305>>,
+    plaintext: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> UnsealingReader<R> {
+    /// Opens the first frame right away, so a wrong passphrase is reported as such.
+    fn new(inner: R, key: [u8; 32], nonce: &[u8]) -> Result<Self, String> {
+        if nonce.len() != SEALED_NONCE_LEN {
+            return Err("Invalid encrypted backup header".to_string());
+        }
+        let mut reader = UnsealingReader {
+            inner,
+            decryptor: Some(DecryptorBE32::new(
+                &key.into(),
+                GenericArray::from_slice(nonce),
+            )),
+            plaintext: Vec::new(),
+            position: 0,
+        };
+        reader
+            .next_frame()
+            .map_err(|_| "Wrong backup passphrase or damaged backup".to_string())?;
+        Ok(reader)
+    }
+
+    fn next_frame(&mut self) -> std::io::Result<()> {
+        let mut frame = vec![0; SEALED_CHUNK_LEN + SEALED_TAG_LEN];
+        let mut len = 0;
+        while len < frame.len() {
+            match self.inner.read(&mut frame[len..]) {
+                Ok(0) => break,
+                Ok(read) => len += read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.plaintext = if len == frame.len() {
+            let decryptor = self.decryptor.as_mut().ok_or_else(unsealing_error)?;
+            decryptor.decrypt_next(frame.as_slice())
+        } else {
+            let decryptor = self.decryptor.take().ok_or_else(unsealing_error)?;
+            decryptor.decrypt_last(&frame[..len])
+        }
+        .map_err(|_| unsealing_error())?;
+        self.position = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for UnsealingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.plaintext.len() {
+            if self.decryptor.is_none() {
+                return Ok(0);
+            }
+            self.next_frame()?;
+        }
+        let len = out.len().min(self.plaintext.len() - self.position);
+        out[..len].copy_from_slice(&self.plaintext[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// The archive's tar stream, unsealed first when it was written with a passphrase.
+fn open_archive(archive: &Path, passphrase: Option<&str>) -> Result<Box<dyn Read>, String> {
+    let file = std::fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    let mut reader = BufReader::new(file);
+    let sealed = reader
+        .fill_buf()
+        .map_err(|e| format!("Failed to read {}: {}", archive.display(), e))?
+        .starts_with(SEALED_MAGIC);
+    if !sealed {
+        return Ok(Box::new(GzDecoder::new(reader)));
+    }
+
+    reader.consume(SEALED_MAGIC.len());
+    let mut line = Vec::new();
+    (&mut reader)
+        .take(SEALED_HEADER_MAX_BYTES)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| format!("Failed to read {}: {}", archive.display(), e))?;
+    let header: SealedHeader = serde_json::from_slice(&line)
+        .map_err(|e| format!("Invalid encrypted backup header: {}", e))?;
+    let passphrase =
+        passphrase.ok_or("This backup is encrypted; enter its passphrase to restore it")?;
+    let salt = BASE64.decode(&header.salt).map_err(|e| e.to_string())?;
+    let nonce = BASE64.decode(&header.nonce).map_err(|e| e.to_string())?;
+    let key = crate::secrets::derive_key(passphrase, &salt, header.kdf)?;
+    Ok(Box::new(GzDecoder::new(UnsealingReader::new(
+        reader, key, &nonce,
+    )?)))
+}
+
+/// Writes `contents` and then the manifest listing them, filling in `manifest.files`.
+fn write_archive<W: Write>(
+    out: W,
+    manifest: &mut BackupManifest,
+    contents: &[(String, BackupSource)],
+    mtime: u64,
+) -> Result<W, String> {
+    let mut builder = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+    for (path, source) in contents {
+        let file = match source {
+            BackupSource::Document(bytes) => {
+                append_file(&mut builder, path, bytes, mtime)?;
+                BackupFile {
+                    path: path.clone(),
+                    size: bytes.len() as u64,
+                    sha256: sha256_hex(bytes),
+                }
+            }
+            BackupSource::File(source) => append_streamed_file(&mut builder, path, source, mtime)?,
+        };
+        manifest.files.push(file);
+    }
+    append_file(
+        &mut builder,
+        MANIFEST_ENTRY,
+        &serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?,
+        mtime,
+    )?;
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to write the backup: {}", e))
+}
+
+/// Writes the backup to `out` through a temporary file and returns its manifest. While data
+/// encryption is on, the backup must be sealed with a passphrase.
+pub(crate) fn create_backup(
+    roots: &BackupRoots,
+    vault: &Vault,
+    frontend: &serde_json::Value,
+    include_webview_storage: bool,
+    passphrase: Option<BackupPassphrase>,
+    out: &Path,
+    now_millis: u64,
+) -> Result<BackupManifest, String> {
+    if passphrase.is_none() && !matches!(vault, Vault::Disabled) {
+        return Err("Data encryption is on; choose a passphrase to encrypt the backup".to_string());
+    }
+    let contents = backup_contents(roots, vault, frontend, include_webview_storage)?;
+    let mut manifest = BackupManifest {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: now_millis,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        includes_webview_storage: include_webview_storage,
+        files: Vec::with_capacity(contents.len()),
+    };
+
+    let tmp_path = with_suffix(out, ".tmp");
+    let file = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+    let mtime = now_millis / 1000;
+    let written = match passphrase {
+        Some(passphrase) => SealingWriter::new(file, &passphrase)
+            .and_then(|sealer| write_archive(sealer, &mut manifest, &contents, mtime))
+            .and_then(|sealer| {
+                sealer
+                    .finish()
+                    .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))
+            }),
+        None => write_archive(file, &mut manifest, &contents, mtime),
+    };
+    let result = written.and_then(|file| {
+        file.sync_all()
+            .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, out)
+        .map_err(|e| format!("Failed to replace {}: {}", out.display(), e))?;
+    Ok(manifest)
+}
+
+/// Where an archive entry is extracted to, or an error for entries a backup never contains.
+fn staged_path(roots: &BackupRoots, path: &str) -> Result<PathBuf, String> {
+    let invalid = || format!("Unexpected file {} in the backup", path);
+    if path == FRONTEND_ENTRY {
+        return Ok(with_suffix(&roots.data_dir, STAGING_SUFFIX).join(RESTORED_FRONTEND_FILE));
+    }
+
+    let (section, relative) = path.split_once('/').ok_or_else(invalid)?;
+    let relative = Path::new(relative);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(invalid());
+    }
+    let top_level = relative
+        .components()
+        .next()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .ok_or_else(invalid)?;
+
+    match section {
+        DATA_SECTION if is_backed_up_entry(&top_level) => {
+            Ok(with_suffix(&roots.data_dir, STAGING_SUFFIX).join(relative))
+        }
+        WEBVIEW_SECTION if !is_app_entry(&top_level) => {
+            let webview_dir = roots
+                .webview_dir
+                .as_deref()
+                .ok_or("This backup holds webview storage, which cannot be restored here")?;
+            Ok(with_suffix(webview_dir, STAGING_SUFFIX).join(relative))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn remove_staging(roots: &BackupRoots) {
+    for dir in roots.staging_dirs() {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+/// Extracts `archive` next to the data directories and checks it against its manifest. Data
+/// files are sealed with `vault` as they are staged. The staged data is swapped in by
+/// [`finish_pending_restore`] on the next start.
+pub(crate) fn stage_restore(
+    roots: &BackupRoots,
+    vault: &Vault,
+    archive: &Path,
+    passphrase: Option<&str>,
+) -> Result<BackupManifest, String> {
+    remove_staging(roots);
+    let result = extract_and_verify(roots, vault, archive, passphrase);
+    if result.is_err() {
+        remove_staging(roots);
+    }
+    result
+}
+
+/// Reads an entry of at most `limit` bytes into memory.
+fn read_document(entry: &mut impl Read, path: &str, limit: u64) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    entry
+        .take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Not a readable AnyChat backup: {}", e))?;
+    if bytes.len() as u64 > limit {
+        return Err(format!("{} is too large for an AnyChat backup", path));
+    }
+    Ok(bytes)
+}
+
+/// Streams an entry to `target` and returns its size and SHA-256.
+fn extract_file(entry: &mut impl Read, target: &Path) -> Result<(u64, String), String> {
+    let mut file = std::fs::File::create(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = entry
+            .read(&mut buffer)
+            .map_err(|e| format!("Not a readable AnyChat backup: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        size += read as u64;
+    }
+    Ok((size, hex(&hasher.finalize())))
+}
+
+fn extract_and_verify(
+    roots: &BackupRoots,
+    vault: &Vault,
+    archive: &Path,
+    passphrase: Option<&str>,
+) -> Result<BackupManifest, String> {
+    let mut entries = tar::Archive::new(open_archive(archive, passphrase)?);
+    let mut manifest: Option<BackupManifest> = None;
+    let mut extracted = BTreeMap::new();
+    let mut total_size = 0u64;
+
+    let not_a_backup = |e: std::io::Error| format!("Not a readable AnyChat backup: {}", e);
+    for entry in entries.entries().map_err(not_a_backup)? {
+        let entry = entry.map_err(not_a_backup)?;
+        let path = entry
+            .path()
+            .map_err(not_a_backup)?
+            .to_string_lossy()
+            .into_owned();
+        if !entry.header().entry_type().is_file() {
+            return Err(format!("Unexpected entry {} in the backup", path));
+        }
+        let size = entry.header().size().map_err(not_a_backup)?;
+        total_size = total_size.saturating_add(size);
+        if total_size > MAX_EXTRACTED_BYTES {
+            return Err("The backup is too large to restore".to_string());
+        }
+
+        if path == MANIFEST_ENTRY {
+            let bytes = read_document(&mut entry.take(size), &path, MAX_DOCUMENT_BYTES)?;
+            manifest = Some(
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| format!("Invalid backup manifest: {}", e))?,
+            );
+            continue;
+        }
+        let target = staged_path(roots, &path)?;
+        if extracted.contains_key(&path) {
+            return Err(format!("{} appears twice in the backup", path));
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let checksum = if path.starts_with(WEBVIEW_SECTION) {
+            if size > MAX_WEBVIEW_FILE_BYTES {
+                return Err(format!("{} is too large for an AnyChat backup", path));
+            }
+            extract_file(&mut entry.take(size), &target)?
+        } else {
+            let bytes = read_document(&mut entry.take(size), &path, MAX_DOCUMENT_BYTES)?;
+            let document: serde_json::Value = serde_json::from_slice(&bytes)
+                .map_err(|e| format!("{} in the backup is not valid JSON: {}", path, e))?;
+            if path == FRONTEND_ENTRY {
+                crate::storage::write_plain_json(&target, &document)?;
+            } else {
+                crate::storage::write_json(vault, &target, &document)?;
+            }
+            (bytes.len() as u64, sha256_hex(&bytes))
+        };
+        extracted.insert(path, checksum);
+    }
+
+    let manifest = manifest.ok_or("The backup has no manifest")?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err("Not an AnyChat backup".to_string());
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err("The backup was made by a newer AnyChat; update this one first".to_string());
+    }
+    let expected = manifest
+        .files
+        .iter()
+        .map(|file| (file.path.clone(), (file.size, file.sha256.clone())))
+        .collect::<BTreeMap<_, _>>();
+    if let Some((path, _)) = expected
+        .iter()
+        .find(|(path, checksum)| extracted.get(*path) != Some(checksum))
+    {
+        return Err(format!("{} is missing or damaged in the backup", path));
+    }
+    if let Some(path) = extracted.keys().find(|path| !expected.contains_key(*path)) {
+        return Err(format!("{} is not listed in the backup manifest", path));
+    }
+
+    let data_staging = with_suffix(&roots.data_dir, STAGING_SUFFIX);
+    std::fs::create_dir_all(&data_staging)
+        .map_err(|e| format!("Failed to create {}: {}", data_staging.display(), e))?;
+    write_marker(
+        &data_staging,
+        manifest.includes_webview_storage && roots.separate_webview_dir().is_none(),
+    )?;
+    if let Some(webview_dir) = roots.separate_webview_dir() {
+        let webview_staging = with_suffix(webview_dir, STAGING_SUFFIX);
+        if webview_staging.is_dir() {
+            write_marker(&webview_staging, true)?;
+        }
+    }
+    Ok(manifest)
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadyMarker {
+    /// Whether webview storage in this directory is replaced rather than carried over.
+    replaces_webview_storage: bool,
+}
+
+fn write_marker(staging: &Path, replaces_webview_storage: bool) -> Result<(), String> {
+    crate::storage::write_plain_json(
+        &staging.join(READY_MARKER),
+        &ReadyMarker {
+            replaces_webview_storage,
+        },
+    )
+}
+
+/// Swaps in data staged by [`stage_restore`]. Safe to run again after an interrupted swap.
+/// Returns whether anything was restored.
+pub(crate) fn finish_pending_restore(roots: &BackupRoots) -> Result<bool, String> {
+    let mut restored = false;
+    let mut targets = vec![roots.data_dir.as_path()];
+    targets.extend(roots.separate_webview_dir());
+    for target in targets {
+        restored |= swap_in(target)?;
+    }
+    Ok(restored)
+}
+
+fn swap_in(target: &Path) -> Result<bool, String> {
+    let staging = with_suffix(target, STAGING_SUFFIX);
+    let marker_path = staging.join(READY_MARKER);
+    if !marker_path.is_file() {
+        // Left over from an extraction that did not finish.
+        if staging.exists() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        return Ok(false);
+    }
+    let marker: ReadyMarker = crate::storage::read_plain_json::<Option<ReadyMarker>>(&marker_path)?
+        .ok_or("Invalid restore marker")?;
+
+    if target.exists() {
+        let entries = std::fs::read_dir(target)
+            .map_err(|e| format!("Failed to read {}: {}", target.display(), e))?;
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let replaced = is_backed_up_entry(&name)
+                || name == READY_MARKER
+                || (marker.replaces_webview_storage && !is_app_entry(&name));
+            let staged = staging.join(&name);
+            if !replaced && !staged.exists() {
+                std::fs::rename(entry.path(), &staged)
+                    .map_err(|e| format!("Failed to carry over {}: {}", name, e))?;
+            }
+        }
+
+        let previous = with_suffix(target, PREVIOUS_SUFFIX);
+        if previous.exists() {
+            std::fs::remove_dir_all(&previous)
+                .map_err(|e| format!("Failed to remove {}: {}", previous.display(), e))?;
+        }
+        std::fs::rename(target, &previous)
+            .map_err(|e| format!("Failed to move {} aside: {}", target.display(), e))?;
+    }
+    std::fs::rename(&staging, target)
+        .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+    let _ = std::fs::remove_file(target.join(READY_MARKER));
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        append_file, create_backup, finish_pending_restore, read_document, sha256_hex,
+        stage_restore, BackupPassphrase, BackupRoots, RESTORED_FRONTEND_FILE,
+    };
+    use crate::secrets::KdfParams;
+    use crate::storage::Vault;
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("anychat-backup-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn backup_restores_data_and_carries_over_everything_else() {
+        let dir = test_dir("roundtrip");
+        let data_dir = dir.join("data");
+        let roots = BackupRoots {
+            data_dir: data_dir.clone(),
+            webview_dir: Some(data_dir.clone()),
+        };
+        write(&data_dir.join("services.json"), r#"{"services":{"a":{}}}"#);
+        write(
+            &data_dir.join("user_scripts/chatgpt.json"),
+            r#"{"revision":1}"#,
+        );
+        write(&data_dir.join("localstorage/main.sqlite"), "webview v1");
+        let archive = dir.join("backup.tar.gz");
+
+        let manifest = create_backup(
+            &roots,
+            &Vault::Disabled,
+            &json!({"services": []}),
+            true,
+            None,
+            &archive,
+            1_000,
+        )
+        .unwrap();
+        assert!(manifest.includes_webview_storage);
+        assert!(manifest
+            .files
+            .iter()
+            .any(|file| file.path == "webview/localstorage/main.sqlite"));
+        assert!(!manifest
+            .files
+            .iter()
+            .any(|file| file.path.starts_with("webview/services.json")));
+        let streamed = manifest
+            .files
+            .iter()
+            .find(|file| file.path == "webview/localstorage/main.sqlite")
+            .unwrap();
+        assert_eq!(streamed.size, 10);
+        assert_eq!(streamed.sha256, sha256_hex(b"webview v1"));
+        // The manifest comes last, after the streamed files it lists.
+        let mut entries = tar::Archive::new(GzDecoder::new(std::fs::File::open(&archive).unwrap()));
+        let last = entries
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                entry
+                    .unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .last();
+        assert_eq!(last.as_deref(), Some("manifest.json"));
+
+        // Changes made after the backup.
+        write(&data_dir.join("services.json"), r#"{"services":{"b":{}}}"#);
+        write(
+            &data_dir.join("user_scripts/claude.json"),
+            r#"{"revision":2}"#,
+        );
+        write(&data_dir.join("localstorage/main.sqlite"), "webview v2");
+        write(&data_dir.join("settings.json"), "{}");
+
+        stage_restore(&roots, &Vault::Disabled, &archive, None).unwrap();
+        // Nothing changes until the next start.
+        assert!(read(&data_dir.join("services.json")).contains("\"b\""));
+
+        assert!(finish_pending_restore(&roots).unwrap());
+        assert!(read(&data_dir.join("services.json")).contains("\"a\""));
+        assert!(data_dir.join("user_scripts/chatgpt.json").is_file());
+        assert!(!data_dir.join("user_scripts/claude.json").exists());
+        assert_eq!(
+            read(&data_dir.join("localstorage/main.sqlite")),
+            "webview v1"
+        );
+        assert_eq!(read(&data_dir.join("settings.json")), "{}");
+        assert!(data_dir.join(RESTORED_FRONTEND_FILE).is_file());
+        assert!(dir
+            .join("data.before-restore/user_scripts/claude.json")
+            .is_file());
+        assert!(!finish_pending_restore(&roots).unwrap());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn damaged_or_foreign_archives_are_rejected_without_touching_data() {
+        let dir = test_dir("damaged");
+        let data_dir = dir.join("data");
+        let roots = BackupRoots {
+            data_dir: data_dir.clone(),
+            webview_dir: None,
+        };
+        write(&data_dir.join("services.json"), r#"{"services":{}}"#);
+        let archive = dir.join("backup.tar.gz");
+        let manifest = create_backup(
+            &roots,
+            &Vault::Disabled,
+            &json!({}),
+            false,
+            None,
+            &archive,
+            1_000,
+        )
+        .unwrap();
+
+        let forge = |entries: &[(&str, &str)]| {
+            let path = dir.join("forged.tar.gz");
+            let file = std::fs::File::create(&path).unwrap();
+            let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+            append_file(
+                &mut builder,
+                "manifest.json",
+                &serde_json::to_vec(&manifest).unwrap(),
+                0,
+            )
+            .unwrap();
+            for (name, contents) in entries {
+                // Written by hand: `tar` refuses to build archives with `..` in a path.
+                let mut header = tar::Header::new_gnu();
+                header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+                header.set_size(contents.len() as u64);
+                header.set_mode(0o600);
+                header.set_cksum();
+                builder.append(&header, contents.as_bytes()).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+            path
+        };
+
+        let tampered = forge(&[("frontend.json", "{}"), ("data/services.json", "{}")]);
+        assert!(stage_restore(&roots, &Vault::Disabled, &tampered, None)
+            .unwrap_err()
+            .contains("missing or damaged"));
+        let escaping = forge(&[("data/../settings.json", "{}")]);
+        assert!(stage_restore(&roots, &Vault::Disabled, &escaping, None).is_err());
+        let settings = forge(&[("data/settings.json", "{}")]);
+        assert!(stage_restore(&roots, &Vault::Disabled, &settings, None).is_err());
+        let webview = forge(&[("webview/cookies", "")]);
+        assert!(stage_restore(&roots, &Vault::Disabled, &webview, None).is_err());
+
+        assert!(!finish_pending_restore(&roots).unwrap());
+        assert_eq!(read(&data_dir.join("services.json")), r#"{"services":{}}"#);
+        assert!(read_document(&mut &b"1234"[..], "frontend.json", 3)
+            .unwrap_err()
+            .contains("too large"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn encrypted_data_is_only_backed_up_under_a_passphrase() {
+        let dir = test_dir("sealed");
+        let data_dir = dir.join("data");
+        let roots = BackupRoots {
+            data_dir: data_dir.clone(),
+            webview_dir: None,
+        };
+        let vault = Vault::Unlocked([7; 32]);
+        crate::storage::write_json(
+            &vault,
+            &data_dir.join("services.json"),
+            &json!({"services": {"a": {}}}),
+        )
+        .unwrap();
+        let archive = dir.join("backup.tar.gz");
+        let passphrase = |passphrase| BackupPassphrase {
+            passphrase,
+            kdf: KdfParams {
+                memory_kib: 64,
+                iterations: 1,
+                parallelism: 1,
+            },
+        };
+
+        assert!(
+            create_backup(&roots, &vault, &json!({}), false, None, &archive, 1_000)
+                .unwrap_err()
+                .contains("passphrase")
+        );
+        assert!(create_backup(
+            &roots,
+            &vault,
+            &json!({}),
+            false,
+            Some(passphrase("short")),
+            &archive,
+            1_000,
+        )
+        .is_err());
+        create_backup(
+            &roots,
+            &vault,
+            &json!({}),
+            false,
+            Some(passphrase("correct horse")),
+            &archive,
+            1_000,
+        )
+        .unwrap();
+        let sealed = std::fs::read(&archive).unwrap();
+        assert!(!sealed.windows(8).any(|window| window == b"services"));
+
+        assert!(stage_restore(&roots, &vault, &archive, None)
+            .unwrap_err()
+            .contains("passphrase"));
+        assert!(stage_restore(&roots, &vault, &archive, Some("wrong horse"))
+            .unwrap_err()
+            .contains("Wrong backup passphrase"));
+        let truncated = dir.join("truncated.tar.gz");
+        std::fs::write(&truncated, &sealed[..sealed.len() - 1]).unwrap();
+        assert!(stage_restore(&roots, &vault, &truncated, Some("correct horse")).is_err());
+
+        stage_restore(&roots, &vault, &archive, Some("correct horse")).unwrap();
+        assert!(finish_pending_restore(&roots).unwrap());
+        // Restored data is sealed with the vault again.
+        assert!(!read(&data_dir.join("services.json")).contains("services"));
+        let services: serde_json::Value =
+            crate::storage::read_json(&vault, &data_dir.join("services.json")).unwrap();
+        assert_eq!(services, json!({"services": {"a": {}}}));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,201 @@
+//! One-way channel from scripts injected into service pages back to Rust.
+//!
+//! Injected scripts report through a WebKit script message handler on Linux and through the
+//! `report_bridge_message` command elsewhere. The page itself can post to either, so messages
+//! are treated as untrusted hints and only ever logged or forwarded.
+
+const MESSAGE_HANDLER: &str = "anychat";
+#[cfg(target_os = "linux")]
+const SCRIPT_RESULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Defines `window.__anychatPost(message)` for the other injected scripts. Messages fall back to
+/// Tauri IPC without a WebKit handler, and to the webview console when neither is available.
+pub(crate) const BRIDGE_SCRIPT: &str = r#"
+(function() {
+    if (window.__anychatPost) return;
+    window.__anychatPost = function(message) {
+        try {
+            var handlers = window.webkit && window.webkit.messageHandlers;
+            if (handlers && handlers.anychat) {
+                handlers.anychat.postMessage(JSON.stringify(message));
+                return;
+            }
+        } catch (_) {}
+        var internals = window.__TAURI_INTERNALS__;
+        if (internals && internals.invoke) {
+            internals
+                .invoke('report_bridge_message', { message: JSON.stringify(message) })
+                .catch(function() {});
+            return;
+        }
+        console.log('[AnyChat]', JSON.stringify(message));
+    };
+})();
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum BridgeMessage {
+    Heartbeat,
+    /// User input in the page, for the app lock's idle timer.
+    Activity,
+    /// Result of a keep-alive ping; `status` is 0 when the request did not complete.
+    KeepAlive {
+        status: u16,
+        #[serde(default)]
+        url: String,
+        #[serde(default)]
+        redirected: bool,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    /// A login DOM probe found one of its selectors.
+    #[serde(rename_all = "camelCase")]
+    LoginProbe {
+        signed_in: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    UserScriptError {
+        script_id: String,
+        message: String,
+        #[serde(default)]
+        url: String,
+    },
+}
+
+pub(crate) fn parse_message(raw: &str) -> Result<BridgeMessage, String> {
+    serde_json::from_str(raw).map_err(|e| format!("Invalid bridge message: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_script_bridge(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use javascriptcore::ValueExt;
+    use webkit2gtk::{UserContentManagerExt, WebViewExt};
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        let Some(manager) = platform_webview.inner().user_content_manager() else {
+            return;
+        };
+
+        if !manager.register_script_message_handler(MESSAGE_HANDLER) {
+            println!(
+                "[AnyChat] Script bridge already registered for {}",
+                service_id
+            );
+            return;
+        }
+
+        manager.connect_script_message_received(Some(MESSAGE_HANDLER), move |_, result| {
+            let Some(value) = result.js_value() else {
+                return;
+            };
+
+            match parse_message(&value.to_str()) {
+                Ok(message) => crate::handle_bridge_message(&app, &service_id, message),
+                Err(e) => println!("[AnyChat] {} ({})", e, service_id),
+            }
+        });
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_script_bridge(
+    _app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    _service_id: &str,
+) {
+}
+
+/// Runs `script` in the page and returns its completion value as a string. Only WebKitGTK hands
+/// results back; elsewhere the script still runs and `None` is returned.
+#[cfg(target_os = "linux")]
+pub(crate) async fn evaluate_script(
+    webview: &tauri::Webview,
+    script: String,
+) -> Result<Option<String>, String> {
+    use javascriptcore::ValueExt;
+    use webkit2gtk::WebViewExt;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    webview
+        .with_webview(move |platform_webview| {
+            platform_webview.inner().evaluate_javascript(
+                &script,
+                None,
+                None,
+                None::<&webkit2gtk::gio::Cancellable>,
+                move |result| {
+                    let _ = sender.send(
+                        result
+                            .map(|value| value.to_str().to_string())
+                            .map_err(|e| e.to_string()),
+                    );
+                },
+            );
+        })
+        .map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || receiver.recv_timeout(SCRIPT_RESULT_TIMEOUT))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|_| "Timed out waiting for the page script".to_string())?
+        .map(Some)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) async fn evaluate_script(
+    webview: &tauri::Webview,
+    script: String,
+) -> Result<Option<String>, String> {
+    webview.eval(&script).map_err(|e| e.to_string())?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_message, BridgeMessage};
+
+    #[test]
+    fn parses_tagged_messages_and_rejects_unknown_ones() {
+        assert_eq!(
+            parse_message(
+                r#"{"type":"userScriptError","scriptId":"wide","message":"boom","url":"https://chatgpt.com/"}"#
+            )
+            .unwrap(),
+            BridgeMessage::UserScriptError {
+                script_id: "wide".to_string(),
+                message: "boom".to_string(),
+                url: "https://chatgpt.com/".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_message(r#"{"type":"heartbeat"}"#).unwrap(),
+            BridgeMessage::Heartbeat
+        );
+        assert_eq!(
+            parse_message(r#"{"type":"activity"}"#).unwrap(),
+            BridgeMessage::Activity
+        );
+        assert_eq!(
+            parse_message(r#"{"type":"keepAlive","status":0,"url":"","redirected":true}"#).unwrap(),
+            BridgeMessage::KeepAlive {
+                status: 0,
+                url: String::new(),
+                redirected: true,
+                error: None,
+            }
+        );
+        assert_eq!(
+            parse_message(r#"{"type":"loginProbe","signedIn":false}"#).unwrap(),
+            BridgeMessage::LoginProbe { signed_in: false }
+        );
+        assert!(parse_message(r#"{"type":"somethingElse"}"#).is_err());
+        assert!(parse_message("not json").is_err());
+    }
+}
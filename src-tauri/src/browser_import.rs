@@ -0,0 +1,628 @@
+//! Imports a service's cookies from the user's own Chromium-based or Firefox profile on Linux,
+//! so services the user is already signed into do not need another login.
+//!
+//! Profiles are only read from the browsers' standard locations, from a copy of the cookie
+//! database so a running browser's lock does not matter. Chromium encrypts values with
+//! AES-128-CBC under a key derived from the "Safe Storage" password in the Secret Service
+//! keyring (`v11`) or from the built-in `peanuts` password (`v10`). Nothing is written without
+//! an explicit import after a preview, which never includes cookie values.
+
+use crate::cookie_backup::{cookie_belongs_to_host, StoredCookie};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Browser {
+    Chrome,
+    Chromium,
+    Brave,
+    Edge,
+    Firefox,
+}
+
+impl Browser {
+    const ALL: [Browser; 5] = [
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Brave,
+        Browser::Edge,
+        Browser::Firefox,
+    ];
+
+    /// Profile roots relative to the home directory.
+    fn roots(self) -> &'static [&'static str] {
+        match self {
+            Browser::Chrome => &[".config/google-chrome"],
+            Browser::Chromium => &[".config/chromium", "snap/chromium/common/chromium"],
+            Browser::Brave => &[".config/BraveSoftware/Brave-Browser"],
+            Browser::Edge => &[".config/microsoft-edge"],
+            Browser::Firefox => &[".mozilla/firefox", "snap/firefox/common/.mozilla/firefox"],
+        }
+    }
+
+    /// `application` attribute of the browser's Safe Storage entry in the keyring.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn keyring_application(self) -> &'static str {
+        match self {
+            Browser::Chrome => "chrome",
+            Browser::Chromium | Browser::Edge => "chromium",
+            Browser::Brave => "brave",
+            Browser::Firefox => "firefox",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrowserProfile {
+    pub browser: Browser,
+    /// Profile directory name, e.g. `Default` or `abcd1234.default-release`.
+    pub name: String,
+    pub cookie_db: String,
+}
+
+/// One importable cookie, without its value.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CookiePreview {
+    pub name: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportPreview {
+    pub service_id: String,
+    pub profile: BrowserProfile,
+    pub cookies: Vec<CookiePreview>,
+    pub expired: usize,
+    /// Cookies whose value could not be decrypted and will be left out.
+    pub undecryptable: usize,
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) struct ProfileCookies {
+    pub cookies: Vec<StoredCookie>,
+    pub expired: usize,
+    pub undecryptable: usize,
+}
+
+impl ProfileCookies {
+    pub fn preview(&self, service_id: &str, profile: &BrowserProfile) -> ImportPreview {
+        ImportPreview {
+            service_id: service_id.to_string(),
+            profile: profile.clone(),
+            cookies: self
+                .cookies
+                .iter()
+                .map(|cookie| CookiePreview {
+                    name: cookie.name.clone(),
+                    domain: cookie.domain.clone(),
+                    path: cookie.path.clone(),
+                    expires: cookie.expires,
+                    secure: cookie.secure,
+                    http_only: cookie.http_only,
+                })
+                .collect(),
+            expired: self.expired,
+            undecryptable: self.undecryptable,
+        }
+    }
+}
+
+fn sorted_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_dir())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Profiles with a cookie database under the standard browser locations in `home`.
+pub(crate) fn discover_profiles(home: &Path) -> Vec<BrowserProfile> {
+    let mut profiles = Vec::new();
+    for browser in Browser::ALL {
+        for root in browser.roots() {
+            for profile_dir in sorted_dirs(&home.join(root)) {
+                let candidates: &[&str] = match browser {
+                    Browser::Firefox => &["cookies.sqlite"],
+                    _ => &["Network/Cookies", "Cookies"],
+                };
+                let Some(cookie_db) = candidates
+                    .iter()
+                    .map(|candidate| profile_dir.join(candidate))
+                    .find(|path| path.is_file())
+                else {
+                    continue;
+                };
+                profiles.push(BrowserProfile {
+                    browser,
+                    name: profile_dir
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    cookie_db: cookie_db.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    profiles
+}
+
+/// Chromium stores microseconds since 1601-01-01; 0 marks a session cookie.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn chromium_time_to_unix(microseconds: i64) -> Option<i64> {
+    const WINDOWS_TO_UNIX_EPOCH_SECS: i64 = 11_644_473_600;
+    (microseconds > 0).then(|| microseconds / 1_000_000 - WINDOWS_TO_UNIX_EPOCH_SECS)
+}
+
+/// Firefox stored seconds and has moved to milliseconds; both occur in the wild.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn firefox_expiry_to_unix(expiry: i64) -> Option<i64> {
+    const MILLISECONDS_THRESHOLD: i64 = 100_000_000_000;
+    match expiry {
+        expiry if expiry <= 0 => None,
+        expiry if expiry > MILLISECONDS_THRESHOLD => Some(expiry / 1000),
+        expiry => Some(expiry),
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn same_site(value: i64, secure: bool) -> Option<String> {
+    match value {
+        // SameSite=None is only accepted on secure cookies.
+        0 if secure => Some("None".to_string()),
+        1 => Some("Lax".to_string()),
+        2 => Some("Strict".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod chromium_crypto {
+    use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+
+    const SALT: &[u8] = b"saltysalt";
+    const IV: [u8; 16] = [b' '; 16];
+    pub(super) const FALLBACK_PASSWORD: &[u8] = b"peanuts";
+
+    pub(super) fn derive_key(password: &[u8]) -> [u8; 16] {
+        let mut key = [0u8; 16];
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, SALT, 1, &mut key);
+        key
+    }
+
+    /// Decrypts a `v10`/`v11` value. Newer databases prefix the plaintext with the SHA-256 of
+    /// the cookie's host, which `strip_host_digest` removes.
+    pub(super) fn decrypt_value(
+        encrypted: &[u8],
+        v10_key: &[u8; 16],
+        v11_key: Option<&[u8; 16]>,
+        strip_host_digest: bool,
+    ) -> Option<String> {
+        let (key, ciphertext) = match encrypted.split_at_checked(3)? {
+            (b"v10", ciphertext) => (v10_key, ciphertext),
+            (b"v11", ciphertext) => (v11_key?, ciphertext),
+            _ => return None,
+        };
+        let mut buffer = ciphertext.to_vec();
+        let plaintext = cbc::Decryptor::<aes::Aes128>::new(key.into(), &IV.into())
+            .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+            .ok()?;
+        let plaintext = if strip_host_digest {
+            plaintext.get(32..)?
+        } else {
+            plaintext
+        };
+        String::from_utf8(plaintext.to_vec()).ok()
+    }
+
+    #[cfg(test)]
+    pub(super) fn encrypt_value(plaintext: &[u8], key: &[u8; 16], version: &str) -> Vec<u8> {
+        use aes::cipher::BlockEncryptMut;
+
+        let mut buffer = vec![0u8; plaintext.len() + 16];
+        let ciphertext = cbc::Encryptor::<aes::Aes128>::new(key.into(), &IV.into())
+            .encrypt_padded_b2b_mut::<Pkcs7>(plaintext, &mut buffer)
+            .unwrap();
+        [version.as_bytes(), ciphertext].concat()
+    }
+}
+
+/// Looks up a Chromium Safe Storage password in the Secret Service keyring. `None` when the
+/// keyring has no unlocked entry for the browser.
+#[cfg(target_os = "linux")]
+fn keyring_password(application: &str) -> Result<Option<Vec<u8>>, String> {
+    crate::keyring::lookup(&[("application", application)])
+}
+
+/// Opens a private copy of a cookie database, which the browser may hold locked. The `-wal`
+/// and `-shm` files are copied along, so cookies the browser has not checkpointed yet are read
+/// too. The copy lives in a fresh owner-only directory, which the caller removes.
+#[cfg(target_os = "linux")]
+fn open_copy(cookie_db: &Path) -> Result<(rusqlite::Connection, PathBuf), String> {
+    use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+    let dir = std::env::temp_dir().join(format!(
+        "anychat-cookie-import-{:016x}",
+        u64::from_be_bytes(crate::secrets::random_bytes::<8>())
+    ));
+    // Fails if the path exists, so nobody else can have prepared it.
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let copy = dir.join("cookies.sqlite");
+    let result = ["", "-wal", "-shm"].iter().try_for_each(|suffix| {
+        let mut source = cookie_db.as_os_str().to_owned();
+        source.push(suffix);
+        let mut source = match std::fs::File::open(&source) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !suffix.is_empty() => {
+                return Ok(())
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", cookie_db.display(), e)),
+        };
+        let mut target = copy.as_os_str().to_owned();
+        target.push(suffix);
+        let mut target = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&target)
+            .map_err(|e| format!("Failed to copy {}: {}", cookie_db.display(), e))?;
+        std::io::copy(&mut source, &mut target)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", cookie_db.display(), e))
+    });
+    // Opened writable so SQLite can replay the copied WAL; the browser's files are untouched.
+    let connection = result.and_then(|_| {
+        rusqlite::Connection::open(&copy)
+            .map_err(|e| format!("Failed to open {}: {}", cookie_db.display(), e))
+    });
+    match connection {
+        Ok(connection) => Ok((connection, dir)),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_chromium_cookies(
+    connection: &rusqlite::Connection,
+    host: &str,
+    v11_password: Option<&[u8]>,
+    now_secs: i64,
+) -> Result<ProfileCookies, String> {
+    let version = connection
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .unwrap_or_default();
+    let strip_host_digest = version >= 24;
+    let v10_key = chromium_crypto::derive_key(chromium_crypto::FALLBACK_PASSWORD);
+    let v11_key = v11_password.map(chromium_crypto::derive_key);
+
+    let mut statement = connection
+        .prepare(
+            "SELECT host_key, name, value, encrypted_value, path, expires_utc, is_secure, \
+             is_httponly, samesite FROM cookies",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, bool>(6)?,
+                row.get::<_, bool>(7)?,
+                row.get::<_, i64>(8)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut result = ProfileCookies::default();
+    for row in rows {
+        let (domain, name, value, encrypted, path, expires, secure, http_only, samesite) =
+            row.map_err(|e| e.to_string())?;
+        if !cookie_belongs_to_host(&domain, host) {
+            continue;
+        }
+        let value = if encrypted.is_empty() {
+            Some(value)
+        } else {
+            chromium_crypto::decrypt_value(
+                &encrypted,
+                &v10_key,
+                v11_key.as_ref(),
+                strip_host_digest,
+            )
+        };
+        let Some(value) = value else {
+            result.undecryptable += 1;
+            continue;
+        };
+        let cookie = StoredCookie {
+            name,
+            value,
+            domain: Some(domain),
+            path: Some(path),
+            secure,
+            http_only,
+            same_site: same_site(samesite, secure),
+            expires: chromium_time_to_unix(expires),
+        };
+        if cookie.is_expired(now_secs) {
+            result.expired += 1;
+        } else {
+            result.cookies.push(cookie);
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(target_os = "linux")]
+fn read_firefox_cookies(
+    connection: &rusqlite::Connection,
+    host: &str,
+    now_secs: i64,
+) -> Result<ProfileCookies, String> {
+    let mut statement = connection
+        .prepare(
+            "SELECT host, name, value, path, expiry, isSecure, isHttpOnly, sameSite \
+             FROM moz_cookies",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, bool>(5)?,
+                row.get::<_, bool>(6)?,
+                row.get::<_, i64>(7)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut result = ProfileCookies::default();
+    for row in rows {
+        let (domain, name, value, path, expiry, secure, http_only, samesite) =
+            row.map_err(|e| e.to_string())?;
+        if !cookie_belongs_to_host(&domain, host) {
+            continue;
+        }
+        let cookie = StoredCookie {
+            name,
+            value,
+            domain: Some(domain),
+            path: Some(path),
+            secure,
+            http_only,
+            same_site: same_site(samesite, secure),
+            expires: firefox_expiry_to_unix(expiry),
+        };
+        if cookie.is_expired(now_secs) {
+            result.expired += 1;
+        } else {
+            result.cookies.push(cookie);
+        }
+    }
+    Ok(result)
+}
+
+/// Reads the unexpired cookies of `host` from `profile`.
+#[cfg(target_os = "linux")]
+pub(crate) fn read_profile_cookies(
+    profile: &BrowserProfile,
+    host: &str,
+    now_secs: i64,
+) -> Result<ProfileCookies, String> {
+    let (connection, copy_dir) = open_copy(Path::new(&profile.cookie_db))?;
+    let result = match profile.browser {
+        Browser::Firefox => read_firefox_cookies(&connection, host, now_secs),
+        browser => {
+            let password = keyring_password(browser.keyring_application()).unwrap_or_else(|e| {
+                println!("[AnyChat] {}; only v10 cookies can be decrypted", e);
+                None
+            });
+            read_chromium_cookies(&connection, host, password.as_deref(), now_secs)
+        }
+    };
+    drop(connection);
+    let _ = std::fs::remove_dir_all(copy_dir);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chromium_time_to_unix, discover_profiles, firefox_expiry_to_unix, Browser, BrowserProfile,
+    };
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "anychat-browser-import-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn profiles_are_found_under_the_standard_locations() {
+        let home = temp_dir("discover");
+        let chrome = home.join(".config/google-chrome/Default/Network");
+        let firefox = home.join(".mozilla/firefox/abcd.default-release");
+        std::fs::create_dir_all(&chrome).unwrap();
+        std::fs::create_dir_all(&firefox).unwrap();
+        std::fs::create_dir_all(home.join(".config/chromium/Empty")).unwrap();
+        std::fs::write(chrome.join("Cookies"), b"").unwrap();
+        std::fs::write(firefox.join("cookies.sqlite"), b"").unwrap();
+
+        assert_eq!(
+            discover_profiles(&home),
+            vec![
+                BrowserProfile {
+                    browser: Browser::Chrome,
+                    name: "Default".to_string(),
+                    cookie_db: chrome.join("Cookies").to_string_lossy().to_string(),
+                },
+                BrowserProfile {
+                    browser: Browser::Firefox,
+                    name: "abcd.default-release".to_string(),
+                    cookie_db: firefox.join("cookies.sqlite").to_string_lossy().to_string(),
+                },
+            ]
+        );
+        let _ = std::fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn browser_timestamps_convert_to_unix_seconds() {
+        assert_eq!(chromium_time_to_unix(0), None);
+        assert_eq!(
+            chromium_time_to_unix(13_380_000_000_000_000),
+            Some(1_735_526_400)
+        );
+        assert_eq!(firefox_expiry_to_unix(0), None);
+        assert_eq!(firefox_expiry_to_unix(1_800_000_000), Some(1_800_000_000));
+        assert_eq!(
+            firefox_expiry_to_unix(1_800_000_000_000),
+            Some(1_800_000_000)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn chromium_values_decrypt_with_the_matching_key() {
+        use super::chromium_crypto::{decrypt_value, derive_key, encrypt_value, FALLBACK_PASSWORD};
+
+        let v10_key = derive_key(FALLBACK_PASSWORD);
+        let v11_key = derive_key(b"keyring secret");
+        let v10 = encrypt_value(b"abc", &v10_key, "v10");
+        assert_eq!(
+            decrypt_value(&v10, &v10_key, None, false).as_deref(),
+            Some("abc")
+        );
+
+        let with_digest = [[7u8; 32].as_slice(), b"def"].concat();
+        let v11 = encrypt_value(&with_digest, &v11_key, "v11");
+        assert_eq!(decrypt_value(&v11, &v10_key, None, true), None);
+        assert_eq!(
+            decrypt_value(&v11, &v10_key, Some(&v11_key), true).as_deref(),
+            Some("def")
+        );
+        assert_eq!(decrypt_value(b"v12xyz", &v10_key, None, false), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn chromium_and_firefox_databases_are_filtered_by_site() {
+        use super::chromium_crypto::{derive_key, encrypt_value, FALLBACK_PASSWORD};
+        use super::{read_chromium_cookies, read_firefox_cookies};
+
+        let now = 1_800_000_000;
+        let chromium = rusqlite::Connection::open_in_memory().unwrap();
+        chromium
+            .execute_batch(
+                "CREATE TABLE meta (key TEXT, value TEXT);
+                 INSERT INTO meta VALUES ('version', '21');
+                 CREATE TABLE cookies (host_key TEXT, name TEXT, value TEXT, encrypted_value BLOB,
+                     path TEXT, expires_utc INTEGER, is_secure INTEGER, is_httponly INTEGER,
+                     samesite INTEGER);",
+            )
+            .unwrap();
+        let encrypted = encrypt_value(b"secret", &derive_key(FALLBACK_PASSWORD), "v10");
+        for (host, name, expires) in [
+            (".chatgpt.com", "session", 0i64),
+            ("chatgpt.com", "old", 13_000_000_000_000_000),
+            ("claude.ai", "other", 0),
+        ] {
+            chromium
+                .execute(
+                    "INSERT INTO cookies VALUES (?1, ?2, '', ?3, '/', ?4, 1, 1, 1)",
+                    rusqlite::params![host, name, encrypted, expires],
+                )
+                .unwrap();
+        }
+        let cookies = read_chromium_cookies(&chromium, "chatgpt.com", None, now).unwrap();
+        assert_eq!((cookies.cookies.len(), cookies.expired), (1, 1));
+        assert_eq!(cookies.cookies[0].value, "secret");
+        assert_eq!(cookies.cookies[0].same_site.as_deref(), Some("Lax"));
+
+        let firefox = rusqlite::Connection::open_in_memory().unwrap();
+        firefox
+            .execute_batch(
+                "CREATE TABLE moz_cookies (host TEXT, name TEXT, value TEXT, path TEXT,
+                     expiry INTEGER, isSecure INTEGER, isHttpOnly INTEGER, sameSite INTEGER);
+                 INSERT INTO moz_cookies VALUES ('.claude.ai', 'sessionKey', 'sk', '/',
+                     1900000000, 1, 1, 0);
+                 INSERT INTO moz_cookies VALUES ('chatgpt.com', 'x', 'y', '/', 1, 0, 0, 0);",
+            )
+            .unwrap();
+        let cookies = read_firefox_cookies(&firefox, "claude.ai", now).unwrap();
+        assert_eq!(cookies.cookies.len(), 1);
+        assert_eq!(cookies.cookies[0].same_site.as_deref(), Some("None"));
+        assert_eq!(cookies.cookies[0].expires, Some(1_900_000_000));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn copies_include_cookies_still_in_the_wal() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("wal");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cookie_db = dir.join("cookies.sqlite");
+        // Kept open, as a running browser would, so the rows stay in the WAL.
+        let browser = rusqlite::Connection::open(&cookie_db).unwrap();
+        browser
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA wal_autocheckpoint = 0;
+                 CREATE TABLE moz_cookies (name TEXT);
+                 INSERT INTO moz_cookies VALUES ('session');",
+            )
+            .unwrap();
+        assert!(dir.join("cookies.sqlite-wal").is_file());
+
+        let (connection, copy_dir) = super::open_copy(&cookie_db).unwrap();
+        let count: i64 = connection
+            .query_row("SELECT COUNT(*) FROM moz_cookies", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            std::fs::metadata(&copy_dir).unwrap().permissions().mode() & 0o777,
+            0o700
+        );
+
+        drop(connection);
+        let _ = std::fs::remove_dir_all(copy_dir);
+        drop(browser);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
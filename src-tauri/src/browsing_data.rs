@@ -0,0 +1,206 @@
+//! Clears the stored data of one service to get a site out of a broken state.
+//!
+//! Services share one data store, so data is cleared by site. WebKitGTK keys its records by
+//! registrable domain: clearing `gemini.google.com` also clears `google.com` cookies. Other
+//! platforms have no per-site API available here, so the service home is loaded, the page clears
+//! its own storage and the cookies of the service host are deleted one by one; their HTTP disk
+//! cache is left alone.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClearScope {
+    /// Cookies, local and session storage, IndexedDB, caches and service workers.
+    All,
+    CacheOnly,
+}
+
+/// Whether a WebKit website data record or cookie domain named `record_name` belongs to
+/// `host`.
+pub(crate) fn record_matches_host(record_name: &str, host: &str) -> bool {
+    let record_name = record_name.trim_start_matches('.').to_ascii_lowercase();
+    !record_name.is_empty() && (host == record_name || host.ends_with(&format!(".{}", record_name)))
+}
+
+/// Clears what the page can reach from script: Cache Storage and, for [`ClearScope::All`],
+/// web storage, IndexedDB and service worker registrations.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub(crate) fn clear_page_storage_script(scope: ClearScope) -> String {
+    format!(
+        r#"(function() {{
+    var cacheOnly = {cache_only};
+    if (window.caches) {{
+        caches.keys().then(function(keys) {{ keys.forEach(function(key) {{ caches.delete(key); }}); }});
+    }}
+    if (cacheOnly) return;
+    try {{ localStorage.clear(); sessionStorage.clear(); }} catch (_) {{}}
+    if (window.indexedDB && indexedDB.databases) {{
+        indexedDB.databases().then(function(databases) {{
+            databases.forEach(function(database) {{ indexedDB.deleteDatabase(database.name); }});
+        }});
+    }}
+    if (navigator.serviceWorker) {{
+        navigator.serviceWorker.getRegistrations().then(function(registrations) {{
+            registrations.forEach(function(registration) {{ registration.unregister(); }});
+        }});
+    }}
+}})();"#,
+        cache_only = scope == ClearScope::CacheOnly
+    )
+}
+
+/// Removes the data of `host` from the webview's data store; returns how many site records
+/// were removed.
+#[cfg(target_os = "linux")]
+pub(crate) async fn clear_site_data(
+    webview: &tauri::Webview,
+    _home: &tauri::Url,
+    host: &str,
+    scope: ClearScope,
+) -> Result<usize, String> {
+    use webkit2gtk::{
+        gio, WebViewExt, WebsiteData, WebsiteDataManagerExt, WebsiteDataManagerExtManual,
+        WebsiteDataTypes,
+    };
+
+    let cache_types = WebsiteDataTypes::DISK_CACHE
+        | WebsiteDataTypes::MEMORY_CACHE
+        | WebsiteDataTypes::OFFLINE_APPLICATION_CACHE
+        | WebsiteDataTypes::DOM_CACHE;
+    let types = match scope {
+        ClearScope::CacheOnly => cache_types,
+        ClearScope::All => {
+            cache_types
+                | WebsiteDataTypes::COOKIES
+                | WebsiteDataTypes::LOCAL_STORAGE
+                | WebsiteDataTypes::SESSION_STORAGE
+                | WebsiteDataTypes::INDEXEDDB_DATABASES
+                | WebsiteDataTypes::WEBSQL_DATABASES
+                | WebsiteDataTypes::SERVICE_WORKER_REGISTRATIONS
+        }
+    };
+
+    let host = host.to_string();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    webview
+        .with_webview(move |platform_webview| {
+            let Some(manager) = platform_webview.inner().website_data_manager() else {
+                let _ = sender.send(Err("Website data manager unavailable".to_string()));
+                return;
+            };
+            let remove_manager = manager.clone();
+            manager.fetch(types, None::<&gio::Cancellable>, move |result| {
+                let records = match result {
+                    Ok(records) => records,
+                    Err(e) => {
+                        let _ = sender.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                let matching = records
+                    .into_iter()
+                    .filter(|record| {
+                        record
+                            .name()
+                            .is_some_and(|name| record_matches_host(&name, &host))
+                    })
+                    .collect::<Vec<WebsiteData>>();
+                if matching.is_empty() {
+                    let _ = sender.send(Ok(0));
+                    return;
+                }
+                let count = matching.len();
+                let matching = matching.iter().collect::<Vec<_>>();
+                remove_manager.remove(types, &matching, None::<&gio::Cancellable>, move |result| {
+                    let _ = sender.send(result.map(|_| count).map_err(|e| e.to_string()));
+                });
+            });
+        })
+        .map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        receiver.recv_timeout(std::time::Duration::from_secs(30))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|_| "Timed out clearing website data".to_string())?
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) async fn clear_site_data(
+    webview: &tauri::Webview,
+    home: &tauri::Url,
+    host: &str,
+    scope: ClearScope,
+) -> Result<usize, String> {
+    let webview = webview.clone();
+    let home = home.clone();
+    let host = host.to_string();
+    // Cookie access deadlocks WebView2 on the main thread. The page script clears the storage
+    // of whatever origin is loaded, so the service home is loaded first when the user has
+    // navigated elsewhere; the pause gives the script time to finish before the webview is
+    // torn down.
+    tauri::async_runtime::spawn_blocking(move || {
+        let on_host = |webview: &tauri::Webview| {
+            webview.url().is_ok_and(|url| {
+                url.host_str()
+                    .is_some_and(|current| record_matches_host(&host, current))
+            })
+        };
+        if !on_host(&webview) {
+            webview.navigate(home).map_err(|e| e.to_string())?;
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+            while !on_host(&webview) {
+                if std::time::Instant::now() >= deadline {
+                    return Err(format!("Timed out loading {} to clear its data", host));
+                }
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1000));
+        }
+        webview
+            .eval(clear_page_storage_script(scope))
+            .map_err(|e| e.to_string())?;
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+        if scope == ClearScope::CacheOnly {
+            return Ok(0);
+        }
+
+        let cookies = webview
+            .cookies()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|cookie| {
+                cookie
+                    .domain()
+                    .is_some_and(|domain| record_matches_host(domain, &host))
+            })
+            .collect::<Vec<_>>();
+        let count = cookies.len();
+        for cookie in cookies {
+            webview.delete_cookie(cookie).map_err(|e| e.to_string())?;
+        }
+        Ok(count)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_page_storage_script, record_matches_host, ClearScope};
+
+    #[test]
+    fn records_match_the_host_and_its_parent_domains() {
+        assert!(record_matches_host("chatgpt.com", "chatgpt.com"));
+        assert!(record_matches_host("google.com", "gemini.google.com"));
+        assert!(record_matches_host(".Google.com", "gemini.google.com"));
+        assert!(!record_matches_host("gle.com", "gemini.google.com"));
+        assert!(!record_matches_host("claude.ai", "chatgpt.com"));
+        assert!(!record_matches_host("", "chatgpt.com"));
+    }
+
+    #[test]
+    fn cache_only_script_keeps_storage() {
+        assert!(clear_page_storage_script(ClearScope::CacheOnly).contains("var cacheOnly = true;"));
+        assert!(clear_page_storage_script(ClearScope::All).contains("var cacheOnly = false;"));
+    }
+}
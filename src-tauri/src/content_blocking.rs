@@ -0,0 +1,701 @@
+//! Offline content blocking for service webviews.
+//!
+//! Rule lists are read from `content_filters/` in the app data directory: EasyList-style `.txt`
+//! files (host blocks, exceptions and element hiding rules) and a simplified `.json` format.
+//! Element hiding works everywhere through injected CSS; request blocking needs the webview to
+//! support interception and is currently implemented with WebKitGTK content filters only.
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+pub(crate) const CONTENT_FILTERS_DIR: &str = "content_filters";
+const GENERIC_STYLE_ID: &str = "anychat-content-blocking";
+const HOST_STYLE_ID: &str = "anychat-content-blocking-host";
+const STATE_KEY: &str = "__anychatContentBlocking";
+
+/// WebKit refuses to compile content blockers beyond this many rules.
+const MAX_NETWORK_RULES: usize = 50_000;
+
+/// Resource types that may be cancelled. Top-level documents are left alone so a rule can never
+/// blank out the service itself.
+const BLOCKED_RESOURCE_TYPES: [&str; 8] = [
+    "image",
+    "style-sheet",
+    "script",
+    "font",
+    "raw",
+    "svg-document",
+    "media",
+    "popup",
+];
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JsonRuleList {
+    blocked_hosts: Vec<String>,
+    allowed_hosts: Vec<String>,
+    hidden_selectors: Vec<String>,
+    host_selectors: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContentFilterStats {
+    pub sources: Vec<String>,
+    pub blocked_hosts: usize,
+    pub hidden_selectors: usize,
+    pub unsupported_rules: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ContentFilters {
+    blocked_hosts: BTreeSet<String>,
+    allowed_hosts: BTreeSet<String>,
+    generic_selectors: BTreeSet<String>,
+    host_selectors: BTreeMap<String, BTreeSet<String>>,
+    /// Element hiding exceptions keyed by host; the empty key applies everywhere.
+    selector_exceptions: BTreeMap<String, BTreeSet<String>>,
+    stats: ContentFilterStats,
+}
+
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch == '-');
+    valid.then_some(host)
+}
+
+/// `host` followed by each parent domain: `a.b.com`, `b.com`, `com`.
+fn host_and_parents(host: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(host), |host| {
+        host.split_once('.').map(|(_, parent)| parent)
+    })
+}
+
+/// Parses `||host^` with no options or only `$third-party`; anything narrower is skipped
+/// instead of being widened into a full host block.
+fn parse_host_rule(rule: &str) -> Option<String> {
+    let rule = rule.strip_prefix("||")?;
+    let (pattern, options) = rule.split_once('$').unwrap_or((rule, ""));
+    if !options.is_empty() && options != "third-party" {
+        return None;
+    }
+
+    normalize_host(pattern.strip_suffix('^').unwrap_or(pattern))
+}
+
+fn is_supported_selector(selector: &str) -> bool {
+    !selector.is_empty()
+        && !selector.starts_with("+js(")
+        && !selector.contains(":-abp-")
+        && !selector.contains(":has-text(")
+        && !selector.contains('{')
+        && !selector.contains('}')
+}
+
+impl ContentFilters {
+    pub fn stats(&self) -> &ContentFilterStats {
+        &self.stats
+    }
+
+    fn add_cosmetic_rule(&mut self, domains: &str, selector: &str, exception: bool) -> bool {
+        let selector = selector.trim();
+        if !is_supported_selector(selector) {
+            return false;
+        }
+
+        let mut hosts = Vec::new();
+        for domain in domains.split(',').filter(|domain| !domain.is_empty()) {
+            // Negated domains would need per-host exceptions to generic rules; not supported.
+            if domain.starts_with('~') {
+                return false;
+            }
+            match normalize_host(domain) {
+                Some(host) => hosts.push(host),
+                None => return false,
+            }
+        }
+
+        let selector = selector.to_string();
+        match (exception, hosts.is_empty()) {
+            (false, true) => {
+                self.generic_selectors.insert(selector);
+            }
+            (false, false) => {
+                for host in hosts {
+                    self.host_selectors
+                        .entry(host)
+                        .or_default()
+                        .insert(selector.clone());
+                }
+            }
+            (true, true) => {
+                self.selector_exceptions
+                    .entry(String::new())
+                    .or_default()
+                    .insert(selector);
+            }
+            (true, false) => {
+                for host in hosts {
+                    self.selector_exceptions
+                        .entry(host)
+                        .or_default()
+                        .insert(selector.clone());
+                }
+            }
+        }
+        true
+    }
+
+    pub fn add_easylist(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+                continue;
+            }
+
+            let supported = if line.contains("#?#") || line.contains("#$#") {
+                false
+            } else if let Some((domains, selector)) = line.split_once("#@#") {
+                self.add_cosmetic_rule(domains, selector, true)
+            } else if let Some((domains, selector)) = line.split_once("##") {
+                self.add_cosmetic_rule(domains, selector, false)
+            } else if let Some(rule) = line.strip_prefix("@@") {
+                parse_host_rule(rule)
+                    .map(|host| self.allowed_hosts.insert(host))
+                    .is_some()
+            } else {
+                parse_host_rule(line)
+                    .map(|host| self.blocked_hosts.insert(host))
+                    .is_some()
+            };
+
+            if !supported {
+                self.stats.unsupported_rules += 1;
+            }
+        }
+    }
+
+    pub fn add_json(&mut self, text: &str) -> Result<(), String> {
+        let rules: JsonRuleList = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+        for host in rules.blocked_hosts {
+            match normalize_host(&host) {
+                Some(host) => {
+                    self.blocked_hosts.insert(host);
+                }
+                None => self.stats.unsupported_rules += 1,
+            }
+        }
+        self.allowed_hosts.extend(
+            rules
+                .allowed_hosts
+                .iter()
+                .filter_map(|host| normalize_host(host)),
+        );
+        for selector in rules.hidden_selectors {
+            if !self.add_cosmetic_rule("", &selector, false) {
+                self.stats.unsupported_rules += 1;
+            }
+        }
+        for (host, selectors) in rules.host_selectors {
+            for selector in selectors {
+                if !self.add_cosmetic_rule(&host, &selector, false) {
+                    self.stats.unsupported_rules += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether requests to `host` (or any of its parent domains) should be cancelled.
+    pub fn is_blocked_host(&self, host: &str) -> bool {
+        let Some(host) = normalize_host(host) else {
+            return false;
+        };
+
+        !host_and_parents(&host).any(|host| self.allowed_hosts.contains(host))
+            && host_and_parents(&host).any(|host| self.blocked_hosts.contains(host))
+    }
+
+    fn is_excepted(&self, host: &str, selector: &str) -> bool {
+        std::iter::once("")
+            .chain(host_and_parents(host))
+            .any(|host| {
+                self.selector_exceptions
+                    .get(host)
+                    .is_some_and(|selectors| selectors.contains(selector))
+            })
+    }
+
+    pub fn generic_selectors(&self, host: &str) -> Vec<String> {
+        let host = normalize_host(host).unwrap_or_default();
+        self.generic_selectors
+            .iter()
+            .filter(|selector| !self.is_excepted(&host, selector))
+            .cloned()
+            .collect()
+    }
+
+    pub fn host_selectors(&self, host: &str) -> Vec<String> {
+        let Some(host) = normalize_host(host) else {
+            return Vec::new();
+        };
+
+        host_and_parents(&host)
+            .filter_map(|host| self.host_selectors.get(host))
+            .flatten()
+            .filter(|selector| !self.is_excepted(&host, selector))
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn effective_blocked_hosts(&self) -> Vec<&str> {
+        self.blocked_hosts
+            .iter()
+            .map(String::as_str)
+            .filter(|host| !host_and_parents(host).any(|host| self.allowed_hosts.contains(host)))
+            .collect()
+    }
+
+    /// Rules in WebKit's content blocker JSON format.
+    pub fn network_rules_json(&self) -> String {
+        let rules = self
+            .effective_blocked_hosts()
+            .into_iter()
+            .take(MAX_NETWORK_RULES)
+            .map(|host| {
+                serde_json::json!({
+                    "trigger": {
+                        "url-filter": format!(
+                            "^[^:]+://+([^:/]+\\.)?{}[:/]",
+                            host.replace('.', "\\.")
+                        ),
+                        "resource-type": BLOCKED_RESOURCE_TYPES,
+                    },
+                    "action": { "type": "block" },
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::Value::Array(rules).to_string()
+    }
+
+    /// Stable name for the compiled form of the current network rules.
+    pub fn network_rules_identifier(&self) -> String {
+        let digest = Sha256::digest(self.network_rules_json().as_bytes());
+        format!(
+            "anychat-{}",
+            digest
+                .iter()
+                .take(8)
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        )
+    }
+}
+
+/// Loads every rule list in `dir`. A missing directory means no rules.
+pub(crate) fn load_filters(dir: &Path) -> Result<ContentFilters, String> {
+    let mut filters = ContentFilters::default();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(filters),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if extension != "txt" && extension != "json" {
+            continue;
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if extension == "json" {
+            filters
+                .add_json(&text)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        } else {
+            filters.add_easylist(&text);
+        }
+
+        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            filters.stats.sources.push(name.to_string());
+        }
+    }
+
+    filters.stats.blocked_hosts = filters.effective_blocked_hosts().len();
+    filters.stats.hidden_selectors = filters.generic_selectors.len()
+        + filters
+            .host_selectors
+            .values()
+            .map(BTreeSet::len)
+            .sum::<usize>();
+    Ok(filters)
+}
+
+/// One rule per selector, so a selector the engine does not understand only drops itself.
+pub(crate) fn cosmetic_css(selectors: &[String]) -> String {
+    selectors
+        .iter()
+        .map(|selector| format!("{} {{ display: none !important; }}", selector))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+fn upsert_style(id: &str, css: &str) -> String {
+    format!(
+        "var style = document.getElementById({id}) || document.createElement('style'); \
+         style.id = {id}; style.textContent = {css}; \
+         (document.head || document.documentElement).appendChild(style);",
+        id = js_string(id),
+        css = js_string(css),
+    )
+}
+
+fn remove_style(id: &str) -> String {
+    format!(
+        "var stale = document.getElementById({}); if (stale) stale.remove();",
+        js_string(id)
+    )
+}
+
+/// Injected at creation when blocking is on. A later toggle in the same tab is remembered in
+/// `sessionStorage`, which this script honours on subsequent navigations.
+pub(crate) fn initialization_script(css: &str) -> String {
+    format!(
+        "(function() {{\n\
+         try {{ if (sessionStorage.getItem({key}) === 'off') return; }} catch (_) {{}}\n\
+         var apply = function() {{ {upsert} }};\n\
+         if (document.documentElement) apply(); else document.addEventListener('DOMContentLoaded', apply, {{ once: true }});\n\
+         }})();",
+        key = js_string(STATE_KEY),
+        upsert = upsert_style(GENERIC_STYLE_ID, css),
+    )
+}
+
+/// Brings a loaded page in line with the service's setting. `generic_css` is only needed when
+/// the webview was created without the initialization script.
+pub(crate) fn sync_script(enabled: bool, generic_css: Option<&str>, host_css: &str) -> String {
+    if !enabled {
+        return format!(
+            "(function() {{ try {{ sessionStorage.setItem({key}, 'off'); }} catch (_) {{}} {generic} {host} }})();",
+            key = js_string(STATE_KEY),
+            generic = remove_style(GENERIC_STYLE_ID),
+            host = remove_style(HOST_STYLE_ID),
+        );
+    }
+
+    let generic = generic_css
+        .map(|css| {
+            format!(
+                "(function() {{ {} }})();",
+                upsert_style(GENERIC_STYLE_ID, css)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        "(function() {{ try {{ sessionStorage.setItem({key}, 'on'); }} catch (_) {{}} }})(); {generic} (function() {{ {host} }})();",
+        key = js_string(STATE_KEY),
+        generic = generic,
+        host = upsert_style(HOST_STYLE_ID, host_css),
+    )
+}
+
+#[cfg(target_os = "linux")]
+mod network {
+    use super::ContentFilters;
+    use std::cell::RefCell;
+    use std::ffi::CString;
+    use std::path::{Path, PathBuf};
+    use webkit2gtk::glib::translate::{from_glib_full, ToGlibPtr};
+    use webkit2gtk::{ffi, gio, glib, UserContentManager, UserContentManagerExt};
+
+    struct CompiledFilter {
+        identifier: String,
+        filter: Option<*mut ffi::WebKitUserContentFilter>,
+        waiting: Vec<UserContentManager>,
+    }
+
+    thread_local! {
+        // GTK objects live on the main thread, which is where `with_webview` runs.
+        static STORE: RefCell<Option<(PathBuf, *mut ffi::WebKitUserContentFilterStore)>> =
+            const { RefCell::new(None) };
+        static NETWORK_FILTER: RefCell<Option<CompiledFilter>> = const { RefCell::new(None) };
+    }
+
+    fn add_filter(manager: &UserContentManager, filter: *mut ffi::WebKitUserContentFilter) {
+        unsafe {
+            ffi::webkit_user_content_manager_add_filter(manager.to_glib_none().0, filter);
+        }
+    }
+
+    fn store(store_dir: &Path) -> Option<*mut ffi::WebKitUserContentFilterStore> {
+        STORE.with(|store| {
+            let mut store = store.borrow_mut();
+            if let Some((path, pointer)) = store.as_ref() {
+                if path == store_dir {
+                    return Some(*pointer);
+                }
+            }
+
+            let path = CString::new(store_dir.to_string_lossy().as_bytes()).ok()?;
+            let pointer = unsafe { ffi::webkit_user_content_filter_store_new(path.as_ptr()) };
+            *store = Some((store_dir.to_path_buf(), pointer));
+            Some(pointer)
+        })
+    }
+
+    unsafe extern "C" fn filter_saved(
+        store: *mut glib::gobject_ffi::GObject,
+        result: *mut gio::ffi::GAsyncResult,
+        user_data: glib::ffi::gpointer,
+    ) {
+        let identifier = Box::from_raw(user_data as *mut String);
+        let mut error = std::ptr::null_mut();
+        let filter = ffi::webkit_user_content_filter_store_save_finish(
+            store as *mut ffi::WebKitUserContentFilterStore,
+            result,
+            &mut error,
+        );
+
+        NETWORK_FILTER.with(|state| {
+            let mut state = state.borrow_mut();
+            let current = state
+                .as_mut()
+                .filter(|compiled| compiled.identifier == *identifier);
+
+            if filter.is_null() {
+                let error: glib::Error = from_glib_full(error);
+                println!(
+                    "[AnyChat] Failed to compile content filter {}: {}",
+                    identifier, error
+                );
+                if current.is_some() {
+                    *state = None;
+                }
+                return;
+            }
+
+            match current {
+                Some(compiled) => {
+                    for manager in compiled.waiting.drain(..) {
+                        add_filter(&manager, filter);
+                    }
+                    compiled.filter = Some(filter);
+                }
+                None => ffi::webkit_user_content_filter_unref(filter),
+            }
+        });
+    }
+
+    /// Replaces whatever network filter the webview had. Compilation happens once per rule set
+    /// and is shared by every webview; webviews asking meanwhile get the filter when it is ready.
+    pub(crate) fn apply(
+        manager: &UserContentManager,
+        filters: &ContentFilters,
+        store_dir: &Path,
+        enabled: bool,
+    ) {
+        manager.remove_all_filters();
+        if !enabled || filters.blocked_hosts.is_empty() {
+            return;
+        }
+
+        let identifier = filters.network_rules_identifier();
+        let ready = NETWORK_FILTER.with(|state| {
+            let mut state = state.borrow_mut();
+            match state.as_mut() {
+                Some(compiled) if compiled.identifier == identifier => {
+                    match compiled.filter {
+                        Some(filter) => add_filter(manager, filter),
+                        None => compiled.waiting.push(manager.clone()),
+                    }
+                    true
+                }
+                _ => {
+                    if let Some(CompiledFilter {
+                        filter: Some(filter),
+                        ..
+                    }) = state.take()
+                    {
+                        unsafe { ffi::webkit_user_content_filter_unref(filter) };
+                    }
+                    *state = Some(CompiledFilter {
+                        identifier: identifier.clone(),
+                        filter: None,
+                        waiting: vec![manager.clone()],
+                    });
+                    false
+                }
+            }
+        });
+        if ready {
+            return;
+        }
+
+        let (Some(store), Ok(c_identifier)) = (store(store_dir), CString::new(identifier.clone()))
+        else {
+            return;
+        };
+        let source = glib::Bytes::from_owned(filters.network_rules_json().into_bytes());
+        println!("[AnyChat] Compiling content filter {}", identifier);
+        unsafe {
+            ffi::webkit_user_content_filter_store_save(
+                store,
+                c_identifier.as_ptr(),
+                source.to_glib_none().0,
+                std::ptr::null_mut(),
+                Some(filter_saved),
+                Box::into_raw(Box::new(identifier)) as glib::ffi::gpointer,
+            );
+        }
+    }
+}
+
+/// Cancels requests to blocked hosts inside `webview`, or lifts the block when `enabled` is
+/// false. Only WebKitGTK exposes request interception here; elsewhere this is a no-op and only
+/// element hiding applies.
+#[cfg(target_os = "linux")]
+pub(crate) fn apply_network_filter(
+    webview: &tauri::Webview,
+    filters: std::sync::Arc<ContentFilters>,
+    store_dir: std::path::PathBuf,
+    enabled: bool,
+) {
+    use webkit2gtk::WebViewExt;
+
+    let _ = webview.with_webview(move |platform_webview| {
+        if let Some(manager) = platform_webview.inner().user_content_manager() {
+            network::apply(&manager, &filters, &store_dir, enabled);
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn apply_network_filter(
+    _webview: &tauri::Webview,
+    _filters: std::sync::Arc<ContentFilters>,
+    _store_dir: std::path::PathBuf,
+    _enabled: bool,
+) {
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cosmetic_css, load_filters, ContentFilters};
+
+    const EASYLIST: &str = "\
+[Adblock Plus 2.0]
+! Title: test list
+||doubleclick.net^
+||ads.example.com^$third-party
+||tracker.example^$script,domain=chatgpt.com
+@@||safe.doubleclick.net^
+##.ad-banner
+chatgpt.com##.upsell-modal
+~gemini.google.com##.nope
+example.org#@#.ad-banner
+example.org#?#div:-abp-has(.ad)
+";
+
+    fn filters() -> ContentFilters {
+        let mut filters = ContentFilters::default();
+        filters.add_easylist(EASYLIST);
+        filters
+    }
+
+    #[test]
+    fn host_rules_block_subdomains_and_respect_exceptions() {
+        let filters = filters();
+        assert!(filters.is_blocked_host("doubleclick.net"));
+        assert!(filters.is_blocked_host("stats.g.doubleclick.net"));
+        assert!(filters.is_blocked_host("ads.example.com"));
+        assert!(!filters.is_blocked_host("safe.doubleclick.net"));
+        assert!(!filters.is_blocked_host("example.com"));
+        assert!(!filters.is_blocked_host("notdoubleclick.net"));
+        // Options narrower than a whole host are skipped rather than over-blocking.
+        assert!(!filters.is_blocked_host("tracker.example"));
+        assert_eq!(filters.stats().unsupported_rules, 3);
+    }
+
+    #[test]
+    fn element_hiding_applies_per_host_with_exceptions() {
+        let filters = filters();
+        assert_eq!(filters.generic_selectors("chatgpt.com"), vec![".ad-banner"]);
+        assert_eq!(filters.host_selectors("chatgpt.com"), vec![".upsell-modal"]);
+        assert!(filters.host_selectors("gemini.google.com").is_empty());
+        assert!(filters.generic_selectors("www.example.org").is_empty());
+        assert_eq!(
+            cosmetic_css(&filters.host_selectors("chatgpt.com")),
+            ".upsell-modal { display: none !important; }"
+        );
+    }
+
+    #[test]
+    fn json_rule_lists_merge_with_easylist_files() {
+        let dir = std::env::temp_dir().join(format!("anychat-filters-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "||doubleclick.net^\n").unwrap();
+        std::fs::write(
+            dir.join("b.json"),
+            r##"{"blockedHosts":["Telemetry.Example.NET"],"hostSelectors":{"claude.ai":["#promo"]}}"##,
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.md"), "ignored").unwrap();
+
+        let filters = load_filters(&dir).unwrap();
+        assert!(filters.is_blocked_host("telemetry.example.net"));
+        assert!(filters.is_blocked_host("doubleclick.net"));
+        assert_eq!(filters.host_selectors("claude.ai"), vec!["#promo"]);
+        assert_eq!(filters.stats().sources, vec!["a.txt", "b.json"]);
+        assert_eq!(filters.stats().blocked_hosts, 2);
+
+        assert_eq!(
+            load_filters(&dir.join("missing")).unwrap(),
+            ContentFilters::default()
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn network_rules_compile_to_webkit_content_blocker_json() {
+        let filters = filters();
+        let rules: serde_json::Value = serde_json::from_str(&filters.network_rules_json()).unwrap();
+        let rules = rules.as_array().unwrap();
+
+        assert_eq!(rules.len(), 2);
+        let url_filter = rules[0]["trigger"]["url-filter"].as_str().unwrap();
+        let regex = regex::Regex::new(url_filter).unwrap();
+        assert!(regex.is_match("https://ads.example.com/pixel.gif"));
+        assert!(regex.is_match("https://cdn.ads.example.com:443/x.js"));
+        assert!(!regex.is_match("https://example.com/ads.example.com/"));
+        assert_eq!(rules[0]["action"]["type"], "block");
+        assert!(!rules[0]["trigger"]["resource-type"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("document")));
+
+        assert!(filters.network_rules_identifier().starts_with("anychat-"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Password-protected cookie backups, so service logins survive a reinstall or a move to
+//! another machine.
+//!
+//! A backup holds the cookies of each selected service's site, sealed with a key derived from
+//! the password. Expired cookies are dropped on export and skipped on import; the rest are
+//! restored with their remaining lifetime.
+
+use crate::secrets::{self, KdfParams, PasswordSealed};
+
+const BACKUP_FORMAT: &str = "anychat-cookies";
+const BACKUP_VERSION: u32 = 1;
+const MIN_PASSWORD_LEN: usize = 8;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupFile {
+    format: String,
+    version: u32,
+    #[serde(flatten)]
+    sealed: PasswordSealed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<String>,
+    /// Unix seconds; `None` for session cookies.
+    pub expires: Option<i64>,
+}
+
+impl StoredCookie {
+    pub fn from_cookie(cookie: &tauri::webview::Cookie<'_>) -> Self {
+        StoredCookie {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain: cookie.domain().map(str::to_string),
+            path: cookie.path().map(str::to_string),
+            secure: cookie.secure().unwrap_or(false),
+            http_only: cookie.http_only().unwrap_or(false),
+            same_site: cookie.same_site().map(|same_site| same_site.to_string()),
+            expires: cookie
+                .expires_datetime()
+                .map(|expires| expires.unix_timestamp()),
+        }
+    }
+
+    pub fn is_expired(&self, now_secs: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now_secs)
+    }
+
+    /// `Set-Cookie` form with the remaining lifetime as `Max-Age`, which every webview backend
+    /// applies.
+    pub fn to_set_cookie(&self, now_secs: i64) -> String {
+        let mut header = format!("{}={}", self.name, self.value);
+        if let Some(domain) = &self.domain {
+            header.push_str(&format!("; Domain={}", domain));
+        }
+        header.push_str(&format!("; Path={}", self.path.as_deref().unwrap_or("/")));
+        if self.secure {
+            header.push_str("; Secure");
+        }
+        if self.http_only {
+            header.push_str("; HttpOnly");
+        }
+        if let Some(same_site) = &self.same_site {
+            header.push_str(&format!("; SameSite={}", same_site));
+        }
+        if let Some(expires) = self.expires {
+            header.push_str(&format!("; Max-Age={}", (expires - now_secs).max(0)));
+        }
+        header
+    }
+
+    pub fn to_cookie(&self, now_secs: i64) -> Result<tauri::webview::Cookie<'static>, String> {
+        tauri::webview::Cookie::parse(self.to_set_cookie(now_secs))
+            .map_err(|e| format!("Invalid cookie {}: {}", self.name, e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServiceCookies {
+    pub service_id: String,
+    pub name: String,
+    pub url: String,
+    pub cookies: Vec<StoredCookie>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CookieBackup {
+    /// Unix time in milliseconds.
+    pub created_at: u64,
+    pub services: Vec<ServiceCookies>,
+}
+
+/// Per-service counts reported after an export or import.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServiceCookieSummary {
+    pub service_id: String,
+    pub name: String,
+    pub cookies: usize,
+    /// Cookies skipped because they had expired.
+    pub expired: usize,
+}
+
+/// A cookie belongs to a service when its domain is the service host, a parent domain of it
+/// (shared sign-in cookies) or one of its subdomains.
+pub(crate) fn cookie_belongs_to_host(domain: &str, host: &str) -> bool {
+    let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+    !domain.is_empty()
+        && (domain == host
+            || host.ends_with(&format!(".{}", domain))
+            || domain.ends_with(&format!(".{}", host)))
+}
+
+pub(crate) fn service_cookies(
+    service: &crate::ServiceHostPayload,
+    host: &str,
+    cookies: &[tauri::webview::Cookie<'static>],
+    now_secs: i64,
+) -> (ServiceCookies, ServiceCookieSummary) {
+    let (cookies, expired): (Vec<_>, Vec<_>) = cookies
+        .iter()
+        .filter(|cookie| {
+            cookie
+                .domain()
+                .is_some_and(|domain| cookie_belongs_to_host(domain, host))
+        })
+        .map(StoredCookie::from_cookie)
+        .partition(|cookie| !cookie.is_expired(now_secs));
+
+    let summary = ServiceCookieSummary {
+        service_id: service.id.clone(),
+        name: service.name.clone(),
+        cookies: cookies.len(),
+        expired: expired.len(),
+    };
+    let service_cookies = ServiceCookies {
+        service_id: service.id.clone(),
+        name: service.name.clone(),
+        url: service.url.clone(),
+        cookies,
+    };
+    (service_cookies, summary)
+}
+
+pub(crate) fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "The backup password needs at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn encrypt_backup(
+    backup: &CookieBackup,
+    password: &str,
+    params: KdfParams,
+) -> Result<Vec<u8>, String> {
+    validate_password(password)?;
+    let plaintext = serde_json::to_vec(backup).map_err(|e| e.to_string())?;
+    let file = BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        sealed: secrets::seal_with_password(password, &plaintext, params)?,
+    };
+    serde_json::to_vec_pretty(&file).map_err(|e| e.to_string())
+}
+
+pub(crate) fn decrypt_backup(bytes: &[u8], password: &str) -> Result<CookieBackup, String> {
+    let file: BackupFile = serde_json::from_slice(bytes)
+        .map_err(|e| format!("Not an AnyChat cookie backup: {}", e))?;
+    if file.format != BACKUP_FORMAT {
+        return Err("Not an AnyChat cookie backup".to_string());
+    }
+    if file.version > BACKUP_VERSION {
+        return Err(format!(
+            "Cookie backup version {} is newer than this AnyChat supports",
+            file.version
+        ));
+    }
+    let plaintext = secrets::open_with_password(password, &file.sealed)?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Invalid cookie backup: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cookie_belongs_to_host, decrypt_backup, encrypt_backup, service_cookies, CookieBackup,
+        StoredCookie,
+    };
+    use crate::secrets::KdfParams;
+
+    const NOW: i64 = 1_800_000_000;
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn service() -> crate::ServiceHostPayload {
+        serde_json::from_value(serde_json::json!({
+            "id": "chatgpt",
+            "name": "ChatGPT",
+            "url": "https://chatgpt.com/",
+            "enabled": true,
+        }))
+        .unwrap()
+    }
+
+    fn cookie(header: &str) -> tauri::webview::Cookie<'static> {
+        tauri::webview::Cookie::parse(header.to_string()).unwrap()
+    }
+
+    #[test]
+    fn cookies_are_matched_by_site() {
+        assert!(cookie_belongs_to_host(".chatgpt.com", "chatgpt.com"));
+        assert!(cookie_belongs_to_host("auth.chatgpt.com", "chatgpt.com"));
+        assert!(cookie_belongs_to_host("google.com", "gemini.google.com"));
+        assert!(!cookie_belongs_to_host("notchatgpt.com", "chatgpt.com"));
+        assert!(!cookie_belongs_to_host("claude.ai", "chatgpt.com"));
+    }
+
+    #[test]
+    fn export_keeps_live_cookies_of_the_service_only() {
+        let cookies = vec![
+            cookie("session=abc; Domain=.chatgpt.com; Path=/; Secure; HttpOnly; SameSite=Lax"),
+            cookie("old=1; Domain=chatgpt.com; Expires=Thu, 01 Jan 2015 00:00:00 GMT"),
+            cookie("other=1; Domain=claude.ai"),
+        ];
+        let (service_cookies, summary) = service_cookies(&service(), "chatgpt.com", &cookies, NOW);
+        assert_eq!((summary.cookies, summary.expired), (1, 1));
+        assert_eq!(
+            service_cookies.cookies[0],
+            StoredCookie {
+                name: "session".to_string(),
+                value: "abc".to_string(),
+                domain: Some("chatgpt.com".to_string()),
+                path: Some("/".to_string()),
+                secure: true,
+                http_only: true,
+                same_site: Some("Lax".to_string()),
+                expires: None,
+            }
+        );
+    }
+
+    #[test]
+    fn restored_cookies_keep_their_remaining_lifetime() {
+        let stored = StoredCookie {
+            name: "session".to_string(),
+            value: "abc".to_string(),
+            domain: Some("chatgpt.com".to_string()),
+            path: None,
+            secure: true,
+            http_only: false,
+            same_site: None,
+            expires: Some(NOW + 3600),
+        };
+        assert_eq!(
+            stored.to_set_cookie(NOW),
+            "session=abc; Domain=chatgpt.com; Path=/; Secure; Max-Age=3600"
+        );
+        let cookie = stored.to_cookie(NOW).unwrap();
+        assert_eq!(cookie.max_age().map(|age| age.whole_seconds()), Some(3600));
+        assert!(!stored.is_expired(NOW));
+        assert!(stored.is_expired(NOW + 3600));
+    }
+
+    #[test]
+    fn backups_round_trip_only_with_the_password() {
+        let (service_cookies, _) = service_cookies(
+            &service(),
+            "chatgpt.com",
+            &[cookie("session=abc; Domain=chatgpt.com")],
+            NOW,
+        );
+        let backup = CookieBackup {
+            created_at: 1,
+            services: vec![service_cookies],
+        };
+        assert!(encrypt_backup(&backup, "short", TEST_KDF).is_err());
+
+        let bytes = encrypt_backup(&backup, "long enough password", TEST_KDF).unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("abc"));
+        assert_eq!(
+            decrypt_backup(&bytes, "long enough password").unwrap(),
+            backup
+        );
+        assert!(decrypt_backup(&bytes, "another password").is_err());
+        assert!(decrypt_backup(b"{}", "long enough password").is_err());
+    }
+}
@@ -0,0 +1,436 @@
+//! Optional encryption at rest for AnyChat's own data files: the service registry, user
+//! scripts, archived conversations and the API key store.
+//!
+//! Files are sealed with XChaCha20-Poly1305 under a random data key. The data key is stored
+//! twice in `encryption.json`, wrapped under a key derived from the user's passphrase (or a
+//! random key kept in the Secret Service keyring on Linux) and under a recovery key the user
+//! exports. `settings.json` stays readable because it is needed before the data is unlocked.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::path::{Path, PathBuf};
+
+use crate::secrets::{self, KdfParams};
+
+pub(crate) const ENCRYPTION_FILE: &str = "encryption.json";
+pub(crate) const DATA_ENCRYPTION_EVENT: &str = "app://data-encryption";
+const ENCRYPTION_VERSION: u32 = 1;
+const SEALED_FILE_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 8;
+const SALT_LEN: usize = 16;
+const MIGRATION_MARKER: &[u8] = b"anychat-pending-migration";
+#[cfg(target_os = "linux")]
+const KEYRING_LABEL: &str = "AnyChat data encryption key";
+#[cfg(target_os = "linux")]
+const KEYRING_ATTRIBUTES: [(&str, &str); 2] =
+    [("application", "anychat"), ("purpose", "data-encryption")];
+
+/// Where the key that unwraps the data key comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum KeySourceKind {
+    Passphrase,
+    Keyring,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum KeySource {
+    Passphrase { kdf: KdfParams, salt: String },
+    Keyring,
+}
+
+impl KeySource {
+    pub fn kind(&self) -> KeySourceKind {
+        match self {
+            KeySource::Passphrase { .. } => KeySourceKind::Passphrase,
+            KeySource::Keyring => KeySourceKind::Keyring,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EncryptionConfig {
+    pub version: u32,
+    pub key_source: KeySource,
+    /// Data key sealed under the key from `key_source`, base64.
+    pub wrapped_key: String,
+    /// Data key sealed under the recovery key, base64.
+    pub recovery_wrapped_key: String,
+    /// Set while the files written before encryption was turned on are still being sealed.
+    /// Sealed under the data key, so it cannot be forged to get a plaintext file accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_migration: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DataState {
+    Disabled,
+    Locked,
+    Unlocked,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataEncryptionStatus {
+    pub state: DataState,
+    pub key_source: Option<KeySourceKind>,
+    pub error: Option<String>,
+}
+
+impl DataEncryptionStatus {
+    pub fn new(vault: &crate::storage::Vault, config: Option<&EncryptionConfig>) -> Self {
+        use crate::storage::Vault;
+
+        let (state, error) = match vault {
+            Vault::Disabled => (DataState::Disabled, None),
+            Vault::Locked => (DataState::Locked, None),
+            Vault::Unlocked(_) => (DataState::Unlocked, None),
+            Vault::Failed(error) => (DataState::Failed, Some(error.clone())),
+        };
+        DataEncryptionStatus {
+            state,
+            key_source: config.map(|config| config.key_source.kind()),
+            error,
+        }
+    }
+}
+
+/// On-disk form of an encrypted data file.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedFile {
+    anychat_encrypted: u32,
+    data: String,
+}
+
+pub(crate) fn seal_file(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let file = SealedFile {
+        anychat_encrypted: SEALED_FILE_VERSION,
+        data: BASE64.encode(secrets::seal(key, plaintext)?),
+    };
+    serde_json::to_vec(&file).map_err(|e| e.to_string())
+}
+
+/// The sealed bytes of an encrypted data file; `None` for a plaintext one.
+pub(crate) fn sealed_payload(bytes: &[u8]) -> Option<Vec<u8>> {
+    let file = serde_json::from_slice::<SealedFile>(bytes).ok()?;
+    BASE64.decode(file.data).ok()
+}
+
+pub(crate) fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "The passphrase needs at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// A passphrase key source with a fresh salt, and the key it yields.
+pub(crate) fn new_passphrase_source(
+    passphrase: &str,
+    kdf: KdfParams,
+) -> Result<(KeySource, [u8; 32]), String> {
+    validate_passphrase(passphrase)?;
+    let salt = secrets::random_bytes::<SALT_LEN>();
+    let key = secrets::derive_key(passphrase, &salt, kdf)?;
+    let source = KeySource::Passphrase {
+        kdf,
+        salt: BASE64.encode(salt),
+    };
+    Ok((source, key))
+}
+
+pub(crate) fn passphrase_key(
+    config: &EncryptionConfig,
+    passphrase: &str,
+) -> Result<[u8; 32], String> {
+    let KeySource::Passphrase { kdf, salt } = &config.key_source else {
+        return Err("Data is not protected by a passphrase".to_string());
+    };
+    let salt = BASE64.decode(salt).map_err(|e| e.to_string())?;
+    secrets::derive_key(passphrase, &salt, *kdf)
+}
+
+pub(crate) fn new_config(
+    key_source: KeySource,
+    source_key: &[u8; 32],
+    data_key: &[u8; 32],
+    recovery_key: &[u8; 32],
+) -> Result<EncryptionConfig, String> {
+    Ok(EncryptionConfig {
+        version: ENCRYPTION_VERSION,
+        key_source,
+        wrapped_key: BASE64.encode(secrets::seal(source_key, data_key)?),
+        recovery_wrapped_key: BASE64.encode(secrets::seal(recovery_key, data_key)?),
+        pending_migration: None,
+    })
+}
+
+/// Marks the plaintext files as still to be sealed on the next open.
+pub(crate) fn begin_migration(
+    config: &mut EncryptionConfig,
+    data_key: &[u8; 32],
+) -> Result<(), String> {
+    config.pending_migration = Some(BASE64.encode(secrets::seal(data_key, MIGRATION_MARKER)?));
+    Ok(())
+}
+
+/// Whether plaintext files may still be sealed, i.e. the migration marker opens under the key.
+pub(crate) fn migration_pending(config: &EncryptionConfig, data_key: &[u8; 32]) -> bool {
+    config
+        .pending_migration
+        .as_deref()
+        .and_then(|marker| BASE64.decode(marker).ok())
+        .and_then(|sealed| secrets::open(data_key, &sealed).ok())
+        .is_some_and(|marker| marker == MIGRATION_MARKER)
+}
+
+pub(crate) fn unwrap_key(wrapping_key: &[u8; 32], wrapped: &str) -> Result<[u8; 32], String> {
+    let sealed = BASE64.decode(wrapped).map_err(|e| e.to_string())?;
+    secrets::open(wrapping_key, &sealed)?
+        .try_into()
+        .map_err(|_| "Invalid data key".to_string())
+}
+
+/// Recovery keys are shown as 16 groups of 4 hex digits.
+pub(crate) fn format_recovery_key(key: &[u8; 32]) -> String {
+    key.chunks(2)
+        .map(|pair| format!("{:02X}{:02X}", pair[0], pair[1]))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Accepts the exported form with any grouping, spacing or case.
+pub(crate) fn parse_recovery_key(text: &str) -> Result<[u8; 32], String> {
+    let digits = text
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != '-')
+        .collect::<String>();
+    if digits.len() != 64 || !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err("A recovery key has 64 hexadecimal digits".to_string());
+    }
+    let mut key = [0u8; 32];
+    for (index, byte) in key.iter_mut().enumerate() {
+        *byte =
+            u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16).map_err(|e| e.to_string())?;
+    }
+    Ok(key)
+}
+
+pub(crate) fn recovery_key_file(key: &[u8; 32]) -> String {
+    format!(
+        "AnyChat data recovery key\n\n{}\n\nKeep this file somewhere safe. It unlocks AnyChat's \
+         encrypted data when the passphrase or the keyring entry is lost.\n",
+        format_recovery_key(key)
+    )
+}
+
+/// Existing data files that are kept encrypted.
+pub(crate) fn protected_files(data_dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![
+        data_dir.join(crate::registry::SERVICE_REGISTRY_FILE),
+        data_dir.join(secrets::SECRETS_FILE),
+        data_dir.join(crate::sync::SYNC_STATE_FILE),
+        data_dir.join(crate::sync::SYNC_CONFLICTS_FILE),
+    ];
+    for dir in [
+        crate::user_scripts::USER_SCRIPTS_DIR,
+        crate::native_chat::CONVERSATIONS_DIR,
+    ] {
+        collect_json_files(&data_dir.join(dir), &mut files);
+    }
+    files.retain(|path| path.is_file());
+    files
+}
+
+fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_json_files(&path, files);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Fails on the first protected file that does not decrypt under `data_key`.
+pub(crate) fn verify_files(files: &[PathBuf], data_key: &[u8; 32]) -> Result<(), String> {
+    for path in files {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if let Some(sealed) = sealed_payload(&bytes) {
+            secrets::open(data_key, &sealed).map_err(|_| {
+                format!(
+                    "Cannot decrypt {}: wrong key or corrupted file",
+                    path.display()
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites each file sealed (`Some(key)`) or in plaintext (`None`); files already in that form
+/// are left alone. Returns how many were rewritten.
+pub(crate) fn rewrite_files(
+    files: &[PathBuf],
+    current_key: &[u8; 32],
+    target_key: Option<&[u8; 32]>,
+) -> Result<usize, String> {
+    let mut rewritten = 0;
+    for path in files {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let plaintext = match sealed_payload(&bytes) {
+            Some(_) if target_key.is_some() => continue,
+            Some(sealed) => secrets::open(current_key, &sealed)?,
+            None if target_key.is_none() => continue,
+            None => bytes,
+        };
+        let output = match target_key {
+            Some(key) => seal_file(key, &plaintext)?,
+            None => plaintext,
+        };
+        crate::storage::write_atomic(path, &output)?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn store_keyring_key(key: &[u8; 32]) -> Result<(), String> {
+    crate::keyring::store(KEYRING_LABEL, &KEYRING_ATTRIBUTES, key)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn keyring_key() -> Result<[u8; 32], String> {
+    crate::keyring::lookup(&KEYRING_ATTRIBUTES)?
+        .ok_or_else(|| "The data encryption key is missing from the keyring".to_string())?
+        .try_into()
+        .map_err(|_| "Invalid data encryption key in the keyring".to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn delete_keyring_key() -> Result<(), String> {
+    crate::keyring::delete(&KEYRING_ATTRIBUTES)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn store_keyring_key(_key: &[u8; 32]) -> Result<(), String> {
+    Err("The system keyring is only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn keyring_key() -> Result<[u8; 32], String> {
+    Err("The system keyring is only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn delete_keyring_key() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        begin_migration, format_recovery_key, migration_pending, new_config, new_passphrase_source,
+        parse_recovery_key, passphrase_key, protected_files, rewrite_files, sealed_payload,
+        unwrap_key, verify_files,
+    };
+    use crate::secrets::KdfParams;
+
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn data_key_unwraps_with_the_passphrase_or_the_recovery_key() {
+        assert!(new_passphrase_source("short", TEST_KDF).is_err());
+
+        let (source, source_key) = new_passphrase_source("a long passphrase", TEST_KDF).unwrap();
+        let data_key = [3; 32];
+        let recovery_key = [9; 32];
+        let config = new_config(source, &source_key, &data_key, &recovery_key).unwrap();
+
+        let key = passphrase_key(&config, "a long passphrase").unwrap();
+        assert_eq!(unwrap_key(&key, &config.wrapped_key).unwrap(), data_key);
+        let wrong = passphrase_key(&config, "another passphrase").unwrap();
+        assert!(unwrap_key(&wrong, &config.wrapped_key).is_err());
+        assert_eq!(
+            unwrap_key(&recovery_key, &config.recovery_wrapped_key).unwrap(),
+            data_key
+        );
+    }
+
+    #[test]
+    fn migration_marker_only_opens_under_the_data_key() {
+        let (source, source_key) = new_passphrase_source("a long passphrase", TEST_KDF).unwrap();
+        let mut config = new_config(source, &source_key, &[3; 32], &[9; 32]).unwrap();
+        assert!(!migration_pending(&config, &[3; 32]));
+
+        begin_migration(&mut config, &[3; 32]).unwrap();
+        assert!(migration_pending(&config, &[3; 32]));
+        assert!(!migration_pending(&config, &[4; 32]));
+        config.pending_migration = Some("bm90IGEgbWFya2Vy".to_string());
+        assert!(!migration_pending(&config, &[3; 32]));
+    }
+
+    #[test]
+    fn recovery_keys_round_trip_through_their_text_form() {
+        let key: [u8; 32] = std::array::from_fn(|index| index as u8 * 7);
+        let text = format_recovery_key(&key);
+        assert_eq!(text.len(), 64 + 15);
+        assert!(text.starts_with("0007-0E15-"));
+        assert_eq!(parse_recovery_key(&text).unwrap(), key);
+        assert_eq!(
+            parse_recovery_key(&format!(" {} ", text.to_lowercase().replace('-', " "))).unwrap(),
+            key
+        );
+        assert!(parse_recovery_key("0007-0E15").is_err());
+        assert!(parse_recovery_key(&text.replace('0', "G")).is_err());
+    }
+
+    #[test]
+    fn existing_files_are_migrated_and_verified() {
+        let dir = std::env::temp_dir().join(format!("anychat-encryption-{}", std::process::id()));
+        let conversations = dir.join("conversations").join("chatgpt");
+        std::fs::create_dir_all(&conversations).unwrap();
+        std::fs::write(dir.join("services.json"), br#"{"services":{}}"#).unwrap();
+        std::fs::write(dir.join("settings.json"), b"{}").unwrap();
+        std::fs::write(conversations.join("c1.json"), br#"{"id":"c1"}"#).unwrap();
+
+        let files = protected_files(&dir);
+        assert_eq!(files.len(), 2);
+
+        let key = [5; 32];
+        assert_eq!(rewrite_files(&files, &key, Some(&key)).unwrap(), 2);
+        assert_eq!(rewrite_files(&files, &key, Some(&key)).unwrap(), 0);
+        for path in &files {
+            assert!(sealed_payload(&std::fs::read(path).unwrap()).is_some());
+        }
+        assert!(verify_files(&files, &key).is_ok());
+        assert!(verify_files(&files, &[6; 32])
+            .unwrap_err()
+            .contains("Cannot decrypt"));
+
+        assert_eq!(rewrite_files(&files, &key, None).unwrap(), 2);
+        assert_eq!(
+            std::fs::read(conversations.join("c1.json")).unwrap(),
+            br#"{"id":"c1"}"#
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
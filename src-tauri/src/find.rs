@@ -0,0 +1,227 @@
+//! Find-in-page for service webviews.
+//!
+//! The search runs inside the page: matches are collected from visible text nodes, painted
+//! with the CSS Custom Highlight API (or the selection where it is missing) and scrolled into
+//! view. The page also gets its own find bar, since the service webview covers the main
+//! window's content area.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum FindDirection {
+    Next,
+    Previous,
+}
+
+/// `current` is 1-based and 0 without matches. Both are `None` where the platform cannot read
+/// script results back; the page's find bar still shows them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FindResult {
+    pub matches: Option<u32>,
+    pub current: Option<u32>,
+}
+
+pub(crate) fn find_script(query: &str, direction: FindDirection) -> String {
+    format!(
+        "{}\nwindow.__anychatFind({}, {});",
+        FIND_SCRIPT,
+        serde_json::Value::String(query.to_string()),
+        direction == FindDirection::Previous
+    )
+}
+
+pub(crate) fn clear_script() -> &'static str {
+    "if (window.__anychatFindClose) window.__anychatFindClose();"
+}
+
+pub(crate) fn open_script() -> String {
+    format!("{}\nwindow.__anychatFindOpen();", FIND_SCRIPT)
+}
+
+pub(crate) fn parse_result(raw: &str) -> Result<FindResult, String> {
+    serde_json::from_str(raw).map_err(|e| format!("Invalid find result: {}", e))
+}
+
+const FIND_SCRIPT: &str = r#"
+(function() {
+    if (window.__anychatFind) return;
+
+    var HIGHLIGHT = 'anychat-find';
+    var CURRENT = 'anychat-find-current';
+    var supportsHighlights = typeof CSS !== 'undefined' && CSS.highlights && typeof Highlight === 'function';
+    var state = { query: '', ranges: [], index: -1 };
+    var bar = null;
+
+    function isOwnNode(node) {
+        return bar && bar.host.contains(node);
+    }
+
+    function collect(query) {
+        var ranges = [];
+        if (!query || !document.body) return ranges;
+
+        var needle = query.toLowerCase();
+        var walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {
+            acceptNode: function(node) {
+                var parent = node.parentElement;
+                if (!parent || /^(SCRIPT|STYLE|NOSCRIPT|TEXTAREA)$/.test(parent.tagName)) return NodeFilter.FILTER_REJECT;
+                if (isOwnNode(parent) || !parent.getClientRects().length) return NodeFilter.FILTER_REJECT;
+                return NodeFilter.FILTER_ACCEPT;
+            }
+        });
+        for (var node = walker.nextNode(); node; node = walker.nextNode()) {
+            var text = node.nodeValue.toLowerCase();
+            for (var at = text.indexOf(needle); at !== -1; at = text.indexOf(needle, at + needle.length)) {
+                var range = document.createRange();
+                range.setStart(node, at);
+                range.setEnd(node, at + needle.length);
+                ranges.push(range);
+            }
+        }
+        return ranges;
+    }
+
+    function ensureStyle() {
+        if (!supportsHighlights || document.getElementById('anychat-find-style')) return;
+        var style = document.createElement('style');
+        style.id = 'anychat-find-style';
+        style.textContent = '::highlight(' + HIGHLIGHT + ') { background: #ffe066; color: #000; } ' +
+            '::highlight(' + CURRENT + ') { background: #ff9632; color: #000; }';
+        (document.head || document.documentElement).appendChild(style);
+    }
+
+    function paint() {
+        var current = state.index >= 0 ? state.ranges[state.index] : null;
+        if (supportsHighlights) {
+            ensureStyle();
+            var all = new Highlight();
+            state.ranges.forEach(function(range) { all.add(range); });
+            var active = new Highlight();
+            if (current) active.add(current);
+            active.priority = 1;
+            CSS.highlights.set(HIGHLIGHT, all);
+            CSS.highlights.set(CURRENT, active);
+        } else {
+            var selection = window.getSelection();
+            selection.removeAllRanges();
+            if (current) selection.addRange(current);
+        }
+
+        if (current && current.startContainer.parentElement) {
+            current.startContainer.parentElement.scrollIntoView({ block: 'center', inline: 'nearest' });
+        }
+        if (bar) {
+            bar.counter.textContent = state.query ? (state.index + 1) + '/' + state.ranges.length : '';
+        }
+    }
+
+    function clear() {
+        state = { query: '', ranges: [], index: -1 };
+        if (supportsHighlights) {
+            CSS.highlights.delete(HIGHLIGHT);
+            CSS.highlights.delete(CURRENT);
+        } else {
+            window.getSelection().removeAllRanges();
+        }
+        if (bar) bar.counter.textContent = '';
+    }
+
+    window.__anychatFind = function(query, backwards) {
+        query = String(query || '');
+        if (!query) {
+            clear();
+            return JSON.stringify({ matches: 0, current: 0 });
+        }
+
+        // Collected again on every step so matches follow pages that re-render.
+        var sameQuery = query === state.query;
+        var ranges = collect(query);
+        var index = -1;
+        if (ranges.length) {
+            if (sameQuery && state.index >= 0) {
+                index = (state.index + (backwards ? -1 : 1) + ranges.length) % ranges.length;
+            } else {
+                index = backwards ? ranges.length - 1 : 0;
+            }
+        }
+        state = { query: query, ranges: ranges, index: index };
+        if (bar && bar.input.value !== query) bar.input.value = query;
+        paint();
+        return JSON.stringify({ matches: ranges.length, current: index + 1 });
+    };
+
+    function createBar() {
+        var host = document.createElement('div');
+        host.style.cssText = 'position:fixed;top:8px;right:16px;z-index:2147483647;';
+        var root = host.attachShadow({ mode: 'closed' });
+        root.innerHTML =
+            '<style>' +
+            '.bar{display:flex;align-items:center;gap:4px;padding:6px 8px;border-radius:8px;background:#fff;color:#222;' +
+            'box-shadow:0 2px 12px rgba(0,0,0,.25);font:13px system-ui,sans-serif;}' +
+            'input{width:180px;border:1px solid #ccc;border-radius:4px;padding:3px 6px;font:inherit;}' +
+            'span{min-width:44px;text-align:center;color:#666;}' +
+            'button{border:0;background:transparent;cursor:pointer;font:inherit;padding:2px 6px;border-radius:4px;}' +
+            'button:hover{background:#eee;}' +
+            '</style>' +
+            '<div class="bar"><input type="search" aria-label="Find in page"><span></span>' +
+            '<button data-action="previous" title="Previous">&#8593;</button>' +
+            '<button data-action="next" title="Next">&#8595;</button>' +
+            '<button data-action="close" title="Close">&#10005;</button></div>';
+
+        var input = root.querySelector('input');
+        input.addEventListener('input', function() { window.__anychatFind(input.value, false); });
+        input.addEventListener('keydown', function(event) {
+            if (event.key === 'Enter') {
+                event.preventDefault();
+                window.__anychatFind(input.value, event.shiftKey);
+            } else if (event.key === 'Escape') {
+                event.preventDefault();
+                window.__anychatFindClose();
+            }
+        });
+        root.querySelector('.bar').addEventListener('click', function(event) {
+            var action = event.target.getAttribute && event.target.getAttribute('data-action');
+            if (action === 'close') window.__anychatFindClose();
+            else if (action) window.__anychatFind(input.value, action === 'previous');
+        });
+
+        return { host: host, input: input, counter: root.querySelector('span') };
+    }
+
+    window.__anychatFindOpen = function() {
+        if (!bar) bar = createBar();
+        if (!bar.host.isConnected) document.documentElement.appendChild(bar.host);
+        bar.input.focus();
+        bar.input.select();
+    };
+
+    window.__anychatFindClose = function() {
+        clear();
+        if (bar && bar.host.isConnected) bar.host.remove();
+    };
+})();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::{find_script, parse_result, FindDirection, FindResult};
+
+    #[test]
+    fn find_script_passes_the_query_as_a_js_string() {
+        let script = find_script("it's \"quoted\"\n", FindDirection::Previous);
+        assert!(script.ends_with(r#"window.__anychatFind("it's \"quoted\"\n", true);"#));
+        assert!(find_script("x", FindDirection::Next).ends_with(r#"("x", false);"#));
+    }
+
+    #[test]
+    fn parses_page_results() {
+        assert_eq!(
+            parse_result(r#"{"matches":4,"current":2}"#).unwrap(),
+            FindResult {
+                matches: Some(4),
+                current: Some(2),
+            }
+        );
+        assert!(parse_result("undefined").is_err());
+    }
+}
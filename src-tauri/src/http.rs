@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+/// Trust configuration shared by every HTTP request made from Rust.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct HttpSettings {
+    /// PEM files holding one or more extra CA certificates (e.g. a TLS-inspecting proxy or internal CA).
+    pub ca_bundle_paths: Vec<String>,
+    /// Trust the operating system certificate store in addition to the bundled roots.
+    pub use_system_roots: bool,
+    /// `http://` proxy for requests made from Rust and for service webviews.
+    pub proxy: Option<String>,
+}
+
+pub(crate) fn load_ca_bundle(path: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem =
+        std::fs::read(path).map_err(|e| format!("Failed to read CA bundle {}: {}", path, e))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {}", path, e))?;
+
+    if certificates.is_empty() {
+        return Err(format!("CA bundle {} contains no certificates", path));
+    }
+
+    Ok(certificates)
+}
+
+pub(crate) fn validate_http_settings(settings: &HttpSettings) -> Result<(), String> {
+    for path in &settings.ca_bundle_paths {
+        load_ca_bundle(path)?;
+    }
+    if let Some(proxy) = &settings.proxy {
+        crate::policy::validate_proxy(proxy)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn build_http_client(
+    settings: &HttpSettings,
+    user_agent: &str,
+    timeout: Duration,
+) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .timeout(timeout)
+        .tls_built_in_webpki_certs(true)
+        .tls_built_in_native_certs(settings.use_system_roots);
+
+    for path in &settings.ca_bundle_paths {
+        match load_ca_bundle(path) {
+            Ok(certificates) => {
+                for certificate in certificates {
+                    builder = builder.add_root_certificate(certificate);
+                }
+            }
+            // A bundle that disappeared after it was configured should not take every request down.
+            Err(e) => println!("[AnyChat] Skipping CA bundle: {}", e),
+        }
+    }
+
+    if let Some(proxy) = &settings.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| e.to_string())?);
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_ca_bundle, validate_http_settings, HttpSettings};
+
+    #[test]
+    fn http_settings_default_to_bundled_roots_only() {
+        let settings: HttpSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, HttpSettings::default());
+        assert!(!settings.use_system_roots);
+    }
+
+    #[test]
+    fn ca_bundle_without_certificates_is_rejected() {
+        let path =
+            std::env::temp_dir().join(format!("anychat-empty-bundle-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        let err = load_ca_bundle(path.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("contains no certificates"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn missing_ca_bundle_fails_validation() {
+        let settings = HttpSettings {
+            ca_bundle_paths: vec!["/definitely/not/here.pem".to_string()],
+            use_system_roots: false,
+            proxy: None,
+        };
+
+        assert!(validate_http_settings(&settings)
+            .unwrap_err()
+            .contains("Failed to read CA bundle"));
+    }
+}
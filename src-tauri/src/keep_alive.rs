@@ -0,0 +1,222 @@
+//! Optional per-service keep-alive: while AnyChat runs, the service page periodically fetches a
+//! cheap same-origin endpoint with its own cookies, so idle sessions are not expired.
+//!
+//! The result comes back over the script bridge. A redirect to a login page, or a redirect to
+//! another origin the page is not allowed to follow, means the session is gone. WebKitGTK
+//! delivers the result through its script message handler, other platforms through the
+//! `report_bridge_message` command, so an expired session is reported everywhere.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub(crate) const KEEP_ALIVE_EVENT: &str = "service://keep-alive";
+pub(crate) const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MIN_INTERVAL_MINUTES: u32 = 5;
+const MAX_INTERVAL_MINUTES: u32 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KeepAliveSettings {
+    /// Absolute path on the service's origin, e.g. `/api/auth/session`.
+    pub path: String,
+    pub interval_minutes: u32,
+}
+
+impl KeepAliveSettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.interval_minutes) * 60)
+    }
+}
+
+pub(crate) fn validate_settings(settings: &KeepAliveSettings) -> Result<(), String> {
+    let path = settings.path.trim();
+    if !path.starts_with('/') || path.starts_with("//") || path.contains('\\') {
+        return Err(format!(
+            "Keep-alive path must be a same-origin path starting with '/': {}",
+            settings.path
+        ));
+    }
+    if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&settings.interval_minutes) {
+        return Err(format!(
+            "Keep-alive interval must be between {} and {} minutes",
+            MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum KeepAliveOutcome {
+    Ok,
+    SignedOut,
+    Failed,
+}
+
+/// `url` is the final URL after redirects, empty when the page could not follow them.
+pub(crate) fn outcome(
+    status: u16,
+    url: &str,
+    redirected: bool,
+    is_login_url: impl Fn(&str) -> bool,
+) -> KeepAliveOutcome {
+    if status == 401 || status == 403 || (redirected && (url.is_empty() || is_login_url(url))) {
+        return KeepAliveOutcome::SignedOut;
+    }
+    if status == 0 || status >= 500 {
+        return KeepAliveOutcome::Failed;
+    }
+    KeepAliveOutcome::Ok
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KeepAliveStatus {
+    pub outcome: KeepAliveOutcome,
+    /// HTTP status of the ping, 0 when it did not complete.
+    pub http_status: u16,
+    pub error: Option<String>,
+    /// Unix time in milliseconds.
+    pub checked_at: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KeepAliveTracker {
+    last_pings: HashMap<String, Instant>,
+    statuses: HashMap<String, KeepAliveStatus>,
+}
+
+impl KeepAliveTracker {
+    /// `true` when the service is due for a ping, which is then counted as sent. A service
+    /// seen for the first time waits a full interval, since its page just loaded.
+    pub fn take_due(&mut self, service_id: &str, interval: Duration, now: Instant) -> bool {
+        match self.last_pings.get(service_id) {
+            Some(last) if now.saturating_duration_since(*last) < interval => false,
+            Some(_) => {
+                self.last_pings.insert(service_id.to_string(), now);
+                true
+            }
+            None => {
+                self.last_pings.insert(service_id.to_string(), now);
+                false
+            }
+        }
+    }
+
+    /// Records a result; `false` when the outcome did not change.
+    pub fn record(&mut self, service_id: &str, status: KeepAliveStatus) -> bool {
+        let changed = self
+            .statuses
+            .get(service_id)
+            .is_none_or(|previous| previous.outcome != status.outcome);
+        self.statuses.insert(service_id.to_string(), status);
+        changed
+    }
+
+    pub fn forget(&mut self, service_id: &str) {
+        self.last_pings.remove(service_id);
+        self.statuses.remove(service_id);
+    }
+
+    pub fn statuses(&self) -> HashMap<String, KeepAliveStatus> {
+        self.statuses.clone()
+    }
+}
+
+pub(crate) fn ping_script(path: &str) -> String {
+    format!(
+        r#"(function() {{
+    var path = {};
+    function post(message) {{
+        message.type = 'keepAlive';
+        if (window.__anychatPost) window.__anychatPost(message);
+    }}
+    var init = {{ credentials: 'include', cache: 'no-store' }};
+    fetch(path, init).then(function(response) {{
+        post({{ status: response.status, url: response.url, redirected: response.redirected }});
+    }}).catch(function(error) {{
+        init.redirect = 'manual';
+        fetch(path, init).then(function(response) {{
+            post({{ status: 0, url: '', redirected: response.type === 'opaqueredirect' }});
+        }}).catch(function() {{
+            post({{ status: 0, url: '', redirected: false, error: String(error) }});
+        }});
+    }});
+}})();"#,
+        serde_json::Value::String(path.trim().to_string())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        outcome, ping_script, validate_settings, KeepAliveOutcome, KeepAliveSettings,
+        KeepAliveStatus, KeepAliveTracker,
+    };
+    use std::time::{Duration, Instant};
+
+    fn settings(path: &str, interval_minutes: u32) -> KeepAliveSettings {
+        KeepAliveSettings {
+            path: path.to_string(),
+            interval_minutes,
+        }
+    }
+
+    #[test]
+    fn only_same_origin_paths_and_sane_intervals_are_accepted() {
+        assert!(validate_settings(&settings("/api/auth/session", 30)).is_ok());
+        assert!(validate_settings(&settings("https://evil.example/", 30)).is_err());
+        assert!(validate_settings(&settings("//evil.example/", 30)).is_err());
+        assert!(validate_settings(&settings("/", 1)).is_err());
+    }
+
+    #[test]
+    fn login_redirects_and_auth_errors_mean_signed_out() {
+        let is_login = |url: &str| url.contains("/login");
+        assert_eq!(
+            outcome(200, "https://chatgpt.com/", false, is_login),
+            KeepAliveOutcome::Ok
+        );
+        assert_eq!(
+            outcome(200, "https://chatgpt.com/login", true, is_login),
+            KeepAliveOutcome::SignedOut
+        );
+        assert_eq!(outcome(0, "", true, is_login), KeepAliveOutcome::SignedOut);
+        assert_eq!(
+            outcome(401, "https://chatgpt.com/api", false, is_login),
+            KeepAliveOutcome::SignedOut
+        );
+        assert_eq!(outcome(0, "", false, is_login), KeepAliveOutcome::Failed);
+        assert_eq!(
+            outcome(502, "https://chatgpt.com/", false, is_login),
+            KeepAliveOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn pings_wait_an_interval_and_only_outcome_changes_count() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(600);
+        let mut tracker = KeepAliveTracker::default();
+        assert!(!tracker.take_due("claude", interval, start));
+        assert!(!tracker.take_due("claude", interval, start + Duration::from_secs(300)));
+        assert!(tracker.take_due("claude", interval, start + Duration::from_secs(600)));
+        assert!(!tracker.take_due("claude", interval, start + Duration::from_secs(601)));
+
+        let status = |outcome, checked_at| KeepAliveStatus {
+            outcome,
+            http_status: 200,
+            error: None,
+            checked_at,
+        };
+        assert!(tracker.record("claude", status(KeepAliveOutcome::Ok, 1)));
+        assert!(!tracker.record("claude", status(KeepAliveOutcome::Ok, 2)));
+        assert!(tracker.record("claude", status(KeepAliveOutcome::SignedOut, 3)));
+        assert_eq!(tracker.statuses()["claude"].checked_at, 3);
+    }
+
+    #[test]
+    fn ping_script_quotes_the_path() {
+        assert!(ping_script(" /api/'x' ").contains(r#"var path = "/api/'x'";"#));
+    }
+}
@@ -0,0 +1,160 @@
+//! Minimal client for the Secret Service D-Bus API (GNOME Keyring, KWallet), used for keys
+//! that should not sit in files. Items are addressed by their attributes. Locked collections
+//! are not unlocked from here: that needs an interactive prompt, so they count as unavailable.
+
+use std::collections::HashMap;
+use webkit2gtk::gio;
+use webkit2gtk::glib::{ToVariant, Variant};
+
+const SECRETS_BUS_NAME: &str = "org.freedesktop.secrets";
+const SECRETS_PATH: &str = "/org/freedesktop/secrets";
+const SERVICE_INTERFACE: &str = "org.freedesktop.Secret.Service";
+const COLLECTION_INTERFACE: &str = "org.freedesktop.Secret.Collection";
+const ITEM_INTERFACE: &str = "org.freedesktop.Secret.Item";
+const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+/// Object path the service returns when no prompt is needed.
+const NO_PROMPT: &str = "/";
+
+struct Session {
+    connection: gio::DBusConnection,
+    /// Object path of a `plain` transfer session.
+    path: Variant,
+}
+
+impl Session {
+    fn open() -> Result<Self, String> {
+        let connection = gio::bus_get_sync(gio::BusType::Session, None::<&gio::Cancellable>)
+            .map_err(|e| format!("Session bus unavailable: {}", e))?;
+        let session = call(
+            &connection,
+            SECRETS_PATH,
+            SERVICE_INTERFACE,
+            "OpenSession",
+            ("plain", "".to_variant()).to_variant(),
+        )?;
+        Ok(Session {
+            connection,
+            path: session.child_value(1),
+        })
+    }
+
+    fn call(
+        &self,
+        path: &str,
+        interface: &str,
+        method: &str,
+        parameters: Variant,
+    ) -> Result<Variant, String> {
+        call(&self.connection, path, interface, method, parameters)
+    }
+
+    /// Unlocked items matching `attributes`.
+    fn search(&self, attributes: &HashMap<String, String>) -> Result<Variant, String> {
+        let items = self.call(
+            SECRETS_PATH,
+            SERVICE_INTERFACE,
+            "SearchItems",
+            (attributes.clone(),).to_variant(),
+        )?;
+        Ok(items.child_value(0))
+    }
+}
+
+fn call(
+    connection: &gio::DBusConnection,
+    path: &str,
+    interface: &str,
+    method: &str,
+    parameters: Variant,
+) -> Result<Variant, String> {
+    connection
+        .call_sync(
+            Some(SECRETS_BUS_NAME),
+            path,
+            interface,
+            method,
+            Some(&parameters),
+            None,
+            gio::DBusCallFlags::NONE,
+            5000,
+            None::<&gio::Cancellable>,
+        )
+        .map_err(|e| format!("Secret Service {} failed: {}", method, e))
+}
+
+fn attribute_map(attributes: &[(&str, &str)]) -> HashMap<String, String> {
+    attributes
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// The secret of the first unlocked item matching `attributes`.
+pub(crate) fn lookup(attributes: &[(&str, &str)]) -> Result<Option<Vec<u8>>, String> {
+    let session = Session::open()?;
+    let unlocked = session.search(&attribute_map(attributes))?;
+    if unlocked.n_children() == 0 {
+        return Ok(None);
+    }
+
+    let secrets = session.call(
+        SECRETS_PATH,
+        SERVICE_INTERFACE,
+        "GetSecrets",
+        Variant::tuple_from_iter([unlocked, session.path.clone()]),
+    )?;
+    let found = secrets.child_value(0);
+    if found.n_children() == 0 {
+        return Ok(None);
+    }
+    // a{o(oayays)}: item path -> (session, parameters, value, content type)
+    Ok(found
+        .child_value(0)
+        .child_value(1)
+        .child_value(2)
+        .get::<Vec<u8>>())
+}
+
+/// Creates or replaces the item matching `attributes` in the default collection.
+pub(crate) fn store(label: &str, attributes: &[(&str, &str)], secret: &[u8]) -> Result<(), String> {
+    let session = Session::open()?;
+    let properties = HashMap::from([
+        (
+            "org.freedesktop.Secret.Item.Label".to_string(),
+            label.to_variant(),
+        ),
+        (
+            "org.freedesktop.Secret.Item.Attributes".to_string(),
+            attribute_map(attributes).to_variant(),
+        ),
+    ]);
+    let secret = Variant::tuple_from_iter([
+        session.path.clone(),
+        Vec::<u8>::new().to_variant(),
+        secret.to_vec().to_variant(),
+        "application/octet-stream".to_variant(),
+    ]);
+    let created = session.call(
+        DEFAULT_COLLECTION,
+        COLLECTION_INTERFACE,
+        "CreateItem",
+        Variant::tuple_from_iter([properties.to_variant(), secret, true.to_variant()]),
+    )?;
+    if created.child_value(1).str() != Some(NO_PROMPT) {
+        return Err("The default keyring is locked; unlock it and try again".to_string());
+    }
+    Ok(())
+}
+
+/// Deletes every unlocked item matching `attributes`.
+pub(crate) fn delete(attributes: &[(&str, &str)]) -> Result<(), String> {
+    let session = Session::open()?;
+    let unlocked = session.search(&attribute_map(attributes))?;
+    for item in unlocked.iter() {
+        let Some(path) = item.str() else {
+            continue;
+        };
+        session.call(path, ITEM_INTERFACE, "Delete", ().to_variant())?;
+    }
+    Ok(())
+}
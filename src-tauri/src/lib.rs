@@ -1,12 +1,54 @@
+mod app_lock;
+mod backup;
+mod bridge;
+mod browser_import;
+mod browsing_data;
+mod content_blocking;
+mod cookie_backup;
+mod encryption;
+mod find;
+mod http;
+mod keep_alive;
+#[cfg(target_os = "linux")]
+mod keyring;
+mod lifecycle;
+mod local_servers;
+mod login_state;
+mod native_chat;
+mod navigation;
+mod pages;
+mod policy;
+mod private_session;
+mod registry;
+mod resume;
+mod secrets;
+mod self_hosted;
+mod settings;
+mod shortcuts;
+mod storage;
+mod sync;
+#[cfg(test)]
+mod test_support;
+mod theme;
+mod tls;
+mod user_scripts;
+mod watchdog;
+mod zoom;
+
+use policy::PolicyBuilderExt;
 use regex::Regex;
+use registry::{ServiceRegistry, ServiceSettings, SERVICE_REGISTRY_FILE};
+use settings::{AppSettings, SETTINGS_FILE};
 use std::collections::{HashMap, HashSet};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 #[cfg(not(target_os = "windows"))]
 use tauri::webview::WebviewBuilder;
 use tauri::{
     menu::{MenuBuilder, MenuItemBuilder},
     tray::TrayIconBuilder,
-    Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, WindowEvent,
+    Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder,
+    WindowEvent,
 };
 use tauri_plugin_opener::OpenerExt;
 
@@ -112,12 +154,35 @@ const WEBVIEW_COMPAT_SCRIPT: &str = r#"
 })();
 "#;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ServiceKind {
+    #[default]
+    Web,
+    /// A chat UI the user runs themselves, typically on localhost or the LAN.
+    SelfHosted,
+    /// An OpenAI-compatible API rendered by the native chat panel in the main webview.
+    OpenAiCompatible,
+}
+
+impl ServiceKind {
+    fn uses_site_quirks(self) -> bool {
+        self == ServiceKind::Web
+    }
+
+    fn has_webview(self) -> bool {
+        self != ServiceKind::OpenAiCompatible
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 struct ServiceHostPayload {
     id: String,
     name: String,
     url: String,
     enabled: bool,
+    #[serde(default)]
+    kind: ServiceKind,
 }
 
 #[derive(Debug, Clone)]
@@ -126,6 +191,7 @@ struct WindowsServiceHost {
     window_label: String,
     name: String,
     url: String,
+    kind: ServiceKind,
 }
 
 impl WindowsServiceHost {
@@ -135,6 +201,7 @@ impl WindowsServiceHost {
             window_label: service_window_label(&service.id),
             name: service.name.clone(),
             url: service.url.clone(),
+            kind: service.kind,
         }
     }
 }
@@ -144,6 +211,33 @@ struct AppState {
     setup_complete: Mutex<bool>,
     windows_service_hosts: Mutex<HashMap<String, WindowsServiceHost>>,
     active_windows_service_id: Mutex<Option<String>>,
+    /// The service last activated by the frontend, target of native shortcuts.
+    active_service_id: Mutex<Option<String>>,
+    settings: Mutex<AppSettings>,
+    service_registry: Mutex<ServiceRegistry>,
+    native_chat_requests: Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>,
+    /// User script revision baked into each live service webview's initialization scripts.
+    user_script_revisions: Mutex<HashMap<String, u64>>,
+    content_filters: Mutex<Arc<content_blocking::ContentFilters>>,
+    /// Services whose live webview was created with the element hiding script.
+    content_blocking_baked: Mutex<HashSet<String>>,
+    /// Last back/forward availability published per service.
+    navigation_states: Mutex<HashMap<String, navigation::NavigationState>>,
+    service_statuses: Mutex<HashMap<String, lifecycle::ServiceStatus>>,
+    watchdog: Mutex<watchdog::Watchdog>,
+    /// Payload each child webview was built from, to rebuild it after a crash.
+    service_payloads: Mutex<HashMap<String, ServiceHostPayload>>,
+    refresh_scheduler: Mutex<resume::RefreshScheduler>,
+    keep_alive: Mutex<keep_alive::KeepAliveTracker>,
+    login_states: Mutex<login_state::LoginTracker>,
+    app_lock: Mutex<app_lock::LockState>,
+    /// Administrator policy, fixed for the life of the process.
+    policy: policy::EffectivePolicy,
+    /// Encryption state of the data files, passed to every encrypted read and write.
+    vault: Mutex<storage::Vault>,
+    sync_running: Mutex<bool>,
+    #[cfg(not(target_os = "linux"))]
+    navigation_history: Mutex<HashMap<String, navigation::HistoryTracker>>,
 }
 
 #[cfg(debug_assertions)]
@@ -205,6 +299,60 @@ fn is_auth_url(url: &str) -> bool {
     false
 }
 
+fn allow_service_navigation(kind: ServiceKind, url: &tauri::Url) -> bool {
+    let url_str = url.as_str();
+    if is_auth_url(url_str) {
+        #[cfg(debug_assertions)]
+        println!("[AnyChat] Allowing OAuth navigation to: {}", url_str);
+    }
+
+    match kind {
+        ServiceKind::Web | ServiceKind::OpenAiCompatible => true,
+        ServiceKind::SelfHosted => {
+            let allowed = self_hosted::is_allowed_navigation(url);
+            if !allowed {
+                println!("[AnyChat] Blocked plain http navigation to: {}", url_str);
+            }
+            allowed
+        }
+    }
+}
+
+fn service_policy<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> &policy::Policy {
+    &app.state::<AppState>().inner().policy.policy
+}
+
+/// Proxy for service webviews: the policy's, else the user's.
+fn effective_proxy<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Option<tauri::Url> {
+    let state = app.state::<AppState>();
+    state.policy.policy.proxy_url().or_else(|| {
+        let settings = state.settings.lock().unwrap();
+        settings
+            .http
+            .proxy
+            .as_deref()
+            .and_then(|proxy| proxy.parse().ok())
+    })
+}
+
+fn policy_allows_navigation<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    url: &tauri::Url,
+) -> bool {
+    let allowed = service_policy(app).url_allowed(url);
+    if !allowed {
+        println!("[AnyChat] Navigation blocked by policy: {}", url);
+    }
+    allowed
+}
+
+fn is_service_auth_popup(kind: ServiceKind, service_url: &str, url: &tauri::Url) -> bool {
+    match kind {
+        ServiceKind::Web | ServiceKind::OpenAiCompatible => is_auth_url(url.as_str()),
+        ServiceKind::SelfHosted => self_hosted::is_auth_popup_url(service_url, url),
+    }
+}
+
 fn should_use_custom_user_agent(url: &str) -> bool {
     let Ok(parsed_url) = tauri::Url::parse(url) else {
         return true;
@@ -219,23 +367,43 @@ fn should_use_custom_user_agent(url: &str) -> bool {
     host != "grok.com"
 }
 
-fn should_inject_webview_compatibility_script(url: &str) -> bool {
-    let Ok(parsed_url) = tauri::Url::parse(url) else {
-        return true;
-    };
+/// Lowercased host of a service URL without a leading `www.`.
+fn service_host(url: &str) -> Option<String> {
+    let parsed_url = tauri::Url::parse(url).ok()?;
 
-    let host = parsed_url
-        .host_str()
-        .unwrap_or_default()
-        .trim_start_matches("www.")
-        .to_ascii_lowercase();
+    Some(
+        parsed_url
+            .host_str()
+            .unwrap_or_default()
+            .trim_start_matches("www.")
+            .to_ascii_lowercase(),
+    )
+}
 
-    !(host == "grok.com"
+/// grok.com and the X properties break when pages are modified by injected scripts.
+fn is_x_family_host(host: &str) -> bool {
+    host == "grok.com"
         || host.ends_with(".grok.com")
         || host == "x.com"
         || host.ends_with(".x.com")
         || host == "x.ai"
-        || host.ends_with(".x.ai"))
+        || host.ends_with(".x.ai")
+}
+
+fn should_inject_webview_compatibility_script(url: &str) -> bool {
+    let Some(host) = service_host(url) else {
+        return true;
+    };
+
+    !is_x_family_host(&host)
+}
+
+/// An explicit per-service choice wins. Otherwise blocking follows the same per-host decision
+/// as the compatibility script, and stays off for self-hosted pages.
+fn content_blocking_enabled(kind: ServiceKind, url: &str, setting: Option<bool>) -> bool {
+    setting.unwrap_or_else(|| {
+        kind.uses_site_quirks() && should_inject_webview_compatibility_script(url)
+    })
 }
 
 fn service_window_label(service_id: &str) -> String {
@@ -310,11 +478,13 @@ fn open_oauth_popup<R: tauri::Runtime>(app: &tauri::AppHandle<R>, url: &tauri::U
             .as_millis()
     );
 
-    let mut builder = WebviewWindowBuilder::new(app, &popup_label, WebviewUrl::External(url.clone()))
-        .title("Sign In")
-        .inner_size(500.0, 700.0)
-        .center()
-        .user_agent(USER_AGENT);
+    let mut builder =
+        WebviewWindowBuilder::new(app, &popup_label, WebviewUrl::External(url.clone()))
+            .title("Sign In")
+            .inner_size(500.0, 700.0)
+            .center()
+            .user_agent(USER_AGENT)
+            .with_policy(service_policy(app), effective_proxy(app));
 
     if should_inject_webview_compatibility_script(url.as_str()) {
         builder = builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
@@ -343,7 +513,6 @@ fn tracked_windows_service_hosts(state: &AppState) -> Vec<WindowsServiceHost> {
         .collect()
 }
 
-#[allow(dead_code)]
 fn resolve_windows_refresh_url(
     current_url: Option<String>,
     service: &ServiceHostPayload,
@@ -368,6 +537,7 @@ fn hide_windows_service_hosts(app: &tauri::AppHandle, state: &AppState) {
     for host in tracked_windows_service_hosts(state) {
         if let Some(window) = app.get_webview_window(&host.window_label) {
             let _ = window.hide();
+            set_service_visible(app, &host.service_id, false);
         }
     }
 }
@@ -444,9 +614,10 @@ fn prune_disabled_windows_service_hosts(
             .collect::<Vec<_>>()
     };
 
-    for (_, window_label) in &stale_hosts {
+    for (service_id, window_label) in &stale_hosts {
         if let Some(window) = app.get_webview_window(window_label) {
             let _ = window.close();
+            emit_lifecycle_event(app, service_id, lifecycle::LifecycleEvent::Destroyed);
         }
     }
 
@@ -500,6 +671,14 @@ fn ensure_windows_service_host(
     }
 
     let app_handle_clone = app.clone();
+    let page_load_app = app.clone();
+    let page_load_service = service.clone();
+    let title_app = app.clone();
+    let title_service_id = service.id.clone();
+    let navigation_app = app.clone();
+    let navigation_service = service.clone();
+    let kind = service.kind;
+    let service_url = service.url.clone();
     let mut builder =
         WebviewWindowBuilder::new(app, &host.window_label, WebviewUrl::External(parsed_url))
             .title(&service.name)
@@ -509,36 +688,71 @@ fn ensure_windows_service_host(
             .decorations(false)
             .shadow(false)
             .skip_taskbar(true)
-            .on_navigation(|url| {
-                let url_str = url.as_str();
-                if is_auth_url(url_str) {
-                    #[cfg(debug_assertions)]
-                    println!("[AnyChat] Allowing OAuth navigation to: {}", url_str);
-                }
-                true
+            .with_policy(service_policy(app), effective_proxy(app))
+            .on_navigation(move |url| {
+                allow_service_navigation(kind, url)
+                    && policy_allows_navigation(&navigation_app, url)
+                    && !is_blocked_content_navigation(&navigation_app, &navigation_service, url)
+            })
+            .on_page_load(move |window, payload| {
+                handle_service_page_load(
+                    &page_load_app,
+                    &page_load_service,
+                    window.as_ref(),
+                    &payload,
+                )
+            })
+            .on_document_title_changed(move |_, title| {
+                emit_lifecycle_event(
+                    &title_app,
+                    &title_service_id,
+                    lifecycle::LifecycleEvent::TitleChanged { title },
+                )
             })
             .on_new_window(move |url, _features| {
                 #[cfg(debug_assertions)]
                 println!("[AnyChat] New window requested: {}", url);
 
-                if is_auth_url(url.as_str()) {
+                let is_auth_popup = is_service_auth_popup(kind, &service_url, &url)
+                    && policy_allows_navigation(&app_handle_clone, &url);
+                if is_auth_popup {
                     open_oauth_popup(&app_handle_clone, &url);
                 }
 
-                handle_external_new_window(&app_handle_clone, &url)
+                handle_external_new_window(&app_handle_clone, &url, is_auth_popup)
             });
 
-    if should_use_custom_user_agent(&service.url) {
-        builder = builder.user_agent(USER_AGENT);
+    if service.kind.uses_site_quirks() {
+        if should_use_custom_user_agent(&service.url) {
+            builder = builder.user_agent(USER_AGENT);
+        }
+
+        if should_inject_webview_compatibility_script(&service.url) {
+            builder = builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
+        }
     }
 
-    if should_inject_webview_compatibility_script(&service.url) {
-        builder = builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
+    for script in service_initialization_scripts(app, service) {
+        builder = builder.initialization_script(&script);
     }
 
     let builder = builder.parent(main_window).map_err(|e| e.to_string())?;
 
     let window = builder.build().map_err(|e| e.to_string())?;
+    tls::attach_tls_error_handler(app, window.as_ref(), &service.id);
+    bridge::attach_script_bridge(app, window.as_ref(), &service.id);
+    shortcuts::attach_service_shortcuts(app, window.as_ref(), &service.id);
+    emit_lifecycle_event(app, &service.id, lifecycle::LifecycleEvent::Created);
+    track_service_navigation(app, &service.id, window.as_ref());
+    lifecycle::attach_load_failure_tracking(app, window.as_ref(), &service.id, &service.name);
+    watchdog::attach_crash_detection(app, window.as_ref(), &service.id);
+    apply_service_zoom(app, &service.id, window.as_ref());
+    if let Ok(page_url) = window.url() {
+        sync_content_blocking(app, service, window.as_ref(), &page_url, true);
+    }
+    if service.kind == ServiceKind::SelfHosted {
+        self_hosted::attach_basic_auth_prompt(window.as_ref());
+    }
     sync_windows_service_host_layout_with_main(app, state, main_window)?;
     let _ = window.hide();
 
@@ -553,6 +767,11 @@ fn show_active_windows_service_host(
         return Ok(());
     };
 
+    if app_locked(app) {
+        hide_windows_service_hosts(app, state);
+        return Ok(());
+    }
+
     let is_visible = main_window.is_visible().map_err(|e| e.to_string())?;
     let is_minimized = main_window.is_minimized().map_err(|e| e.to_string())?;
 
@@ -575,6 +794,7 @@ fn show_active_windows_service_host(
         name: active_host.name.clone(),
         url: active_host.url.clone(),
         enabled: true,
+        kind: active_host.kind,
     };
 
     let _ = ensure_windows_service_host(app, state, &main_window, &restore_service)?;
@@ -582,12 +802,14 @@ fn show_active_windows_service_host(
 
     for host in tracked_windows_service_hosts(state) {
         if let Some(window) = app.get_webview_window(&host.window_label) {
-            if host.service_id == active_host.service_id {
+            let visible = host.service_id == active_host.service_id;
+            if visible {
                 let _ = window.show();
                 let _ = window.set_focus();
             } else {
                 let _ = window.hide();
             }
+            set_service_visible(app, &host.service_id, visible);
         }
     }
 
@@ -597,8 +819,9 @@ fn show_active_windows_service_host(
 fn handle_external_new_window<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     url: &tauri::Url,
+    is_auth_popup: bool,
 ) -> tauri::webview::NewWindowResponse<R> {
-    if !is_auth_url(url.as_str()) {
+    if !is_auth_popup {
         if let Err(err) = app.opener().open_url(url.as_str(), None::<String>) {
             println!("[AnyChat] Failed to open external url: {}", err);
         }
@@ -624,11 +847,12 @@ fn decide_show_action(is_visible: bool, is_minimized: bool) -> ShowAction {
 #[cfg(test)]
 mod tests {
     use super::{
-        compute_docked_window_bounds_from_metrics, decide_show_action, resolve_windows_refresh_url,
-        should_inject_webview_compatibility_script,
+        allow_service_navigation, bridge_scripts, compute_docked_window_bounds_from_metrics,
+        content_blocking_enabled, decide_show_action, is_user_activity,
+        resolve_windows_refresh_url, should_inject_webview_compatibility_script,
         should_navigate_existing_windows_service_host, should_show_windows_service_hosts,
-        should_use_custom_user_agent, stale_windows_service_ids, ServiceHostPayload, ShowAction,
-        WindowsServiceHost,
+        should_use_custom_user_agent, stale_windows_service_ids, ServiceHostPayload, ServiceKind,
+        ShowAction, WindowsServiceHost,
     };
     use regex::Regex;
     use std::collections::HashMap;
@@ -684,6 +908,7 @@ mod tests {
                 window_label: "svc_chatgpt".to_string(),
                 name: "ChatGPT".to_string(),
                 url: "https://chatgpt.com".to_string(),
+                kind: ServiceKind::Web,
             },
         );
         tracked_hosts.insert(
@@ -693,6 +918,7 @@ mod tests {
                 window_label: "svc_gemini".to_string(),
                 name: "Gemini".to_string(),
                 url: "https://gemini.google.com".to_string(),
+                kind: ServiceKind::Web,
             },
         );
 
@@ -704,12 +930,14 @@ mod tests {
                     name: "ChatGPT".to_string(),
                     url: "https://chatgpt.com".to_string(),
                     enabled: true,
+                    kind: ServiceKind::Web,
                 },
                 ServiceHostPayload {
                     id: "gemini".to_string(),
                     name: "Gemini".to_string(),
                     url: "https://gemini.google.com".to_string(),
                     enabled: false,
+                    kind: ServiceKind::Web,
                 },
             ],
         );
@@ -724,12 +952,14 @@ mod tests {
             window_label: "svc_chatgpt".to_string(),
             name: "ChatGPT".to_string(),
             url: "https://chatgpt.com".to_string(),
+            kind: ServiceKind::Web,
         };
         let service = ServiceHostPayload {
             id: "chatgpt".to_string(),
             name: "ChatGPT".to_string(),
             url: "https://chatgpt.com".to_string(),
             enabled: true,
+            kind: ServiceKind::Web,
         };
 
         assert!(!should_navigate_existing_windows_service_host(
@@ -745,12 +975,14 @@ mod tests {
             window_label: "svc_chatgpt".to_string(),
             name: "ChatGPT".to_string(),
             url: "https://chatgpt.com".to_string(),
+            kind: ServiceKind::Web,
         };
         let service = ServiceHostPayload {
             id: "chatgpt".to_string(),
             name: "ChatGPT".to_string(),
             url: "https://chatgpt.com/new".to_string(),
             enabled: true,
+            kind: ServiceKind::Web,
         };
 
         assert!(should_navigate_existing_windows_service_host(
@@ -766,6 +998,7 @@ mod tests {
             name: "ChatGPT".to_string(),
             url: "https://chatgpt.com".to_string(),
             enabled: true,
+            kind: ServiceKind::Web,
         };
 
         assert_eq!(
@@ -778,6 +1011,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn content_blocking_defaults_follow_the_compatibility_script_host_decision() {
+        assert!(content_blocking_enabled(
+            ServiceKind::Web,
+            "https://chatgpt.com",
+            None
+        ));
+        assert!(!content_blocking_enabled(
+            ServiceKind::Web,
+            "https://grok.com",
+            None
+        ));
+        assert!(!content_blocking_enabled(
+            ServiceKind::SelfHosted,
+            "http://localhost:3000",
+            None
+        ));
+        assert!(content_blocking_enabled(
+            ServiceKind::Web,
+            "https://grok.com",
+            Some(true)
+        ));
+        assert!(!content_blocking_enabled(
+            ServiceKind::Web,
+            "https://chatgpt.com",
+            Some(false)
+        ));
+    }
+
+    #[test]
+    fn service_payload_defaults_to_web_kind_and_accepts_self_hosted() {
+        let service: ServiceHostPayload = serde_json::from_str(
+            r#"{"id":"chatgpt","name":"ChatGPT","url":"https://chatgpt.com","enabled":true}"#,
+        )
+        .unwrap();
+        assert_eq!(service.kind, ServiceKind::Web);
+        assert!(service.kind.uses_site_quirks());
+
+        let service: ServiceHostPayload = serde_json::from_str(
+            r#"{"id":"custom-1","name":"Open WebUI","url":"http://localhost:3000","enabled":true,"kind":"selfHosted"}"#,
+        )
+        .unwrap();
+        assert_eq!(service.kind, ServiceKind::SelfHosted);
+        assert!(!service.kind.uses_site_quirks());
+    }
+
+    #[test]
+    fn self_hosted_services_block_plain_http_to_public_hosts() {
+        assert!(allow_service_navigation(
+            ServiceKind::SelfHosted,
+            &"http://192.168.1.20:8080/c/1".parse().unwrap()
+        ));
+        assert!(!allow_service_navigation(
+            ServiceKind::SelfHosted,
+            &"http://example.com".parse().unwrap()
+        ));
+        assert!(allow_service_navigation(
+            ServiceKind::Web,
+            &"http://example.com".parse().unwrap()
+        ));
+    }
+
     #[test]
     fn grok_uses_default_webview_user_agent() {
         assert!(!should_use_custom_user_agent("https://grok.com"));
@@ -798,6 +1093,13 @@ mod tests {
         assert!(!should_inject_webview_compatibility_script("https://accounts.x.ai/account"));
     }
 
+    #[test]
+    fn x_family_services_get_no_bridge_scripts() {
+        assert!(bridge_scripts("https://x.com/i/grok").is_empty());
+        assert!(bridge_scripts("https://grok.x.ai").is_empty());
+        assert_eq!(bridge_scripts("https://chatgpt.com").len(), 3);
+    }
+
     #[test]
     fn other_services_keep_webview_compatibility_script() {
         assert!(should_inject_webview_compatibility_script("https://chatgpt.com"));
@@ -860,6 +1162,77 @@ mod tests {
             "main window close handling should exit on Windows and only preserve hide-to-tray behavior on non-Windows"
         );
     }
+
+    #[test]
+    fn only_the_shown_active_service_counts_as_activity_while_focused() {
+        assert!(is_user_activity(Some("chatgpt"), "chatgpt", true, true));
+        assert!(!is_user_activity(Some("claude"), "chatgpt", true, true));
+        assert!(!is_user_activity(None, "chatgpt", true, true));
+        assert!(!is_user_activity(Some("chatgpt"), "chatgpt", false, true));
+        assert!(!is_user_activity(Some("chatgpt"), "chatgpt", true, false));
+    }
+
+    #[test]
+    fn native_chat_commands_check_the_service_policy_first() {
+        let source = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs"));
+        for command in ["native_chat_send", "native_chat_list_models"] {
+            let pattern = Regex::new(&format!(
+                r#"(?s)fn {}\(.*?\) -> Result<[^{{]*\{{\s*service_policy\(&app\)\.check_service\(&service\.id, &service\.url\)\?;"#,
+                command
+            ))
+            .unwrap();
+            assert!(
+                pattern.is_match(source),
+                "{} should refuse services the policy blocks before touching the network",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn only_the_main_webview_may_call_app_commands() {
+        let names = |source: &str, pattern: &str| -> Vec<String> {
+            Regex::new(pattern)
+                .unwrap()
+                .captures_iter(source)
+                .map(|capture| capture[1].replace('-', "_"))
+                .collect()
+        };
+        let lib = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs"));
+        let handler = &lib[lib.rfind("generate_handler![").unwrap()..];
+        let handler = &handler[..handler.find("])").unwrap()];
+        let mut registered = names(handler, r"(?m)^\s+([a-z_]+),?$");
+        registered.sort();
+
+        let build = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/build.rs"));
+        let mut manifest = names(build, r#"(?m)^\s+"([a-z_]+)",$"#);
+        manifest.sort();
+        assert_eq!(
+            manifest, registered,
+            "build.rs COMMANDS must match generate_handler!"
+        );
+
+        let default = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/permissions/default.toml"
+        ));
+        let mut allowed = names(default, r#""allow-([a-z-]+)""#);
+        allowed.push("report_bridge_message".to_string());
+        allowed.sort();
+        assert_eq!(
+            allowed, registered,
+            "the main webview should be allowed every command"
+        );
+
+        let bridge = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/capabilities/service-bridge.json"
+        ));
+        assert_eq!(
+            names(bridge, r#""allow-([a-z-]+)""#),
+            vec!["report_bridge_message".to_string()]
+        );
+    }
 }
 
 fn show_main_window(app_handle: &tauri::AppHandle) {
@@ -920,6 +1293,7 @@ fn show_main_window(app_handle: &tauri::AppHandle) {
                     .title("")
                     .inner_size(1200.0, 800.0)
                     .min_inner_size(800.0, 600.0)
+                    .devtools(!service_policy(app_handle).disable_devtools)
                     .build()
             };
 
@@ -1024,346 +1398,3440 @@ fn show_main_window(app_handle: &tauri::AppHandle) {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
-fn create_webview_for_service(
-    app: &tauri::AppHandle,
-    label: &str,
-    url: &str,
-    state: &AppState,
-    // Use Window here because the main window hosts multiple webviews (add_child).
-    window: &tauri::Window,
-) -> Result<(), String> {
-    println!(
-        "[AnyChat] create_webview_for_service: creating {} -> {}",
-        label, url
-    );
-
-    let (pos, size) = compute_webview_bounds(window)?;
-
-    let app_handle_clone = app.clone();
-    let parsed_url: tauri::Url = url.parse().map_err(|e| format!("{}", e))?;
-
-    let mut webview_builder = WebviewBuilder::new(label, WebviewUrl::External(parsed_url))
-        .user_agent(USER_AGENT)
-        .on_navigation(|url| {
-            let url_str = url.as_str();
-            if is_auth_url(url_str) {
-                #[cfg(debug_assertions)]
-                println!("[AnyChat] Allowing OAuth navigation to: {}", url_str);
-            }
-            true
-        })
-        .on_new_window(move |url, _features| {
-            #[cfg(debug_assertions)]
-            println!("[AnyChat] New window requested: {}", url);
+/// The live webview of a service: a child webview of the main window, or on Windows the
+/// webview of its docked window.
+fn service_webview(app: &tauri::AppHandle, service_id: &str) -> Option<tauri::Webview> {
+    #[cfg(target_os = "windows")]
+    {
+        app.get_webview_window(&service_window_label(service_id))
+            .map(|window| window.as_ref().clone())
+    }
 
-            if is_auth_url(url.as_str()) {
-                #[cfg(debug_assertions)]
-                println!("[AnyChat] Creating OAuth popup window");
-                open_oauth_popup(&app_handle_clone, &url);
-            }
+    #[cfg(not(target_os = "windows"))]
+    {
+        app.get_webview(service_id)
+    }
+}
 
-            handle_external_new_window(&app_handle_clone, &url)
-        });
+fn service_zoom_percent(app: &tauri::AppHandle, service_id: &str) -> u16 {
+    app.state::<AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(service_id)
+        .zoom_percent
+        .unwrap_or(zoom::DEFAULT_ZOOM_PERCENT)
+}
 
-    if should_inject_webview_compatibility_script(url) {
-        webview_builder = webview_builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
+/// Zoom is a property of the webview, so every newly built webview needs the stored factor.
+fn apply_service_zoom(app: &tauri::AppHandle, service_id: &str, webview: &tauri::Webview) {
+    let percent = service_zoom_percent(app, service_id);
+    if percent == zoom::DEFAULT_ZOOM_PERCENT {
+        return;
     }
+    if let Err(e) = webview.set_zoom(zoom::zoom_factor(percent)) {
+        println!("[AnyChat] Failed to apply zoom for {}: {}", service_id, e);
+    }
+}
 
-    println!("[AnyChat] create_webview_for_service: calling add_child");
-
-    let webview = window.add_child(webview_builder, pos, size).map_err(|e| {
-        println!("[AnyChat] add_child failed: {}", e);
-        e.to_string()
+fn zoom_service(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    action: zoom::ZoomAction,
+) -> Result<u16, String> {
+    let percent = zoom::next_zoom(service_zoom_percent(app, service_id), action);
+    update_service_registry(app, |registry| {
+        registry.settings_mut(service_id).zoom_percent =
+            (percent != zoom::DEFAULT_ZOOM_PERCENT).then_some(percent);
     })?;
 
-    // Some platforms/versions may not apply the initial bounds reliably. Enforce once right away.
-    let _ = webview.set_position(pos);
-    let _ = webview.set_size(size);
-
-    let mut created = state.created_webviews.lock().unwrap();
-    created.insert(label.to_string());
+    if let Some(webview) = service_webview(app, service_id) {
+        webview
+            .set_zoom(zoom::zoom_factor(percent))
+            .map_err(|e| e.to_string())?;
+    }
+    let _ = app.emit(
+        zoom::ZOOM_EVENT,
+        serde_json::json!({ "serviceId": service_id, "percent": percent }),
+    );
+    Ok(percent)
+}
 
-    println!("[AnyChat] Created webview: {} -> {}", label, url);
+fn open_service_find(app: &tauri::AppHandle, service_id: &str) -> Result<(), String> {
+    let webview = service_webview(app, service_id)
+        .ok_or_else(|| format!("Service webview {} not found", service_id))?;
+    webview
+        .eval(find::open_script())
+        .map_err(|e| e.to_string())?;
+    webview.set_focus().map_err(|e| e.to_string())
+}
 
-    #[cfg(debug_assertions)]
-    if should_open_devtools() {
-        if let Some(webview) = app.get_webview(label) {
-            let _ = webview.open_devtools();
-            println!("[AnyChat] DevTools opened for webview: {}", label);
+pub(crate) fn handle_service_shortcut(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    shortcut: shortcuts::ServiceShortcut,
+) {
+    let result = match shortcut {
+        shortcuts::ServiceShortcut::Zoom(action) => {
+            zoom_service(app, service_id, action).map(|_| ())
+        }
+        shortcuts::ServiceShortcut::Find => open_service_find(app, service_id),
+        shortcuts::ServiceShortcut::Navigate(direction) => {
+            navigate_service_history(app, service_id, direction)
         }
+    };
+    if let Err(e) = result {
+        println!("[AnyChat] Shortcut failed for {}: {}", service_id, e);
     }
+}
 
-    Ok(())
+fn load_user_scripts(app: &tauri::AppHandle, service_id: &str) -> user_scripts::UserScriptSet {
+    storage::data_dir(app)
+        .and_then(|data_dir| user_scripts::load_scripts(&data_dir, &data_vault(app), service_id))
+        .unwrap_or_else(|e| {
+            println!(
+                "[AnyChat] Failed to load user scripts for {}: {}",
+                service_id, e
+            );
+            user_scripts::UserScriptSet::default()
+        })
 }
 
-#[cfg(not(target_os = "windows"))]
-fn activate_child_webview_content(
-    // Use Window instead of WebviewWindow to avoid IPC failure in multi-webview windows.
-    parent: &tauri::Window,
-    app: &tauri::AppHandle,
-    label: &str,
-    url: &str,
-) -> Result<(), String> {
-    println!(
-        "[AnyChat] activate_child_webview_content called: label={}, url={}, parent={}",
-        label,
-        url,
-        parent.label()
-    );
+/// Scripts every service webview gets at creation, after the site compatibility shims.
+/// The script bridge and the scripts reporting through it follow the same per-host decision as
+/// the compatibility script; without them keep-alive pings and login probes stay silent.
+fn bridge_scripts(url: &str) -> Vec<String> {
+    if !should_inject_webview_compatibility_script(url) {
+        return Vec::new();
+    }
+    vec![
+        bridge::BRIDGE_SCRIPT.to_string(),
+        watchdog::HEARTBEAT_SCRIPT.to_string(),
+        app_lock::ACTIVITY_SCRIPT.to_string(),
+    ]
+}
 
+fn service_initialization_scripts(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+) -> Vec<String> {
     let state = app.state::<AppState>();
+    let user_scripts = load_user_scripts(app, &service.id);
+    state
+        .user_script_revisions
+        .lock()
+        .unwrap()
+        .insert(service.id.clone(), user_scripts.revision);
 
-    {
-        let setup_complete = state.setup_complete.lock().unwrap();
-        if !*setup_complete {
-            println!("[AnyChat] Setup not complete yet, skipping activate_child_webview_content");
-            return Ok(());
-        }
+    let mut scripts = bridge_scripts(&service.url);
+    if let Some(scheme) = service_color_scheme(app, &service.id) {
+        scripts.push(theme::initialization_script(Some(scheme)));
     }
-
-    if app.get_webview(label).is_none() {
-        println!("[AnyChat] Webview {} not found, creating...", label);
-        match create_webview_for_service(app, label, url, &state, parent) {
-            Ok(_) => println!("[AnyChat] Successfully created webview: {}", label),
-            Err(e) => {
-                println!("[AnyChat] ERROR creating webview {}: {}", label, e);
-                return Err(e);
-            }
+    scripts.extend(user_scripts::initialization_scripts(&user_scripts));
+
+    let mut baked = state.content_blocking_baked.lock().unwrap();
+    baked.remove(&service.id);
+    if service_content_blocking_enabled(app, service) {
+        let host = service_host(&service.url).unwrap_or_default();
+        let filters = state.content_filters.lock().unwrap().clone();
+        let mut selectors = filters.generic_selectors(&host);
+        selectors.extend(filters.host_selectors(&host));
+        if !selectors.is_empty() {
+            scripts.push(content_blocking::initialization_script(
+                &content_blocking::cosmetic_css(&selectors),
+            ));
+            baked.insert(service.id.clone());
         }
-    } else {
-        println!("[AnyChat] Webview {} already exists", label);
     }
+    scripts
+}
 
-    if let Some(webview) = app.get_webview(label) {
-        {
-            let created = state.created_webviews.lock().unwrap();
-            for existing_label in created.iter() {
-                if existing_label == label {
-                    continue;
-                }
-
-                if let Some(existing_webview) = app.get_webview(existing_label) {
-                    let _ = existing_webview.hide();
-                }
-            }
-        }
+fn service_color_scheme(app: &tauri::AppHandle, service_id: &str) -> Option<theme::ColorScheme> {
+    let state = app.state::<AppState>();
+    let app_theme = state.settings.lock().unwrap().theme;
+    let service_theme = state
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(service_id)
+        .theme;
+    theme::effective_scheme(service_theme, app_theme)
+}
 
-        if let Ok((pos, size)) = compute_webview_bounds(&parent) {
-            let _ = webview.set_position(pos);
-            let _ = webview.set_size(size);
-        }
-        let _ = webview.show();
-        let _ = webview.set_focus();
-        println!(
-            "[AnyChat] activate_child_webview_content: showing webview {}",
-            label
-        );
-    } else {
+fn apply_service_theme(app: &tauri::AppHandle, service_id: &str, webview: &tauri::Webview) {
+    let scheme = service_color_scheme(app, service_id);
+    if let Err(e) = webview.eval(theme::update_script(scheme)) {
         println!(
-            "[AnyChat] activate_child_webview_content: failed to get webview after creation: {}",
-            label
+            "[AnyChat] Failed to apply {} theme to {}: {}",
+            theme::scheme_name(scheme),
+            service_id,
+            e
         );
     }
-
-    Ok(())
 }
 
-#[tauri::command]
-async fn activate_service_content(
+/// Ids of services that currently have a webview.
+fn live_service_ids(app: &tauri::AppHandle) -> Vec<String> {
+    let state = app.state::<AppState>();
+
+    #[cfg(target_os = "windows")]
+    let service_ids = state
+        .windows_service_hosts
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+
+    #[cfg(not(target_os = "windows"))]
+    let service_ids = state
+        .created_webviews
+        .lock()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect();
+
+    service_ids
+}
+
+fn service_content_blocking_enabled(app: &tauri::AppHandle, service: &ServiceHostPayload) -> bool {
+    let setting = app
+        .state::<AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(&service.id)
+        .content_blocking;
+    content_blocking_enabled(service.kind, &service.url, setting)
+}
+
+/// Top-level navigations can be cancelled on every platform, so ad redirects are stopped even
+/// where subresource requests cannot be intercepted.
+fn is_blocked_content_navigation(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    url: &tauri::Url,
+) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+
+    let blocked = app
+        .state::<AppState>()
+        .content_filters
+        .lock()
+        .unwrap()
+        .is_blocked_host(host)
+        && service_content_blocking_enabled(app, service);
+    if blocked {
+        println!("[AnyChat] Blocked navigation to filtered host: {}", url);
+    }
+    blocked
+}
+
+/// Applies the service's content blocking setting to a live webview: request blocking, plus
+/// element hiding for the page currently at `page_url`.
+fn sync_content_blocking(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    webview: &tauri::Webview,
+    page_url: &tauri::Url,
+    include_network: bool,
+) {
+    let state = app.state::<AppState>();
+    let enabled = service_content_blocking_enabled(app, service);
+    let filters = state.content_filters.lock().unwrap().clone();
+
+    if include_network {
+        match app.path().app_cache_dir() {
+            Ok(cache_dir) => content_blocking::apply_network_filter(
+                webview,
+                filters.clone(),
+                cache_dir.join(content_blocking::CONTENT_FILTERS_DIR),
+                enabled,
+            ),
+            Err(e) => println!("[AnyChat] Content filter cache unavailable: {}", e),
+        }
+    }
+
+    let baked = state
+        .content_blocking_baked
+        .lock()
+        .unwrap()
+        .contains(&service.id);
+    if !enabled && !baked {
+        return;
+    }
+
+    let host = page_url.host_str().unwrap_or_default();
+    let generic_css =
+        (!baked).then(|| content_blocking::cosmetic_css(&filters.generic_selectors(host)));
+    let host_css = content_blocking::cosmetic_css(&filters.host_selectors(host));
+    let _ = webview.eval(content_blocking::sync_script(
+        enabled,
+        generic_css.as_deref(),
+        &host_css,
+    ));
+}
+
+fn handle_service_page_load(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    webview: &tauri::Webview,
+    payload: &tauri::webview::PageLoadPayload<'_>,
+) {
+    if payload.event() == tauri::webview::PageLoadEvent::Started {
+        emit_lifecycle_event(
+            app,
+            &service.id,
+            lifecycle::LifecycleEvent::NavigationStarted {
+                url: payload.url().to_string(),
+            },
+        );
+        return;
+    }
+
+    let baked_revision = app
+        .state::<AppState>()
+        .user_script_revisions
+        .lock()
+        .unwrap()
+        .get(&service.id)
+        .copied()
+        .unwrap_or_default();
+    let user_scripts = load_user_scripts(app, &service.id);
+    if user_scripts.revision > baked_revision {
+        let _ = webview.eval(user_scripts::page_load_script(&user_scripts));
+    }
+
+    apply_service_theme(app, &service.id, webview);
+    sync_content_blocking(app, service, webview, payload.url(), false);
+
+    let title = service_status(app, &service.id).title;
+    emit_lifecycle_event(
+        app,
+        &service.id,
+        lifecycle::LifecycleEvent::PageLoaded {
+            url: payload.url().to_string(),
+            title,
+        },
+    );
+    check_service_login(app, service, webview, payload.url());
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let navigation_state = app
+            .state::<AppState>()
+            .navigation_history
+            .lock()
+            .unwrap()
+            .entry(service.id.clone())
+            .or_default()
+            .record(payload.url().as_str());
+        publish_navigation_state(app, &service.id, navigation_state);
+    }
+}
+
+fn service_status(app: &tauri::AppHandle, service_id: &str) -> lifecycle::ServiceStatus {
+    app.state::<AppState>()
+        .service_statuses
+        .lock()
+        .unwrap()
+        .get(service_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub(crate) fn emit_lifecycle_event(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    event: lifecycle::LifecycleEvent,
+) {
+    let changed = app
+        .state::<AppState>()
+        .service_statuses
+        .lock()
+        .unwrap()
+        .entry(service_id.to_string())
+        .or_default()
+        .apply(&event);
+    if changed {
+        let _ = app.emit(
+            lifecycle::LIFECYCLE_EVENT,
+            lifecycle::ServiceLifecycleEvent {
+                service_id,
+                private: false,
+                event: &event,
+            },
+        );
+    }
+}
+
+/// Private sessions have no tracked status; every event is forwarded.
+fn emit_private_lifecycle_event(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    event: lifecycle::LifecycleEvent,
+) {
+    let _ = app.emit(
+        lifecycle::LIFECYCLE_EVENT,
+        lifecycle::ServiceLifecycleEvent {
+            service_id,
+            private: true,
+            event: &event,
+        },
+    );
+}
+
+/// Renders the error page for a failed load and marks it so its own load is not reported. The
+/// page retries the last URL that loaded, falling back to the failing one.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn service_load_failure_page(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    service_name: &str,
+    failing_url: &str,
+    reason: &str,
+) -> String {
+    let state = app.state::<AppState>();
+    let mut statuses = state.service_statuses.lock().unwrap();
+    let status = statuses.entry(service_id.to_string()).or_default();
+    status.showing_error_page = true;
+    println!(
+        "[AnyChat] Load failed for {} ({}), attempt {}: {}",
+        failing_url, service_id, status.failed_attempts, reason
+    );
+    lifecycle::load_failure_page_html(
+        service_name,
+        reason,
+        status.url.as_deref().unwrap_or(failing_url),
+        lifecycle::retry_delay(status.failed_attempts),
+    )
+}
+
+fn set_service_visible(app: &tauri::AppHandle, service_id: &str, visible: bool) {
+    if visible {
+        app.state::<AppState>()
+            .watchdog
+            .lock()
+            .unwrap()
+            .grace(service_id, Instant::now());
+    }
+    let event = if visible {
+        lifecycle::LifecycleEvent::Shown
+    } else {
+        lifecycle::LifecycleEvent::Hidden
+    };
+    emit_lifecycle_event(app, service_id, event);
+}
+
+/// Starts navigation tracking for a freshly built webview; history of a previous webview of the
+/// same service is gone with it.
+fn track_service_navigation(app: &tauri::AppHandle, service_id: &str, webview: &tauri::Webview) {
+    let state = app.state::<AppState>();
+    state.navigation_states.lock().unwrap().remove(service_id);
+    #[cfg(not(target_os = "linux"))]
+    state.navigation_history.lock().unwrap().remove(service_id);
+    navigation::attach_navigation_tracking(app, webview, service_id);
+}
+
+pub(crate) fn publish_navigation_state(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    navigation_state: navigation::NavigationState,
+) {
+    let previous = app
+        .state::<AppState>()
+        .navigation_states
+        .lock()
+        .unwrap()
+        .insert(service_id.to_string(), navigation_state);
+    if previous != Some(navigation_state) {
+        let _ = app.emit(
+            navigation::NAVIGATION_EVENT,
+            serde_json::json!({
+                "serviceId": service_id,
+                "canGoBack": navigation_state.can_go_back,
+                "canGoForward": navigation_state.can_go_forward,
+            }),
+        );
+    }
+}
+
+fn navigate_service_history(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    direction: navigation::HistoryDirection,
+) -> Result<(), String> {
+    let webview = service_webview(app, service_id)
+        .ok_or_else(|| format!("Service webview {} not found", service_id))?;
+    webview.eval(direction.script()).map_err(|e| e.to_string())
+}
+
+/// Pages keep firing events in the background, so only input in the service on screen while
+/// AnyChat has focus keeps the app unlocked.
+fn is_user_activity(
+    active_service_id: Option<&str>,
+    service_id: &str,
+    window_shown: bool,
+    app_focused: bool,
+) -> bool {
+    active_service_id == Some(service_id) && window_shown && app_focused
+}
+
+fn service_activity_counts(app: &tauri::AppHandle, service_id: &str) -> bool {
+    let active_service_id = app
+        .state::<AppState>()
+        .active_service_id
+        .lock()
+        .unwrap()
+        .clone();
+    let window_shown = service_webview(app, service_id).is_some_and(|webview| {
+        let window = webview.window();
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(true)
+    });
+    let app_focused = app
+        .windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false));
+    is_user_activity(
+        active_service_id.as_deref(),
+        service_id,
+        window_shown,
+        app_focused,
+    )
+}
+
+fn handle_bridge_message(app: &tauri::AppHandle, service_id: &str, message: bridge::BridgeMessage) {
+    match message {
+        bridge::BridgeMessage::Heartbeat => {
+            app.state::<AppState>()
+                .watchdog
+                .lock()
+                .unwrap()
+                .heartbeat(service_id, Instant::now());
+        }
+        bridge::BridgeMessage::Activity => {
+            if service_activity_counts(app, service_id) {
+                app.state::<AppState>()
+                    .app_lock
+                    .lock()
+                    .unwrap()
+                    .touch(Instant::now());
+            }
+        }
+        bridge::BridgeMessage::KeepAlive {
+            status,
+            url,
+            redirected,
+            error,
+        } => record_keep_alive(app, service_id, status, &url, redirected, error),
+        bridge::BridgeMessage::LoginProbe { signed_in } => {
+            update_login_state(app, service_id, |signals| {
+                signals.dom_signed_in = Some(signed_in)
+            });
+        }
+        bridge::BridgeMessage::UserScriptError {
+            script_id,
+            message,
+            url,
+        } => {
+            let line = user_scripts::error_log_line(
+                native_chat::now_millis(),
+                service_id,
+                &script_id,
+                &url,
+                &message,
+            );
+            println!("[AnyChat] User script error: {}", line);
+            let logged = storage::data_file_path(app, user_scripts::USER_SCRIPTS_LOG)
+                .and_then(|path| storage::append_line(&path, &line, storage::MAX_LOG_BYTES));
+            if let Err(e) = logged {
+                println!("[AnyChat] Failed to write user script log: {}", e);
+            }
+        }
+    }
+}
+
+/// Bridge messages from service pages where no WebKit message handler is available.
+#[tauri::command]
+fn report_bridge_message(app: tauri::AppHandle, webview: tauri::Webview, message: String) {
+    #[cfg(target_os = "windows")]
+    let service_id = webview.label().strip_prefix("svc_").unwrap_or_default();
+    #[cfg(not(target_os = "windows"))]
+    let service_id = webview.label();
+    // Only service webviews report; anything else calling in is ignored.
+    if service_webview(&app, service_id).is_none_or(|service| service.label() != webview.label()) {
+        return;
+    }
+    match bridge::parse_message(&message) {
+        Ok(message) => handle_bridge_message(&app, service_id, message),
+        Err(e) => println!("[AnyChat] {} ({})", e, service_id),
+    }
+}
+
+/// Rebuilds a crashed or hung service webview and reopens the last page it loaded.
+pub(crate) fn recover_service_webview(app: &tauri::AppHandle, service_id: &str, reason: &str) {
+    let state = app.state::<AppState>();
+    if !state
+        .watchdog
+        .lock()
+        .unwrap()
+        .begin_recovery(service_id, Instant::now())
+    {
+        println!(
+            "[AnyChat] Not recovering {} again so soon ({})",
+            service_id, reason
+        );
+        return;
+    }
+
+    let status = service_status(app, service_id);
+    let line = format!(
+        "{} [{}] {}; reopening {}",
+        native_chat::now_millis(),
+        service_id,
+        reason,
+        status.url.as_deref().unwrap_or("the service home")
+    );
+    println!("[AnyChat] Watchdog: {}", line);
+    let logged = storage::data_file_path(app, watchdog::WATCHDOG_LOG)
+        .and_then(|path| storage::append_line(&path, &line, storage::MAX_LOG_BYTES));
+    if let Err(e) = logged {
+        println!("[AnyChat] Failed to write watchdog log: {}", e);
+    }
+
+    emit_lifecycle_event(
+        app,
+        service_id,
+        lifecycle::LifecycleEvent::Crashed {
+            reason: reason.to_string(),
+        },
+    );
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = rebuild_service_webview(&app, &service_id, status.visible)
+            .await
+            .and_then(
+                |webview| match status.url.as_deref().map(str::parse::<tauri::Url>) {
+                    Some(Ok(url)) => webview.navigate(url).map_err(|e| e.to_string()),
+                    _ => Ok(()),
+                },
+            );
+        if let Err(e) = result {
+            println!("[AnyChat] Failed to recover {}: {}", service_id, e);
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+async fn rebuild_service_webview(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    visible: bool,
+) -> Result<tauri::Webview, String> {
+    let host = app
+        .state::<AppState>()
+        .windows_service_hosts
+        .lock()
+        .unwrap()
+        .remove(service_id)
+        .ok_or_else(|| format!("No service host for {}", service_id))?;
+    if let Some(window) = app.get_webview_window(&host.window_label) {
+        let _ = window.destroy();
+    }
+    // The label stays taken until the old window is gone.
+    for _ in 0..40 {
+        if app.get_webview_window(&host.window_label).is_none() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    emit_lifecycle_event(app, service_id, lifecycle::LifecycleEvent::Destroyed);
+
+    let service = ServiceHostPayload {
+        id: host.service_id.clone(),
+        name: host.name.clone(),
+        url: host.url.clone(),
+        enabled: true,
+        kind: host.kind,
+    };
+    let main_window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let state = app.state::<AppState>();
+    ensure_windows_service_host(app, &state, &main_window, &service)?;
+    if visible {
+        show_active_windows_service_host(app, &state)?;
+    }
+    service_webview(app, service_id)
+        .ok_or_else(|| format!("Service webview {} not found", service_id))
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn rebuild_service_webview(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    visible: bool,
+) -> Result<tauri::Webview, String> {
+    let state = app.state::<AppState>();
+    let service = state
+        .service_payloads
+        .lock()
+        .unwrap()
+        .get(service_id)
+        .cloned()
+        .ok_or_else(|| format!("No service payload for {}", service_id))?;
+    if let Some(webview) = app.get_webview(service_id) {
+        let _ = webview.close();
+    }
+    state.created_webviews.lock().unwrap().remove(service_id);
+    emit_lifecycle_event(app, service_id, lifecycle::LifecycleEvent::Destroyed);
+
+    let window = app
+        .get_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    create_webview_for_service(app, &service, &state, &window)?;
+    let webview = app
+        .get_webview(service_id)
+        .ok_or_else(|| format!("Service webview {} not found", service_id))?;
+    if visible && !app_locked(app) {
+        if let Ok((pos, size)) = compute_webview_bounds(&window) {
+            let _ = webview.set_position(pos);
+            let _ = webview.set_size(size);
+        }
+        let _ = webview.show();
+        set_service_visible(app, service_id, true);
+    } else {
+        let _ = webview.hide();
+    }
+    Ok(webview)
+}
+
+fn live_service_payload(app: &tauri::AppHandle, service_id: &str) -> Option<ServiceHostPayload> {
+    let state = app.state::<AppState>();
+
+    #[cfg(target_os = "windows")]
+    let service = state
+        .windows_service_hosts
+        .lock()
+        .unwrap()
+        .get(service_id)
+        .map(|host| ServiceHostPayload {
+            id: host.service_id.clone(),
+            name: host.name.clone(),
+            url: host.url.clone(),
+            enabled: true,
+            kind: host.kind,
+        });
+
+    #[cfg(not(target_os = "windows"))]
+    let service = state
+        .service_payloads
+        .lock()
+        .unwrap()
+        .get(service_id)
+        .cloned();
+
+    service
+}
+
+pub(crate) fn request_service_refresh(app: &tauri::AppHandle, reason: &str) {
+    println!("[AnyChat] Scheduling service refresh: {}", reason);
+    app.state::<AppState>()
+        .refresh_scheduler
+        .lock()
+        .unwrap()
+        .request(Instant::now());
+}
+
+/// Reloads every live service at the page it is on, hidden ones included.
+fn refresh_live_services(app: &tauri::AppHandle) {
+    for service_id in live_service_ids(app) {
+        if service_status(app, &service_id).loading {
+            continue;
+        }
+        let (Some(webview), Some(service)) = (
+            service_webview(app, &service_id),
+            live_service_payload(app, &service_id),
+        ) else {
+            continue;
+        };
+
+        let refresh_url = resolve_windows_refresh_url(
+            webview
+                .url()
+                .ok()
+                .map(|current_url| current_url.to_string()),
+            &service,
+        );
+        match refresh_url.parse::<tauri::Url>() {
+            Ok(url) => {
+                let _ = webview.navigate(url);
+            }
+            Err(e) => println!("[AnyChat] Cannot refresh {}: {}", service_id, e),
+        }
+    }
+}
+
+/// Polls for suspends and connectivity changes the system signals may miss, and runs the
+/// debounced refresh.
+fn start_resume_monitor(app: &tauri::AppHandle) {
+    resume::watch_system_signals(app);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last_wall = std::time::SystemTime::now();
+        let mut last_tick = Instant::now();
+        let mut network = resume::network_available();
+        loop {
+            std::thread::sleep(resume::POLL_INTERVAL);
+
+            let wall_elapsed = last_wall.elapsed().unwrap_or_default();
+            if resume::slept(wall_elapsed, last_tick.elapsed()) {
+                request_service_refresh(&app, "suspend detected");
+            }
+            last_wall = std::time::SystemTime::now();
+            last_tick = Instant::now();
+
+            let available = resume::network_available();
+            if network == Some(false) && available == Some(true) {
+                request_service_refresh(&app, "default route appeared");
+            }
+            network = available;
+
+            let due = app
+                .state::<AppState>()
+                .refresh_scheduler
+                .lock()
+                .unwrap()
+                .take_due(Instant::now());
+            if due {
+                refresh_live_services(&app);
+            }
+        }
+    });
+}
+
+fn service_login_rules(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+) -> login_state::LoginRules {
+    app.state::<AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(&service.id)
+        .login_rules
+        .unwrap_or_else(|| {
+            login_state::default_rules(&service_host(&service.url).unwrap_or_default())
+        })
+}
+
+fn update_login_state(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    change: impl FnOnce(&mut login_state::LoginSignals),
+) {
+    let changed = app
+        .state::<AppState>()
+        .login_states
+        .lock()
+        .unwrap()
+        .update(service_id, change);
+    if let Some(state) = changed {
+        println!("[AnyChat] Login state of {}: {:?}", service_id, state);
+        let _ = app.emit(
+            login_state::LOGIN_STATE_EVENT,
+            serde_json::json!({ "serviceId": service_id, "state": state }),
+        );
+    }
+}
+
+/// Re-evaluates the login state for the page at `url`. The URL check is immediate; the DOM
+/// probe and the cookie lookup report back later.
+fn check_service_login(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    webview: &tauri::Webview,
+    url: &tauri::Url,
+) {
+    let rules = service_login_rules(app, service);
+    let on_sign_in_page = login_state::is_sign_in_url(
+        url.as_str(),
+        &rules,
+        is_service_auth_popup(service.kind, &service.url, url),
+    );
+    let probe = login_state::probe_script(&rules);
+    update_login_state(app, &service.id, |signals| {
+        signals.on_sign_in_page = on_sign_in_page;
+        signals.keep_alive_signed_out = false;
+        if probe.is_none() {
+            signals.dom_signed_in = None;
+        }
+        if rules.session_cookie.is_none() {
+            signals.session_cookie = None;
+        }
+    });
+    if on_sign_in_page {
+        return;
+    }
+
+    if let Some(script) = probe {
+        let _ = webview.eval(script);
+    }
+
+    // Reading cookies deadlocks WebView2 when done from an event handler.
+    if let Some(cookie_name) = rules.session_cookie {
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        let app = app.clone();
+        let service_id = service.id.clone();
+        let webview = webview.clone();
+        let url = url.clone();
+        std::thread::spawn(move || match webview.cookies_for_url(url) {
+            Ok(cookies) => {
+                let present = cookies.iter().any(|cookie| cookie.name() == cookie_name);
+                update_login_state(&app, &service_id, |signals| {
+                    signals.session_cookie = Some(present)
+                });
+            }
+            Err(e) => println!("[AnyChat] Cookie check failed for {}: {}", service_id, e),
+        });
+    }
+}
+
+fn record_keep_alive(
+    app: &tauri::AppHandle,
+    service_id: &str,
+    http_status: u16,
+    url: &str,
+    redirected: bool,
+    error: Option<String>,
+) {
+    let status = keep_alive::KeepAliveStatus {
+        outcome: keep_alive::outcome(http_status, url, redirected, is_auth_url),
+        http_status,
+        error,
+        checked_at: native_chat::now_millis(),
+    };
+    let changed = app
+        .state::<AppState>()
+        .keep_alive
+        .lock()
+        .unwrap()
+        .record(service_id, status.clone());
+    update_login_state(app, service_id, |signals| {
+        signals.keep_alive_signed_out = status.outcome == keep_alive::KeepAliveOutcome::SignedOut
+    });
+    if changed {
+        println!(
+            "[AnyChat] Keep-alive for {}: {:?} (HTTP {})",
+            service_id, status.outcome, http_status
+        );
+    }
+    let _ = app.emit(
+        keep_alive::KEEP_ALIVE_EVENT,
+        serde_json::json!({ "serviceId": service_id, "status": status }),
+    );
+}
+
+/// Pings live services that have keep-alive enabled once their interval has passed.
+fn start_keep_alive(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(keep_alive::CHECK_INTERVAL);
+
+        for service_id in live_service_ids(&app) {
+            let state = app.state::<AppState>();
+            let Some(settings) = state
+                .service_registry
+                .lock()
+                .unwrap()
+                .settings(&service_id)
+                .keep_alive
+            else {
+                continue;
+            };
+            let status = service_status(&app, &service_id);
+            if status.loading || status.showing_error_page || status.error.is_some() {
+                continue;
+            }
+            let due = state.keep_alive.lock().unwrap().take_due(
+                &service_id,
+                settings.interval(),
+                Instant::now(),
+            );
+            if !due {
+                continue;
+            }
+            if let Some(webview) = service_webview(&app, &service_id) {
+                if let Err(e) = webview.eval(keep_alive::ping_script(&settings.path)) {
+                    println!("[AnyChat] Keep-alive ping failed for {}: {}", service_id, e);
+                }
+            }
+        }
+    });
+}
+
+/// Periodically rebuilds visible webviews whose heartbeat stopped.
+fn start_service_watchdog(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(watchdog::CHECK_INTERVAL);
+
+        let unresponsive = {
+            let state = app.state::<AppState>();
+            let statuses = state.service_statuses.lock().unwrap().clone();
+            let watchdog = state.watchdog.lock().unwrap();
+            watchdog.unresponsive(Instant::now(), |service_id| {
+                statuses.get(service_id).is_some_and(|status| {
+                    status.visible && !status.loading && !status.showing_error_page
+                })
+            })
+        };
+        for service_id in unresponsive {
+            recover_service_webview(&app, &service_id, "page stopped responding");
+        }
+    });
+}
+
+#[cfg(not(target_os = "windows"))]
+fn create_webview_for_service(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    state: &AppState,
+    // Use Window here because the main window hosts multiple webviews (add_child).
+    window: &tauri::Window,
+) -> Result<(), String> {
+    let label = service.id.as_str();
+    let url = service.url.as_str();
+    println!(
+        "[AnyChat] create_webview_for_service: creating {} -> {}",
+        label, url
+    );
+
+    let (pos, size) = compute_webview_bounds(window)?;
+
+    let app_handle_clone = app.clone();
+    let page_load_app = app.clone();
+    let page_load_service = service.clone();
+    let title_app = app.clone();
+    let title_service_id = service.id.clone();
+    let navigation_app = app.clone();
+    let navigation_service = service.clone();
+    let parsed_url: tauri::Url = url.parse().map_err(|e| format!("{}", e))?;
+    let kind = service.kind;
+    let service_url = service.url.clone();
+
+    let mut webview_builder = WebviewBuilder::new(label, WebviewUrl::External(parsed_url))
+        .with_policy(service_policy(app), effective_proxy(app))
+        .on_navigation(move |url| {
+            allow_service_navigation(kind, url)
+                && policy_allows_navigation(&navigation_app, url)
+                && !is_blocked_content_navigation(&navigation_app, &navigation_service, url)
+        })
+        .on_page_load(move |webview, payload| {
+            handle_service_page_load(&page_load_app, &page_load_service, &webview, &payload)
+        })
+        .on_document_title_changed(move |_, title| {
+            emit_lifecycle_event(
+                &title_app,
+                &title_service_id,
+                lifecycle::LifecycleEvent::TitleChanged { title },
+            )
+        })
+        .on_new_window(move |url, _features| {
+            #[cfg(debug_assertions)]
+            println!("[AnyChat] New window requested: {}", url);
+
+            let is_auth_popup = is_service_auth_popup(kind, &service_url, &url)
+                && policy_allows_navigation(&app_handle_clone, &url);
+            if is_auth_popup {
+                #[cfg(debug_assertions)]
+                println!("[AnyChat] Creating OAuth popup window");
+                open_oauth_popup(&app_handle_clone, &url);
+            }
+
+            handle_external_new_window(&app_handle_clone, &url, is_auth_popup)
+        });
+
+    if kind.uses_site_quirks() {
+        webview_builder = webview_builder.user_agent(USER_AGENT);
+
+        if should_inject_webview_compatibility_script(url) {
+            webview_builder = webview_builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
+        }
+    }
+
+    for script in service_initialization_scripts(app, service) {
+        webview_builder = webview_builder.initialization_script(&script);
+    }
+
+    println!("[AnyChat] create_webview_for_service: calling add_child");
+
+    let webview = window.add_child(webview_builder, pos, size).map_err(|e| {
+        println!("[AnyChat] add_child failed: {}", e);
+        e.to_string()
+    })?;
+
+    // Some platforms/versions may not apply the initial bounds reliably. Enforce once right away.
+    let _ = webview.set_position(pos);
+    let _ = webview.set_size(size);
+    tls::attach_tls_error_handler(app, &webview, label);
+    bridge::attach_script_bridge(app, &webview, label);
+    shortcuts::attach_service_shortcuts(app, &webview, label);
+    emit_lifecycle_event(app, label, lifecycle::LifecycleEvent::Created);
+    track_service_navigation(app, label, &webview);
+    lifecycle::attach_load_failure_tracking(app, &webview, label, &service.name);
+    watchdog::attach_crash_detection(app, &webview, label);
+    apply_service_zoom(app, label, &webview);
+    if let Ok(page_url) = webview.url() {
+        sync_content_blocking(app, service, &webview, &page_url, true);
+    }
+    if kind == ServiceKind::SelfHosted {
+        self_hosted::attach_basic_auth_prompt(&webview);
+    }
+
+    state
+        .service_payloads
+        .lock()
+        .unwrap()
+        .insert(label.to_string(), service.clone());
+    let mut created = state.created_webviews.lock().unwrap();
+    created.insert(label.to_string());
+
+    println!("[AnyChat] Created webview: {} -> {}", label, url);
+
+    #[cfg(debug_assertions)]
+    if should_open_devtools() {
+        if let Some(webview) = app.get_webview(label) {
+            webview.open_devtools();
+            println!("[AnyChat] DevTools opened for webview: {}", label);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn activate_child_webview_content(
+    // Use Window instead of WebviewWindow to avoid IPC failure in multi-webview windows.
+    parent: &tauri::Window,
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+) -> Result<(), String> {
+    let label = service.id.as_str();
+    println!(
+        "[AnyChat] activate_child_webview_content called: label={}, url={}, parent={}",
+        label,
+        service.url,
+        parent.label()
+    );
+
+    let state = app.state::<AppState>();
+
+    {
+        let setup_complete = state.setup_complete.lock().unwrap();
+        if !*setup_complete {
+            println!("[AnyChat] Setup not complete yet, skipping activate_child_webview_content");
+            return Ok(());
+        }
+    }
+
+    if app_locked(app) {
+        println!("[AnyChat] App locked, not showing {}", label);
+        return Ok(());
+    }
+
+    if app.get_webview(label).is_none() {
+        println!("[AnyChat] Webview {} not found, creating...", label);
+        match create_webview_for_service(app, service, &state, parent) {
+            Ok(_) => println!("[AnyChat] Successfully created webview: {}", label),
+            Err(e) => {
+                println!("[AnyChat] ERROR creating webview {}: {}", label, e);
+                return Err(e);
+            }
+        }
+    } else {
+        println!("[AnyChat] Webview {} already exists", label);
+    }
+
+    if let Some(webview) = app.get_webview(label) {
+        {
+            let created = state.created_webviews.lock().unwrap();
+            for existing_label in created.iter() {
+                if existing_label == label {
+                    continue;
+                }
+
+                if let Some(existing_webview) = app.get_webview(existing_label) {
+                    let _ = existing_webview.hide();
+                    set_service_visible(app, existing_label, false);
+                }
+            }
+        }
+
+        if let Ok((pos, size)) = compute_webview_bounds(parent) {
+            let _ = webview.set_position(pos);
+            let _ = webview.set_size(size);
+        }
+        let _ = webview.show();
+        let _ = webview.set_focus();
+        set_service_visible(app, label, true);
+        println!(
+            "[AnyChat] activate_child_webview_content: showing webview {}",
+            label
+        );
+    } else {
+        println!(
+            "[AnyChat] activate_child_webview_content: failed to get webview after creation: {}",
+            label
+        );
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn activate_service_content(
     parent: tauri::Window,
     app: tauri::AppHandle,
-    service: ServiceHostPayload,
-    _services: Option<Vec<ServiceHostPayload>>,
+    service: ServiceHostPayload,
+    _services: Option<Vec<ServiceHostPayload>>,
+) -> Result<(), String> {
+    if service.kind == ServiceKind::SelfHosted {
+        self_hosted::validate_service_url(&service.url)?;
+    }
+    service_policy(&app).check_service(&service.id, &service.url)?;
+
+    *app.state::<AppState>().active_service_id.lock().unwrap() =
+        service.kind.has_webview().then(|| service.id.clone());
+
+    if !service.kind.has_webview() {
+        #[cfg(target_os = "windows")]
+        set_active_windows_service_id(&app.state::<AppState>(), None);
+        return hide_all_service_content(app);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let state = app.state::<AppState>();
+        let tracked_services = tracked_services_or_current(_services, &service);
+        let main_window = app
+            .get_webview_window(parent.label())
+            .ok_or_else(|| format!("Main window {} not found", parent.label()))?;
+
+        {
+            let setup_complete = state.setup_complete.lock().unwrap();
+            if !*setup_complete {
+                println!("[AnyChat] Setup not complete yet, skipping activate_service_content");
+                return Ok(());
+            }
+        }
+
+        prune_disabled_windows_service_hosts(&app, &state, &tracked_services);
+        set_active_windows_service_id(&state, Some(service.id.clone()));
+        let _ = ensure_windows_service_host(&app, &state, &main_window, &service)?;
+        return show_active_windows_service_host(&app, &state);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        activate_child_webview_content(&parent, &app, &service)
+    }
+}
+
+#[tauri::command]
+fn refresh_service_content(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+    _services: Option<Vec<ServiceHostPayload>>,
+) -> Result<(), String> {
+    if !service.kind.has_webview() {
+        return Ok(());
+    }
+    service_policy(&app).check_service(&service.id, &service.url)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let state = app.state::<AppState>();
+        let tracked_services = tracked_services_or_current(_services, &service);
+
+        prune_disabled_windows_service_hosts(&app, &state, &tracked_services);
+        set_active_windows_service_id(&state, Some(service.id.clone()));
+
+        let main_window = app
+            .get_webview_window("main")
+            .ok_or_else(|| "Main window not found".to_string())?;
+        let host = ensure_windows_service_host(&app, &state, &main_window, &service)?;
+        let window = app
+            .get_webview_window(&host.window_label)
+            .ok_or_else(|| format!("Service window {} not found", host.window_label))?;
+        let refresh_url = resolve_windows_refresh_url(
+            window.url().ok().map(|current_url| current_url.to_string()),
+            &service,
+        );
+        let url: tauri::Url = refresh_url.parse().map_err(|e| format!("{}", e))?;
+        window.navigate(url).map_err(|e| e.to_string())?;
+        return show_active_windows_service_host(&app, &state);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(webview) = app.get_webview(&service.id) {
+            if let Ok(url) = webview.url() {
+                let _ = webview.navigate(url);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+fn hide_all_service_content(app: tauri::AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let state = app.state::<AppState>();
+        hide_windows_service_hosts(&app, &state);
+        return Ok(());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let state = app.state::<AppState>();
+        let created = state.created_webviews.lock().unwrap();
+
+        for label in created.iter() {
+            if let Some(webview) = app.get_webview(label) {
+                let _ = webview.hide();
+                set_service_visible(&app, label, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[tauri::command]
+fn sync_service_host_state(
+    app: tauri::AppHandle,
+    services: Vec<ServiceHostPayload>,
+    active_service_id: Option<String>,
+) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let state = app.state::<AppState>();
+        return sync_windows_service_host_state(&app, &state, &services, active_service_id);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, services, active_service_id);
+        Ok(())
+    }
+}
+
+#[tauri::command]
+fn sync_docked_content_layout(app: tauri::AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let state = app.state::<AppState>();
+        return sync_windows_service_host_layout(&app, &state);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app;
+        Ok(())
+    }
+}
+
+fn http_client(
+    app: &tauri::AppHandle,
+    timeout: std::time::Duration,
+) -> Result<reqwest::Client, String> {
+    let http_settings = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .http
+        .clone();
+    let http_settings = http::HttpSettings {
+        proxy: service_policy(app).proxy.clone().or(http_settings.proxy),
+        ..http_settings
+    };
+    http::build_http_client(&http_settings, USER_AGENT, timeout)
+}
+
+#[tauri::command]
+async fn discover_site_icon(app: tauri::AppHandle, url: String) -> Result<Option<String>, String> {
+    let parsed_url = reqwest::Url::parse(&url).map_err(|e| e.to_string())?;
+    let client = http_client(&app, std::time::Duration::from_secs(5))?;
+
+    let response = client
+        .get(parsed_url.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    let html = response.text().await.map_err(|e| e.to_string())?;
+    Ok(extract_site_icon_url(&parsed_url, &html))
+}
+
+fn extract_site_icon_url(base_url: &reqwest::Url, html: &str) -> Option<String> {
+    let link_tag_regex = Regex::new(r"(?is)<link\b[^>]*>").ok()?;
+    let rel_regex = Regex::new(r#"(?i)rel\s*=\s*["']([^"']+)["']"#).ok()?;
+    let href_regex = Regex::new(r#"(?i)href\s*=\s*["']([^"']+)["']"#).ok()?;
+
+    for tag_match in link_tag_regex.find_iter(html) {
+        let tag = tag_match.as_str();
+        let Some(rel_value) = rel_regex
+            .captures(tag)
+            .and_then(|captures| captures.get(1))
+            .map(|value| value.as_str().to_ascii_lowercase())
+        else {
+            continue;
+        };
+
+        if !rel_value.contains("icon") {
+            continue;
+        }
+
+        let Some(href) = href_regex
+            .captures(tag)
+            .and_then(|captures| captures.get(1))
+            .map(|value| value.as_str().trim())
+        else {
+            continue;
+        };
+
+        if href.is_empty() || href.starts_with("data:") {
+            continue;
+        }
+
+        if let Ok(resolved_url) = base_url.join(href) {
+            return Some(resolved_url.to_string());
+        }
+    }
+
+    None
+}
+
+fn load_persisted_state(app: &tauri::AppHandle, state: &AppState) {
+    match storage::data_file_path(app, SETTINGS_FILE)
+        .and_then(|path| storage::read_plain_json(&path))
+    {
+        Ok(settings) => *state.settings.lock().unwrap() = settings,
+        Err(e) => println!("[AnyChat] Failed to load settings: {}", e),
+    }
+
+    init_data_encryption(app);
+    load_encrypted_state(app, state);
+
+    match load_content_filters(app) {
+        Ok(filters) => *state.content_filters.lock().unwrap() = Arc::new(filters),
+        Err(e) => println!("[AnyChat] Failed to load content filters: {}", e),
+    }
+}
+
+/// State kept in files that may be encrypted; loaded again once the data is unlocked.
+fn load_encrypted_state(app: &tauri::AppHandle, state: &AppState) {
+    if data_locked(app) {
+        println!("[AnyChat] Data is locked, service registry not loaded yet");
+        return;
+    }
+
+    match storage::data_file_path(app, SERVICE_REGISTRY_FILE)
+        .and_then(|path| storage::read_json(&data_vault(app), &path))
+    {
+        Ok(registry) => *state.service_registry.lock().unwrap() = registry,
+        Err(e) => println!("[AnyChat] Failed to load service registry: {}", e),
+    }
+}
+
+fn load_content_filters(
+    app: &tauri::AppHandle,
+) -> Result<content_blocking::ContentFilters, String> {
+    content_blocking::load_filters(&storage::data_file_path(
+        app,
+        content_blocking::CONTENT_FILTERS_DIR,
+    )?)
+}
+
+fn save_app_settings(app: &tauri::AppHandle, settings: &AppSettings) -> Result<(), String> {
+    storage::write_plain_json(&storage::data_file_path(app, SETTINGS_FILE)?, settings)
+}
+
+fn update_service_registry<T>(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&mut ServiceRegistry) -> T,
+) -> Result<T, String> {
+    let state = app.state::<AppState>();
+    let mut registry = state.service_registry.lock().unwrap();
+    let result = update(&mut registry);
+    storage::write_json(
+        &data_vault(app),
+        &storage::data_file_path(app, SERVICE_REGISTRY_FILE)?,
+        &*registry,
+    )?;
+    Ok(result)
+}
+
+fn sync_status(app: &tauri::AppHandle) -> Result<sync::SyncStatus, String> {
+    let data_dir = storage::data_dir(app)?;
+    let state: sync::SyncState =
+        storage::read_json(&data_vault(app), &data_dir.join(sync::SYNC_STATE_FILE))?;
+    Ok(sync::SyncStatus {
+        settings: app
+            .state::<AppState>()
+            .settings
+            .lock()
+            .unwrap()
+            .sync
+            .clone(),
+        has_webdav_password: secrets::has_secret(
+            &data_dir,
+            &data_vault(app),
+            sync::WEBDAV_PASSWORD_SECRET,
+        )?,
+        has_passphrase: secrets::has_secret(&data_dir, &data_vault(app), sync::PASSPHRASE_SECRET)?,
+        last_synced_at: state.last_synced_at,
+    })
+}
+
+/// Items offered for sync. Default service settings and empty script lists are left out, so
+/// resetting them syncs as a deletion.
+fn local_sync_records(
+    app: &tauri::AppHandle,
+    data_dir: &std::path::Path,
+    services: &[serde_json::Value],
+) -> Result<std::collections::BTreeMap<String, serde_json::Value>, String> {
+    let mut records = std::collections::BTreeMap::new();
+    for service in services {
+        if let Some(id) = service.get("id").and_then(serde_json::Value::as_str) {
+            records.insert(
+                sync::record_key(sync::RecordKind::Service, id),
+                service.clone(),
+            );
+        }
+    }
+
+    let registry = app
+        .state::<AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .clone();
+    for (id, settings) in registry.services {
+        if settings != ServiceSettings::default() {
+            records.insert(
+                sync::record_key(sync::RecordKind::ServiceSettings, &id),
+                serde_json::to_value(settings).map_err(|e| e.to_string())?,
+            );
+        }
+    }
+
+    for (id, set) in user_scripts::load_all_scripts(data_dir, &data_vault(app))? {
+        if !set.scripts.is_empty() {
+            records.insert(
+                sync::record_key(sync::RecordKind::UserScripts, &id),
+                serde_json::to_value(set.scripts).map_err(|e| e.to_string())?,
+            );
+        }
+    }
+    Ok(records)
+}
+
+/// Returns the new service list when synced changes touched it.
+fn apply_sync_changes(
+    app: &tauri::AppHandle,
+    data_dir: &std::path::Path,
+    changes: Vec<sync::SyncChange>,
+    mut services: Vec<serde_json::Value>,
+) -> Result<Option<Vec<serde_json::Value>>, String> {
+    let mut services_changed = false;
+    for change in changes {
+        match change {
+            sync::SyncChange::Service { id, value } => {
+                services.retain(|service| {
+                    service.get("id").and_then(serde_json::Value::as_str) != Some(id.as_str())
+                });
+                services.extend(value);
+                services_changed = true;
+            }
+            sync::SyncChange::ServiceSettings { id, settings } => {
+                update_service_registry(app, |registry| {
+                    if settings == ServiceSettings::default() {
+                        registry.services.remove(&id);
+                    } else {
+                        registry.services.insert(id, settings);
+                    }
+                })?;
+            }
+            sync::SyncChange::UserScripts { id, scripts } => {
+                user_scripts::replace_scripts(data_dir, &data_vault(app), &id, scripts)?;
+            }
+        }
+    }
+
+    if !services_changed {
+        return Ok(None);
+    }
+    services.sort_by_key(|service| {
+        service
+            .get("order")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(i64::MAX)
+    });
+    Ok(Some(services))
+}
+
+async fn run_sync(
+    app: &tauri::AppHandle,
+    settings: &sync::SyncSettings,
+    services: Vec<serde_json::Value>,
+) -> Result<sync::SyncReport, String> {
+    let target = settings.target.as_ref().ok_or("Sync is not set up")?;
+    let data_dir = storage::data_dir(app)?;
+    let passphrase = if settings.encrypt {
+        Some(
+            secrets::get_secret(&data_dir, &data_vault(app), sync::PASSPHRASE_SECRET)?
+                .ok_or("Set a sync passphrase to encrypt synced data")?,
+        )
+    } else {
+        None
+    };
+    let backend = sync::SyncBackend::new(
+        target,
+        http_client(app, sync::SYNC_TIMEOUT)?,
+        secrets::get_secret(&data_dir, &data_vault(app), sync::WEBDAV_PASSWORD_SECRET)?,
+    )?;
+    let state_path = data_dir.join(sync::SYNC_STATE_FILE);
+
+    for _ in 0..sync::MAX_ATTEMPTS {
+        let snapshot = backend.read().await?;
+        let (remote, sealed) = match &snapshot {
+            Some(snapshot) => sync::decode_sync_file(&snapshot.bytes, passphrase.as_deref())?,
+            None => Default::default(),
+        };
+        let local = local_sync_records(app, &data_dir, &services)?;
+        let mut state: sync::SyncState = storage::read_json(&data_vault(app), &state_path)?;
+        let now = native_chat::now_millis();
+        let merged = sync::merge(&mut state, &local, &remote, now);
+        let changes = sync::parse_changes(&merged.apply)?;
+
+        let upload = merged.upload || snapshot.is_none() || sealed != passphrase.is_some();
+        if upload {
+            let bytes = sync::encode_sync_file(
+                &merged.remote,
+                passphrase.as_deref(),
+                secrets::KdfParams::default(),
+            )?;
+            if !backend.write(bytes, snapshot.as_ref()).await? {
+                println!("[AnyChat] Sync file changed during sync, retrying");
+                continue;
+            }
+        }
+
+        let applied = changes.len();
+        let services = apply_sync_changes(app, &data_dir, changes, services)?;
+        storage::write_json(&data_vault(app), &state_path, &state)?;
+        if !merged.conflicts.is_empty() {
+            let log_path = data_dir.join(sync::SYNC_CONFLICTS_FILE);
+            let mut log: Vec<sync::SyncConflict> = storage::read_json(&data_vault(app), &log_path)?;
+            sync::log_conflicts(&mut log, merged.conflicts.iter().cloned());
+            storage::write_json(&data_vault(app), &log_path, &log)?;
+        }
+        println!(
+            "[AnyChat] Synced: uploaded={}, applied={}, conflicts={}",
+            upload,
+            applied,
+            merged.conflicts.len()
+        );
+        return Ok(sync::SyncReport {
+            synced_at: now,
+            uploaded: upload,
+            applied,
+            conflicts: merged.conflicts,
+            services,
+        });
+    }
+    Err("The sync file kept changing during sync; try again later".to_string())
+}
+
+#[tauri::command]
+fn get_sync_status(app: tauri::AppHandle) -> Result<sync::SyncStatus, String> {
+    sync_status(&app)
+}
+
+/// `None` keeps a stored secret and an empty string removes it.
+#[tauri::command]
+fn set_sync_settings(
+    app: tauri::AppHandle,
+    settings: sync::SyncSettings,
+    webdav_password: Option<String>,
+    passphrase: Option<String>,
+) -> Result<sync::SyncStatus, String> {
+    if data_locked(&app) {
+        return Err("Unlock AnyChat's data first".to_string());
+    }
+    if let Some(target) = &settings.target {
+        target.validate()?;
+    }
+    if let Some(passphrase) = passphrase.as_deref().filter(|value| !value.is_empty()) {
+        encryption::validate_passphrase(passphrase)?;
+    }
+
+    let data_dir = storage::data_dir(&app)?;
+    let will_have_passphrase = match passphrase.as_deref() {
+        Some(passphrase) => !passphrase.is_empty(),
+        None => secrets::has_secret(&data_dir, &data_vault(&app), sync::PASSPHRASE_SECRET)?,
+    };
+    if settings.encrypt && !will_have_passphrase {
+        return Err("Set a sync passphrase to encrypt synced data".to_string());
+    }
+
+    for (name, value) in [
+        (sync::WEBDAV_PASSWORD_SECRET, webdav_password),
+        (sync::PASSPHRASE_SECRET, passphrase),
+    ] {
+        if let Some(value) = value {
+            secrets::set_secret(
+                &data_dir,
+                &data_vault(&app),
+                name,
+                Some(value.as_str()).filter(|v| !v.is_empty()),
+            )?;
+        }
+    }
+
+    let target_changed = {
+        let state = app.state::<AppState>();
+        let mut app_settings = state.settings.lock().unwrap();
+        let target_changed = app_settings.sync.target != settings.target;
+        app_settings.sync = settings;
+        save_app_settings(&app, &app_settings)?;
+        target_changed
+    };
+    if target_changed {
+        let state_path = data_dir.join(sync::SYNC_STATE_FILE);
+        let mut state: sync::SyncState = storage::read_json(&data_vault(&app), &state_path)?;
+        state.reset_remote();
+        storage::write_json(&data_vault(&app), &state_path, &state)?;
+    }
+    sync_status(&app)
+}
+
+/// Syncs once. `services` is the frontend's service list; when the report carries a new one,
+/// the frontend replaces its list with it.
+#[tauri::command]
+async fn sync_now(
+    app: tauri::AppHandle,
+    services: Vec<serde_json::Value>,
+) -> Result<sync::SyncReport, String> {
+    if app_locked(&app) {
+        return Err("AnyChat is locked".to_string());
+    }
+    let settings = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .sync
+        .clone();
+    {
+        let state = app.state::<AppState>();
+        let mut running = state.sync_running.lock().unwrap();
+        if *running {
+            return Err("A sync is already running".to_string());
+        }
+        *running = true;
+    }
+
+    let result = run_sync(&app, &settings, services).await;
+    *app.state::<AppState>().sync_running.lock().unwrap() = false;
+    if let Err(e) = &result {
+        println!("[AnyChat] Sync failed: {}", e);
+    }
+    result
+}
+
+/// Logged sync conflicts, oldest first.
+#[tauri::command]
+fn get_sync_conflicts(app: tauri::AppHandle) -> Result<Vec<sync::SyncConflict>, String> {
+    storage::read_json(
+        &data_vault(&app),
+        &storage::data_file_path(&app, sync::SYNC_CONFLICTS_FILE)?,
+    )
+}
+
+fn backup_roots(app: &tauri::AppHandle) -> Result<backup::BackupRoots, String> {
+    #[cfg(target_os = "macos")]
+    let webview_dir = None;
+    #[cfg(not(target_os = "macos"))]
+    let webview_dir = Some(app.path().app_local_data_dir().map_err(|e| e.to_string())?);
+    Ok(backup::BackupRoots {
+        data_dir: storage::data_dir(app)?,
+        webview_dir,
+    })
+}
+
+/// Writes a backup to `path`. `frontend` holds the frontend's service list and icon cache. With
+/// a `passphrase`, required while data encryption is on, the backup is encrypted.
+#[tauri::command]
+async fn create_backup(
+    app: tauri::AppHandle,
+    path: String,
+    include_webview_storage: bool,
+    frontend: serde_json::Value,
+    passphrase: Option<String>,
+) -> Result<backup::BackupManifest, String> {
+    if data_locked(&app) {
+        return Err("Unlock AnyChat's data first".to_string());
+    }
+    let roots = backup_roots(&app)?;
+    let vault = data_vault(&app);
+    let manifest = tauri::async_runtime::spawn_blocking(move || {
+        backup::create_backup(
+            &roots,
+            &vault,
+            &frontend,
+            include_webview_storage,
+            passphrase
+                .as_deref()
+                .map(|passphrase| backup::BackupPassphrase {
+                    passphrase,
+                    kdf: secrets::KdfParams::default(),
+                }),
+            std::path::Path::new(&path),
+            native_chat::now_millis(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    println!(
+        "[AnyChat] Backup created with {} files",
+        manifest.files.len()
+    );
+    Ok(manifest)
+}
+
+/// Verifies the backup at `path`, closes every service webview and relaunches; the backup is
+/// swapped in before anything loads again. `passphrase` opens an encrypted backup.
+#[tauri::command]
+async fn restore_backup(
+    app: tauri::AppHandle,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    if data_locked(&app) {
+        return Err("Unlock AnyChat's data first".to_string());
+    }
+    let roots = backup_roots(&app)?;
+    let vault = data_vault(&app);
+    let manifest = tauri::async_runtime::spawn_blocking(move || {
+        backup::stage_restore(
+            &roots,
+            &vault,
+            std::path::Path::new(&path),
+            passphrase.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    println!(
+        "[AnyChat] Backup from {} verified, relaunching to restore it",
+        manifest.created_at
+    );
+
+    for (label, webview) in app.webviews() {
+        if label != "main" {
+            let _ = webview.close();
+        }
+    }
+    for (label, window) in app.webview_windows() {
+        if label != "main" {
+            let _ = window.destroy();
+        }
+    }
+    app.restart()
+}
+
+/// Frontend state restored from a backup, handed out once after the relaunch.
+#[tauri::command]
+fn take_restored_frontend_state(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let path = storage::data_file_path(&app, backup::RESTORED_FRONTEND_FILE)?;
+    if !path.is_file() {
+        return Ok(serde_json::Value::Null);
+    }
+    let state = storage::read_plain_json(&path)?;
+    std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+    Ok(state)
+}
+
+#[tauri::command]
+fn get_effective_policy(app: tauri::AppHandle) -> policy::EffectivePolicy {
+    app.state::<AppState>().policy.clone()
+}
+
+#[tauri::command]
+fn get_http_settings(app: tauri::AppHandle) -> http::HttpSettings {
+    app.state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .http
+        .clone()
+}
+
+#[tauri::command]
+fn set_http_settings(app: tauri::AppHandle, settings: http::HttpSettings) -> Result<(), String> {
+    http::validate_http_settings(&settings)?;
+    if service_policy(&app).proxy.is_some() && settings.proxy.is_some() {
+        return Err("The proxy is set by policy".to_string());
+    }
+
+    let state = app.state::<AppState>();
+    let mut app_settings = state.settings.lock().unwrap();
+    app_settings.http = settings;
+    save_app_settings(&app, &app_settings)
+}
+
+#[tauri::command]
+fn get_service_settings(app: tauri::AppHandle, service_id: String) -> ServiceSettings {
+    app.state::<AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(&service_id)
+}
+
+#[tauri::command]
+fn set_service_tls_policy(
+    app: tauri::AppHandle,
+    service_id: String,
+    policy: tls::TlsErrorPolicy,
+) -> Result<(), String> {
+    let policy = policy.validated(tls::CERTIFICATE_PINNING_SUPPORTED)?;
+
+    update_service_registry(&app, |registry| {
+        registry.settings_mut(&service_id).tls_error_policy = policy;
+    })
+}
+
+#[tauri::command]
+fn set_local_server_ports(app: tauri::AppHandle, ports: Vec<u16>) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut app_settings = state.settings.lock().unwrap();
+    app_settings.local_server_ports = ports;
+    save_app_settings(&app, &app_settings)
+}
+
+#[tauri::command]
+async fn discover_local_servers(
+    app: tauri::AppHandle,
+) -> Result<Vec<local_servers::DiscoveredServer>, String> {
+    let ports = local_servers::scan_ports(
+        &app.state::<AppState>()
+            .settings
+            .lock()
+            .unwrap()
+            .local_server_ports,
+    );
+    let client = local_servers::probe_client(local_servers::DISCOVERY_TIMEOUT)?;
+
+    Ok(local_servers::discover(&client, local_servers::DISCOVERY_HOST, &ports).await)
+}
+
+fn native_chat_api_key(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+) -> Result<Option<String>, String> {
+    secrets::get_secret(
+        &storage::data_dir(app)?,
+        &data_vault(app),
+        &native_chat::api_key_secret_name(&service.id),
+    )?
+    .map(|stored| native_chat::api_key_for_url(&stored, &service.url))
+    .transpose()
+}
+
+#[tauri::command]
+fn native_chat_send(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+    model: String,
+    content: String,
+    conversation_id: Option<String>,
+) -> Result<native_chat::ChatStreamEvent, String> {
+    service_policy(&app).check_service(&service.id, &service.url)?;
+    let data_dir = storage::data_dir(&app)?;
+    let mut conversation = match conversation_id {
+        Some(conversation_id) => native_chat::load_conversation(
+            &data_dir,
+            &data_vault(&app),
+            &service.id,
+            &conversation_id,
+        )?,
+        None => native_chat::new_conversation(&service.id, &model),
+    };
+    if conversation.title.is_empty() {
+        conversation.title = native_chat::conversation_title(&content);
+    }
+    conversation.model = model;
+    conversation.updated_at = native_chat::now_millis();
+    conversation.messages.push(native_chat::ChatMessage {
+        role: "user".to_string(),
+        content,
+    });
+    native_chat::save_conversation(&data_dir, &data_vault(&app), &conversation)?;
+
+    let api_key = native_chat_api_key(&app, &service)?;
+    let client = http_client(&app, native_chat::CHAT_TIMEOUT)?;
+    let started = native_chat::ChatStreamEvent {
+        request_id: format!("{}-{}", conversation.id, conversation.messages.len()),
+        service_id: service.id.clone(),
+        conversation_id: conversation.id.clone(),
+        delta: None,
+        message: None,
+        error: None,
+    };
+
+    let event = started.clone();
+    let task_app = app.clone();
+    // The task waits until its handle is stored, so a request that finishes right away still
+    // removes it.
+    let (stored_tx, stored_rx) = tokio::sync::oneshot::channel::<()>();
+    let task = tauri::async_runtime::spawn(async move {
+        if stored_rx.await.is_err() {
+            return;
+        }
+        let result = native_chat::stream_chat_completion(
+            &client,
+            &service.url,
+            api_key.as_deref(),
+            &conversation.model,
+            &conversation.messages,
+            |delta| {
+                let _ = task_app.emit(
+                    native_chat::DELTA_EVENT,
+                    native_chat::ChatStreamEvent {
+                        delta: Some(delta.to_string()),
+                        ..event.clone()
+                    },
+                );
+            },
+        )
+        .await;
+
+        let outcome = result.and_then(|reply| {
+            let message = native_chat::ChatMessage {
+                role: "assistant".to_string(),
+                content: reply,
+            };
+            conversation.messages.push(message.clone());
+            conversation.updated_at = native_chat::now_millis();
+            native_chat::save_conversation(&data_dir, &data_vault(&task_app), &conversation)?;
+            Ok(message)
+        });
+
+        match outcome {
+            Ok(message) => {
+                let _ = task_app.emit(
+                    native_chat::DONE_EVENT,
+                    native_chat::ChatStreamEvent {
+                        message: Some(message),
+                        ..event.clone()
+                    },
+                );
+            }
+            Err(e) => {
+                println!(
+                    "[AnyChat] Native chat request {} failed: {}",
+                    event.request_id, e
+                );
+                let _ = task_app.emit(
+                    native_chat::ERROR_EVENT,
+                    native_chat::ChatStreamEvent {
+                        error: Some(e),
+                        ..event.clone()
+                    },
+                );
+            }
+        }
+
+        task_app
+            .state::<AppState>()
+            .native_chat_requests
+            .lock()
+            .unwrap()
+            .remove(&event.request_id);
+    });
+
+    app.state::<AppState>()
+        .native_chat_requests
+        .lock()
+        .unwrap()
+        .insert(started.request_id.clone(), task);
+    let _ = stored_tx.send(());
+    Ok(started)
+}
+
+/// Stops streaming; the user message stays in the conversation without a reply.
+#[tauri::command]
+fn native_chat_cancel(app: tauri::AppHandle, request_id: String) {
+    if let Some(task) = app
+        .state::<AppState>()
+        .native_chat_requests
+        .lock()
+        .unwrap()
+        .remove(&request_id)
+    {
+        task.abort();
+        println!("[AnyChat] Cancelled native chat request {}", request_id);
+    }
+}
+
+#[tauri::command]
+async fn native_chat_list_models(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+) -> Result<Vec<String>, String> {
+    service_policy(&app).check_service(&service.id, &service.url)?;
+    let api_key = native_chat_api_key(&app, &service)?;
+    let client = http_client(&app, native_chat::MODELS_TIMEOUT)?;
+    native_chat::list_models(&client, &service.url, api_key.as_deref()).await
+}
+
+#[tauri::command]
+fn native_chat_list_conversations(
+    app: tauri::AppHandle,
+    service_id: String,
+) -> Result<Vec<native_chat::ConversationSummary>, String> {
+    native_chat::list_conversations(&storage::data_dir(&app)?, &data_vault(&app), &service_id)
+}
+
+#[tauri::command]
+fn native_chat_load_conversation(
+    app: tauri::AppHandle,
+    service_id: String,
+    conversation_id: String,
+) -> Result<native_chat::Conversation, String> {
+    native_chat::load_conversation(
+        &storage::data_dir(&app)?,
+        &data_vault(&app),
+        &service_id,
+        &conversation_id,
+    )
+}
+
+#[tauri::command]
+fn native_chat_delete_conversation(
+    app: tauri::AppHandle,
+    service_id: String,
+    conversation_id: String,
+) -> Result<(), String> {
+    native_chat::delete_conversation(&storage::data_dir(&app)?, &service_id, &conversation_id)
+}
+
+#[tauri::command]
+fn native_chat_set_api_key(
+    app: tauri::AppHandle,
+    service_id: String,
+    base_url: String,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    let stored = api_key
+        .filter(|api_key| !api_key.trim().is_empty())
+        .map(|api_key| native_chat::encode_api_key(&base_url, api_key.trim()))
+        .transpose()?;
+    secrets::set_secret(
+        &storage::data_dir(&app)?,
+        &data_vault(&app),
+        &native_chat::api_key_secret_name(&service_id),
+        stored.as_deref(),
+    )
+}
+
+#[tauri::command]
+fn native_chat_has_api_key(app: tauri::AppHandle, service_id: String) -> Result<bool, String> {
+    secrets::has_secret(
+        &storage::data_dir(&app)?,
+        &data_vault(&app),
+        &native_chat::api_key_secret_name(&service_id),
+    )
+}
+
+#[tauri::command]
+fn list_user_scripts(
+    app: tauri::AppHandle,
+    service_id: String,
+) -> Result<Vec<user_scripts::UserScript>, String> {
+    Ok(
+        user_scripts::load_scripts(&storage::data_dir(&app)?, &data_vault(&app), &service_id)?
+            .scripts,
+    )
+}
+
+#[tauri::command]
+fn save_user_script(
+    app: tauri::AppHandle,
+    service_id: String,
+    script: user_scripts::UserScript,
+) -> Result<user_scripts::UserScript, String> {
+    let (script, revision) = user_scripts::save_script(
+        &storage::data_dir(&app)?,
+        &data_vault(&app),
+        &service_id,
+        script,
+    )?;
+
+    if let Some(webview) = service_webview(&app, &service_id) {
+        webview
+            .eval(user_scripts::live_update_script(revision, &script))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(script)
+}
+
+#[tauri::command]
+fn delete_user_script(
+    app: tauri::AppHandle,
+    service_id: String,
+    script_id: String,
+) -> Result<(), String> {
+    let Some(revision) = user_scripts::delete_script(
+        &storage::data_dir(&app)?,
+        &data_vault(&app),
+        &service_id,
+        &script_id,
+    )?
+    else {
+        return Ok(());
+    };
+
+    if let Some(webview) = service_webview(&app, &service_id) {
+        webview
+            .eval(user_scripts::live_remove_script(revision, &script_id))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The most recent user script errors, oldest first.
+#[tauri::command]
+fn read_user_script_log(
+    app: tauri::AppHandle,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let path = storage::data_file_path(&app, user_scripts::USER_SCRIPTS_LOG)?;
+    let log = storage::read_log_tail(&path, storage::MAX_LOG_BYTES)?;
+
+    let lines = log.lines().collect::<Vec<_>>();
+    let start = lines.len().saturating_sub(limit.unwrap_or(200));
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+#[tauri::command]
+fn get_content_filter_stats(app: tauri::AppHandle) -> content_blocking::ContentFilterStats {
+    app.state::<AppState>()
+        .content_filters
+        .lock()
+        .unwrap()
+        .stats()
+        .clone()
+}
+
+/// Re-reads the rule lists and applies them to the given live services.
+#[tauri::command]
+fn reload_content_filters(
+    app: tauri::AppHandle,
+    services: Vec<ServiceHostPayload>,
+) -> Result<content_blocking::ContentFilterStats, String> {
+    let filters = Arc::new(load_content_filters(&app)?);
+    let stats = filters.stats().clone();
+    *app.state::<AppState>().content_filters.lock().unwrap() = filters;
+
+    for service in services {
+        if let Some(webview) = service_webview(&app, &service.id) {
+            if let Ok(page_url) = webview.url() {
+                sync_content_blocking(&app, &service, &webview, &page_url, true);
+            }
+        }
+    }
+
+    println!(
+        "[AnyChat] Loaded content filters: {} blocked hosts, {} hidden selectors, {} unsupported rules",
+        stats.blocked_hosts, stats.hidden_selectors, stats.unsupported_rules
+    );
+    Ok(stats)
+}
+
+/// `enabled: None` returns the service to the per-host default.
+#[tauri::command]
+fn set_service_content_blocking(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+    enabled: Option<bool>,
+) -> Result<bool, String> {
+    update_service_registry(&app, |registry| {
+        registry.settings_mut(&service.id).content_blocking = enabled;
+    })?;
+
+    if let Some(webview) = service_webview(&app, &service.id) {
+        if let Ok(page_url) = webview.url() {
+            sync_content_blocking(&app, &service, &webview, &page_url, true);
+        }
+    }
+    Ok(service_content_blocking_enabled(&app, &service))
+}
+
+#[tauri::command]
+fn get_app_theme(app: tauri::AppHandle) -> theme::AppTheme {
+    app.state::<AppState>().settings.lock().unwrap().theme
+}
+
+/// Called by the frontend whenever the AnyChat theme changes; live pages follow without reload.
+#[tauri::command]
+fn set_app_theme(app: tauri::AppHandle, theme: theme::AppTheme) -> Result<(), String> {
+    {
+        let state = app.state::<AppState>();
+        let mut app_settings = state.settings.lock().unwrap();
+        if app_settings.theme == theme {
+            return Ok(());
+        }
+        app_settings.theme = theme;
+        save_app_settings(&app, &app_settings)?;
+    }
+
+    for service_id in live_service_ids(&app) {
+        if let Some(webview) = service_webview(&app, &service_id) {
+            apply_service_theme(&app, &service_id, &webview);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_service_theme(
+    app: tauri::AppHandle,
+    service_id: String,
+    theme: theme::ServiceTheme,
 ) -> Result<(), String> {
+    update_service_registry(&app, |registry| {
+        registry.settings_mut(&service_id).theme = theme;
+    })?;
+
+    if let Some(webview) = service_webview(&app, &service_id) {
+        apply_service_theme(&app, &service_id, &webview);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_service_zoom(app: tauri::AppHandle, service_id: String) -> u16 {
+    service_zoom_percent(&app, &service_id)
+}
+
+#[tauri::command]
+fn zoom_service_in(app: tauri::AppHandle, service_id: String) -> Result<u16, String> {
+    zoom_service(&app, &service_id, zoom::ZoomAction::In)
+}
+
+#[tauri::command]
+fn zoom_service_out(app: tauri::AppHandle, service_id: String) -> Result<u16, String> {
+    zoom_service(&app, &service_id, zoom::ZoomAction::Out)
+}
+
+#[tauri::command]
+fn zoom_service_reset(app: tauri::AppHandle, service_id: String) -> Result<u16, String> {
+    zoom_service(&app, &service_id, zoom::ZoomAction::Reset)
+}
+
+/// `percent` is clamped to the supported range; the applied value is returned.
+#[tauri::command]
+fn zoom_service_set(
+    app: tauri::AppHandle,
+    service_id: String,
+    percent: u16,
+) -> Result<u16, String> {
+    zoom_service(&app, &service_id, zoom::ZoomAction::Set { percent })
+}
+
+/// Steps through matches of `query` in the service page; an empty query clears the highlights.
+#[tauri::command]
+async fn find_in_service(
+    app: tauri::AppHandle,
+    service_id: String,
+    query: String,
+    direction: find::FindDirection,
+) -> Result<find::FindResult, String> {
+    let webview = service_webview(&app, &service_id)
+        .ok_or_else(|| format!("Service webview {} not found", service_id))?;
+    match bridge::evaluate_script(&webview, find::find_script(&query, direction)).await? {
+        Some(raw) => find::parse_result(&raw),
+        None => Ok(find::FindResult::default()),
+    }
+}
+
+/// Every regular webview shares one cookie store; the main webview always exists to reach it.
+fn cookie_store_webview(app: &tauri::AppHandle) -> Result<tauri::Webview, String> {
+    app.get_webview("main")
+        .ok_or_else(|| "Main window not found".to_string())
+}
+
+fn unix_seconds() -> i64 {
+    (native_chat::now_millis() / 1000) as i64
+}
+
+/// Writes the cookies of `services` to a password-protected backup at `path`.
+#[tauri::command]
+async fn export_service_cookies(
+    app: tauri::AppHandle,
+    services: Vec<ServiceHostPayload>,
+    path: String,
+    password: String,
+) -> Result<Vec<cookie_backup::ServiceCookieSummary>, String> {
+    cookie_backup::validate_password(&password)?;
+    let path = std::path::PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(format!("Backup path must be absolute: {}", path.display()));
+    }
+
+    let webview = cookie_store_webview(&app)?;
+    // Reading cookies deadlocks WebView2 on the main thread; key derivation is slow anyway.
+    tauri::async_runtime::spawn_blocking(move || {
+        let cookies = webview.cookies().map_err(|e| e.to_string())?;
+        let now = unix_seconds();
+        let mut backup = cookie_backup::CookieBackup {
+            created_at: native_chat::now_millis(),
+            services: Vec::new(),
+        };
+        let mut summaries = Vec::new();
+        for service in &services {
+            let Some(host) = service_host(&service.url).filter(|host| !host.is_empty()) else {
+                continue;
+            };
+            let (service_cookies, summary) =
+                cookie_backup::service_cookies(service, &host, &cookies, now);
+            backup.services.push(service_cookies);
+            summaries.push(summary);
+        }
+
+        let bytes =
+            cookie_backup::encrypt_backup(&backup, &password, secrets::KdfParams::default())?;
+        storage::write_atomic(&path, &bytes)?;
+        println!(
+            "[AnyChat] Exported cookies of {} services to {}",
+            summaries.len(),
+            path.display()
+        );
+        Ok(summaries)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Restores the unexpired cookies of a backup and reloads the affected live services.
+#[tauri::command]
+async fn import_service_cookies(
+    app: tauri::AppHandle,
+    path: String,
+    password: String,
+) -> Result<Vec<cookie_backup::ServiceCookieSummary>, String> {
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let webview = cookie_store_webview(&app)?;
+    let summaries = tauri::async_runtime::spawn_blocking(move || {
+        let backup = cookie_backup::decrypt_backup(&bytes, &password)?;
+        let now = unix_seconds();
+        let mut summaries = Vec::new();
+        for service in backup.services {
+            let mut summary = cookie_backup::ServiceCookieSummary {
+                service_id: service.service_id,
+                name: service.name,
+                cookies: 0,
+                expired: 0,
+            };
+            for cookie in service.cookies {
+                if cookie.is_expired(now) {
+                    summary.expired += 1;
+                    continue;
+                }
+                match cookie
+                    .to_cookie(now)
+                    .and_then(|cookie| webview.set_cookie(cookie).map_err(|e| e.to_string()))
+                {
+                    Ok(()) => summary.cookies += 1,
+                    Err(e) => {
+                        println!("[AnyChat] Skipped cookie for {}: {}", summary.service_id, e)
+                    }
+                }
+            }
+            summaries.push(summary);
+        }
+        Ok::<_, String>(summaries)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    for summary in summaries.iter().filter(|summary| summary.cookies > 0) {
+        println!(
+            "[AnyChat] Restored {} cookies for {} ({} expired)",
+            summary.cookies, summary.service_id, summary.expired
+        );
+        if let Some(webview) = service_webview(&app, &summary.service_id) {
+            let _ = webview.eval("location.reload();");
+        }
+    }
+    Ok(summaries)
+}
+
+fn browser_profiles(app: &tauri::AppHandle) -> Vec<browser_import::BrowserProfile> {
+    app.path()
+        .home_dir()
+        .map(|home| browser_import::discover_profiles(&home))
+        .unwrap_or_default()
+}
+
+/// Reads the service's cookies from `profile`, which must be one of the discovered profiles.
+#[cfg(target_os = "linux")]
+async fn read_browser_cookies(
+    app: &tauri::AppHandle,
+    service: &ServiceHostPayload,
+    profile: &browser_import::BrowserProfile,
+) -> Result<browser_import::ProfileCookies, String> {
+    if !browser_profiles(app).contains(profile) {
+        return Err(format!("Unknown browser profile {}", profile.cookie_db));
+    }
+    let host = service_host(&service.url)
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| format!("Service {} has no host", service.id))?;
+    let profile = profile.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        browser_import::read_profile_cookies(&profile, &host, unix_seconds())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn read_browser_cookies(
+    _app: &tauri::AppHandle,
+    _service: &ServiceHostPayload,
+    _profile: &browser_import::BrowserProfile,
+) -> Result<browser_import::ProfileCookies, String> {
+    Err("Importing browser sessions is only supported on Linux".to_string())
+}
+
+/// Chromium-based and Firefox profiles on this machine that have a cookie database.
+#[tauri::command]
+fn list_browser_profiles(app: tauri::AppHandle) -> Vec<browser_import::BrowserProfile> {
+    if cfg!(target_os = "linux") {
+        browser_profiles(&app)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Lists the cookies an import from `profile` would bring in, without their values.
+#[tauri::command]
+async fn preview_browser_cookie_import(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+    profile: browser_import::BrowserProfile,
+) -> Result<browser_import::ImportPreview, String> {
+    let cookies = read_browser_cookies(&app, &service, &profile).await?;
+    Ok(cookies.preview(&service.id, &profile))
+}
+
+/// Copies the service's cookies from `profile` into the webview store and reloads the service.
+#[tauri::command]
+async fn import_browser_cookies(
+    app: tauri::AppHandle,
+    service: ServiceHostPayload,
+    profile: browser_import::BrowserProfile,
+) -> Result<cookie_backup::ServiceCookieSummary, String> {
+    let cookies = read_browser_cookies(&app, &service, &profile).await?;
+    let webview = cookie_store_webview(&app)?;
+    let now = unix_seconds();
+    let mut summary = cookie_backup::ServiceCookieSummary {
+        service_id: service.id.clone(),
+        name: service.name.clone(),
+        cookies: 0,
+        expired: cookies.expired,
+    };
+    for cookie in &cookies.cookies {
+        match cookie
+            .to_cookie(now)
+            .and_then(|cookie| webview.set_cookie(cookie).map_err(|e| e.to_string()))
+        {
+            Ok(()) => summary.cookies += 1,
+            Err(e) => println!("[AnyChat] Skipped cookie for {}: {}", service.id, e),
+        }
+    }
+
+    println!(
+        "[AnyChat] Imported {} cookies for {} from {:?} profile {}",
+        summary.cookies, service.id, profile.browser, profile.name
+    );
+    if let Some(webview) = service_webview(&app, &service.id) {
+        let _ = webview.eval("location.reload();");
+    }
+    Ok(summary)
+}
+
+fn data_vault(app: &tauri::AppHandle) -> storage::Vault {
+    app.state::<AppState>().vault.lock().unwrap().clone()
+}
+
+fn set_data_vault(app: &tauri::AppHandle, vault: storage::Vault) {
+    *app.state::<AppState>().vault.lock().unwrap() = vault;
+}
+
+/// Whether encrypted data cannot be read right now.
+fn data_locked(app: &tauri::AppHandle) -> bool {
+    app.state::<AppState>().vault.lock().unwrap().is_locked()
+}
+
+/// Service content stays hidden while the app lock is on or encrypted data is not unlocked.
+fn app_locked(app: &tauri::AppHandle) -> bool {
+    data_locked(app) || app.state::<AppState>().app_lock.lock().unwrap().is_locked()
+}
+
+fn app_lock_status(app: &tauri::AppHandle) -> app_lock::AppLockStatus {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().unwrap().app_lock.clone();
+    let locked = state.app_lock.lock().unwrap().is_locked();
+    app_lock::AppLockStatus {
+        enabled: settings.enabled(),
+        locked,
+        idle_minutes: settings.idle_minutes,
+        lock_on_screen_lock: settings.lock_on_screen_lock,
+    }
+}
+
+/// Hides all service content and every window other than the main one, which shows the lock
+/// screen. Does nothing without a lock password.
+fn lock_app_now(app: &tauri::AppHandle, reason: &str) {
+    let state = app.state::<AppState>();
+    if !state.settings.lock().unwrap().app_lock.enabled() {
+        return;
+    }
+    if !state.app_lock.lock().unwrap().lock() {
+        return;
+    }
+    println!("[AnyChat] App locked: {}", reason);
+
+    let _ = hide_all_service_content(app.clone());
+    hide_windows_service_hosts(app, &state);
+    let service_host_labels = tracked_windows_service_hosts(&state)
+        .into_iter()
+        .map(|host| host.window_label)
+        .collect::<HashSet<_>>();
+    let mut hidden = Vec::new();
+    for (label, window) in app.webview_windows() {
+        if label == "main" || service_host_labels.contains(&label) {
+            continue;
+        }
+        if window.is_visible().unwrap_or(false) && window.hide().is_ok() {
+            hidden.push(label);
+        }
+    }
+    state.app_lock.lock().unwrap().remember_hidden(hidden);
+    let _ = app.emit(app_lock::APP_LOCK_EVENT, app_lock_status(app));
+}
+
+fn lock_on_screen_lock(app: &tauri::AppHandle) {
+    let enabled = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .app_lock
+        .lock_on_screen_lock;
+    if enabled {
+        lock_app_now(app, "screen locked");
+    }
+}
+
+/// Shows the active service and the windows the lock hid.
+fn restore_after_unlock(app: &tauri::AppHandle, hidden_windows: Vec<String>) {
+    for label in hidden_windows {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.show();
+        }
+    }
+
+    let state = app.state::<AppState>();
     #[cfg(target_os = "windows")]
     {
-        let state = app.state::<AppState>();
-        let tracked_services = tracked_services_or_current(_services, &service);
-        let main_window = app
-            .get_webview_window(parent.label())
-            .ok_or_else(|| format!("Main window {} not found", parent.label()))?;
+        let _ = show_active_windows_service_host(app, &state);
+    }
 
+    #[cfg(not(target_os = "windows"))]
+    {
+        let active_service =
+            state
+                .active_service_id
+                .lock()
+                .unwrap()
+                .clone()
+                .and_then(|service_id| {
+                    state
+                        .service_payloads
+                        .lock()
+                        .unwrap()
+                        .get(&service_id)
+                        .cloned()
+                });
+        if let (Some(service), Some(window)) = (active_service, app.get_window("main")) {
+            if let Err(e) = activate_child_webview_content(&window, app, &service) {
+                println!(
+                    "[AnyChat] Failed to restore {} after unlock: {}",
+                    service.id, e
+                );
+            }
+        }
+    }
+}
+
+/// Locks after the configured idle time. Without a script bridge, input in service pages is
+/// invisible to AnyChat, so a focused AnyChat window keeps the app unlocked there.
+fn start_app_lock_monitor(app: &tauri::AppHandle) {
+    app_lock::watch_screen_lock(app);
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(app_lock::CHECK_INTERVAL);
+
+        let state = app.state::<AppState>();
+        let settings = state.settings.lock().unwrap().app_lock.clone();
+        let Some(timeout) = settings.idle_timeout().filter(|_| settings.enabled()) else {
+            continue;
+        };
+        #[cfg(not(target_os = "linux"))]
+        if app
+            .webview_windows()
+            .values()
+            .any(|window| window.is_focused().unwrap_or(false))
         {
-            let setup_complete = state.setup_complete.lock().unwrap();
-            if !*setup_complete {
-                println!("[AnyChat] Setup not complete yet, skipping activate_service_content");
-                return Ok(());
+            state.app_lock.lock().unwrap().touch(Instant::now());
+        }
+        let expired = state
+            .app_lock
+            .lock()
+            .unwrap()
+            .idle_expired(Instant::now(), timeout);
+        if expired {
+            lock_app_now(&app, "idle");
+        }
+    });
+}
+
+#[tauri::command]
+fn get_app_lock_status(app: tauri::AppHandle) -> app_lock::AppLockStatus {
+    app_lock_status(&app)
+}
+
+/// Sets, changes or (with no `new_password`) removes the lock password. Changing an existing
+/// password needs the current one.
+#[tauri::command]
+async fn set_app_lock_password(
+    app: tauri::AppHandle,
+    current_password: Option<String>,
+    new_password: Option<String>,
+) -> Result<app_lock::AppLockStatus, String> {
+    let existing_hash = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .app_lock
+        .password_hash
+        .clone();
+    let password_hash = tauri::async_runtime::spawn_blocking(move || {
+        if let Some(existing_hash) = existing_hash {
+            let current_password = current_password.unwrap_or_default();
+            if !app_lock::verify_password(&existing_hash, &current_password) {
+                std::thread::sleep(app_lock::FAILED_UNLOCK_DELAY);
+                return Err("Wrong lock password".to_string());
+            }
+        }
+        new_password
+            .map(|password| app_lock::hash_password(&password, secrets::KdfParams::default()))
+            .transpose()
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    {
+        let state = app.state::<AppState>();
+        let mut app_settings = state.settings.lock().unwrap();
+        let removed = password_hash.is_none();
+        app_settings.app_lock.password_hash = password_hash;
+        save_app_settings(&app, &app_settings)?;
+        if removed {
+            let hidden_windows = state.app_lock.lock().unwrap().unlock(Instant::now());
+            drop(app_settings);
+            restore_after_unlock(&app, hidden_windows);
+        }
+    }
+    let status = app_lock_status(&app);
+    let _ = app.emit(app_lock::APP_LOCK_EVENT, &status);
+    Ok(status)
+}
+
+#[tauri::command]
+fn set_app_lock_options(
+    app: tauri::AppHandle,
+    idle_minutes: Option<u32>,
+    lock_on_screen_lock: bool,
+) -> Result<app_lock::AppLockStatus, String> {
+    app_lock::validate_idle_minutes(idle_minutes)?;
+    {
+        let state = app.state::<AppState>();
+        let mut app_settings = state.settings.lock().unwrap();
+        app_settings.app_lock.idle_minutes = idle_minutes;
+        app_settings.app_lock.lock_on_screen_lock = lock_on_screen_lock;
+        save_app_settings(&app, &app_settings)?;
+    }
+    app.state::<AppState>()
+        .app_lock
+        .lock()
+        .unwrap()
+        .touch(Instant::now());
+    Ok(app_lock_status(&app))
+}
+
+#[tauri::command]
+fn lock_app(app: tauri::AppHandle) -> Result<(), String> {
+    if !app_lock_status(&app).enabled {
+        return Err("Set a lock password first".to_string());
+    }
+    lock_app_now(&app, "requested");
+    Ok(())
+}
+
+#[tauri::command]
+async fn unlock_app(app: tauri::AppHandle, password: String) -> Result<(), String> {
+    let Some(password_hash) = app
+        .state::<AppState>()
+        .settings
+        .lock()
+        .unwrap()
+        .app_lock
+        .password_hash
+        .clone()
+    else {
+        return Ok(());
+    };
+    let verified = tauri::async_runtime::spawn_blocking(move || {
+        let verified = app_lock::verify_password(&password_hash, &password);
+        if !verified {
+            std::thread::sleep(app_lock::FAILED_UNLOCK_DELAY);
+        }
+        verified
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if !verified {
+        return Err("Wrong lock password".to_string());
+    }
+
+    let hidden_windows = app
+        .state::<AppState>()
+        .app_lock
+        .lock()
+        .unwrap()
+        .unlock(Instant::now());
+    println!("[AnyChat] App unlocked");
+    let _ = app.emit(app_lock::APP_LOCK_EVENT, app_lock_status(&app));
+    restore_after_unlock(&app, hidden_windows);
+    Ok(())
+}
+
+/// Input in the AnyChat UI itself, throttled by the frontend.
+#[tauri::command]
+fn report_app_activity(app: tauri::AppHandle, webview: tauri::Webview) {
+    if webview.label() != "main" {
+        return;
+    }
+    app.state::<AppState>()
+        .app_lock
+        .lock()
+        .unwrap()
+        .touch(Instant::now());
+}
+
+fn load_encryption_config(
+    app: &tauri::AppHandle,
+) -> Result<Option<encryption::EncryptionConfig>, String> {
+    storage::read_plain_json(&storage::data_file_path(app, encryption::ENCRYPTION_FILE)?)
+}
+
+fn save_encryption_config(
+    app: &tauri::AppHandle,
+    config: &encryption::EncryptionConfig,
+) -> Result<(), String> {
+    storage::write_plain_json(
+        &storage::data_file_path(app, encryption::ENCRYPTION_FILE)?,
+        config,
+    )
+}
+
+fn data_encryption_status(app: &tauri::AppHandle) -> encryption::DataEncryptionStatus {
+    let config = load_encryption_config(app).ok().flatten();
+    encryption::DataEncryptionStatus::new(&data_vault(app), config.as_ref())
+}
+
+fn emit_data_encryption_status(app: &tauri::AppHandle) -> encryption::DataEncryptionStatus {
+    let status = data_encryption_status(app);
+    let _ = app.emit(encryption::DATA_ENCRYPTION_EVENT, &status);
+    status
+}
+
+/// Checks that every protected file decrypts before anything is read. Files still in plaintext
+/// are sealed only while the migration started by `enable_data_encryption` is unfinished. On
+/// failure the vault stays failed, so nothing is read or overwritten.
+fn open_vault(app: &tauri::AppHandle, data_key: [u8; 32]) -> Result<(), String> {
+    let files = encryption::protected_files(&storage::data_dir(app)?);
+    if let Err(e) = encryption::verify_files(&files, &data_key) {
+        println!("[AnyChat] Encrypted data check failed: {}", e);
+        set_data_vault(app, storage::Vault::Failed(e.clone()));
+        return Err(e);
+    }
+    set_data_vault(app, storage::Vault::Unlocked(data_key));
+    if let Some(mut config) = load_encryption_config(app)?
+        .filter(|config| encryption::migration_pending(config, &data_key))
+    {
+        match finish_data_migration(app, &mut config, &files, &data_key) {
+            Ok(count) => println!("[AnyChat] Encrypted {} plaintext data files", count),
+            Err(e) => println!("[AnyChat] Failed to encrypt plaintext data files: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Seals the files written before encryption was turned on and clears the migration marker.
+fn finish_data_migration(
+    app: &tauri::AppHandle,
+    config: &mut encryption::EncryptionConfig,
+    files: &[std::path::PathBuf],
+    data_key: &[u8; 32],
+) -> Result<usize, String> {
+    let count = encryption::rewrite_files(files, data_key, Some(data_key))?;
+    config.pending_migration = None;
+    save_encryption_config(app, config)?;
+    Ok(count)
+}
+
+/// Keyring-protected data opens at startup; passphrase-protected data waits for `unlock_data`.
+fn init_data_encryption(app: &tauri::AppHandle) {
+    let config = match load_encryption_config(app) {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            set_data_vault(
+                app,
+                storage::Vault::Failed(format!("Cannot read the encryption settings: {}", e)),
+            );
+            return;
+        }
+    };
+
+    match config.key_source {
+        encryption::KeySource::Passphrase { .. } => set_data_vault(app, storage::Vault::Locked),
+        encryption::KeySource::Keyring => {
+            match encryption::keyring_key()
+                .and_then(|key| encryption::unwrap_key(&key, &config.wrapped_key))
+            {
+                Ok(data_key) => {
+                    let _ = open_vault(app, data_key);
+                }
+                Err(e) => set_data_vault(
+                    app,
+                    storage::Vault::Failed(format!(
+                        "Cannot unlock AnyChat's data with the keyring: {}",
+                        e
+                    )),
+                ),
             }
         }
+    }
+}
 
-        prune_disabled_windows_service_hosts(&app, &state, &tracked_services);
-        set_active_windows_service_id(&state, Some(service.id.clone()));
-        let _ = ensure_windows_service_host(&app, &state, &main_window, &service)?;
-        return show_active_windows_service_host(&app, &state);
+/// Key source for the data key and the key it yields; keyring keys are stored right away.
+fn new_key_source(
+    key_source: encryption::KeySourceKind,
+    passphrase: Option<&str>,
+) -> Result<(encryption::KeySource, [u8; 32]), String> {
+    match key_source {
+        encryption::KeySourceKind::Passphrase => encryption::new_passphrase_source(
+            passphrase.ok_or_else(|| "A passphrase is required".to_string())?,
+            secrets::KdfParams::default(),
+        ),
+        encryption::KeySourceKind::Keyring => {
+            let key = secrets::random_bytes::<32>();
+            encryption::store_keyring_key(&key)?;
+            Ok((encryption::KeySource::Keyring, key))
+        }
     }
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        activate_child_webview_content(&parent, &app, &service.id, &service.url)
+/// Reloads the encrypted state after the data opened and shows service content again unless
+/// the app lock is still on.
+fn finish_data_unlock(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    load_encrypted_state(app, &state);
+    emit_data_encryption_status(app);
+    let _ = app.emit(app_lock::APP_LOCK_EVENT, app_lock_status(app));
+    if !app_locked(app) {
+        restore_after_unlock(app, Vec::new());
     }
 }
 
 #[tauri::command]
-fn refresh_service_content(
+fn get_data_encryption_status(app: tauri::AppHandle) -> encryption::DataEncryptionStatus {
+    data_encryption_status(&app)
+}
+
+/// Encrypts the existing data files and returns the recovery key, which is not stored anywhere.
+#[tauri::command]
+async fn enable_data_encryption(
     app: tauri::AppHandle,
-    service: ServiceHostPayload,
-    _services: Option<Vec<ServiceHostPayload>>,
-) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let state = app.state::<AppState>();
-        let tracked_services = tracked_services_or_current(_services, &service);
+    key_source: encryption::KeySourceKind,
+    passphrase: Option<String>,
+) -> Result<String, String> {
+    if !matches!(data_vault(&app), storage::Vault::Disabled) {
+        return Err("Data encryption is already on".to_string());
+    }
 
-        prune_disabled_windows_service_hosts(&app, &state, &tracked_services);
-        set_active_windows_service_id(&state, Some(service.id.clone()));
+    let config_app = app.clone();
+    let (mut config, data_key, recovery_key) = tauri::async_runtime::spawn_blocking(move || {
+        let (source, source_key) = new_key_source(key_source, passphrase.as_deref())?;
+        let data_key = secrets::random_bytes::<32>();
+        let recovery_key = secrets::random_bytes::<32>();
+        let mut config = encryption::new_config(source, &source_key, &data_key, &recovery_key)?;
+        // Saved before any file is sealed so an interrupted migration resumes on the next open.
+        encryption::begin_migration(&mut config, &data_key)?;
+        save_encryption_config(&config_app, &config)?;
+        Ok::<_, String>((config, data_key, recovery_key))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    set_data_vault(&app, storage::Vault::Unlocked(data_key));
+    let files = encryption::protected_files(&storage::data_dir(&app)?);
+    let count = finish_data_migration(&app, &mut config, &files, &data_key)?;
+    println!(
+        "[AnyChat] Data encryption enabled ({:?}), {} files encrypted",
+        key_source, count
+    );
+    emit_data_encryption_status(&app);
+    Ok(encryption::format_recovery_key(&recovery_key))
+}
 
-        let main_window = app
-            .get_webview_window("main")
-            .ok_or_else(|| "Main window not found".to_string())?;
-        let host = ensure_windows_service_host(&app, &state, &main_window, &service)?;
-        let window = app
-            .get_webview_window(&host.window_label)
-            .ok_or_else(|| format!("Service window {} not found", host.window_label))?;
-        let refresh_url = resolve_windows_refresh_url(
-            window.url().ok().map(|current_url| current_url.to_string()),
-            &service,
-        );
-        let url: tauri::Url = refresh_url.parse().map_err(|e| format!("{}", e))?;
-        window.navigate(url).map_err(|e| e.to_string())?;
-        return show_active_windows_service_host(&app, &state);
+#[tauri::command]
+async fn unlock_data(
+    app: tauri::AppHandle,
+    passphrase: String,
+) -> Result<encryption::DataEncryptionStatus, String> {
+    if !matches!(data_vault(&app), storage::Vault::Locked) {
+        return Ok(data_encryption_status(&app));
     }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        if let Some(webview) = app.get_webview(&service.id) {
-            if let Ok(url) = webview.url() {
-                let _ = webview.navigate(url);
-            }
+    let config =
+        load_encryption_config(&app)?.ok_or_else(|| "Data encryption is not set up".to_string())?;
+
+    let data_key = tauri::async_runtime::spawn_blocking(move || {
+        let unwrapped = encryption::passphrase_key(&config, &passphrase)
+            .and_then(|key| encryption::unwrap_key(&key, &config.wrapped_key));
+        if unwrapped.is_err() {
+            std::thread::sleep(app_lock::FAILED_UNLOCK_DELAY);
         }
-        Ok(())
-    }
+        unwrapped.map_err(|_| "Wrong passphrase".to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let opened = open_vault(&app, data_key);
+    finish_data_unlock(&app);
+    opened?;
+    println!("[AnyChat] Data unlocked");
+    Ok(data_encryption_status(&app))
 }
 
+/// Opens the data with the recovery key and protects it with a new passphrase or keyring key.
+/// The recovery key stays valid.
 #[tauri::command]
-fn hide_all_service_content(app: tauri::AppHandle) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let state = app.state::<AppState>();
-        hide_windows_service_hosts(&app, &state);
-        return Ok(());
+async fn recover_data(
+    app: tauri::AppHandle,
+    recovery_key: String,
+    key_source: encryption::KeySourceKind,
+    passphrase: Option<String>,
+) -> Result<encryption::DataEncryptionStatus, String> {
+    let config =
+        load_encryption_config(&app)?.ok_or_else(|| "Data encryption is not set up".to_string())?;
+
+    let config_app = app.clone();
+    let data_key = tauri::async_runtime::spawn_blocking(move || {
+        let recovery_key = encryption::parse_recovery_key(&recovery_key)?;
+        let data_key = encryption::unwrap_key(&recovery_key, &config.recovery_wrapped_key)
+            .map_err(|_| "Wrong recovery key".to_string())?;
+        let (source, source_key) = new_key_source(key_source, passphrase.as_deref())?;
+        let mut recovered = encryption::new_config(source, &source_key, &data_key, &recovery_key)?;
+        recovered.pending_migration = config.pending_migration;
+        save_encryption_config(&config_app, &recovered)?;
+        Ok::<_, String>(data_key)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let opened = open_vault(&app, data_key);
+    finish_data_unlock(&app);
+    opened?;
+    println!(
+        "[AnyChat] Data recovered, now protected by {:?}",
+        key_source
+    );
+    Ok(data_encryption_status(&app))
+}
+
+/// Writes a new recovery key to `path`; the previous one stops working.
+#[tauri::command]
+fn export_data_recovery_key(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let path = std::path::PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err("Recovery key path must be absolute".to_string());
     }
+    let storage::Vault::Unlocked(data_key) = data_vault(&app) else {
+        return Err("Unlock AnyChat's data first".to_string());
+    };
+    let mut config =
+        load_encryption_config(&app)?.ok_or_else(|| "Data encryption is not set up".to_string())?;
+
+    let recovery_key = secrets::random_bytes::<32>();
+    let rewrapped = encryption::new_config(
+        config.key_source.clone(),
+        &recovery_key,
+        &data_key,
+        &recovery_key,
+    )?;
+    config.recovery_wrapped_key = rewrapped.recovery_wrapped_key;
+    storage::write_atomic(
+        &path,
+        encryption::recovery_key_file(&recovery_key).as_bytes(),
+    )?;
+    save_encryption_config(&app, &config)?;
+    println!("[AnyChat] Exported a new data recovery key");
+    Ok(())
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let state = app.state::<AppState>();
-        let created = state.created_webviews.lock().unwrap();
+/// Decrypts the data files back to plaintext and forgets the keys.
+#[tauri::command]
+fn disable_data_encryption(
+    app: tauri::AppHandle,
+) -> Result<encryption::DataEncryptionStatus, String> {
+    let storage::Vault::Unlocked(data_key) = data_vault(&app) else {
+        return Err("Unlock AnyChat's data first".to_string());
+    };
+    let config = load_encryption_config(&app)?;
 
-        for label in created.iter() {
-            if let Some(webview) = app.get_webview(label) {
-                let _ = webview.hide();
-            }
+    set_data_vault(&app, storage::Vault::Disabled);
+    let files = encryption::protected_files(&storage::data_dir(&app)?);
+    if let Err(e) = encryption::rewrite_files(&files, &data_key, None) {
+        set_data_vault(&app, storage::Vault::Unlocked(data_key));
+        return Err(e);
+    }
+    let config_path = storage::data_file_path(&app, encryption::ENCRYPTION_FILE)?;
+    std::fs::remove_file(&config_path)
+        .map_err(|e| format!("Failed to remove {}: {}", config_path.display(), e))?;
+    if config.is_some_and(|config| config.key_source == encryption::KeySource::Keyring) {
+        if let Err(e) = encryption::delete_keyring_key() {
+            println!("[AnyChat] Failed to remove the keyring key: {}", e);
         }
-
-        Ok(())
     }
+    println!("[AnyChat] Data encryption disabled");
+    Ok(emit_data_encryption_status(&app))
 }
 
+/// Opens the service in an ephemeral session window, or focuses the one already open.
+/// Returns the window label.
 #[tauri::command]
-fn sync_service_host_state(
+async fn open_private_service(
     app: tauri::AppHandle,
-    services: Vec<ServiceHostPayload>,
-    active_service_id: Option<String>,
-) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let state = app.state::<AppState>();
-        return sync_windows_service_host_state(&app, &state, &services, active_service_id);
+    service: ServiceHostPayload,
+) -> Result<String, String> {
+    if app_locked(&app) {
+        return Err("AnyChat is locked".to_string());
+    }
+    service_policy(&app).check_service(&service.id, &service.url)?;
+    let label = private_session::private_label(&service.id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(label);
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = (app, services, active_service_id);
-        Ok(())
+    let parsed_url: tauri::Url = service.url.parse().map_err(|e| format!("{}", e))?;
+    let kind = service.kind;
+    let service_url = service.url.clone();
+    let page_load_app = app.clone();
+    let page_load_service_id = service.id.clone();
+    let title_app = app.clone();
+    let title_service_id = service.id.clone();
+    let new_window_app = app.clone();
+    let new_window_label = label.clone();
+    let navigation_app = app.clone();
+
+    let mut builder = WebviewWindowBuilder::new(&app, &label, WebviewUrl::External(parsed_url))
+        .title(private_session::window_title(&service.name))
+        .inner_size(1000.0, 760.0)
+        .center()
+        .incognito(true)
+        .with_policy(service_policy(&app), effective_proxy(&app))
+        .on_navigation(move |url| {
+            allow_service_navigation(kind, url) && policy_allows_navigation(&navigation_app, url)
+        })
+        .on_page_load(move |_, payload| {
+            let event = match payload.event() {
+                tauri::webview::PageLoadEvent::Started => {
+                    lifecycle::LifecycleEvent::NavigationStarted {
+                        url: payload.url().to_string(),
+                    }
+                }
+                tauri::webview::PageLoadEvent::Finished => lifecycle::LifecycleEvent::PageLoaded {
+                    url: payload.url().to_string(),
+                    title: None,
+                },
+            };
+            emit_private_lifecycle_event(&page_load_app, &page_load_service_id, event);
+        })
+        .on_document_title_changed(move |_, title| {
+            emit_private_lifecycle_event(
+                &title_app,
+                &title_service_id,
+                lifecycle::LifecycleEvent::TitleChanged { title },
+            )
+        })
+        .on_new_window(move |url, _features| {
+            // A regular popup would sign in through the shared store; stay in this window.
+            let is_auth_popup = is_service_auth_popup(kind, &service_url, &url)
+                && policy_allows_navigation(&new_window_app, &url);
+            if is_auth_popup {
+                if let Some(window) = new_window_app.get_webview_window(&new_window_label) {
+                    let _ = window.navigate(url.clone());
+                }
+            }
+            handle_external_new_window(&new_window_app, &url, is_auth_popup)
+        });
+
+    if service.kind.uses_site_quirks() {
+        if should_use_custom_user_agent(&service.url) {
+            builder = builder.user_agent(USER_AGENT);
+        }
+        if should_inject_webview_compatibility_script(&service.url) {
+            builder = builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
+        }
     }
+
+    let window = builder.build().map_err(|e| e.to_string())?;
+    let destroyed_app = app.clone();
+    let destroyed_service_id = service.id.clone();
+    window.on_window_event(move |event| {
+        if matches!(event, tauri::WindowEvent::Destroyed) {
+            emit_private_lifecycle_event(
+                &destroyed_app,
+                &destroyed_service_id,
+                lifecycle::LifecycleEvent::Destroyed,
+            );
+        }
+    });
+    emit_private_lifecycle_event(&app, &service.id, lifecycle::LifecycleEvent::Created);
+    println!("[AnyChat] Opened private session for {}", service.id);
+    Ok(label)
 }
 
+/// Closes the service's ephemeral session window, discarding its data.
 #[tauri::command]
-fn sync_docked_content_layout(app: tauri::AppHandle) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let state = app.state::<AppState>();
-        return sync_windows_service_host_layout(&app, &state);
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = app;
-        Ok(())
+fn close_private_service(app: tauri::AppHandle, service_id: String) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(&private_session::private_label(&service_id)) {
+        window.destroy().map_err(|e| e.to_string())?;
     }
+    Ok(())
 }
 
+/// Clears the service's site data, or only its caches, then recreates its webview at the
+/// service home. Returns the number of site records removed where the platform reports it.
 #[tauri::command]
-async fn discover_site_icon(url: String) -> Result<Option<String>, String> {
-    let parsed_url = reqwest::Url::parse(&url).map_err(|e| e.to_string())?;
-    let client = reqwest::Client::builder()
-        .user_agent(USER_AGENT)
-        .redirect(reqwest::redirect::Policy::limited(5))
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| e.to_string())?;
+async fn clear_service_browsing_data(
+    app: tauri::AppHandle,
+    service_id: String,
+    cache_only: bool,
+) -> Result<usize, String> {
+    let (Some(webview), Some(service)) = (
+        service_webview(&app, &service_id),
+        live_service_payload(&app, &service_id),
+    ) else {
+        return Err(format!("Service webview {} not found", service_id));
+    };
+    let home = tauri::Url::parse(&service.url).map_err(|e| e.to_string())?;
+    let host = service_host(&service.url)
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| format!("Service {} has no host to clear", service_id))?;
+    let scope = if cache_only {
+        browsing_data::ClearScope::CacheOnly
+    } else {
+        browsing_data::ClearScope::All
+    };
 
-    let response = client
-        .get(parsed_url.clone())
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let removed = browsing_data::clear_site_data(&webview, &home, &host, scope).await?;
+    println!(
+        "[AnyChat] Cleared {:?} browsing data of {} ({} records for {})",
+        scope, service_id, removed, host
+    );
 
-    if !response.status().is_success() {
-        return Ok(None);
+    let visible = service_status(&app, &service_id).visible;
+    rebuild_service_webview(&app, &service_id, visible).await?;
+    Ok(removed)
+}
+
+/// Shows the find bar inside the service page and gives it keyboard focus.
+#[tauri::command]
+fn open_find_in_service(app: tauri::AppHandle, service_id: String) -> Result<(), String> {
+    open_service_find(&app, &service_id)
+}
+
+#[tauri::command]
+fn stop_find_in_service(app: tauri::AppHandle, service_id: String) -> Result<(), String> {
+    if let Some(webview) = service_webview(&app, &service_id) {
+        webview
+            .eval(find::clear_script())
+            .map_err(|e| e.to_string())?;
     }
+    Ok(())
+}
 
-    let html = response.text().await.map_err(|e| e.to_string())?;
-    Ok(extract_site_icon_url(&parsed_url, &html))
+#[tauri::command]
+fn service_go_back(app: tauri::AppHandle, service_id: String) -> Result<(), String> {
+    navigate_service_history(&app, &service_id, navigation::HistoryDirection::Back)
 }
 
-fn extract_site_icon_url(base_url: &reqwest::Url, html: &str) -> Option<String> {
-    let link_tag_regex = Regex::new(r"(?is)<link\b[^>]*>").ok()?;
-    let rel_regex = Regex::new(r#"(?i)rel\s*=\s*["']([^"']+)["']"#).ok()?;
-    let href_regex = Regex::new(r#"(?i)href\s*=\s*["']([^"']+)["']"#).ok()?;
+#[tauri::command]
+fn service_go_forward(app: tauri::AppHandle, service_id: String) -> Result<(), String> {
+    navigate_service_history(&app, &service_id, navigation::HistoryDirection::Forward)
+}
 
-    for tag_match in link_tag_regex.find_iter(html) {
-        let tag = tag_match.as_str();
-        let Some(rel_value) = rel_regex
-            .captures(tag)
-            .and_then(|captures| captures.get(1))
-            .map(|value| value.as_str().to_ascii_lowercase())
-        else {
-            continue;
-        };
+/// Returns to the service's configured start page.
+#[tauri::command]
+fn service_go_home(app: tauri::AppHandle, service: ServiceHostPayload) -> Result<(), String> {
+    let webview = service_webview(&app, &service.id)
+        .ok_or_else(|| format!("Service webview {} not found", service.id))?;
+    let url: tauri::Url = service.url.parse().map_err(|e| format!("{}", e))?;
+    webview.navigate(url).map_err(|e| e.to_string())
+}
 
-        if !rel_value.contains("icon") {
-            continue;
-        }
+#[tauri::command]
+fn get_service_navigation_state(
+    app: tauri::AppHandle,
+    service_id: String,
+) -> navigation::NavigationState {
+    app.state::<AppState>()
+        .navigation_states
+        .lock()
+        .unwrap()
+        .get(&service_id)
+        .copied()
+        .unwrap_or_default()
+}
 
-        let Some(href) = href_regex
-            .captures(tag)
-            .and_then(|captures| captures.get(1))
-            .map(|value| value.as_str().trim())
-        else {
-            continue;
-        };
+/// Current status of every service that has had a webview, for listeners that start late.
+#[tauri::command]
+fn get_service_statuses(app: tauri::AppHandle) -> HashMap<String, lifecycle::ServiceStatus> {
+    app.state::<AppState>()
+        .service_statuses
+        .lock()
+        .unwrap()
+        .clone()
+}
 
-        if href.is_empty() || href.starts_with("data:") {
-            continue;
-        }
+/// `None` turns keep-alive off for the service.
+#[tauri::command]
+fn set_service_keep_alive(
+    app: tauri::AppHandle,
+    service_id: String,
+    keep_alive: Option<keep_alive::KeepAliveSettings>,
+) -> Result<(), String> {
+    let keep_alive = keep_alive
+        .map(|settings| {
+            keep_alive::validate_settings(&settings)?;
+            Ok::<_, String>(keep_alive::KeepAliveSettings {
+                path: settings.path.trim().to_string(),
+                ..settings
+            })
+        })
+        .transpose()?;
+    update_service_registry(&app, |registry| {
+        registry.settings_mut(&service_id).keep_alive = keep_alive;
+    })?;
 
-        if let Ok(resolved_url) = base_url.join(href) {
-            return Some(resolved_url.to_string());
+    app.state::<AppState>()
+        .keep_alive
+        .lock()
+        .unwrap()
+        .forget(&service_id);
+    Ok(())
+}
+
+/// `None` restores the built-in rules for the service's host. The live page is re-checked.
+#[tauri::command]
+fn set_service_login_rules(
+    app: tauri::AppHandle,
+    service_id: String,
+    rules: Option<login_state::LoginRules>,
+) -> Result<(), String> {
+    update_service_registry(&app, |registry| {
+        registry.settings_mut(&service_id).login_rules = rules;
+    })?;
+
+    if let (Some(webview), Some(service)) = (
+        service_webview(&app, &service_id),
+        live_service_payload(&app, &service_id),
+    ) {
+        if let Ok(url) = webview.url() {
+            check_service_login(&app, &service, &webview, &url);
         }
     }
+    Ok(())
+}
 
-    None
+/// Login state of every service that has loaded a page. Features acting on several services
+/// at once should skip those that are not `signedIn`.
+#[tauri::command]
+fn get_service_login_states(app: tauri::AppHandle) -> HashMap<String, login_state::LoginState> {
+    app.state::<AppState>()
+        .login_states
+        .lock()
+        .unwrap()
+        .states()
+}
+
+/// Last keep-alive result per service; services that were never pinged are absent.
+#[tauri::command]
+fn get_keep_alive_statuses(app: tauri::AppHandle) -> HashMap<String, keep_alive::KeepAliveStatus> {
+    app.state::<AppState>()
+        .keep_alive
+        .lock()
+        .unwrap()
+        .statuses()
 }
 
 #[tauri::command]
@@ -1393,15 +4861,46 @@ pub fn run() {
             setup_complete: Mutex::new(false),
             windows_service_hosts: Mutex::new(HashMap::new()),
             active_windows_service_id: Mutex::new(None),
+            active_service_id: Mutex::new(None),
+            settings: Mutex::new(AppSettings::default()),
+            service_registry: Mutex::new(ServiceRegistry::default()),
+            native_chat_requests: Mutex::new(HashMap::new()),
+            user_script_revisions: Mutex::new(HashMap::new()),
+            content_filters: Mutex::new(Arc::default()),
+            content_blocking_baked: Mutex::new(HashSet::new()),
+            navigation_states: Mutex::new(HashMap::new()),
+            service_statuses: Mutex::new(HashMap::new()),
+            watchdog: Mutex::new(watchdog::Watchdog::default()),
+            service_payloads: Mutex::new(HashMap::new()),
+            refresh_scheduler: Mutex::new(resume::RefreshScheduler::default()),
+            keep_alive: Mutex::new(keep_alive::KeepAliveTracker::default()),
+            login_states: Mutex::new(login_state::LoginTracker::default()),
+            app_lock: Mutex::new(app_lock::LockState::default()),
+            policy: policy::load(&policy::policy_path()),
+            vault: Mutex::new(storage::Vault::default()),
+            sync_running: Mutex::new(false),
+            #[cfg(not(target_os = "linux"))]
+            navigation_history: Mutex::new(HashMap::new()),
         })
         .setup(|app| {
             println!("[AnyChat] Setup starting...");
+            match backup_roots(app.handle())
+                .and_then(|roots| backup::finish_pending_restore(&roots))
+            {
+                Ok(true) => println!("[AnyChat] Restored data from backup"),
+                Ok(false) => {}
+                Err(e) => println!(
+                    "[AnyChat] ERROR: Failed to finish restoring a backup: {}",
+                    e
+                ),
+            }
 
             let main_webview_window =
                 match WebviewWindowBuilder::new(app, "main", WebviewUrl::App("index.html".into()))
                     .title("")
                     .inner_size(1200.0, 800.0)
                     .min_inner_size(800.0, 600.0)
+                    .devtools(!service_policy(app.handle()).disable_devtools)
                     .build()
                 {
                     Ok(w) => {
@@ -1415,74 +4914,49 @@ pub fn run() {
                 };
 
             let state = app.state::<AppState>();
+            load_persisted_state(app.handle(), &state);
+            if state.settings.lock().unwrap().app_lock.enabled() {
+                state.app_lock.lock().unwrap().lock();
+            }
+
             #[cfg(debug_assertions)]
             if should_open_devtools() {
-                let _ = main_webview_window.open_devtools();
+                main_webview_window.open_devtools();
                 println!("[AnyChat] DevTools opened for main webview window");
             }
 
             #[cfg(not(target_os = "windows"))]
             {
                 let window = main_webview_window.as_ref().window();
-                let (pos, size) = compute_webview_bounds(&window)?;
-
                 let default_services = [
-                    ("chatgpt", "https://chatgpt.com"),
-                    ("gemini", "https://gemini.google.com"),
+                    ("chatgpt", "ChatGPT", "https://chatgpt.com"),
+                    ("gemini", "Gemini", "https://gemini.google.com"),
                 ];
 
-                for (index, (label, url)) in default_services.iter().enumerate() {
-                    let app_handle_clone = app.handle().clone();
-
-                    let mut webview_builder =
-                        WebviewBuilder::new(*label, WebviewUrl::External(url.parse().unwrap()))
-                            .user_agent(USER_AGENT)
-                            .on_navigation(|url| {
-                                let url_str = url.as_str();
-                                if is_auth_url(url_str) {
-                                    #[cfg(debug_assertions)]
-                                    println!("[AnyChat] Allowing OAuth navigation to: {}", url_str);
-                                }
-                                true
-                            })
-                            .on_new_window(move |url, _features| {
-                                #[cfg(debug_assertions)]
-                                println!("[AnyChat] New window requested: {}", url);
-
-                                if is_auth_url(url.as_str()) {
-                                    open_oauth_popup(&app_handle_clone, &url);
-                                }
-
-                                handle_external_new_window(&app_handle_clone, &url)
-                            });
-
-                    if should_inject_webview_compatibility_script(url) {
-                        webview_builder =
-                            webview_builder.initialization_script(WEBVIEW_COMPAT_SCRIPT);
-                    }
-
-                    let webview = window
-                        .add_child(webview_builder, pos, size)
-                        .map_err(|e| e.to_string())?;
-
-                    // Some platforms/versions may not apply the initial bounds reliably. Enforce once right away.
-                    let _ = webview.set_position(pos);
-                    let _ = webview.set_size(size);
-
-                    {
-                        let mut created = state.created_webviews.lock().unwrap();
-                        created.insert(label.to_string());
-                    }
-
-                    #[cfg(debug_assertions)]
-                    if index == 0 && should_open_devtools() {
-                        let _ = webview.open_devtools();
-                        println!("[AnyChat] DevTools opened for initial webview: {}", label);
+                let mut shown = false;
+                for (id, name, url) in default_services {
+                    if let Err(e) = service_policy(app.handle()).check_service(id, url) {
+                        println!("[AnyChat] Not creating {}: {}", id, e);
+                        continue;
                     }
-
-                    if index != 0 {
+                    let service = ServiceHostPayload {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        url: url.to_string(),
+                        enabled: true,
+                        kind: ServiceKind::Web,
+                    };
+                    create_webview_for_service(app.handle(), &service, &state, &window)?;
+                    let Some(webview) = app.get_webview(id) else {
+                        continue;
+                    };
+
+                    let visible = !shown && !app_locked(app.handle());
+                    shown = true;
+                    if !visible {
                         let _ = webview.hide();
                     }
+                    set_service_visible(app.handle(), id, visible);
                 }
             }
 
@@ -1525,6 +4999,32 @@ pub fn run() {
                 *setup_complete = true;
             }
 
+            #[cfg(target_os = "macos")]
+            {
+                let menu = tauri::menu::Menu::default(app.handle())?;
+                menu.append(&shortcuts::service_menu(app.handle())?)?;
+                app.set_menu(menu)?;
+                app.on_menu_event(|app_handle, event| {
+                    let Some(shortcut) = shortcuts::menu_shortcut(event.id().as_ref()) else {
+                        return;
+                    };
+                    let service_id = app_handle
+                        .state::<AppState>()
+                        .active_service_id
+                        .lock()
+                        .unwrap()
+                        .clone();
+                    if let Some(service_id) = service_id {
+                        handle_service_shortcut(app_handle, &service_id, shortcut);
+                    }
+                });
+            }
+
+            start_service_watchdog(app.handle());
+            start_keep_alive(app.handle());
+            start_resume_monitor(app.handle());
+            start_app_lock_monitor(app.handle());
+
             let show_item = MenuItemBuilder::with_id("show", "显示窗口").build(app)?;
             let hide_item = MenuItemBuilder::with_id("hide", "隐藏窗口").build(app)?;
             let quit_item = MenuItemBuilder::with_id("quit", "退出").build(app)?;
@@ -1573,7 +5073,7 @@ pub fn run() {
                 WindowEvent::CloseRequested { api: _api, .. } => {
                     let app_handle = window.app_handle();
                     let state = app_handle.state::<AppState>();
-                    hide_windows_service_hosts(&app_handle, &state);
+                    hide_windows_service_hosts(app_handle, &state);
 
                     #[cfg(target_os = "windows")]
                     {
@@ -1592,7 +5092,7 @@ pub fn run() {
                 | WindowEvent::ScaleFactorChanged { .. } => {
                     let app_handle = window.app_handle();
                     let state = app_handle.state::<AppState>();
-                    let _ = show_active_windows_service_host(&app_handle, &state);
+                    let _ = show_active_windows_service_host(app_handle, &state);
                 }
                 _ => {}
             }
@@ -1604,7 +5104,77 @@ pub fn run() {
             refresh_service_content,
             hide_all_service_content,
             sync_service_host_state,
-            sync_docked_content_layout
+            sync_docked_content_layout,
+            get_http_settings,
+            set_http_settings,
+            get_service_settings,
+            set_service_tls_policy,
+            set_local_server_ports,
+            discover_local_servers,
+            native_chat_send,
+            native_chat_cancel,
+            native_chat_list_models,
+            native_chat_list_conversations,
+            native_chat_load_conversation,
+            native_chat_delete_conversation,
+            native_chat_set_api_key,
+            native_chat_has_api_key,
+            list_user_scripts,
+            save_user_script,
+            delete_user_script,
+            read_user_script_log,
+            get_content_filter_stats,
+            reload_content_filters,
+            set_service_content_blocking,
+            get_app_theme,
+            set_app_theme,
+            set_service_theme,
+            get_service_zoom,
+            zoom_service_in,
+            zoom_service_out,
+            zoom_service_reset,
+            zoom_service_set,
+            find_in_service,
+            open_find_in_service,
+            stop_find_in_service,
+            service_go_back,
+            service_go_forward,
+            service_go_home,
+            get_service_navigation_state,
+            get_service_statuses,
+            set_service_keep_alive,
+            get_keep_alive_statuses,
+            set_service_login_rules,
+            get_service_login_states,
+            clear_service_browsing_data,
+            open_private_service,
+            close_private_service,
+            export_service_cookies,
+            import_service_cookies,
+            list_browser_profiles,
+            preview_browser_cookie_import,
+            import_browser_cookies,
+            get_app_lock_status,
+            set_app_lock_password,
+            set_app_lock_options,
+            lock_app,
+            unlock_app,
+            report_app_activity,
+            get_data_encryption_status,
+            enable_data_encryption,
+            unlock_data,
+            recover_data,
+            export_data_recovery_key,
+            disable_data_encryption,
+            get_effective_policy,
+            get_sync_status,
+            set_sync_settings,
+            sync_now,
+            get_sync_conflicts,
+            create_backup,
+            restore_backup,
+            take_restored_frontend_state,
+            report_bridge_message
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
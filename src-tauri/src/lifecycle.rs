@@ -0,0 +1,353 @@
+//! Typed lifecycle events for service webviews, emitted on `service://lifecycle` with the
+//! service id flattened into the payload, and the page shown when a service fails to load.
+
+use crate::pages::render_error_page;
+use std::time::Duration;
+
+pub(crate) const LIFECYCLE_EVENT: &str = "service://lifecycle";
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum LifecycleEvent {
+    Created,
+    Destroyed,
+    Shown,
+    Hidden,
+    NavigationStarted {
+        url: String,
+    },
+    PageLoaded {
+        url: String,
+        title: Option<String>,
+    },
+    LoadFailed {
+        url: String,
+        error: String,
+    },
+    TitleChanged {
+        title: String,
+    },
+    /// The webview died or hung and is about to be rebuilt.
+    Crashed {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServiceLifecycleEvent<'a> {
+    pub service_id: &'a str,
+    /// Set for the service's ephemeral session window; omitted otherwise.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub private: bool,
+    #[serde(flatten)]
+    pub event: &'a LifecycleEvent,
+}
+
+/// What the events so far say about a service webview, for frontends that subscribe late.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ServiceStatus {
+    pub created: bool,
+    pub visible: bool,
+    pub loading: bool,
+    /// Last successfully loaded URL.
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub error: Option<String>,
+    /// Consecutive failed loads, reset by the next successful one.
+    pub failed_attempts: u32,
+    /// Set while the AnyChat error page itself loads, so its load events are not reported.
+    #[serde(skip)]
+    pub showing_error_page: bool,
+}
+
+impl ServiceStatus {
+    /// Applies `event`; `false` means it repeats the current state and needs no emit.
+    pub fn apply(&mut self, event: &LifecycleEvent) -> bool {
+        match event {
+            LifecycleEvent::Created => {
+                *self = ServiceStatus {
+                    created: true,
+                    ..ServiceStatus::default()
+                };
+            }
+            LifecycleEvent::Destroyed => {
+                if !self.created {
+                    return false;
+                }
+                *self = ServiceStatus::default();
+            }
+            LifecycleEvent::Shown | LifecycleEvent::Hidden => {
+                let visible = *event == LifecycleEvent::Shown;
+                if self.visible == visible {
+                    return false;
+                }
+                self.visible = visible;
+            }
+            LifecycleEvent::NavigationStarted { .. } => {
+                if self.showing_error_page {
+                    return false;
+                }
+                self.loading = true;
+                self.error = None;
+            }
+            LifecycleEvent::PageLoaded { url, title } => {
+                if self.showing_error_page {
+                    self.showing_error_page = false;
+                    return false;
+                }
+                // WebKit finishes failed loads too, showing its error page.
+                if self.error.is_some() {
+                    return false;
+                }
+                self.loading = false;
+                self.failed_attempts = 0;
+                self.url = Some(url.clone());
+                if title.is_some() {
+                    self.title = title.clone();
+                }
+            }
+            LifecycleEvent::LoadFailed { error, .. } => {
+                self.loading = false;
+                self.error = Some(error.clone());
+                self.failed_attempts += 1;
+            }
+            LifecycleEvent::TitleChanged { title } => {
+                if self.title.as_deref() == Some(title) {
+                    return false;
+                }
+                self.title = Some(title.clone());
+            }
+            LifecycleEvent::Crashed { reason } => {
+                self.loading = false;
+                self.error = Some(reason.clone());
+            }
+        }
+        true
+    }
+}
+
+/// Backoff before the error page retries on its own: doubling from 5 seconds, capped at 5 minutes.
+pub(crate) fn retry_delay(failed_attempts: u32) -> Duration {
+    FIRST_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(failed_attempts.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// The page shown in place of a service that failed to load. It retries `retry_url` after
+/// `delay`, or as soon as the system reports being back online.
+pub(crate) fn load_failure_page_html(
+    service_name: &str,
+    reason: &str,
+    retry_url: &str,
+    delay: Duration,
+) -> String {
+    let retry_url_literal = serde_json::Value::String(retry_url.to_string())
+        .to_string()
+        .replace("</", "<\\/");
+    let extra_html = format!(
+        r#"<p><button id="retry">Retry</button> <span id="status"></span></p>
+<script>
+(function() {{
+    var url = {retry_url};
+    var remaining = {delay_secs};
+    var status = document.getElementById('status');
+    function retry() {{ location.replace(url); }}
+    document.getElementById('retry').addEventListener('click', retry);
+    window.addEventListener('online', retry);
+    setInterval(function() {{
+        if (!navigator.onLine) {{
+            status.textContent = 'Waiting for the network…';
+            return;
+        }}
+        remaining -= 1;
+        if (remaining <= 0) retry();
+        else status.textContent = 'Retrying in ' + remaining + 's';
+    }}, 1000);
+}})();
+</script>"#,
+        retry_url = retry_url_literal,
+        delay_secs = delay.as_secs().max(1),
+    );
+
+    render_error_page(
+        &format!("{} could not be loaded", service_name),
+        "AnyChat will try again automatically when the connection is back.",
+        &[reason.to_string(), retry_url.to_string()],
+        &extra_html,
+    )
+}
+
+/// Reports failed main-frame loads and replaces WebKitGTK's error page with AnyChat's.
+/// Cancelled loads (navigating away, downloads) are not failures. Other platforms do not
+/// surface load errors through Tauri and keep their native error page.
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_load_failure_tracking(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+    service_name: &str,
+) {
+    use webkit2gtk::{NetworkError, PolicyError, WebViewExt};
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let service_name = service_name.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        platform_webview
+            .inner()
+            .connect_load_failed(move |webview, _, failing_uri, error| {
+                if error.matches(NetworkError::Cancelled)
+                    || error.matches(PolicyError::FrameLoadInterruptedByPolicyChange)
+                {
+                    return false;
+                }
+                crate::emit_lifecycle_event(
+                    &app,
+                    &service_id,
+                    LifecycleEvent::LoadFailed {
+                        url: failing_uri.to_string(),
+                        error: error.message().to_string(),
+                    },
+                );
+                let html = crate::service_load_failure_page(
+                    &app,
+                    &service_id,
+                    &service_name,
+                    failing_uri,
+                    error.message(),
+                );
+                webview.load_alternate_html(&html, failing_uri, None);
+                true
+            });
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_load_failure_tracking(
+    _app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    _service_id: &str,
+    _service_name: &str,
+) {
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        load_failure_page_html, retry_delay, LifecycleEvent, ServiceLifecycleEvent, ServiceStatus,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn status_follows_events_and_skips_repeats() {
+        let mut status = ServiceStatus::default();
+        assert!(status.apply(&LifecycleEvent::Created));
+        assert!(!status.apply(&LifecycleEvent::Hidden));
+        assert!(status.apply(&LifecycleEvent::Shown));
+        assert!(status.apply(&LifecycleEvent::NavigationStarted {
+            url: "https://chatgpt.com/".to_string(),
+        }));
+        assert!(status.loading);
+        assert!(status.apply(&LifecycleEvent::TitleChanged {
+            title: "ChatGPT".to_string(),
+        }));
+        assert!(!status.apply(&LifecycleEvent::TitleChanged {
+            title: "ChatGPT".to_string(),
+        }));
+        assert!(status.apply(&LifecycleEvent::LoadFailed {
+            url: "https://chatgpt.com/c/1".to_string(),
+            error: "offline".to_string(),
+        }));
+        assert!(!status.apply(&LifecycleEvent::PageLoaded {
+            url: "https://chatgpt.com/c/1".to_string(),
+            title: None,
+        }));
+        assert_eq!(status.url, None);
+        assert_eq!(status.error.as_deref(), Some("offline"));
+        assert_eq!(status.failed_attempts, 1);
+        assert!(status.apply(&LifecycleEvent::Destroyed));
+        assert!(!status.apply(&LifecycleEvent::Destroyed));
+    }
+
+    #[test]
+    fn error_page_loads_are_not_reported_as_navigation() {
+        let mut status = ServiceStatus::default();
+        status.apply(&LifecycleEvent::LoadFailed {
+            url: "https://chatgpt.com/c/1".to_string(),
+            error: "offline".to_string(),
+        });
+        status.showing_error_page = true;
+        let error_page = "https://chatgpt.com/c/1".to_string();
+        assert!(!status.apply(&LifecycleEvent::NavigationStarted {
+            url: error_page.clone(),
+        }));
+        assert!(!status.apply(&LifecycleEvent::PageLoaded {
+            url: error_page.clone(),
+            title: None,
+        }));
+        assert!(status.apply(&LifecycleEvent::NavigationStarted {
+            url: error_page.clone()
+        }));
+        assert!(status.apply(&LifecycleEvent::PageLoaded {
+            url: error_page,
+            title: None,
+        }));
+        assert_eq!(status.failed_attempts, 0);
+        assert_eq!(status.error, None);
+    }
+
+    #[test]
+    fn retries_back_off_up_to_five_minutes() {
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(3), Duration::from_secs(20));
+        assert_eq!(retry_delay(40), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn failure_page_embeds_the_retry_url_safely() {
+        let html = load_failure_page_html(
+            "Chat<GPT>",
+            "offline",
+            "https://chatgpt.com/c/1?q=</script>",
+            Duration::from_secs(10),
+        );
+        assert!(html.contains("<h1>Chat&lt;GPT&gt; could not be loaded</h1>"));
+        assert!(html.contains(r#"var url = "https://chatgpt.com/c/1?q=<\/script>";"#));
+        assert!(html.contains("var remaining = 10;"));
+    }
+
+    #[test]
+    fn events_serialize_with_the_service_id_flattened() {
+        let event = LifecycleEvent::PageLoaded {
+            url: "https://claude.ai/".to_string(),
+            title: None,
+        };
+        assert_eq!(
+            serde_json::to_value(ServiceLifecycleEvent {
+                service_id: "claude",
+                private: false,
+                event: &event,
+            })
+            .unwrap(),
+            serde_json::json!({
+                "serviceId": "claude",
+                "type": "pageLoaded",
+                "url": "https://claude.ai/",
+                "title": null,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ServiceLifecycleEvent {
+                service_id: "claude",
+                private: true,
+                event: &LifecycleEvent::Created,
+            })
+            .unwrap(),
+            serde_json::json!({ "serviceId": "claude", "private": true, "type": "created" })
+        );
+    }
+}
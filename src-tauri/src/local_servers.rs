@@ -0,0 +1,304 @@
+//! Finds AI servers running on this machine so they can be added as services in one click.
+
+use std::time::Duration;
+
+pub(crate) const DISCOVERY_TIMEOUT: Duration = Duration::from_millis(800);
+pub(crate) const DISCOVERY_HOST: &str = "127.0.0.1";
+
+/// Ports probed even when the user has not configured any.
+const DEFAULT_PORTS: [u16; 5] = [11434, 1234, 8080, 3000, 1337];
+
+const JAN_PORT: u16 = 1337;
+const LM_STUDIO_PORT: u16 = 1234;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum LocalServerProduct {
+    Ollama,
+    LmStudio,
+    LlamaCpp,
+    OpenWebUi,
+    Jan,
+    OpenAiCompatible,
+}
+
+impl LocalServerProduct {
+    fn display_name(self) -> &'static str {
+        match self {
+            LocalServerProduct::Ollama => "Ollama",
+            LocalServerProduct::LmStudio => "LM Studio",
+            LocalServerProduct::LlamaCpp => "llama.cpp",
+            LocalServerProduct::OpenWebUi => "Open WebUI",
+            LocalServerProduct::Jan => "Jan",
+            LocalServerProduct::OpenAiCompatible => "Local model server",
+        }
+    }
+
+    fn icon_url(self) -> Option<&'static str> {
+        match self {
+            LocalServerProduct::Ollama => Some("https://ollama.com/public/ollama.png"),
+            LocalServerProduct::LmStudio => Some("https://lmstudio.ai/favicon.ico"),
+            LocalServerProduct::LlamaCpp => None,
+            LocalServerProduct::OpenWebUi => Some("https://openwebui.com/favicon.png"),
+            LocalServerProduct::Jan => Some("https://jan.ai/favicon.ico"),
+            LocalServerProduct::OpenAiCompatible => None,
+        }
+    }
+
+    /// Whether the server answers `/` with a chat UI that can be shown in a service webview.
+    fn has_web_ui(self) -> bool {
+        matches!(
+            self,
+            LocalServerProduct::OpenWebUi | LocalServerProduct::LlamaCpp
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiscoveredServer {
+    pub product: LocalServerProduct,
+    pub name: String,
+    pub url: String,
+    pub port: u16,
+    pub icon_url: Option<String>,
+    pub has_web_ui: bool,
+}
+
+impl DiscoveredServer {
+    fn new(product: LocalServerProduct, base_url: &str, port: u16) -> Self {
+        Self {
+            product,
+            name: product.display_name().to_string(),
+            url: base_url.to_string(),
+            port,
+            icon_url: product.icon_url().map(str::to_string),
+            has_web_ui: product.has_web_ui(),
+        }
+    }
+}
+
+/// Client for the probes. They only ever go to this machine, so a configured or environment proxy
+/// is bypassed: it could not reach the servers, or would see which ports are open.
+pub(crate) fn probe_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .no_proxy()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Configured ports first, then the well-known defaults, without duplicates.
+pub(crate) fn scan_ports(configured_ports: &[u16]) -> Vec<u16> {
+    let mut ports = Vec::new();
+    for port in configured_ports.iter().chain(DEFAULT_PORTS.iter()) {
+        if *port != 0 && !ports.contains(port) {
+            ports.push(*port);
+        }
+    }
+    ports
+}
+
+enum Probe {
+    Refused,
+    Missing,
+    Json(serde_json::Value),
+}
+
+async fn probe_json(client: &reqwest::Client, base_url: &str, path: &str) -> Probe {
+    let response = match client.get(format!("{}{}", base_url, path)).send().await {
+        Ok(response) => response,
+        Err(e) if e.is_connect() || e.is_timeout() => return Probe::Refused,
+        Err(_) => return Probe::Missing,
+    };
+
+    if !response.status().is_success() {
+        return Probe::Missing;
+    }
+
+    match response.json::<serde_json::Value>().await {
+        Ok(value) => Probe::Json(value),
+        Err(_) => Probe::Missing,
+    }
+}
+
+fn has_array(value: &serde_json::Value, key: &str) -> bool {
+    value.get(key).is_some_and(serde_json::Value::is_array)
+}
+
+/// Identifies the server on `host:port` by the HTTP APIs it answers, most specific first.
+pub(crate) async fn probe_server(
+    client: &reqwest::Client,
+    host: &str,
+    port: u16,
+) -> Option<DiscoveredServer> {
+    let base_url = format!("http://{}:{}", host, port);
+    let found = |product| Some(DiscoveredServer::new(product, &base_url, port));
+
+    match probe_json(client, &base_url, "/api/config").await {
+        Probe::Refused => return None,
+        Probe::Json(config)
+            if config
+                .get("name")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|name| name.contains("Open WebUI")) =>
+        {
+            return found(LocalServerProduct::OpenWebUi);
+        }
+        _ => {}
+    }
+
+    if let Probe::Json(version) = probe_json(client, &base_url, "/api/version").await {
+        if version
+            .get("version")
+            .is_some_and(serde_json::Value::is_string)
+        {
+            return found(LocalServerProduct::Ollama);
+        }
+    }
+
+    if let Probe::Json(props) = probe_json(client, &base_url, "/props").await {
+        if props.get("default_generation_settings").is_some() {
+            return found(LocalServerProduct::LlamaCpp);
+        }
+    }
+
+    if let Probe::Json(models) = probe_json(client, &base_url, "/api/v0/models").await {
+        if has_array(&models, "data") {
+            return found(LocalServerProduct::LmStudio);
+        }
+    }
+
+    if let Probe::Json(models) = probe_json(client, &base_url, "/v1/models").await {
+        if has_array(&models, "data") {
+            return found(match port {
+                JAN_PORT => LocalServerProduct::Jan,
+                LM_STUDIO_PORT => LocalServerProduct::LmStudio,
+                _ => LocalServerProduct::OpenAiCompatible,
+            });
+        }
+    }
+
+    None
+}
+
+pub(crate) async fn discover(
+    client: &reqwest::Client,
+    host: &str,
+    ports: &[u16],
+) -> Vec<DiscoveredServer> {
+    let probes = ports
+        .iter()
+        .map(|port| {
+            let client = client.clone();
+            let host = host.to_string();
+            let port = *port;
+            tauri::async_runtime::spawn(async move { probe_server(&client, &host, port).await })
+        })
+        .collect::<Vec<_>>();
+
+    let mut servers = Vec::new();
+    for probe in probes {
+        if let Ok(Some(server)) = probe.await {
+            servers.push(server);
+        }
+    }
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{discover, probe_client, probe_server, scan_ports, LocalServerProduct};
+    use crate::test_support::{spawn_stub_server, StubResponse};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    fn client() -> reqwest::Client {
+        probe_client(Duration::from_secs(2)).unwrap()
+    }
+
+    fn probe(port: u16) -> Option<LocalServerProduct> {
+        tauri::async_runtime::block_on(probe_server(&client(), "127.0.0.1", port))
+            .map(|server| server.product)
+    }
+
+    #[test]
+    fn scan_ports_puts_configured_ports_first_without_duplicates() {
+        assert_eq!(
+            scan_ports(&[5000, 11434, 0]),
+            vec![5000, 11434, 1234, 8080, 3000, 1337]
+        );
+    }
+
+    #[test]
+    fn identifies_ollama_by_version_endpoint() {
+        let port = spawn_stub_server(vec![(
+            "/api/version",
+            StubResponse::json(r#"{"version":"0.5.7"}"#),
+        )])
+        .port;
+        assert_eq!(probe(port), Some(LocalServerProduct::Ollama));
+    }
+
+    #[test]
+    fn identifies_open_webui_before_its_version_endpoint_looks_like_ollama() {
+        let port = spawn_stub_server(vec![
+            (
+                "/api/config",
+                StubResponse::json(r#"{"name":"Open WebUI","version":"0.5.0"}"#),
+            ),
+            ("/api/version", StubResponse::json(r#"{"version":"0.5.0"}"#)),
+        ])
+        .port;
+        let server =
+            tauri::async_runtime::block_on(probe_server(&client(), "127.0.0.1", port)).unwrap();
+
+        assert_eq!(server.product, LocalServerProduct::OpenWebUi);
+        assert_eq!(server.name, "Open WebUI");
+        assert_eq!(server.url, format!("http://127.0.0.1:{}", port));
+        assert!(server.has_web_ui);
+    }
+
+    #[test]
+    fn identifies_llama_cpp_and_lm_studio_specific_apis() {
+        let llama_port = spawn_stub_server(vec![(
+            "/props",
+            StubResponse::json(r#"{"default_generation_settings":{},"total_slots":1}"#),
+        )])
+        .port;
+        let lm_studio_port = spawn_stub_server(vec![(
+            "/api/v0/models",
+            StubResponse::json(r#"{"data":[]}"#),
+        )])
+        .port;
+
+        assert_eq!(probe(llama_port), Some(LocalServerProduct::LlamaCpp));
+        assert_eq!(probe(lm_studio_port), Some(LocalServerProduct::LmStudio));
+    }
+
+    #[test]
+    fn falls_back_to_generic_openai_compatible_server() {
+        let port = spawn_stub_server(vec![(
+            "/v1/models",
+            StubResponse::json(r#"{"object":"list","data":[{"id":"qwen"}]}"#),
+        )])
+        .port;
+        assert_eq!(probe(port), Some(LocalServerProduct::OpenAiCompatible));
+    }
+
+    #[test]
+    fn unrelated_http_servers_and_closed_ports_are_ignored() {
+        let unrelated_port = spawn_stub_server(vec![("/", StubResponse::json("{}"))]).port;
+        let closed_port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+
+        let servers = tauri::async_runtime::block_on(discover(
+            &client(),
+            "127.0.0.1",
+            &[unrelated_port, closed_port],
+        ));
+        assert!(servers.is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+//! Whether each service is signed in, derived from per-host rules.
+//!
+//! A page load on a sign-in URL is the strongest signal. Otherwise a DOM probe run in the page
+//! decides, then a signed-out keep-alive ping, then the presence of the session cookie. Rules
+//! come with built-in defaults for well-known hosts and can be replaced per service.
+
+use std::collections::HashMap;
+
+pub(crate) const LOGIN_STATE_EVENT: &str = "service://login-state";
+/// How long the DOM probe waits for a single-page app to render either selector.
+const PROBE_TIMEOUT_MS: u32 = 8000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum LoginState {
+    #[default]
+    Unknown,
+    SignedIn,
+    SignedOut,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct LoginRules {
+    /// Cookie that is only present while signed in.
+    pub session_cookie: Option<String>,
+    /// URL substrings of sign-in pages, checked in addition to the common auth URLs.
+    pub signed_out_url_patterns: Vec<String>,
+    /// Selector that only matches for signed-in users.
+    pub signed_in_selector: Option<String>,
+    /// Selector that only matches for signed-out users, e.g. a login button.
+    pub signed_out_selector: Option<String>,
+}
+
+fn rules(
+    session_cookie: Option<&str>,
+    signed_out_url_patterns: &[&str],
+    signed_out_selector: Option<&str>,
+) -> LoginRules {
+    LoginRules {
+        session_cookie: session_cookie.map(str::to_string),
+        signed_out_url_patterns: signed_out_url_patterns
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect(),
+        signed_in_selector: None,
+        signed_out_selector: signed_out_selector.map(str::to_string),
+    }
+}
+
+/// Built-in rules for `host` (lowercase, without `www.`); unknown hosts only get the common
+/// auth URL check.
+pub(crate) fn default_rules(host: &str) -> LoginRules {
+    match host {
+        "chatgpt.com" => rules(
+            Some("__Secure-next-auth.session-token"),
+            &["/auth/login"],
+            Some(r#"[data-testid="login-button"]"#),
+        ),
+        "claude.ai" => rules(Some("sessionKey"), &["/login"], None),
+        "gemini.google.com" => rules(Some("SID"), &[], None),
+        "grok.com" => rules(Some("sso"), &["/sign-in"], None),
+        "perplexity.ai" => rules(Some("__Secure-next-auth.session-token"), &[], None),
+        "chat.deepseek.com" => rules(None, &["/sign_in"], None),
+        _ => LoginRules::default(),
+    }
+}
+
+pub(crate) fn is_sign_in_url(url: &str, rules: &LoginRules, is_auth_url: bool) -> bool {
+    is_auth_url
+        || rules
+            .signed_out_url_patterns
+            .iter()
+            .any(|pattern| !pattern.is_empty() && url.contains(pattern.as_str()))
+}
+
+/// Everything currently known about one service's session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LoginSignals {
+    pub on_sign_in_page: bool,
+    pub dom_signed_in: Option<bool>,
+    pub keep_alive_signed_out: bool,
+    pub session_cookie: Option<bool>,
+}
+
+impl LoginSignals {
+    pub fn state(&self) -> LoginState {
+        let signed_in = if self.on_sign_in_page {
+            Some(false)
+        } else if self.dom_signed_in.is_some() {
+            self.dom_signed_in
+        } else if self.keep_alive_signed_out {
+            Some(false)
+        } else {
+            self.session_cookie
+        };
+        match signed_in {
+            Some(true) => LoginState::SignedIn,
+            Some(false) => LoginState::SignedOut,
+            None => LoginState::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LoginTracker {
+    signals: HashMap<String, LoginSignals>,
+}
+
+impl LoginTracker {
+    /// Updates the signals of a service; returns the new state when it changed.
+    pub fn update(
+        &mut self,
+        service_id: &str,
+        change: impl FnOnce(&mut LoginSignals),
+    ) -> Option<LoginState> {
+        let signals = self.signals.entry(service_id.to_string()).or_default();
+        let previous = signals.state();
+        change(signals);
+        let state = signals.state();
+        (state != previous).then_some(state)
+    }
+
+    pub fn states(&self) -> HashMap<String, LoginState> {
+        self.signals
+            .iter()
+            .map(|(service_id, signals)| (service_id.clone(), signals.state()))
+            .collect()
+    }
+}
+
+/// Waits for either selector to match and posts `{type: 'loginProbe', signedIn}`. `None` when
+/// the rules have no selectors.
+pub(crate) fn probe_script(rules: &LoginRules) -> Option<String> {
+    if rules.signed_in_selector.is_none() && rules.signed_out_selector.is_none() {
+        return None;
+    }
+    let literal = |selector: &Option<String>| {
+        selector
+            .as_ref()
+            .map(|selector| serde_json::Value::String(selector.clone()).to_string())
+            .unwrap_or_else(|| "null".to_string())
+    };
+
+    Some(format!(
+        r#"(function() {{
+    var signedIn = {signed_in};
+    var signedOut = {signed_out};
+    var deadline = Date.now() + {timeout};
+    function matches(selector) {{
+        try {{ return !!(selector && document.querySelector(selector)); }} catch (_) {{ return false; }}
+    }}
+    function probe() {{
+        var result = matches(signedOut) ? false : matches(signedIn) ? true : null;
+        if (result !== null) {{
+            if (window.__anychatPost) window.__anychatPost({{ type: 'loginProbe', signedIn: result }});
+        }} else if (Date.now() < deadline) {{
+            setTimeout(probe, 500);
+        }}
+    }}
+    probe();
+}})();"#,
+        signed_in = literal(&rules.signed_in_selector),
+        signed_out = literal(&rules.signed_out_selector),
+        timeout = PROBE_TIMEOUT_MS,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        default_rules, is_sign_in_url, probe_script, LoginRules, LoginSignals, LoginState,
+        LoginTracker,
+    };
+
+    #[test]
+    fn sign_in_pages_beat_the_dom_and_the_dom_beats_cookies() {
+        let mut signals = LoginSignals {
+            session_cookie: Some(true),
+            ..LoginSignals::default()
+        };
+        assert_eq!(signals.state(), LoginState::SignedIn);
+        signals.keep_alive_signed_out = true;
+        assert_eq!(signals.state(), LoginState::SignedOut);
+        signals.dom_signed_in = Some(true);
+        assert_eq!(signals.state(), LoginState::SignedIn);
+        signals.on_sign_in_page = true;
+        assert_eq!(signals.state(), LoginState::SignedOut);
+        assert_eq!(LoginSignals::default().state(), LoginState::Unknown);
+    }
+
+    #[test]
+    fn tracker_reports_only_state_changes() {
+        let mut tracker = LoginTracker::default();
+        assert_eq!(
+            tracker.update("claude", |signals| signals.session_cookie = Some(true)),
+            Some(LoginState::SignedIn)
+        );
+        assert_eq!(
+            tracker.update("claude", |signals| signals.dom_signed_in = Some(true)),
+            None
+        );
+        assert_eq!(
+            tracker.update("claude", |signals| signals.on_sign_in_page = true),
+            Some(LoginState::SignedOut)
+        );
+        assert_eq!(tracker.states()["claude"], LoginState::SignedOut);
+    }
+
+    #[test]
+    fn sign_in_urls_come_from_rules_and_common_auth_urls() {
+        let rules = default_rules("chatgpt.com");
+        assert!(is_sign_in_url(
+            "https://chatgpt.com/auth/login?next=/",
+            &rules,
+            false
+        ));
+        assert!(!is_sign_in_url("https://chatgpt.com/c/1", &rules, false));
+        assert!(is_sign_in_url(
+            "https://accounts.google.com/",
+            &LoginRules::default(),
+            true
+        ));
+        assert_eq!(default_rules("example.com"), LoginRules::default());
+    }
+
+    #[test]
+    fn probe_script_needs_a_selector_and_quotes_it() {
+        assert_eq!(probe_script(&default_rules("claude.ai")), None);
+        let script = probe_script(&default_rules("chatgpt.com")).unwrap();
+        assert!(script.contains(r#"var signedOut = "[data-testid=\"login-button\"]";"#));
+        assert!(script.contains("var signedIn = null;"));
+    }
+}
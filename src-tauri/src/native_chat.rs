@@ -0,0 +1,615 @@
+//! Native chat against OpenAI-compatible `/v1/chat/completions` endpoints (Ollama, vLLM,
+//! LM Studio, company gateways). Requests run in Rust, streamed deltas are emitted as events
+//! and conversations are kept as JSON files in the app data directory.
+
+use crate::storage::{is_safe_file_stem, Vault};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub(crate) const CHAT_TIMEOUT: Duration = Duration::from_secs(600);
+pub(crate) const MODELS_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) const DELTA_EVENT: &str = "native-chat://delta";
+pub(crate) const DONE_EVENT: &str = "native-chat://done";
+pub(crate) const ERROR_EVENT: &str = "native-chat://error";
+
+pub(crate) const CONVERSATIONS_DIR: &str = "conversations";
+const TITLE_MAX_CHARS: usize = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct Conversation {
+    pub id: String,
+    pub service_id: String,
+    pub model: String,
+    pub title: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConversationSummary {
+    pub id: String,
+    pub title: String,
+    pub model: String,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChatStreamEvent {
+    pub request_id: String,
+    pub service_id: String,
+    pub conversation_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub(crate) fn api_key_secret_name(service_id: &str) -> String {
+    format!("native-chat/{}/api-key", service_id)
+}
+
+/// An API key together with the endpoint it was entered for, so it is never sent anywhere else.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredApiKey {
+    base_url: String,
+    api_key: String,
+}
+
+pub(crate) fn encode_api_key(base_url: &str, api_key: &str) -> Result<String, String> {
+    serde_json::to_string(&StoredApiKey {
+        base_url: base_url.to_string(),
+        api_key: api_key.to_string(),
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the stored key only when `base_url` is the endpoint it was saved for.
+pub(crate) fn api_key_for_url(stored: &str, base_url: &str) -> Result<String, String> {
+    let stored: StoredApiKey = serde_json::from_str(stored)
+        .map_err(|_| "The saved API key has no endpoint; save it again".to_string())?;
+    if endpoint_url(&stored.base_url, "") != endpoint_url(base_url, "") {
+        return Err(format!(
+            "The saved API key belongs to {}; save it again for {}",
+            stored.base_url, base_url
+        ));
+    }
+    Ok(stored.api_key)
+}
+
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Accepts both `http://host:11434` and `http://host:11434/v1` as the endpoint base.
+pub(crate) fn endpoint_url(base_url: &str, path: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    if base_url.ends_with("/v1") {
+        format!("{}/{}", base_url, path)
+    } else {
+        format!("{}/v1/{}", base_url, path)
+    }
+}
+
+pub(crate) fn conversation_path(
+    data_dir: &Path,
+    service_id: &str,
+    conversation_id: &str,
+) -> Result<PathBuf, String> {
+    if !is_safe_file_stem(service_id) || !is_safe_file_stem(conversation_id) {
+        return Err("Invalid conversation id".to_string());
+    }
+
+    Ok(data_dir
+        .join(CONVERSATIONS_DIR)
+        .join(service_id)
+        .join(format!("{}.json", conversation_id)))
+}
+
+pub(crate) fn new_conversation(service_id: &str, model: &str) -> Conversation {
+    let now = now_millis();
+    Conversation {
+        // Two conversations started within the same millisecond must not share a file.
+        id: format!(
+            "conv-{}-{:08x}",
+            now,
+            u32::from_be_bytes(crate::secrets::random_bytes::<4>())
+        ),
+        service_id: service_id.to_string(),
+        model: model.to_string(),
+        created_at: now,
+        updated_at: now,
+        ..Default::default()
+    }
+}
+
+pub(crate) fn conversation_title(first_message: &str) -> String {
+    let line = first_message.lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= TITLE_MAX_CHARS {
+        return line.to_string();
+    }
+
+    let mut title = line.chars().take(TITLE_MAX_CHARS).collect::<String>();
+    title.push('…');
+    title
+}
+
+pub(crate) fn load_conversation(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+    conversation_id: &str,
+) -> Result<Conversation, String> {
+    let path = conversation_path(data_dir, service_id, conversation_id)?;
+    if !path.exists() {
+        return Err(format!("Conversation {} not found", conversation_id));
+    }
+    crate::storage::read_json(vault, &path)
+}
+
+pub(crate) fn save_conversation(
+    data_dir: &Path,
+    vault: &Vault,
+    conversation: &Conversation,
+) -> Result<(), String> {
+    let path = conversation_path(data_dir, &conversation.service_id, &conversation.id)?;
+    crate::storage::write_json(vault, &path, conversation)
+}
+
+pub(crate) fn delete_conversation(
+    data_dir: &Path,
+    service_id: &str,
+    conversation_id: &str,
+) -> Result<(), String> {
+    let path = conversation_path(data_dir, service_id, conversation_id)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete {}: {}", path.display(), e)),
+    }
+}
+
+/// Newest first.
+pub(crate) fn list_conversations(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+) -> Result<Vec<ConversationSummary>, String> {
+    if !is_safe_file_stem(service_id) {
+        return Err("Invalid service id".to_string());
+    }
+
+    let dir = data_dir.join(CONVERSATIONS_DIR).join(service_id);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut summaries = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .filter_map(|path| crate::storage::read_json::<Conversation>(vault, &path).ok())
+        .map(|conversation| ConversationSummary {
+            id: conversation.id,
+            title: conversation.title,
+            model: conversation.model,
+            updated_at: conversation.updated_at,
+        })
+        .collect::<Vec<_>>();
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.updated_at));
+    Ok(summaries)
+}
+
+/// Incremental parser for `text/event-stream` bodies; yields the `data` of each complete event.
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer
+            .extend(chunk.iter().copied().filter(|byte| *byte != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|window| window == b"\n\n") {
+            let block = self.buffer.drain(..end + 2).collect::<Vec<_>>();
+            let block = String::from_utf8_lossy(&block);
+            let data = block
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect::<Vec<_>>();
+
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
+fn error_message(value: &serde_json::Value) -> Option<String> {
+    let error = value.get("error")?;
+    Some(
+        error
+            .get("message")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
+/// Extracts the text delta from one streamed chunk.
+pub(crate) fn parse_stream_chunk(data: &str) -> Result<Option<String>, String> {
+    let value: serde_json::Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    if let Some(message) = error_message(&value) {
+        return Err(message);
+    }
+
+    Ok(value
+        .pointer("/choices/0/delta/content")
+        .and_then(serde_json::Value::as_str)
+        .filter(|delta| !delta.is_empty())
+        .map(str::to_string))
+}
+
+async fn response_error(response: reqwest::Response) -> String {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let detail = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| error_message(&value))
+        .unwrap_or_else(|| body.chars().take(200).collect());
+
+    if detail.is_empty() {
+        format!("Request failed with status {}", status)
+    } else {
+        format!("Request failed with status {}: {}", status, detail)
+    }
+}
+
+fn authorized(request: reqwest::RequestBuilder, api_key: Option<&str>) -> reqwest::RequestBuilder {
+    match api_key {
+        Some(api_key) if !api_key.is_empty() => request.bearer_auth(api_key),
+        _ => request,
+    }
+}
+
+pub(crate) async fn list_models(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let response = authorized(client.get(endpoint_url(base_url, "models")), api_key)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+
+    let value = response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut models = value
+        .get("data")
+        .and_then(serde_json::Value::as_array)
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|model| model.get("id").and_then(serde_json::Value::as_str))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    models.sort();
+    Ok(models)
+}
+
+/// Sends the conversation and calls `on_delta` for every streamed piece of the reply.
+/// Servers that ignore `stream: true` and answer with a single JSON body are handled too.
+pub(crate) async fn stream_chat_completion(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    messages: &[ChatMessage],
+    mut on_delta: impl FnMut(&str),
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "model": model,
+        "messages": messages,
+        "stream": true,
+    });
+    let mut response = authorized(
+        client.post(endpoint_url(base_url, "chat/completions")),
+        api_key,
+    )
+    .json(&body)
+    .send()
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+
+    let is_event_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+
+    if !is_event_stream {
+        let value = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(message) = error_message(&value) {
+            return Err(message);
+        }
+        let content = value
+            .pointer("/choices/0/message/content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        on_delta(&content);
+        return Ok(content);
+    }
+
+    let mut parser = SseParser::default();
+    let mut content = String::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        for data in parser.push(&chunk) {
+            if data.trim() == "[DONE]" {
+                return Ok(content);
+            }
+
+            if let Some(delta) = parse_stream_chunk(&data)? {
+                on_delta(&delta);
+                content.push_str(&delta);
+            }
+        }
+    }
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        api_key_for_url, conversation_path, conversation_title, encode_api_key, endpoint_url,
+        list_conversations, list_models, load_conversation, new_conversation, parse_stream_chunk,
+        save_conversation, stream_chat_completion, ChatMessage, SseParser,
+    };
+    use crate::storage::Vault;
+    use crate::test_support::{spawn_stub_server, StubResponse};
+
+    fn user(content: &str) -> Vec<ChatMessage> {
+        vec![ChatMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+        }]
+    }
+
+    #[test]
+    fn endpoint_url_accepts_bases_with_or_without_v1() {
+        assert_eq!(
+            endpoint_url("http://localhost:11434", "chat/completions"),
+            "http://localhost:11434/v1/chat/completions"
+        );
+        assert_eq!(
+            endpoint_url("https://gateway.example.com/v1/", "models"),
+            "https://gateway.example.com/v1/models"
+        );
+    }
+
+    #[test]
+    fn api_key_is_only_released_for_the_endpoint_it_was_saved_for() {
+        let stored = encode_api_key("https://gateway.example.com/v1/", "sk-test").unwrap();
+        assert_eq!(
+            api_key_for_url(&stored, "https://gateway.example.com").unwrap(),
+            "sk-test"
+        );
+        assert!(api_key_for_url(&stored, "https://attacker.example.com/v1").is_err());
+        assert!(api_key_for_url(&stored, "http://gateway.example.com/v1").is_err());
+        assert!(api_key_for_url("sk-unbound", "https://gateway.example.com").is_err());
+    }
+
+    #[test]
+    fn sse_parser_handles_split_chunks_and_crlf() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: {\"a\"").is_empty());
+        assert_eq!(
+            parser.push(b":1}\r\n\r\n: comment\n\ndata: [DONE]\n\n"),
+            vec!["{\"a\":1}".to_string(), "[DONE]".to_string()]
+        );
+    }
+
+    #[test]
+    fn stream_chunks_yield_deltas_and_surface_errors() {
+        assert_eq!(
+            parse_stream_chunk(r#"{"choices":[{"delta":{"content":"Hi"}}]}"#).unwrap(),
+            Some("Hi".to_string())
+        );
+        assert_eq!(
+            parse_stream_chunk(r#"{"choices":[{"delta":{"role":"assistant"}}]}"#).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_stream_chunk(r#"{"error":{"message":"model not found"}}"#).unwrap_err(),
+            "model not found"
+        );
+    }
+
+    #[test]
+    fn streams_completion_from_mock_server_with_api_key() {
+        let server = spawn_stub_server(vec![(
+            "POST /v1/chat/completions",
+            StubResponse::event_stream(concat!(
+                "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+                "data: [DONE]\n\n"
+            )),
+        )]);
+
+        let mut deltas = Vec::new();
+        let content = tauri::async_runtime::block_on(stream_chat_completion(
+            &reqwest::Client::new(),
+            &server.base_url(),
+            Some("sk-test"),
+            "llama3",
+            &user("Hi"),
+            |delta| deltas.push(delta.to_string()),
+        ))
+        .unwrap();
+
+        assert_eq!(content, "Hello");
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests[0].header("authorization"), Some("Bearer sk-test"));
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["model"], "llama3");
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["messages"][0]["content"], "Hi");
+    }
+
+    #[test]
+    fn non_streaming_and_failed_completions_are_handled() {
+        let server = spawn_stub_server(vec![(
+            "POST /v1/chat/completions",
+            StubResponse::json(
+                r#"{"choices":[{"message":{"role":"assistant","content":"Whole"}}]}"#,
+            ),
+        )]);
+        let content = tauri::async_runtime::block_on(stream_chat_completion(
+            &reqwest::Client::new(),
+            &server.base_url(),
+            None,
+            "m",
+            &user("Hi"),
+            |_| {},
+        ))
+        .unwrap();
+        assert_eq!(content, "Whole");
+        assert_eq!(
+            server.requests.lock().unwrap()[0].header("authorization"),
+            None
+        );
+
+        let failing = spawn_stub_server(vec![(
+            "POST /v1/chat/completions",
+            StubResponse::status(401, r#"{"error":{"message":"invalid api key"}}"#),
+        )]);
+        let error = tauri::async_runtime::block_on(stream_chat_completion(
+            &reqwest::Client::new(),
+            &failing.base_url(),
+            None,
+            "m",
+            &user("Hi"),
+            |_| {},
+        ))
+        .unwrap_err();
+        assert!(error.contains("401"));
+        assert!(error.contains("invalid api key"));
+    }
+
+    #[test]
+    fn lists_models_sorted_by_id() {
+        let server = spawn_stub_server(vec![(
+            "/v1/models",
+            StubResponse::json(r#"{"object":"list","data":[{"id":"qwen2"},{"id":"llama3"}]}"#),
+        )]);
+        let models = tauri::async_runtime::block_on(list_models(
+            &reqwest::Client::new(),
+            &server.base_url(),
+            None,
+        ))
+        .unwrap();
+        assert_eq!(models, vec!["llama3", "qwen2"]);
+    }
+
+    #[test]
+    fn conversations_persist_and_list_newest_first() {
+        let dir =
+            std::env::temp_dir().join(format!("anychat-conversations-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut older = new_conversation("local-ollama", "llama3");
+        older.id = "conv-1".to_string();
+        older.updated_at = 1;
+        older.messages = user("First");
+        let mut newer = older.clone();
+        newer.id = "conv-2".to_string();
+        newer.updated_at = 2;
+
+        save_conversation(&dir, &Vault::Disabled, &older).unwrap();
+        save_conversation(&dir, &Vault::Disabled, &newer).unwrap();
+
+        assert_eq!(
+            load_conversation(&dir, &Vault::Disabled, "local-ollama", "conv-1").unwrap(),
+            older
+        );
+        let ids = list_conversations(&dir, &Vault::Disabled, "local-ollama")
+            .unwrap()
+            .into_iter()
+            .map(|summary| summary.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["conv-2", "conv-1"]);
+        assert!(list_conversations(&dir, &Vault::Disabled, "other")
+            .unwrap()
+            .is_empty());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn conversation_ids_cannot_escape_the_data_dir() {
+        let dir = std::path::Path::new("/tmp/anychat");
+        assert!(conversation_path(dir, "svc", "../../etc/passwd").is_err());
+        assert!(conversation_path(dir, "../svc", "conv-1").is_err());
+        assert!(conversation_path(dir, "svc", "conv-1").is_ok());
+    }
+
+    #[test]
+    fn new_conversations_get_distinct_ids() {
+        let first = new_conversation("svc", "llama3");
+        let second = new_conversation("svc", "llama3");
+        assert_ne!(first.id, second.id);
+        assert!(conversation_path(std::path::Path::new("/tmp"), "svc", &first.id).is_ok());
+    }
+
+    #[test]
+    fn conversation_title_uses_first_line_and_truncates() {
+        assert_eq!(conversation_title("Hello\nworld"), "Hello");
+        let title = conversation_title(&"a".repeat(100));
+        assert_eq!(title.chars().count(), 61);
+        assert!(title.ends_with('…'));
+    }
+}
@@ -0,0 +1,141 @@
+//! Back/forward/home navigation for service webviews.
+//!
+//! WebKitGTK reports its own back/forward list and supports swipe gestures natively. Elsewhere
+//! the possible directions are inferred from the sequence of loaded pages, which misses
+//! in-page `pushState` entries but is enough to enable the toolbar buttons.
+
+pub(crate) const NAVIGATION_EVENT: &str = "service://navigation";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryDirection {
+    Back,
+    Forward,
+}
+
+impl HistoryDirection {
+    pub fn script(self) -> &'static str {
+        match self {
+            HistoryDirection::Back => "history.back();",
+            HistoryDirection::Forward => "history.forward();",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NavigationState {
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+/// Page loads of one webview. A load matching the neighbouring entry is taken as a back or
+/// forward step, whoever triggered it.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+#[derive(Debug, Default)]
+pub(crate) struct HistoryTracker {
+    entries: Vec<String>,
+    index: usize,
+}
+
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+impl HistoryTracker {
+    pub fn record(&mut self, url: &str) -> NavigationState {
+        if self.entries.is_empty() {
+            self.entries.push(url.to_string());
+        } else if self.entries[self.index] == url {
+            // Reload.
+        } else if self.index > 0 && self.entries[self.index - 1] == url {
+            self.index -= 1;
+        } else if self
+            .entries
+            .get(self.index + 1)
+            .is_some_and(|next| next == url)
+        {
+            self.index += 1;
+        } else {
+            self.entries.truncate(self.index + 1);
+            self.entries.push(url.to_string());
+            self.index += 1;
+        }
+        self.state()
+    }
+
+    pub fn state(&self) -> NavigationState {
+        NavigationState {
+            can_go_back: self.index > 0,
+            can_go_forward: self.index + 1 < self.entries.len(),
+        }
+    }
+}
+
+/// Enables swipe gestures and reports back/forward availability whenever the URI changes,
+/// including same-document navigations.
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_navigation_tracking(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use webkit2gtk::{SettingsExt, WebViewExt};
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        let webview = platform_webview.inner();
+        if let Some(settings) = webview.settings() {
+            settings.set_enable_back_forward_navigation_gestures(true);
+        }
+        webview.connect_uri_notify(move |webview| {
+            crate::publish_navigation_state(
+                &app,
+                &service_id,
+                NavigationState {
+                    can_go_back: webview.can_go_back(),
+                    can_go_forward: webview.can_go_forward(),
+                },
+            );
+        });
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_navigation_tracking(
+    _app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    _service_id: &str,
+) {
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistoryTracker, NavigationState};
+
+    fn state(can_go_back: bool, can_go_forward: bool) -> NavigationState {
+        NavigationState {
+            can_go_back,
+            can_go_forward,
+        }
+    }
+
+    #[test]
+    fn tracker_follows_loads_and_back_forward_steps() {
+        let mut history = HistoryTracker::default();
+        assert_eq!(history.record("https://a/"), state(false, false));
+        assert_eq!(history.record("https://a/"), state(false, false));
+        assert_eq!(history.record("https://b/"), state(true, false));
+        assert_eq!(history.record("https://c/"), state(true, false));
+        assert_eq!(history.record("https://b/"), state(true, true));
+        assert_eq!(history.record("https://a/"), state(false, true));
+        assert_eq!(history.record("https://b/"), state(true, true));
+    }
+
+    #[test]
+    fn a_new_page_after_going_back_drops_forward_entries() {
+        let mut history = HistoryTracker::default();
+        history.record("https://a/");
+        history.record("https://b/");
+        history.record("https://a/");
+        assert_eq!(history.record("https://d/"), state(true, false));
+        assert_eq!(history.record("https://a/"), state(false, true));
+    }
+}
@@ -0,0 +1,83 @@
+//! Static pages AnyChat renders inside a service webview in place of a broken site.
+
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Renders the shared AnyChat error page. `details` are shown as a list and `extra_html` is
+/// inserted verbatim below it, so callers must escape anything user-controlled they put there.
+pub(crate) fn render_error_page(
+    title: &str,
+    message: &str,
+    details: &[String],
+    extra_html: &str,
+) -> String {
+    let details_html = details
+        .iter()
+        .map(|detail| format!("<li>{}</li>", escape_html(detail)))
+        .collect::<String>();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+  :root {{ color-scheme: light dark; }}
+  body {{ font-family: system-ui, -apple-system, sans-serif; display: flex; align-items: center; justify-content: center; min-height: 100vh; margin: 0; }}
+  main {{ max-width: 560px; padding: 32px; }}
+  h1 {{ font-size: 20px; margin: 0 0 12px; }}
+  p {{ line-height: 1.5; opacity: 0.85; }}
+  ul {{ padding-left: 20px; opacity: 0.75; }}
+  code {{ word-break: break-all; font-size: 12px; }}
+  button {{ font: inherit; padding: 8px 18px; border-radius: 8px; border: 1px solid currentColor; background: transparent; color: inherit; cursor: pointer; }}
+</style>
+</head>
+<body>
+<main>
+<h1>{title}</h1>
+<p>{message}</p>
+<ul>{details_html}</ul>
+{extra_html}
+</main>
+</body>
+</html>"#,
+        title = escape_html(title),
+        message = escape_html(message),
+        details_html = details_html,
+        extra_html = extra_html,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_html, render_error_page};
+
+    #[test]
+    fn escape_html_neutralizes_markup() {
+        assert_eq!(
+            escape_html(r#"<script>alert("x&y")</script>"#),
+            "&lt;script&gt;alert(&quot;x&amp;y&quot;)&lt;/script&gt;"
+        );
+    }
+
+    #[test]
+    fn error_page_escapes_title_message_and_details() {
+        let html = render_error_page("<b>", "a & b", &["<i>".to_string()], "");
+        assert!(html.contains("<h1>&lt;b&gt;</h1>"));
+        assert!(html.contains("<p>a &amp; b</p>"));
+        assert!(html.contains("<li>&lt;i&gt;</li>"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Read-only policy file administrators ship to lock AnyChat down, e.g.
+//! `/etc/anychat/policy.json`. It is read once at startup and cannot be changed from the app.
+//!
+//! A policy file that exists but cannot be read or parsed locks everything down rather than
+//! being ignored. Host rules apply to every page a service loads, so sign-in providers must be
+//! listed as well.
+
+use std::path::{Path, PathBuf};
+use tauri::webview::{DownloadEvent, WebviewBuilder, WebviewWindowBuilder};
+
+const POLICY_FILE: &str = "policy.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub(crate) struct Policy {
+    /// Hosts services may load, each also allowing its subdomains; `None` allows any host.
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Forbids services added by the user.
+    pub disable_custom_services: bool,
+    /// Built-in services that are always on, by id.
+    pub force_enabled_services: Vec<String>,
+    /// Built-in services that are always off, by id.
+    pub force_disabled_services: Vec<String>,
+    /// `http://` proxy for all traffic; replaces the user's proxy setting.
+    pub proxy: Option<String>,
+    pub disable_devtools: bool,
+    pub block_downloads: bool,
+}
+
+/// The policy in force and where it came from, as shown to the frontend.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EffectivePolicy {
+    #[serde(flatten)]
+    pub policy: Policy,
+    /// Path of the policy file; `None` when no policy is installed.
+    pub source: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Policy {
+    fn locked_down() -> Self {
+        Policy {
+            allowed_hosts: Some(Vec::new()),
+            disable_custom_services: true,
+            disable_devtools: true,
+            block_downloads: true,
+            ..Policy::default()
+        }
+    }
+
+    pub fn host_allowed(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.allowed_hosts.as_ref().is_none_or(|allowed| {
+            allowed.iter().any(|allowed| {
+                let allowed = allowed.trim_start_matches('.').to_ascii_lowercase();
+                host == allowed || host.ends_with(&format!(".{}", allowed))
+            })
+        })
+    }
+
+    /// Pages without a network host (`about:blank`, `data:`, `blob:`) are always allowed.
+    pub fn url_allowed(&self, url: &tauri::Url) -> bool {
+        match url.scheme() {
+            "http" | "https" | "ws" | "wss" => {
+                url.host_str().is_some_and(|host| self.host_allowed(host))
+            }
+            _ => true,
+        }
+    }
+
+    /// Forced state of a service's enabled switch, if the policy pins it.
+    pub fn enabled_override(&self, service_id: &str) -> Option<bool> {
+        if self
+            .force_disabled_services
+            .iter()
+            .any(|id| id == service_id)
+        {
+            Some(false)
+        } else if self
+            .force_enabled_services
+            .iter()
+            .any(|id| id == service_id)
+        {
+            Some(true)
+        } else if self.disable_custom_services && is_custom_service(service_id) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn check_service(&self, service_id: &str, url: &str) -> Result<(), String> {
+        if self.disable_custom_services && is_custom_service(service_id) {
+            return Err("Custom services are disabled by policy".to_string());
+        }
+        if self.enabled_override(service_id) == Some(false) {
+            return Err(format!("Service {} is disabled by policy", service_id));
+        }
+        let url: tauri::Url = url.parse().map_err(|e| format!("{}", e))?;
+        if !self.url_allowed(&url) {
+            return Err(format!(
+                "{} is not an allowed host",
+                url.host_str().unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn proxy_url(&self) -> Option<tauri::Url> {
+        self.proxy.as_deref().and_then(|proxy| proxy.parse().ok())
+    }
+}
+
+/// Services the user added get ids of this form; built-in ones do not.
+pub(crate) fn is_custom_service(service_id: &str) -> bool {
+    service_id.starts_with("custom-")
+}
+
+pub(crate) fn validate_proxy(proxy: &str) -> Result<(), String> {
+    let url: tauri::Url = proxy
+        .parse()
+        .map_err(|e| format!("Invalid proxy {}: {}", proxy, e))?;
+    if url.scheme() != "http" || url.host_str().is_none() {
+        return Err(format!("Proxy {} must be an http:// URL", proxy));
+    }
+    Ok(())
+}
+
+pub(crate) fn policy_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(
+        std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string()),
+    )
+    .join("AnyChat");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/AnyChat");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let dir = PathBuf::from("/etc/anychat");
+    dir.join(POLICY_FILE)
+}
+
+pub(crate) fn load(path: &Path) -> EffectivePolicy {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return EffectivePolicy::default(),
+        Err(e) => return locked_down(path, format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let policy = match serde_json::from_slice::<Policy>(&bytes) {
+        Ok(policy) => policy,
+        Err(e) => return locked_down(path, format!("Invalid policy {}: {}", path.display(), e)),
+    };
+    if let Some(Err(e)) = policy.proxy.as_deref().map(validate_proxy) {
+        return locked_down(path, e);
+    }
+    EffectivePolicy {
+        policy,
+        source: Some(path.display().to_string()),
+        error: None,
+    }
+}
+
+fn locked_down(path: &Path, error: String) -> EffectivePolicy {
+    println!("[AnyChat] {}; locking everything down", error);
+    EffectivePolicy {
+        policy: Policy::locked_down(),
+        source: Some(path.display().to_string()),
+        error: Some(error),
+    }
+}
+
+/// Applies the webview-level rules: devtools, downloads and the proxy.
+pub(crate) trait PolicyBuilderExt: Sized {
+    fn with_policy(self, policy: &Policy, proxy: Option<tauri::Url>) -> Self;
+}
+
+impl<R: tauri::Runtime> PolicyBuilderExt for WebviewBuilder<R> {
+    fn with_policy(self, policy: &Policy, proxy: Option<tauri::Url>) -> Self {
+        let mut builder = self.devtools(!policy.disable_devtools);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy_url(proxy);
+        }
+        if policy.block_downloads {
+            builder = builder.on_download(|_, event| allow_download(&event));
+        }
+        builder
+    }
+}
+
+impl<'a, R: tauri::Runtime, M: tauri::Manager<R>> PolicyBuilderExt
+    for WebviewWindowBuilder<'a, R, M>
+{
+    fn with_policy(self, policy: &Policy, proxy: Option<tauri::Url>) -> Self {
+        let mut builder = self.devtools(!policy.disable_devtools);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy_url(proxy);
+        }
+        if policy.block_downloads {
+            builder = builder.on_download(|_, event| allow_download(&event));
+        }
+        builder
+    }
+}
+
+fn allow_download(event: &DownloadEvent<'_>) -> bool {
+    if let DownloadEvent::Requested { url, .. } = event {
+        println!("[AnyChat] Download blocked by policy: {}", url);
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, Policy};
+
+    fn write_policy(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "anychat-policy-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn hosts_and_services_are_checked_against_the_policy() {
+        let policy: Policy = serde_json::from_str(
+            r#"{
+                "allowedHosts": ["chatgpt.com", "openai.com"],
+                "disableCustomServices": true,
+                "forceDisabledServices": ["gemini"],
+                "forceEnabledServices": ["chatgpt"]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(policy.host_allowed("chatgpt.com"));
+        assert!(policy.host_allowed("auth.openai.com"));
+        assert!(!policy.host_allowed("notchatgpt.com"));
+        assert!(policy.url_allowed(&"about:blank".parse().unwrap()));
+        assert!(!policy.url_allowed(&"https://claude.ai/".parse().unwrap()));
+
+        assert!(policy
+            .check_service("chatgpt", "https://chatgpt.com")
+            .is_ok());
+        assert!(policy.check_service("claude", "https://claude.ai").is_err());
+        assert!(policy
+            .check_service("gemini", "https://chatgpt.com")
+            .is_err());
+        assert!(policy
+            .check_service("custom-1", "https://chatgpt.com")
+            .is_err());
+        assert_eq!(policy.enabled_override("chatgpt"), Some(true));
+        assert_eq!(policy.enabled_override("gemini"), Some(false));
+        assert_eq!(policy.enabled_override("custom-1"), Some(false));
+        assert_eq!(policy.enabled_override("claude"), None);
+
+        assert!(Policy::default().host_allowed("anything.example"));
+    }
+
+    #[test]
+    fn policy_blocks_native_chat_endpoints_outside_the_allowed_hosts() {
+        let policy: Policy =
+            serde_json::from_str(r#"{"allowedHosts": ["llm.corp.example"]}"#).unwrap();
+
+        assert!(policy
+            .check_service("custom-ollama", "http://llm.corp.example:11434/v1")
+            .is_ok());
+        assert!(policy
+            .check_service("custom-ollama", "http://localhost:11434")
+            .is_err());
+        assert!(policy
+            .check_service("custom-gateway", "https://api.openai.com/v1")
+            .is_err());
+    }
+
+    #[test]
+    fn missing_policy_allows_everything_and_broken_policy_locks_down() {
+        let missing = load(&std::env::temp_dir().join("anychat-policy-missing.json"));
+        assert_eq!(missing.policy, Policy::default());
+        assert!(missing.source.is_none());
+
+        let path = write_policy(
+            "valid",
+            r#"{"proxy":"http://proxy.corp:3128","blockDownloads":true}"#,
+        );
+        let valid = load(&path);
+        assert!(valid.error.is_none());
+        assert!(valid.policy.block_downloads);
+        assert_eq!(
+            valid.policy.proxy_url().map(|url| url.to_string()),
+            Some("http://proxy.corp:3128/".to_string())
+        );
+        let _ = std::fs::remove_file(path);
+
+        for (name, contents) in [
+            ("broken", "{ not json"),
+            ("unknown", r#"{"allowHosts":["chatgpt.com"]}"#),
+            ("proxy", r#"{"proxy":"ftp://proxy.corp"}"#),
+        ] {
+            let path = write_policy(name, contents);
+            let policy = load(&path);
+            assert!(policy.error.is_some(), "{}", name);
+            assert!(!policy.policy.host_allowed("chatgpt.com"));
+            assert!(policy.policy.disable_devtools && policy.policy.block_downloads);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Ephemeral ("incognito") sessions of a service, opened in their own window next to the
+//! regular webview. They use a non-persistent data store, so nothing they load or sign into
+//! reaches the shared store, and everything is discarded when the window closes.
+
+const PRIVATE_LABEL_PREFIX: &str = "private-";
+
+pub(crate) fn private_label(service_id: &str) -> String {
+    format!("{}{}", PRIVATE_LABEL_PREFIX, service_id)
+}
+
+pub(crate) fn window_title(service_name: &str) -> String {
+    format!("{}（无痕）", service_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{private_label, window_title};
+
+    #[test]
+    fn private_sessions_get_their_own_label_and_a_marked_title() {
+        assert_eq!(private_label("chatgpt"), "private-chatgpt");
+        assert_ne!(private_label("chatgpt"), "chatgpt");
+        assert_eq!(window_title("ChatGPT"), "ChatGPT（无痕）");
+    }
+}
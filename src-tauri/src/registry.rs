@@ -0,0 +1,61 @@
+use crate::keep_alive::KeepAliveSettings;
+use crate::login_state::LoginRules;
+use crate::theme::ServiceTheme;
+use crate::tls::TlsErrorPolicy;
+use std::collections::BTreeMap;
+
+pub(crate) const SERVICE_REGISTRY_FILE: &str = "services.json";
+
+/// Per-service options that live alongside the frontend's service list, keyed by service id.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ServiceSettings {
+    pub tls_error_policy: TlsErrorPolicy,
+    /// `None` follows the per-host default.
+    pub content_blocking: Option<bool>,
+    pub theme: ServiceTheme,
+    /// Page zoom in percent; `None` is 100%.
+    pub zoom_percent: Option<u16>,
+    /// `None` disables the periodic session ping.
+    pub keep_alive: Option<KeepAliveSettings>,
+    /// `None` uses the built-in rules for the service's host.
+    pub login_rules: Option<LoginRules>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct ServiceRegistry {
+    pub services: BTreeMap<String, ServiceSettings>,
+}
+
+impl ServiceRegistry {
+    pub fn settings(&self, service_id: &str) -> ServiceSettings {
+        self.services.get(service_id).cloned().unwrap_or_default()
+    }
+
+    pub fn settings_mut(&mut self, service_id: &str) -> &mut ServiceSettings {
+        self.services.entry(service_id.to_string()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceRegistry, ServiceSettings};
+    use crate::tls::TlsErrorPolicy;
+
+    #[test]
+    fn unknown_services_get_default_settings() {
+        let registry = ServiceRegistry::default();
+        assert_eq!(registry.settings("chatgpt"), ServiceSettings::default());
+    }
+
+    #[test]
+    fn registry_tolerates_missing_fields_from_older_files() {
+        let registry: ServiceRegistry =
+            serde_json::from_str(r#"{"services":{"local":{}}}"#).unwrap();
+        assert_eq!(
+            registry.settings("local").tls_error_policy,
+            TlsErrorPolicy::Block
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Soft refresh of service webviews after the system resumes from sleep or the network comes
+//! back, when open websockets are usually dead.
+//!
+//! On Linux, logind's `PrepareForSleep` and NetworkManager's `StateChanged` D-Bus signals
+//! trigger a refresh. A polling fallback catches the rest: a suspend shows up as wall-clock
+//! time passing faster than the monotonic clock, and a network change as the default route
+//! appearing. Triggers are debounced so a burst of signals refreshes once.
+
+use std::time::{Duration, Instant};
+
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEBOUNCE: Duration = Duration::from_secs(5);
+/// Wall-clock drift beyond this during one poll means the system was suspended.
+const SLEEP_GAP: Duration = Duration::from_secs(10);
+#[cfg(target_os = "linux")]
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+
+#[derive(Debug, Default)]
+pub(crate) struct RefreshScheduler {
+    due: Option<Instant>,
+}
+
+impl RefreshScheduler {
+    /// Schedules a refresh, pushing back one that is already pending.
+    pub fn request(&mut self, now: Instant) {
+        self.due = Some(now + DEBOUNCE);
+    }
+
+    /// `true` once when a scheduled refresh becomes due.
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        match self.due {
+            Some(due) if due <= now => {
+                self.due = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(crate) fn slept(wall_elapsed: Duration, monotonic_elapsed: Duration) -> bool {
+    wall_elapsed.saturating_sub(monotonic_elapsed) > SLEEP_GAP
+}
+
+/// Parses `/proc/net/route`: a default route has destination `00000000`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn has_default_route(route_table: &str) -> bool {
+    route_table
+        .lines()
+        .skip(1)
+        .any(|line| line.split_whitespace().nth(1) == Some("00000000"))
+}
+
+/// `None` where the route table cannot be read.
+pub(crate) fn network_available() -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_to_string("/proc/net/route")
+            .ok()
+            .map(|table| has_default_route(&table))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Subscribes to resume and connectivity signals on the shared system bus connection, which
+/// stays open for the life of the process. Must run on the GTK main thread, whose main loop
+/// dispatches the callbacks.
+#[cfg(target_os = "linux")]
+pub(crate) fn watch_system_signals(app: &tauri::AppHandle) {
+    use webkit2gtk::gio::{self, DBusSignalFlags};
+
+    let connection = match gio::bus_get_sync(gio::BusType::System, None::<&gio::Cancellable>) {
+        Ok(connection) => connection,
+        Err(e) => {
+            println!("[AnyChat] System bus unavailable, polling only: {}", e);
+            return;
+        }
+    };
+
+    let resume_app = app.clone();
+    connection.signal_subscribe(
+        Some("org.freedesktop.login1"),
+        Some("org.freedesktop.login1.Manager"),
+        Some("PrepareForSleep"),
+        Some("/org/freedesktop/login1"),
+        None,
+        DBusSignalFlags::NONE,
+        move |_, _, _, _, _, parameters| {
+            if parameters.get::<(bool,)>() == Some((false,)) {
+                crate::request_service_refresh(&resume_app, "system resumed");
+            }
+        },
+    );
+
+    let network_app = app.clone();
+    connection.signal_subscribe(
+        Some("org.freedesktop.NetworkManager"),
+        Some("org.freedesktop.NetworkManager"),
+        Some("StateChanged"),
+        Some("/org/freedesktop/NetworkManager"),
+        None,
+        DBusSignalFlags::NONE,
+        move |_, _, _, _, _, parameters| {
+            if parameters.get::<(u32,)>() == Some((NM_STATE_CONNECTED_GLOBAL,)) {
+                crate::request_service_refresh(&network_app, "network connected");
+            }
+        },
+    );
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn watch_system_signals(_app: &tauri::AppHandle) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_default_route, slept, RefreshScheduler};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn requests_are_debounced_into_one_refresh() {
+        let start = Instant::now();
+        let mut scheduler = RefreshScheduler::default();
+        scheduler.request(start);
+        scheduler.request(start + Duration::from_secs(3));
+        assert!(!scheduler.take_due(start + Duration::from_secs(6)));
+        assert!(scheduler.take_due(start + Duration::from_secs(8)));
+        assert!(!scheduler.take_due(start + Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn detects_suspend_and_default_routes() {
+        assert!(slept(Duration::from_secs(600), Duration::from_secs(2)));
+        assert!(!slept(Duration::from_secs(3), Duration::from_secs(2)));
+
+        let table = "Iface\tDestination\tGateway\tFlags\n\
+                     wlp2s0\t0010A8C0\t00000000\t0001\n";
+        assert!(!has_default_route(table));
+        assert!(has_default_route(&format!(
+            "{}wlp2s0\t00000000\t0110A8C0\t0003\n",
+            table
+        )));
+    }
+}
@@ -0,0 +1,291 @@
+//! Small encrypted key/value store for credentials such as API keys.
+//!
+//! Values are sealed with XChaCha20-Poly1305 under a random key kept next to the store with
+//! owner-only permissions, so secrets never sit in plaintext JSON in the app data directory.
+//! Data that leaves the machine is sealed under a key derived from a password with Argon2id.
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::storage::Vault;
+
+pub(crate) const SECRETS_FILE: &str = "secrets.json";
+pub(crate) const SECRETS_KEY_FILE: &str = "secrets.key";
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct SecretsFile {
+    entries: BTreeMap<String, String>,
+}
+
+pub(crate) fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+pub(crate) fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("Encrypted data is truncated".to_string());
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt data: wrong key or corrupted file".to_string())
+}
+
+/// Argon2id cost parameters, stored with the data they protect so they can be raised later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+pub(crate) fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<[u8; 32], String> {
+    let params = argon2::Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+pub(crate) fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Data sealed under a password, with everything needed to derive the key again.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PasswordSealed {
+    pub kdf: KdfParams,
+    pub salt: String,
+    pub data: String,
+}
+
+pub(crate) fn seal_with_password(
+    password: &str,
+    plaintext: &[u8],
+    params: KdfParams,
+) -> Result<PasswordSealed, String> {
+    let salt = random_bytes::<SALT_LEN>();
+    let key = derive_key(password, &salt, params)?;
+    Ok(PasswordSealed {
+        kdf: params,
+        salt: BASE64.encode(salt),
+        data: BASE64.encode(seal(&key, plaintext)?),
+    })
+}
+
+pub(crate) fn open_with_password(
+    password: &str,
+    sealed: &PasswordSealed,
+) -> Result<Vec<u8>, String> {
+    let salt = BASE64.decode(&sealed.salt).map_err(|e| e.to_string())?;
+    let data = BASE64.decode(&sealed.data).map_err(|e| e.to_string())?;
+    let key = derive_key(password, &salt, sealed.kdf)?;
+    open(&key, &data).map_err(|_| "Wrong password or corrupted file".to_string())
+}
+
+fn load_or_create_key(data_dir: &Path) -> Result<[u8; 32], String> {
+    let path = data_dir.join(SECRETS_KEY_FILE);
+
+    match std::fs::read(&path) {
+        Ok(bytes) => {
+            return bytes
+                .try_into()
+                .map_err(|_| format!("Invalid key file {}", path.display()));
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+
+    let key: [u8; 32] = XChaCha20Poly1305::generate_key(&mut OsRng).into();
+    crate::storage::write_atomic(&path, &key)?;
+    restrict_to_owner(&path)?;
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn restrict_to_owner(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn restrict_to_owner(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+pub(crate) fn get_secret(
+    data_dir: &Path,
+    vault: &Vault,
+    name: &str,
+) -> Result<Option<String>, String> {
+    let secrets: SecretsFile = crate::storage::read_json(vault, &data_dir.join(SECRETS_FILE))?;
+    let Some(encoded) = secrets.entries.get(name) else {
+        return Ok(None);
+    };
+
+    let key = load_or_create_key(data_dir)?;
+    let sealed = BASE64.decode(encoded).map_err(|e| e.to_string())?;
+    let plaintext = open(&key, &sealed)?;
+    String::from_utf8(plaintext)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Stores `value` under `name`, or removes the entry when `value` is `None`.
+pub(crate) fn set_secret(
+    data_dir: &Path,
+    vault: &Vault,
+    name: &str,
+    value: Option<&str>,
+) -> Result<(), String> {
+    let path = data_dir.join(SECRETS_FILE);
+    let mut secrets: SecretsFile = crate::storage::read_json(vault, &path)?;
+
+    match value {
+        Some(value) => {
+            let key = load_or_create_key(data_dir)?;
+            let sealed = seal(&key, value.as_bytes())?;
+            secrets
+                .entries
+                .insert(name.to_string(), BASE64.encode(sealed));
+        }
+        None => {
+            secrets.entries.remove(name);
+        }
+    }
+
+    crate::storage::write_json(vault, &path, &secrets)
+}
+
+pub(crate) fn has_secret(data_dir: &Path, vault: &Vault, name: &str) -> Result<bool, String> {
+    let secrets: SecretsFile = crate::storage::read_json(vault, &data_dir.join(SECRETS_FILE))?;
+    Ok(secrets.entries.contains_key(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_secret, has_secret, open, open_with_password, seal, seal_with_password, set_secret,
+        KdfParams, SECRETS_FILE,
+    };
+    use crate::storage::Vault;
+
+    /// Cheap parameters so tests stay fast.
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("anychat-secrets-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn sealed_data_round_trips_and_rejects_tampering() {
+        let key = [7u8; 32];
+        let mut sealed = seal(&key, b"sk-test").unwrap();
+        assert_eq!(open(&key, &sealed).unwrap(), b"sk-test");
+
+        assert!(open(&[8u8; 32], &sealed).is_err());
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(open(&key, &sealed).is_err());
+        assert!(open(&key, &[0u8; 4]).is_err());
+    }
+
+    #[test]
+    fn password_sealed_data_needs_the_same_password() {
+        let sealed = seal_with_password("correct horse", b"cookies", TEST_KDF).unwrap();
+        assert_eq!(
+            open_with_password("correct horse", &sealed).unwrap(),
+            b"cookies"
+        );
+        assert!(open_with_password("wrong horse", &sealed).is_err());
+        assert_ne!(
+            seal_with_password("correct horse", b"cookies", TEST_KDF)
+                .unwrap()
+                .salt,
+            sealed.salt
+        );
+    }
+
+    #[test]
+    fn secrets_are_stored_encrypted_and_can_be_removed() {
+        let dir = temp_dir("store");
+
+        set_secret(
+            &dir,
+            &Vault::Disabled,
+            "native-chat/local/api-key",
+            Some("sk-very-secret"),
+        )
+        .unwrap();
+        assert!(has_secret(&dir, &Vault::Disabled, "native-chat/local/api-key").unwrap());
+        assert_eq!(
+            get_secret(&dir, &Vault::Disabled, "native-chat/local/api-key")
+                .unwrap()
+                .as_deref(),
+            Some("sk-very-secret")
+        );
+
+        let on_disk = std::fs::read_to_string(dir.join(SECRETS_FILE)).unwrap();
+        assert!(!on_disk.contains("sk-very-secret"));
+
+        set_secret(&dir, &Vault::Disabled, "native-chat/local/api-key", None).unwrap();
+        assert_eq!(
+            get_secret(&dir, &Vault::Disabled, "native-chat/local/api-key").unwrap(),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,254 @@
+//! Support for chat UIs the user runs themselves (Open WebUI, LibreChat, LobeChat, ...).
+//!
+//! These services skip the public-site quirks (spoofed user agent, WebAuthn shim, path-based
+//! OAuth popup detection) and may use plain http, but only towards loopback and private hosts.
+
+use std::net::IpAddr;
+
+const PRIVATE_HOST_SUFFIXES: [&str; 5] =
+    [".localhost", ".local", ".lan", ".internal", ".home.arpa"];
+
+/// Whether `host` (as returned by `Url::host_str`) points at this machine or the local network.
+/// Bare single-label names like `nas` are treated as LAN hosts.
+pub(crate) fn is_private_network_host(host: &str) -> bool {
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+
+    if host.is_empty() {
+        return false;
+    }
+
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return match ip {
+            IpAddr::V4(ip) => {
+                let [first, second, ..] = ip.octets();
+                ip.is_loopback()
+                    || ip.is_private()
+                    || ip.is_link_local()
+                    // 100.64.0.0/10, used by carrier-grade NAT and overlay VPNs such as Tailscale.
+                    || (first == 100 && (second & 0b1100_0000) == 64)
+            }
+            IpAddr::V6(ip) => {
+                let first_segment = ip.segments()[0];
+                ip.is_loopback()
+                    || (first_segment & 0xfe00) == 0xfc00
+                    || (first_segment & 0xffc0) == 0xfe80
+                    || ip
+                        .to_ipv4_mapped()
+                        .is_some_and(|mapped| is_private_network_host(&mapped.to_string()))
+            }
+        };
+    }
+
+    host == "localhost"
+        || !host.contains('.')
+        || PRIVATE_HOST_SUFFIXES
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+}
+
+/// Plain http is only acceptable when it never leaves the machine or the LAN.
+pub(crate) fn is_allowed_navigation(url: &tauri::Url) -> bool {
+    match url.scheme() {
+        "http" => url.host_str().is_some_and(is_private_network_host),
+        _ => true,
+    }
+}
+
+pub(crate) fn validate_service_url(url: &str) -> Result<(), String> {
+    let parsed_url = tauri::Url::parse(url).map_err(|e| e.to_string())?;
+
+    match parsed_url.scheme() {
+        "https" => Ok(()),
+        "http" if is_allowed_navigation(&parsed_url) => Ok(()),
+        "http" => Err(format!(
+            "Plain http is only allowed for loopback and private network hosts: {}",
+            url
+        )),
+        scheme => Err(format!(
+            "Unsupported scheme for a self-hosted service: {}",
+            scheme
+        )),
+    }
+}
+
+/// Self-hosted UIs commonly serve their own `/auth` and `/login` routes, so only a different
+/// origin that looks like an identity provider is treated as an OAuth popup.
+pub(crate) fn is_auth_popup_url(service_url: &str, url: &tauri::Url) -> bool {
+    let same_origin = tauri::Url::parse(service_url)
+        .map(|service_url| service_url.origin() == url.origin())
+        .unwrap_or(false);
+
+    !same_origin && crate::is_auth_url(url.as_str())
+}
+
+/// Answers HTTP basic/digest challenges with a GTK credential dialog instead of failing the load.
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_basic_auth_prompt(webview: &tauri::Webview) {
+    use gtk::prelude::*;
+    use webkit2gtk::glib::translate::{mut_override, ToGlibPtr};
+    use webkit2gtk::{
+        AuthenticationRequestExt, AuthenticationScheme, Credential, CredentialPersistence,
+        WebViewExt,
+    };
+
+    let _ = webview.with_webview(|platform_webview| {
+        platform_webview
+            .inner()
+            .connect_authenticate(|webview, request| {
+                if !matches!(
+                    request.scheme(),
+                    AuthenticationScheme::HttpBasic | AuthenticationScheme::HttpDigest
+                ) {
+                    return false;
+                }
+
+                let host = request
+                    .host()
+                    .map(|host| host.to_string())
+                    .unwrap_or_default();
+                let realm = request
+                    .realm()
+                    .map(|realm| realm.to_string())
+                    .unwrap_or_default();
+                let message = if request.is_retry() {
+                    format!("The credentials for {} were rejected. Try again.", host)
+                } else if realm.is_empty() {
+                    format!("{} requires a username and password.", host)
+                } else {
+                    format!(
+                        "{} requires a username and password for \"{}\".",
+                        host, realm
+                    )
+                };
+
+                let parent = webview
+                    .toplevel()
+                    .and_then(|toplevel| toplevel.downcast::<gtk::Window>().ok());
+                let dialog = gtk::Dialog::with_buttons(
+                    Some("Authentication Required"),
+                    parent.as_ref(),
+                    gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+                    &[
+                        ("Cancel", gtk::ResponseType::Cancel),
+                        ("Sign In", gtk::ResponseType::Accept),
+                    ],
+                );
+                dialog.set_default_response(gtk::ResponseType::Accept);
+
+                let username = gtk::Entry::new();
+                username.set_placeholder_text(Some("Username"));
+                let password = gtk::Entry::new();
+                password.set_placeholder_text(Some("Password"));
+                password.set_visibility(false);
+                password.set_activates_default(true);
+
+                let content = dialog.content_area();
+                content.set_spacing(8);
+                content.set_border_width(12);
+                content.add(&gtk::Label::new(Some(&message)));
+                content.add(&username);
+                content.add(&password);
+                dialog.show_all();
+
+                let request = request.clone();
+                dialog.connect_response(move |dialog, response| {
+                    if response == gtk::ResponseType::Accept {
+                        let credential = Credential::new(
+                            &username.text(),
+                            &password.text(),
+                            CredentialPersistence::ForSession,
+                        );
+                        // webkit2gtk-rs does not wrap webkit_authentication_request_authenticate.
+                        unsafe {
+                            webkit2gtk::ffi::webkit_authentication_request_authenticate(
+                                request.to_glib_none().0,
+                                mut_override(credential.to_glib_none().0),
+                            );
+                        }
+                    } else {
+                        request.cancel();
+                    }
+                    dialog.close();
+                });
+
+                true
+            });
+    });
+}
+
+/// WebView2 shows its own credential prompt on Windows. WKWebView has none, so on macOS pages
+/// behind basic auth fail to load; the add-service dialog tells the user as much.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_basic_auth_prompt(_webview: &tauri::Webview) {}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_auth_popup_url, is_private_network_host, validate_service_url};
+
+    #[test]
+    fn loopback_and_private_hosts_are_recognized() {
+        for host in [
+            "localhost",
+            "open-webui.localhost",
+            "127.0.0.1",
+            "10.0.0.5",
+            "172.16.4.2",
+            "192.168.1.20",
+            "169.254.10.1",
+            "100.101.102.103",
+            "[::1]",
+            "[fd12:3456::1]",
+            "[fe80::1]",
+            "nas",
+            "librechat.local",
+            "chat.home.arpa",
+        ] {
+            assert!(is_private_network_host(host), "{} should be private", host);
+        }
+    }
+
+    #[test]
+    fn public_hosts_are_not_private() {
+        for host in [
+            "chatgpt.com",
+            "8.8.8.8",
+            "172.32.0.1",
+            "100.128.0.1",
+            "[2001:4860:4860::8888]",
+            "",
+        ] {
+            assert!(!is_private_network_host(host), "{} should be public", host);
+        }
+    }
+
+    #[test]
+    fn self_hosted_urls_allow_plain_http_only_on_private_networks() {
+        assert!(validate_service_url("http://localhost:3000").is_ok());
+        assert!(validate_service_url("http://192.168.1.20:8080/").is_ok());
+        assert!(validate_service_url("https://chat.example.com").is_ok());
+        assert!(validate_service_url("http://chat.example.com").is_err());
+        assert!(validate_service_url("ftp://localhost").is_err());
+    }
+
+    #[test]
+    fn same_origin_auth_routes_are_not_oauth_popups() {
+        let service_url = "http://localhost:3000";
+
+        assert!(!is_auth_popup_url(
+            service_url,
+            &"http://localhost:3000/auth/signin".parse().unwrap()
+        ));
+        assert!(is_auth_popup_url(
+            service_url,
+            &"https://accounts.google.com/o/oauth2/auth".parse().unwrap()
+        ));
+        assert!(!is_auth_popup_url(
+            service_url,
+            &"https://docs.openwebui.com".parse().unwrap()
+        ));
+    }
+}
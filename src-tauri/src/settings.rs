@@ -0,0 +1,18 @@
+use crate::app_lock::AppLockSettings;
+use crate::http::HttpSettings;
+use crate::sync::SyncSettings;
+use crate::theme::AppTheme;
+
+pub(crate) const SETTINGS_FILE: &str = "settings.json";
+
+/// App-wide preferences owned by the Rust side and persisted in the app data directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct AppSettings {
+    pub http: HttpSettings,
+    /// Extra loopback ports to probe for local model servers, on top of the well-known defaults.
+    pub local_server_ports: Vec<u16>,
+    pub theme: AppTheme,
+    pub app_lock: AppLockSettings,
+    pub sync: SyncSettings,
+}
@@ -0,0 +1,207 @@
+//! Shortcuts for the active service that must work while a service page has keyboard focus.
+//!
+//! Key events inside a service webview never reach the main window's handlers, so the same
+//! bindings are captured natively: on the GTK widget on Linux and as app menu accelerators on
+//! macOS.
+
+use crate::navigation::HistoryDirection;
+use crate::zoom::ZoomAction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServiceShortcut {
+    Zoom(ZoomAction),
+    Find,
+    Navigate(HistoryDirection),
+}
+
+impl ServiceShortcut {
+    const MENU: [ServiceShortcut; 6] = [
+        ServiceShortcut::Navigate(HistoryDirection::Back),
+        ServiceShortcut::Navigate(HistoryDirection::Forward),
+        ServiceShortcut::Find,
+        ServiceShortcut::Zoom(ZoomAction::In),
+        ServiceShortcut::Zoom(ZoomAction::Out),
+        ServiceShortcut::Zoom(ZoomAction::Reset),
+    ];
+
+    fn menu_id(self) -> Option<&'static str> {
+        match self {
+            ServiceShortcut::Zoom(ZoomAction::In) => Some("service-zoom-in"),
+            ServiceShortcut::Zoom(ZoomAction::Out) => Some("service-zoom-out"),
+            ServiceShortcut::Zoom(ZoomAction::Reset) => Some("service-zoom-reset"),
+            ServiceShortcut::Zoom(ZoomAction::Set { .. }) => None,
+            ServiceShortcut::Find => Some("service-find"),
+            ServiceShortcut::Navigate(HistoryDirection::Back) => Some("service-back"),
+            ServiceShortcut::Navigate(HistoryDirection::Forward) => Some("service-forward"),
+        }
+    }
+}
+
+/// Maps a key name (GDK keyval names, e.g. `equal`, `KP_Add`) pressed together with Ctrl/Cmd
+/// (`primary`) or Alt.
+pub(crate) fn key_shortcut(key: &str, primary: bool, alt: bool) -> Option<ServiceShortcut> {
+    if alt && !primary {
+        return match key {
+            "Left" => Some(ServiceShortcut::Navigate(HistoryDirection::Back)),
+            "Right" => Some(ServiceShortcut::Navigate(HistoryDirection::Forward)),
+            _ => None,
+        };
+    }
+    if !primary {
+        return None;
+    }
+
+    match key {
+        "plus" | "equal" | "KP_Add" => Some(ServiceShortcut::Zoom(ZoomAction::In)),
+        "minus" | "underscore" | "KP_Subtract" => Some(ServiceShortcut::Zoom(ZoomAction::Out)),
+        "0" | "KP_0" => Some(ServiceShortcut::Zoom(ZoomAction::Reset)),
+        "f" | "F" => Some(ServiceShortcut::Find),
+        _ => None,
+    }
+}
+
+/// The side buttons of a mouse, numbered as in X11/GDK.
+pub(crate) fn mouse_shortcut(button: u32) -> Option<ServiceShortcut> {
+    match button {
+        8 => Some(ServiceShortcut::Navigate(HistoryDirection::Back)),
+        9 => Some(ServiceShortcut::Navigate(HistoryDirection::Forward)),
+        _ => None,
+    }
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn menu_shortcut(id: &str) -> Option<ServiceShortcut> {
+    ServiceShortcut::MENU
+        .into_iter()
+        .find(|shortcut| shortcut.menu_id() == Some(id))
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_service_shortcuts(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use gtk::gdk::ModifierType;
+    use gtk::glib::Propagation;
+    use gtk::prelude::WidgetExt;
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        let webview = platform_webview.inner();
+        let dispatch = std::rc::Rc::new(move |shortcut: Option<ServiceShortcut>| match shortcut {
+            Some(shortcut) => {
+                crate::handle_service_shortcut(&app, &service_id, shortcut);
+                Propagation::Stop
+            }
+            None => Propagation::Proceed,
+        });
+
+        let on_key = dispatch.clone();
+        webview.connect_key_press_event(move |_, event| {
+            let state = event.state();
+            let key = event.keyval().name();
+            on_key(key.and_then(|key| {
+                key_shortcut(
+                    &key,
+                    state.contains(ModifierType::CONTROL_MASK),
+                    state.contains(ModifierType::MOD1_MASK),
+                )
+            }))
+        });
+        webview
+            .connect_button_press_event(move |_, event| dispatch(mouse_shortcut(event.button())));
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_service_shortcuts(
+    _app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    _service_id: &str,
+) {
+}
+
+/// A "服务" submenu whose accelerators act on the active service.
+#[cfg(target_os = "macos")]
+pub(crate) fn service_menu(
+    app: &tauri::AppHandle,
+) -> tauri::Result<tauri::menu::Submenu<tauri::Wry>> {
+    use tauri::menu::{MenuItemBuilder, SubmenuBuilder};
+
+    let mut submenu = SubmenuBuilder::new(app, "服务");
+    for shortcut in ServiceShortcut::MENU {
+        let (Some(id), Some((label, accelerator))) = (shortcut.menu_id(), menu_item(shortcut))
+        else {
+            continue;
+        };
+        let item = MenuItemBuilder::with_id(id, label)
+            .accelerator(accelerator)
+            .build(app)?;
+        submenu = submenu.item(&item);
+    }
+    submenu.build()
+}
+
+#[cfg(target_os = "macos")]
+fn menu_item(shortcut: ServiceShortcut) -> Option<(&'static str, &'static str)> {
+    match shortcut {
+        ServiceShortcut::Zoom(ZoomAction::In) => Some(("放大", "CmdOrCtrl+=")),
+        ServiceShortcut::Zoom(ZoomAction::Out) => Some(("缩小", "CmdOrCtrl+-")),
+        ServiceShortcut::Zoom(ZoomAction::Reset) => Some(("实际大小", "CmdOrCtrl+0")),
+        ServiceShortcut::Zoom(ZoomAction::Set { .. }) => None,
+        ServiceShortcut::Find => Some(("查找", "CmdOrCtrl+F")),
+        ServiceShortcut::Navigate(HistoryDirection::Back) => Some(("后退", "CmdOrCtrl+[")),
+        ServiceShortcut::Navigate(HistoryDirection::Forward) => Some(("前进", "CmdOrCtrl+]")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key_shortcut, menu_shortcut, mouse_shortcut, ServiceShortcut};
+    use crate::navigation::HistoryDirection;
+    use crate::zoom::ZoomAction;
+
+    #[test]
+    fn zoom_keys_need_the_primary_modifier() {
+        assert_eq!(
+            key_shortcut("equal", true, false),
+            Some(ServiceShortcut::Zoom(ZoomAction::In))
+        );
+        assert_eq!(
+            key_shortcut("KP_Subtract", true, false),
+            Some(ServiceShortcut::Zoom(ZoomAction::Out))
+        );
+        assert_eq!(
+            key_shortcut("0", true, false),
+            Some(ServiceShortcut::Zoom(ZoomAction::Reset))
+        );
+        assert_eq!(key_shortcut("F", true, false), Some(ServiceShortcut::Find));
+        assert_eq!(key_shortcut("equal", false, false), None);
+        assert_eq!(key_shortcut("a", true, false), None);
+    }
+
+    #[test]
+    fn menu_ids_round_trip() {
+        assert_eq!(
+            menu_shortcut("service-zoom-out"),
+            Some(ServiceShortcut::Zoom(ZoomAction::Out))
+        );
+        assert_eq!(menu_shortcut("show"), None);
+    }
+
+    #[test]
+    fn alt_arrows_and_side_buttons_navigate_history() {
+        assert_eq!(
+            key_shortcut("Left", false, true),
+            Some(ServiceShortcut::Navigate(HistoryDirection::Back))
+        );
+        assert_eq!(key_shortcut("Left", true, true), None);
+        assert_eq!(
+            mouse_shortcut(9),
+            Some(ServiceShortcut::Navigate(HistoryDirection::Forward))
+        );
+        assert_eq!(mouse_shortcut(1), None);
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+pub(crate) fn data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+pub(crate) fn data_file_path(app: &tauri::AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    Ok(data_dir(app)?.join(file_name))
+}
+
+/// Encryption state of the data files. The live one is kept in `AppState` and handed to every
+/// reader and writer.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum Vault {
+    #[default]
+    Disabled,
+    /// Encrypted, waiting for the passphrase.
+    Locked,
+    Unlocked([u8; 32]),
+    /// Encrypted data could not be read; nothing is read or written until it is recovered.
+    Failed(String),
+}
+
+impl Vault {
+    /// Whether encrypted data cannot be read right now.
+    pub fn is_locked(&self) -> bool {
+        matches!(self, Vault::Locked | Vault::Failed(_))
+    }
+}
+
+/// Plaintext files are refused while encryption is on: files from before it was turned on are
+/// sealed when the vault opens, so a plaintext file showing up later was not written by AnyChat.
+fn decode(vault: &Vault, path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let sealed = crate::encryption::sealed_payload(&bytes);
+    match (vault, sealed) {
+        (Vault::Failed(error), _) => Err(error.clone()),
+        (Vault::Disabled, None) => Ok(bytes),
+        (Vault::Unlocked(_) | Vault::Locked, None) => Err(format!(
+            "{} is not encrypted although data encryption is on; refusing to read it",
+            path.display()
+        )),
+        (Vault::Unlocked(key), Some(sealed)) => crate::secrets::open(key, &sealed).map_err(|_| {
+            format!(
+                "Cannot decrypt {}: wrong key or corrupted file",
+                path.display()
+            )
+        }),
+        (Vault::Locked, Some(_)) => Err(format!(
+            "{} is encrypted; unlock AnyChat's data first",
+            path.display()
+        )),
+        (Vault::Disabled, Some(_)) => Err(format!(
+            "{} is encrypted but no encryption key is configured",
+            path.display()
+        )),
+    }
+}
+
+fn encode(vault: &Vault, path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    match vault {
+        Vault::Disabled => Ok(bytes),
+        Vault::Unlocked(key) => crate::encryption::seal_file(key, &bytes),
+        Vault::Locked => Err(format!(
+            "Cannot write {}: AnyChat's data is locked",
+            path.display()
+        )),
+        Vault::Failed(error) => Err(error.clone()),
+    }
+}
+
+fn read_file(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(path: &Path, bytes: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Reads a JSON document, falling back to the default value when the file does not exist yet.
+/// Encrypted documents are decrypted with the vault key.
+pub(crate) fn read_json<T: DeserializeOwned + Default>(
+    vault: &Vault,
+    path: &Path,
+) -> Result<T, String> {
+    match read_file(path)? {
+        Some(bytes) => parse_json(path, &decode(vault, path, bytes)?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Writes a JSON document through a temporary file so a crash never leaves a half-written file.
+/// The document is encrypted while the vault is unlocked.
+pub(crate) fn write_json<T: Serialize>(
+    vault: &Vault,
+    path: &Path,
+    value: &T,
+) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &encode(vault, path, bytes)?)
+}
+
+/// [`read_json`] for files needed before the data can be unlocked, which are never encrypted.
+pub(crate) fn read_plain_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    match read_file(path)? {
+        Some(bytes) => parse_json(path, &bytes),
+        None => Ok(T::default()),
+    }
+}
+
+pub(crate) fn write_plain_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &bytes)
+}
+
+/// Whether `name` can be used as a file name inside the data directory without escaping it.
+pub(crate) fn is_safe_file_stem(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+/// Appends one line to a log file, creating it on first use.
+/// Size past which a log file is rotated, keeping one previous generation.
+pub(crate) const MAX_LOG_BYTES: u64 = 256 * 1024;
+pub(crate) const ROTATED_LOG_SUFFIX: &str = ".old";
+
+/// Appends `line` to a log. Past `max_bytes` the log is first moved to `<name>.old`, replacing
+/// the previous one, so a log and its rotated copy stay bounded.
+pub(crate) fn append_line(path: &Path, line: &str, max_bytes: u64) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    if std::fs::metadata(path).is_ok_and(|metadata| metadata.len() >= max_bytes) {
+        let mut rotated = path.as_os_str().to_owned();
+        rotated.push(ROTATED_LOG_SUFFIX);
+        std::fs::rename(path, &rotated)
+            .map_err(|e| format!("Failed to rotate {}: {}", path.display(), e))?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// The last `max_bytes` of a log, starting at a line boundary; empty when there is no log yet.
+pub(crate) fn read_log_tail(path: &Path, max_bytes: u64) -> Result<String, String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    let start = len.saturating_sub(max_bytes);
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.take(max_bytes).read_to_end(&mut bytes))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if start > 0 {
+        let first_line_end = bytes
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map_or(0, |i| i + 1);
+        bytes.drain(..first_line_end);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_line, decode, encode, read_json, read_log_tail, write_json, Vault};
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    #[test]
+    fn logs_rotate_past_their_size_limit_and_are_read_from_the_tail() {
+        let dir = std::env::temp_dir().join(format!("anychat-storage-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let log = dir.join("test.log");
+
+        for index in 0..10 {
+            append_line(&log, &format!("line {}", index), 32).unwrap();
+        }
+        let current = std::fs::read_to_string(&log).unwrap();
+        let rotated = std::fs::read_to_string(dir.join("test.log.old")).unwrap();
+        assert!(current.len() < 40 && rotated.len() < 40);
+        assert!(current.ends_with("line 9\n"));
+
+        assert_eq!(read_log_tail(&log, 10).unwrap(), "line 9\n");
+        assert_eq!(read_log_tail(&dir.join("missing.log"), 10).unwrap(), "");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn missing_file_reads_as_default() {
+        let dir =
+            std::env::temp_dir().join(format!("anychat-storage-missing-{}", std::process::id()));
+        let value: BTreeMap<String, u32> =
+            read_json(&Vault::Disabled, &dir.join("nope.json")).unwrap();
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn json_round_trips_through_atomic_write() {
+        let dir =
+            std::env::temp_dir().join(format!("anychat-storage-roundtrip-{}", std::process::id()));
+        let path = dir.join("nested").join("value.json");
+        let mut value = BTreeMap::new();
+        value.insert("zoom".to_string(), 3u32);
+
+        write_json(&Vault::Disabled, &path, &value).unwrap();
+        let loaded: BTreeMap<String, u32> = read_json(&Vault::Disabled, &path).unwrap();
+
+        assert_eq!(loaded, value);
+        assert!(!path.with_extension("json.tmp").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn encrypted_documents_need_the_unlocked_vault() {
+        let path = Path::new("services.json");
+        let plaintext = br#"{"services":{}}"#.to_vec();
+        let unlocked = Vault::Unlocked([7; 32]);
+
+        assert_eq!(
+            encode(&Vault::Disabled, path, plaintext.clone()).unwrap(),
+            plaintext
+        );
+        let sealed = encode(&unlocked, path, plaintext.clone()).unwrap();
+        assert!(!String::from_utf8_lossy(&sealed).contains("services"));
+        assert_eq!(decode(&unlocked, path, sealed.clone()).unwrap(), plaintext);
+        assert!(decode(&Vault::Unlocked([8; 32]), path, sealed.clone())
+            .unwrap_err()
+            .contains("Cannot decrypt services.json"));
+        assert!(decode(&Vault::Locked, path, sealed.clone()).is_err());
+        assert!(decode(&Vault::Disabled, path, sealed).is_err());
+
+        // Plaintext dropped into an encrypted data directory is not trusted.
+        assert!(decode(&unlocked, path, plaintext.clone())
+            .unwrap_err()
+            .contains("not encrypted"));
+        assert!(decode(&Vault::Locked, path, plaintext.clone()).is_err());
+        assert_eq!(
+            decode(&Vault::Disabled, path, plaintext.clone()).unwrap(),
+            plaintext
+        );
+        let failed = Vault::Failed("broken".to_string());
+        assert!(decode(&failed, path, plaintext.clone()).is_err());
+        assert!(encode(&failed, path, plaintext.clone()).is_err());
+        assert!(encode(&Vault::Locked, path, plaintext).is_err());
+    }
+}
@@ -0,0 +1,882 @@
+//! Syncs the service list, per-service settings and user scripts between machines through one
+//! shared file, `anychat-sync.json`, kept in a WebDAV collection or in a folder that another tool
+//! (Syncthing, Dropbox) replicates.
+//!
+//! The file holds one record per item, stamped with when and on which device it last changed.
+//! Changes are noticed by comparing content hashes with the last sync, so a record's timestamp
+//! is when this device first saw the change. When both sides changed a record since the last
+//! sync, the newer change wins and the other one is kept in the conflict log. Deletions travel
+//! as empty records. On the first sync with a remote, records it already holds win, so a new
+//! machine adopts the existing setup instead of overwriting it with defaults.
+
+use crate::registry::ServiceSettings;
+use crate::secrets::{self, KdfParams, PasswordSealed};
+use crate::storage::is_safe_file_stem;
+use crate::user_scripts::UserScript;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub(crate) const SYNC_FILE: &str = "anychat-sync.json";
+pub(crate) const SYNC_STATE_FILE: &str = "sync_state.json";
+pub(crate) const SYNC_CONFLICTS_FILE: &str = "sync_conflicts.json";
+pub(crate) const WEBDAV_PASSWORD_SECRET: &str = "sync.webdavPassword";
+pub(crate) const PASSPHRASE_SECRET: &str = "sync.passphrase";
+pub(crate) const SYNC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// Attempts before giving up when another device keeps writing in between.
+pub(crate) const MAX_ATTEMPTS: usize = 3;
+const SYNC_FORMAT: &str = "anychat-sync";
+const SYNC_VERSION: u32 = 1;
+const MAX_LOGGED_CONFLICTS: usize = 100;
+const DELETED_HASH: &str = "deleted";
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum SyncTarget {
+    Folder {
+        path: String,
+    },
+    /// `url` is the collection that holds the sync file.
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        username: String,
+    },
+}
+
+impl SyncTarget {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SyncTarget::Folder { path } => {
+                if !std::path::Path::new(path).is_absolute() {
+                    return Err("The sync folder must be an absolute path".to_string());
+                }
+            }
+            SyncTarget::WebDav { url, .. } => {
+                let url: tauri::Url = url
+                    .parse()
+                    .map_err(|e| format!("Invalid WebDAV URL {}: {}", url, e))?;
+                let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+                if url.scheme() != "https" && !(url.scheme() == "http" && loopback) {
+                    return Err("The WebDAV URL must use https://".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct SyncSettings {
+    /// `None` turns sync off.
+    pub target: Option<SyncTarget>,
+    /// Seal the sync file with the passphrase stored under [`PASSPHRASE_SECRET`].
+    pub encrypt: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RecordKind {
+    /// An entry of the frontend's service list, including its position.
+    Service,
+    ServiceSettings,
+    UserScripts,
+}
+
+impl RecordKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            RecordKind::Service => "service",
+            RecordKind::ServiceSettings => "serviceSettings",
+            RecordKind::UserScripts => "userScripts",
+        }
+    }
+}
+
+pub(crate) fn record_key(kind: RecordKind, id: &str) -> String {
+    format!("{}/{}", kind.prefix(), id)
+}
+
+pub(crate) fn parse_record_key(key: &str) -> Option<(RecordKind, &str)> {
+    let (prefix, id) = key.split_once('/')?;
+    [
+        RecordKind::Service,
+        RecordKind::ServiceSettings,
+        RecordKind::UserScripts,
+    ]
+    .into_iter()
+    .find(|kind| kind.prefix() == prefix)
+    .map(|kind| (kind, id))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncRecord {
+    /// Unix time in milliseconds.
+    pub modified_at: u64,
+    pub device_id: String,
+    /// `None` once the record was deleted.
+    pub value: Option<Value>,
+}
+
+/// What this device last knew about a record.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordState {
+    hash: String,
+    modified_at: u64,
+    device_id: String,
+    /// Hash both sides agreed on after the last sync; `None` before the first one.
+    synced_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct SyncState {
+    pub device_id: String,
+    pub last_synced_at: Option<u64>,
+    records: BTreeMap<String, RecordState>,
+}
+
+impl SyncState {
+    pub fn ensure_device_id(&mut self) {
+        if self.device_id.is_empty() {
+            self.device_id = secrets::random_bytes::<8>()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+        }
+    }
+
+    /// Forgets what was agreed with the previous remote, e.g. after switching targets.
+    pub fn reset_remote(&mut self) {
+        self.last_synced_at = None;
+        for record in self.records.values_mut() {
+            record.synced_hash = None;
+        }
+    }
+
+    /// Stamps every record whose content changed or disappeared since it was last seen.
+    fn observe(&mut self, local: &BTreeMap<String, Value>, now: u64) {
+        let device_id = self.device_id.clone();
+        for (key, value) in local {
+            let hash = value_hash(Some(value));
+            let record = self
+                .records
+                .entry(key.clone())
+                .or_insert_with(|| RecordState {
+                    hash: String::new(),
+                    modified_at: now,
+                    device_id: device_id.clone(),
+                    synced_hash: None,
+                });
+            if record.hash != hash {
+                record.hash = hash;
+                record.modified_at = now;
+                record.device_id = device_id.clone();
+            }
+        }
+        for (key, record) in self.records.iter_mut() {
+            if !local.contains_key(key) && record.hash != DELETED_HASH {
+                record.hash = DELETED_HASH.to_string();
+                record.modified_at = now;
+                record.device_id = device_id.clone();
+            }
+        }
+    }
+}
+
+fn value_hash(value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return DELETED_HASH.to_string();
+    };
+    Sha256::digest(value.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ConflictWinner {
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncConflict {
+    pub key: String,
+    /// Unix time in milliseconds.
+    pub detected_at: u64,
+    pub winner: ConflictWinner,
+    pub local_modified_at: u64,
+    pub remote_modified_at: u64,
+    pub remote_device_id: String,
+    /// The losing side's value, for manual recovery.
+    pub discarded: Option<Value>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MergeResult {
+    /// Records the remote should hold afterwards.
+    pub remote: BTreeMap<String, SyncRecord>,
+    /// Whether `remote` differs from what was read.
+    pub upload: bool,
+    /// Remote values to apply locally; `None` deletes the item.
+    pub apply: BTreeMap<String, Option<Value>>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// Merges the local items into the remote records, last writer wins. `state` is updated as if
+/// the result was stored on both sides.
+pub(crate) fn merge(
+    state: &mut SyncState,
+    local: &BTreeMap<String, Value>,
+    remote: &BTreeMap<String, SyncRecord>,
+    now: u64,
+) -> MergeResult {
+    state.ensure_device_id();
+    state.observe(local, now);
+
+    let mut result = MergeResult {
+        remote: remote.clone(),
+        ..MergeResult::default()
+    };
+    let keys = state
+        .records
+        .keys()
+        .chain(remote.keys())
+        .cloned()
+        .collect::<std::collections::BTreeSet<_>>();
+
+    for key in keys {
+        let local_value = local.get(&key);
+        let take_remote = match (state.records.get(&key), remote.get(&key)) {
+            (None, None) => continue,
+            (None, Some(_)) => true,
+            (Some(record), None) => {
+                if record.hash == DELETED_HASH && record.synced_hash.is_none() {
+                    // Created and deleted here without ever being synced.
+                    state.records.remove(&key);
+                    continue;
+                }
+                false
+            }
+            (Some(record), Some(remote_record)) => {
+                let remote_hash = value_hash(remote_record.value.as_ref());
+                if remote_hash == record.hash {
+                    let record = state.records.get_mut(&key).unwrap();
+                    record.synced_hash = Some(remote_hash);
+                    continue;
+                }
+                let local_changed = record
+                    .synced_hash
+                    .as_ref()
+                    .is_some_and(|synced| *synced != record.hash);
+                let remote_changed = record.synced_hash.as_ref() != Some(&remote_hash);
+                if !local_changed {
+                    true
+                } else if !remote_changed {
+                    false
+                } else {
+                    let local_wins = (record.modified_at, &record.device_id)
+                        > (remote_record.modified_at, &remote_record.device_id);
+                    result.conflicts.push(SyncConflict {
+                        key: key.clone(),
+                        detected_at: now,
+                        winner: if local_wins {
+                            ConflictWinner::Local
+                        } else {
+                            ConflictWinner::Remote
+                        },
+                        local_modified_at: record.modified_at,
+                        remote_modified_at: remote_record.modified_at,
+                        remote_device_id: remote_record.device_id.clone(),
+                        discarded: if local_wins {
+                            remote_record.value.clone()
+                        } else {
+                            local_value.cloned()
+                        },
+                    });
+                    !local_wins
+                }
+            }
+        };
+
+        if take_remote {
+            let remote_record = &remote[&key];
+            let remote_hash = value_hash(remote_record.value.as_ref());
+            if value_hash(local_value) != remote_hash {
+                result
+                    .apply
+                    .insert(key.clone(), remote_record.value.clone());
+            }
+            state.records.insert(
+                key,
+                RecordState {
+                    hash: remote_hash.clone(),
+                    modified_at: remote_record.modified_at,
+                    device_id: remote_record.device_id.clone(),
+                    synced_hash: Some(remote_hash),
+                },
+            );
+        } else {
+            let record = state.records.get_mut(&key).unwrap();
+            record.synced_hash = Some(record.hash.clone());
+            result.remote.insert(
+                key,
+                SyncRecord {
+                    modified_at: record.modified_at,
+                    device_id: record.device_id.clone(),
+                    value: local_value.cloned(),
+                },
+            );
+        }
+    }
+
+    state.last_synced_at = Some(now);
+    result.upload = result.remote != *remote;
+    result
+}
+
+/// A remote change checked and ready to apply locally.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SyncChange {
+    /// `None` removes the service from the list.
+    Service { id: String, value: Option<Value> },
+    ServiceSettings {
+        id: String,
+        settings: ServiceSettings,
+    },
+    UserScripts {
+        id: String,
+        scripts: Vec<UserScript>,
+    },
+}
+
+/// Checks every remote value before anything is applied, so a bad record fails the whole sync.
+/// Records of kinds this version does not know are left alone.
+pub(crate) fn parse_changes(
+    apply: &BTreeMap<String, Option<Value>>,
+) -> Result<Vec<SyncChange>, String> {
+    let mut changes = Vec::new();
+    for (key, value) in apply {
+        let Some((kind, id)) = parse_record_key(key) else {
+            continue;
+        };
+        if !is_safe_file_stem(id) {
+            return Err(format!("Invalid synced record {}", key));
+        }
+        let invalid = |e: serde_json::Error| format!("Invalid synced record {}: {}", key, e);
+        let id = id.to_string();
+        changes.push(match kind {
+            RecordKind::Service => {
+                if value.as_ref().is_some_and(|value| {
+                    value.get("id").and_then(Value::as_str) != Some(id.as_str())
+                }) {
+                    return Err(format!("Invalid synced record {}", key));
+                }
+                SyncChange::Service {
+                    id,
+                    value: value.clone(),
+                }
+            }
+            RecordKind::ServiceSettings => SyncChange::ServiceSettings {
+                id,
+                settings: value
+                    .clone()
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .map_err(invalid)?
+                    .unwrap_or_default(),
+            },
+            RecordKind::UserScripts => SyncChange::UserScripts {
+                id,
+                scripts: value
+                    .clone()
+                    .map(serde_json::from_value)
+                    .transpose()
+                    .map_err(invalid)?
+                    .unwrap_or_default(),
+            },
+        });
+    }
+    Ok(changes)
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncReport {
+    /// Unix time in milliseconds.
+    pub synced_at: u64,
+    pub uploaded: bool,
+    /// Remote changes applied on this device.
+    pub applied: usize,
+    pub conflicts: Vec<SyncConflict>,
+    /// The merged service list when it changed, for the frontend to adopt.
+    pub services: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncStatus {
+    pub settings: SyncSettings,
+    pub has_webdav_password: bool,
+    pub has_passphrase: bool,
+    pub last_synced_at: Option<u64>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncFile {
+    format: String,
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    records: Option<BTreeMap<String, SyncRecord>>,
+    /// The records, sealed with the sync passphrase instead of stored in `records`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sealed: Option<PasswordSealed>,
+}
+
+pub(crate) fn encode_sync_file(
+    records: &BTreeMap<String, SyncRecord>,
+    passphrase: Option<&str>,
+    params: KdfParams,
+) -> Result<Vec<u8>, String> {
+    let (records, sealed) = match passphrase {
+        Some(passphrase) => {
+            let plaintext = serde_json::to_vec(records).map_err(|e| e.to_string())?;
+            (
+                None,
+                Some(secrets::seal_with_password(passphrase, &plaintext, params)?),
+            )
+        }
+        None => (Some(records.clone()), None),
+    };
+    serde_json::to_vec_pretty(&SyncFile {
+        format: SYNC_FORMAT.to_string(),
+        version: SYNC_VERSION,
+        records,
+        sealed,
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the records and whether they were sealed.
+pub(crate) fn decode_sync_file(
+    bytes: &[u8],
+    passphrase: Option<&str>,
+) -> Result<(BTreeMap<String, SyncRecord>, bool), String> {
+    let file: SyncFile =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid sync file: {}", e))?;
+    if file.format != SYNC_FORMAT {
+        return Err("Not an AnyChat sync file".to_string());
+    }
+    if file.version > SYNC_VERSION {
+        return Err("The sync file was written by a newer AnyChat; update this one".to_string());
+    }
+
+    match (file.sealed, file.records) {
+        (Some(sealed), _) => {
+            let passphrase = passphrase
+                .ok_or("The sync file is encrypted; set the sync passphrase to read it")?;
+            let plaintext = secrets::open_with_password(passphrase, &sealed)
+                .map_err(|_| "Wrong sync passphrase or corrupted sync file".to_string())?;
+            let records = serde_json::from_slice(&plaintext)
+                .map_err(|e| format!("Invalid sync file: {}", e))?;
+            Ok((records, true))
+        }
+        (None, records) => Ok((records.unwrap_or_default(), false)),
+    }
+}
+
+/// The sync file as last read, to detect writes from other devices in between.
+#[derive(Debug, Clone)]
+pub(crate) struct RemoteSnapshot {
+    pub bytes: Vec<u8>,
+    etag: Option<String>,
+}
+
+pub(crate) enum SyncBackend {
+    Folder(PathBuf),
+    WebDav {
+        client: reqwest::Client,
+        /// Always ends with `/`.
+        collection_url: String,
+        username: String,
+        password: Option<String>,
+    },
+}
+
+impl SyncBackend {
+    pub fn new(
+        target: &SyncTarget,
+        client: reqwest::Client,
+        password: Option<String>,
+    ) -> Result<Self, String> {
+        target.validate()?;
+        Ok(match target {
+            SyncTarget::Folder { path } => SyncBackend::Folder(PathBuf::from(path)),
+            SyncTarget::WebDav { url, username } => SyncBackend::WebDav {
+                client,
+                collection_url: format!("{}/", url.trim_end_matches('/')),
+                username: username.clone(),
+                password,
+            },
+        })
+    }
+
+    fn request(&self, method: &str) -> Option<reqwest::RequestBuilder> {
+        let SyncBackend::WebDav {
+            client,
+            collection_url,
+            username,
+            password,
+        } = self
+        else {
+            return None;
+        };
+        let (method, url) = match method {
+            "MKCOL" => (
+                reqwest::Method::from_bytes(b"MKCOL").unwrap(),
+                collection_url.clone(),
+            ),
+            method => (
+                method.parse().unwrap(),
+                format!("{}{}", collection_url, SYNC_FILE),
+            ),
+        };
+        let request = client.request(method, url);
+        Some(if username.is_empty() {
+            request
+        } else {
+            request.basic_auth(username, password.as_deref())
+        })
+    }
+
+    pub async fn read(&self) -> Result<Option<RemoteSnapshot>, String> {
+        if let SyncBackend::Folder(dir) = self {
+            let path = dir.join(SYNC_FILE);
+            return match std::fs::read(&path) {
+                Ok(bytes) => Ok(Some(RemoteSnapshot { bytes, etag: None })),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+            };
+        }
+
+        let response = self
+            .request("GET")
+            .unwrap()
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV request failed: {}", e))?;
+        match response.status().as_u16() {
+            404 => Ok(None),
+            200 => {
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+                Ok(Some(RemoteSnapshot {
+                    bytes: bytes.to_vec(),
+                    etag,
+                }))
+            }
+            401 | 403 => Err("The WebDAV server rejected the credentials".to_string()),
+            status => Err(format!("WebDAV GET returned HTTP {}", status)),
+        }
+    }
+
+    /// Replaces the sync file unless it changed since `previous` was read; returns false then.
+    pub async fn write(
+        &self,
+        bytes: Vec<u8>,
+        previous: Option<&RemoteSnapshot>,
+    ) -> Result<bool, String> {
+        if let SyncBackend::Folder(dir) = self {
+            let path = dir.join(SYNC_FILE);
+            let current = std::fs::read(&path).ok();
+            if current.as_deref() != previous.map(|snapshot| snapshot.bytes.as_slice()) {
+                return Ok(false);
+            }
+            crate::storage::write_atomic(&path, &bytes)?;
+            return Ok(true);
+        }
+
+        for attempt in 0..2 {
+            let mut request = self.request("PUT").unwrap().body(bytes.clone());
+            request = match previous {
+                Some(RemoteSnapshot {
+                    etag: Some(etag), ..
+                }) => request.header(reqwest::header::IF_MATCH, etag),
+                Some(_) => request,
+                None => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+            };
+            let status = request
+                .send()
+                .await
+                .map_err(|e| format!("WebDAV request failed: {}", e))?
+                .status()
+                .as_u16();
+            match status {
+                200..=299 => return Ok(true),
+                412 => return Ok(false),
+                // The collection does not exist yet.
+                409 if attempt == 0 => {
+                    let status = self
+                        .request("MKCOL")
+                        .unwrap()
+                        .send()
+                        .await
+                        .map_err(|e| format!("WebDAV request failed: {}", e))?
+                        .status()
+                        .as_u16();
+                    if !matches!(status, 200..=299 | 405) {
+                        return Err(format!("WebDAV MKCOL returned HTTP {}", status));
+                    }
+                }
+                401 | 403 => return Err("The WebDAV server rejected the credentials".to_string()),
+                status => return Err(format!("WebDAV PUT returned HTTP {}", status)),
+            }
+        }
+        Err("WebDAV PUT failed after creating the collection".to_string())
+    }
+}
+
+/// Appends to the conflict log, keeping the most recent entries.
+pub(crate) fn log_conflicts(
+    log: &mut Vec<SyncConflict>,
+    conflicts: impl IntoIterator<Item = SyncConflict>,
+) {
+    log.extend(conflicts);
+    let excess = log.len().saturating_sub(MAX_LOGGED_CONFLICTS);
+    log.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_sync_file, encode_sync_file, merge, record_key, ConflictWinner, RecordKind,
+        SyncBackend, SyncRecord, SyncState, SyncTarget,
+    };
+    use crate::secrets::KdfParams;
+    use crate::test_support::spawn_webdav_stub;
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    const TEST_KDF: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn items(entries: &[(&str, Value)]) -> BTreeMap<String, Value> {
+        entries
+            .iter()
+            .map(|(id, value)| (record_key(RecordKind::Service, id), value.clone()))
+            .collect()
+    }
+
+    fn device(id: &str) -> SyncState {
+        SyncState {
+            device_id: id.to_string(),
+            ..SyncState::default()
+        }
+    }
+
+    #[test]
+    fn changes_flow_between_devices_and_conflicts_go_to_the_newer_side() {
+        let mut laptop = device("laptop");
+        let mut desktop = device("desktop");
+        let chatgpt = record_key(RecordKind::Service, "chatgpt");
+
+        // The first device seeds the remote.
+        let seeded = merge(
+            &mut laptop,
+            &items(&[
+                ("chatgpt", json!({"order": 0})),
+                ("custom-1", json!({"order": 1})),
+            ]),
+            &BTreeMap::new(),
+            100,
+        );
+        assert!(seeded.upload && seeded.apply.is_empty());
+        let remote = seeded.remote;
+
+        // A new device adopts the remote instead of pushing its defaults.
+        let joined = merge(
+            &mut desktop,
+            &items(&[("chatgpt", json!({"order": 5}))]),
+            &remote,
+            200,
+        );
+        assert_eq!(joined.apply[&chatgpt], Some(json!({"order": 0})));
+        assert_eq!(
+            joined.apply[&record_key(RecordKind::Service, "custom-1")],
+            Some(json!({"order": 1}))
+        );
+        assert!(!joined.upload && joined.conflicts.is_empty());
+
+        // One-sided edits and deletions travel without conflicts.
+        let edited = merge(
+            &mut desktop,
+            &items(&[("chatgpt", json!({"order": 1}))]),
+            &remote,
+            300,
+        );
+        assert!(edited.upload && edited.conflicts.is_empty());
+        assert_eq!(
+            edited.remote[&record_key(RecordKind::Service, "custom-1")].value,
+            None
+        );
+        let remote = edited.remote;
+        let pulled = merge(
+            &mut laptop,
+            &items(&[
+                ("chatgpt", json!({"order": 0})),
+                ("custom-1", json!({"order": 1})),
+            ]),
+            &remote,
+            400,
+        );
+        assert_eq!(pulled.apply[&chatgpt], Some(json!({"order": 1})));
+        assert_eq!(
+            pulled.apply[&record_key(RecordKind::Service, "custom-1")],
+            None
+        );
+
+        // Both edit the same record: the later change wins and the other is logged.
+        let laptop_edit = merge(
+            &mut laptop,
+            &items(&[("chatgpt", json!({"order": 2}))]),
+            &remote,
+            500,
+        );
+        let conflicted = merge(
+            &mut desktop,
+            &items(&[("chatgpt", json!({"order": 3}))]),
+            &laptop_edit.remote,
+            600,
+        );
+        assert_eq!(conflicted.conflicts.len(), 1);
+        assert_eq!(conflicted.conflicts[0].winner, ConflictWinner::Local);
+        assert_eq!(conflicted.conflicts[0].discarded, Some(json!({"order": 2})));
+        assert_eq!(
+            conflicted.remote[&chatgpt],
+            SyncRecord {
+                modified_at: 600,
+                device_id: "desktop".to_string(),
+                value: Some(json!({"order": 3})),
+            }
+        );
+
+        // Syncing again with nothing new changes nothing.
+        let settled = merge(
+            &mut desktop,
+            &items(&[("chatgpt", json!({"order": 3}))]),
+            &conflicted.remote,
+            700,
+        );
+        assert!(!settled.upload && settled.apply.is_empty() && settled.conflicts.is_empty());
+    }
+
+    #[test]
+    fn sync_file_is_sealed_with_the_passphrase() {
+        let records = BTreeMap::from([(
+            record_key(RecordKind::UserScripts, "chatgpt"),
+            SyncRecord {
+                modified_at: 1,
+                device_id: "laptop".to_string(),
+                value: Some(json!([{"name": "wide"}])),
+            },
+        )]);
+
+        let plain = encode_sync_file(&records, None, TEST_KDF).unwrap();
+        assert_eq!(
+            decode_sync_file(&plain, None).unwrap(),
+            (records.clone(), false)
+        );
+
+        let sealed = encode_sync_file(&records, Some("correct horse"), TEST_KDF).unwrap();
+        assert!(!String::from_utf8_lossy(&sealed).contains("wide"));
+        assert_eq!(
+            decode_sync_file(&sealed, Some("correct horse")).unwrap(),
+            (records, true)
+        );
+        assert!(decode_sync_file(&sealed, None).is_err());
+        assert!(decode_sync_file(&sealed, Some("wrong horse")).is_err());
+        assert!(decode_sync_file(br#"{"format":"anychat-sync","version":99}"#, None).is_err());
+    }
+
+    #[test]
+    fn webdav_backend_creates_the_collection_and_detects_concurrent_writes() {
+        let server = spawn_webdav_stub();
+        let target = SyncTarget::WebDav {
+            url: format!("{}/anychat", server.base_url()),
+            username: "me".to_string(),
+        };
+        let backend =
+            SyncBackend::new(&target, reqwest::Client::new(), Some("secret".to_string())).unwrap();
+
+        tauri::async_runtime::block_on(async {
+            assert!(backend.read().await.unwrap().is_none());
+            assert!(backend.write(b"one".to_vec(), None).await.unwrap());
+            let first = backend.read().await.unwrap().unwrap();
+            assert_eq!(first.bytes, b"one");
+
+            assert!(backend.write(b"two".to_vec(), Some(&first)).await.unwrap());
+            // Another device wrote since `first` was read.
+            assert!(!backend
+                .write(b"three".to_vec(), Some(&first))
+                .await
+                .unwrap());
+            assert!(!backend.write(b"three".to_vec(), None).await.unwrap());
+            assert_eq!(backend.read().await.unwrap().unwrap().bytes, b"two");
+        });
+
+        let requests = server.requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .any(|request| request.method == "MKCOL" && request.path == "/anychat/"));
+        assert!(requests.iter().all(|request| request
+            .header("authorization")
+            .is_some_and(|value| value.starts_with("Basic "))));
+    }
+
+    #[test]
+    fn folder_backend_detects_concurrent_writes() {
+        let dir = std::env::temp_dir().join(format!("anychat-sync-folder-{}", std::process::id()));
+        let target = SyncTarget::Folder {
+            path: dir.display().to_string(),
+        };
+        let backend = SyncBackend::new(&target, reqwest::Client::new(), None).unwrap();
+
+        tauri::async_runtime::block_on(async {
+            assert!(backend.write(b"one".to_vec(), None).await.unwrap());
+            let first = backend.read().await.unwrap().unwrap();
+            assert!(!backend.write(b"two".to_vec(), None).await.unwrap());
+            assert!(backend.write(b"two".to_vec(), Some(&first)).await.unwrap());
+            assert!(!backend
+                .write(b"three".to_vec(), Some(&first))
+                .await
+                .unwrap());
+        });
+        let _ = std::fs::remove_dir_all(dir);
+
+        assert!(SyncTarget::Folder {
+            path: "relative".to_string()
+        }
+        .validate()
+        .is_err());
+        assert!(SyncTarget::WebDav {
+            url: "http://dav.example.com/".to_string(),
+            username: String::new(),
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Helpers shared by unit tests that talk to a local HTTP stand-in instead of real services.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone)]
+pub(crate) struct StubResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl StubResponse {
+    pub fn json(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    pub fn event_stream(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/event-stream",
+            body: body.to_string(),
+        }
+    }
+
+    pub fn status(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: body.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StubRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl StubRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) struct StubServer {
+    pub port: u16,
+    pub requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+impl StubServer {
+    pub fn base_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+}
+
+fn read_request(stream: &TcpStream) -> StubRequest {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    let _ = reader.read_line(&mut request_line);
+
+    let mut request = StubRequest::default();
+    let mut parts = request_line.split_whitespace();
+    request.method = parts.next().unwrap_or_default().to_string();
+    request.path = parts.next().unwrap_or_default().to_string();
+
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+        if let Some((key, value)) = line.trim_end().split_once(':') {
+            request
+                .headers
+                .push((key.trim().to_string(), value.trim().to_string()));
+        }
+        line.clear();
+    }
+
+    let content_length = request
+        .header("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; content_length];
+    let _ = reader.read_exact(&mut body);
+    request.body = String::from_utf8_lossy(&body).into_owned();
+    request
+}
+
+fn write_response(mut stream: TcpStream, response: &StubResponse, extra_headers: &str) {
+    let _ = stream.write_all(
+        format!(
+            "HTTP/1.1 {} Stub\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+            response.status,
+            response.content_type,
+            response.body.len(),
+            extra_headers,
+            response.body
+        )
+        .as_bytes(),
+    );
+}
+
+/// Minimal HTTP/1.1 server answering `METHOD path` or `path` routes with canned responses;
+/// anything else is a 404. Every request is recorded for assertions.
+pub(crate) fn spawn_stub_server(routes: Vec<(&'static str, StubResponse)>) -> StubServer {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded_requests = requests.clone();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let request = read_request(&stream);
+            let method_route = format!("{} {}", request.method, request.path);
+            let response = routes
+                .iter()
+                .find(|(route, _)| *route == request.path || *route == method_route)
+                .map(|(_, response)| response.clone())
+                .unwrap_or_else(|| StubResponse::status(404, ""));
+            recorded_requests.lock().unwrap().push(request);
+            write_response(stream, &response, "");
+        }
+    });
+
+    StubServer { port, requests }
+}
+
+/// In-memory WebDAV stand-in supporting `GET`, `PUT` and `MKCOL`. Files get a numeric `ETag`
+/// and `PUT` honours `If-Match` and `If-None-Match: *`. A `PUT` into a collection that was not
+/// created first fails with 409, like real servers do. `/` always exists.
+pub(crate) fn spawn_webdav_stub() -> StubServer {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded_requests = requests.clone();
+
+    std::thread::spawn(move || {
+        let mut collections = HashSet::from(["/".to_string()]);
+        let mut files: HashMap<String, (u64, String)> = HashMap::new();
+        let mut next_etag = 1u64;
+
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let request = read_request(&stream);
+            let path = request.path.clone();
+            let parent = match path.trim_end_matches('/').rsplit_once('/') {
+                Some(("", _)) | None => "/".to_string(),
+                Some((parent, _)) => format!("{}/", parent),
+            };
+            let current_etag = files.get(&path).map(|(etag, _)| format!("\"{}\"", etag));
+
+            let (response, etag) = match request.method.as_str() {
+                "GET" => match files.get(&path) {
+                    Some((_, body)) => (
+                        StubResponse {
+                            status: 200,
+                            content_type: "application/octet-stream",
+                            body: body.clone(),
+                        },
+                        current_etag,
+                    ),
+                    None => (StubResponse::status(404, ""), None),
+                },
+                "PUT" if !collections.contains(&parent) => (StubResponse::status(409, ""), None),
+                "PUT"
+                    if request
+                        .header("if-match")
+                        .is_some_and(|expected| Some(expected) != current_etag.as_deref())
+                        || (request.header("if-none-match") == Some("*")
+                            && current_etag.is_some()) =>
+                {
+                    (StubResponse::status(412, ""), None)
+                }
+                "PUT" => {
+                    let status = if current_etag.is_some() { 204 } else { 201 };
+                    files.insert(path.clone(), (next_etag, request.body.clone()));
+                    next_etag += 1;
+                    let etag = format!("\"{}\"", next_etag - 1);
+                    (StubResponse::status(status, ""), Some(etag))
+                }
+                "MKCOL" if collections.contains(&format!("{}/", path.trim_end_matches('/'))) => {
+                    (StubResponse::status(405, ""), None)
+                }
+                "MKCOL" if !collections.contains(&parent) => (StubResponse::status(409, ""), None),
+                "MKCOL" => {
+                    collections.insert(format!("{}/", path.trim_end_matches('/')));
+                    (StubResponse::status(201, ""), None)
+                }
+                _ => (StubResponse::status(405, ""), None),
+            };
+            recorded_requests.lock().unwrap().push(request);
+            let extra_headers = etag
+                .map(|etag| format!("ETag: {}\r\n", etag))
+                .unwrap_or_default();
+            write_response(stream, &response, &extra_headers);
+        }
+    });
+
+    StubServer { port, requests }
+}
@@ -0,0 +1,307 @@
+//! Forced light/dark appearance for service webviews.
+//!
+//! Pages only see the OS color scheme, so the override is emulated inside the page: scripted
+//! `matchMedia('(prefers-color-scheme: …)')` queries are answered with the forced scheme and
+//! `prefers-color-scheme` media rules in readable stylesheets are rewritten. Pages that stay
+//! light under a forced dark scheme get an inversion filter instead.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AppTheme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ServiceTheme {
+    #[default]
+    FollowApp,
+    ForceLight,
+    ForceDark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }
+    }
+}
+
+/// `None` leaves the page on the OS scheme.
+pub(crate) fn effective_scheme(service: ServiceTheme, app: AppTheme) -> Option<ColorScheme> {
+    match (service, app) {
+        (ServiceTheme::ForceLight, _) => Some(ColorScheme::Light),
+        (ServiceTheme::ForceDark, _) => Some(ColorScheme::Dark),
+        (ServiceTheme::FollowApp, AppTheme::Light) => Some(ColorScheme::Light),
+        (ServiceTheme::FollowApp, AppTheme::Dark) => Some(ColorScheme::Dark),
+        (ServiceTheme::FollowApp, AppTheme::System) => None,
+    }
+}
+
+fn scheme_literal(scheme: Option<ColorScheme>) -> &'static str {
+    match scheme {
+        Some(ColorScheme::Light) => "'light'",
+        Some(ColorScheme::Dark) => "'dark'",
+        None => "null",
+    }
+}
+
+/// Installs `window.__anychatSetTheme` and applies the scheme the webview was created with,
+/// unless a later change in the same tab was remembered in `sessionStorage`.
+pub(crate) fn initialization_script(scheme: Option<ColorScheme>) -> String {
+    format!(
+        "{}\n(function() {{ var mode = {}; try {{ var stored = sessionStorage.getItem('__anychatTheme'); if (stored) mode = stored === 'system' ? null : stored; }} catch (_) {{}} window.__anychatSetTheme(mode, false); }})();",
+        THEME_SCRIPT,
+        scheme_literal(scheme)
+    )
+}
+
+/// Switches a loaded page to `scheme` without reloading it, installing the emulation first if
+/// the webview was created without an override.
+pub(crate) fn update_script(scheme: Option<ColorScheme>) -> String {
+    match scheme {
+        Some(_) => format!(
+            "{}\nwindow.__anychatSetTheme({}, true);",
+            THEME_SCRIPT,
+            scheme_literal(scheme)
+        ),
+        None => "if (window.__anychatSetTheme) window.__anychatSetTheme(null, true);".to_string(),
+    }
+}
+
+pub(crate) fn scheme_name(scheme: Option<ColorScheme>) -> &'static str {
+    scheme.map_or("system", ColorScheme::as_str)
+}
+
+const THEME_SCRIPT: &str = r#"
+(function() {
+    if (window.__anychatSetTheme) return;
+
+    var SCHEME_PATTERN = /\(\s*prefers-color-scheme\s*:\s*(dark|light)\s*\)/gi;
+    var SCHEME_QUERY = /\(\s*prefers-color-scheme\s*:\s*(dark|light)\s*\)/i;
+    var ALWAYS = '(min-width: 0px)';
+    var NEVER = '(max-width: -1px)';
+    var INVERT_STYLE_ID = 'anychat-theme-invert';
+    var mode = null;
+    var originals = new WeakMap();
+    var nativeMatchMedia = window.matchMedia.bind(window);
+    var emulated = [];
+
+    function rewrite(text) {
+        if (!mode) return text;
+        return text.replace(SCHEME_PATTERN, function(_, scheme) {
+            return scheme.toLowerCase() === mode ? ALWAYS : NEVER;
+        });
+    }
+
+    function rewriteRules(rules) {
+        for (var i = 0; i < rules.length; i++) {
+            var rule = rules[i];
+            if (rule.media && rule.cssRules) {
+                var original = originals.has(rule) ? originals.get(rule) : rule.media.mediaText;
+                if (SCHEME_QUERY.test(original)) {
+                    originals.set(rule, original);
+                    rule.media.mediaText = rewrite(original);
+                }
+            }
+            if (rule.cssRules) rewriteRules(rule.cssRules);
+        }
+    }
+
+    function rewriteStyleSheets() {
+        for (var i = 0; i < document.styleSheets.length; i++) {
+            try {
+                rewriteRules(document.styleSheets[i].cssRules);
+            } catch (_) {
+                // Cross-origin stylesheet without CORS; its rules cannot be read.
+            }
+        }
+
+        var elements = document.querySelectorAll('link[media], style[media], source[media]');
+        for (var j = 0; j < elements.length; j++) {
+            var element = elements[j];
+            if (!element.hasAttribute('data-anychat-media')) {
+                var media = element.getAttribute('media');
+                if (!SCHEME_QUERY.test(media)) continue;
+                element.setAttribute('data-anychat-media', media);
+            }
+            element.setAttribute('media', rewrite(element.getAttribute('data-anychat-media')));
+        }
+    }
+
+    function schemeMatches(scheme, nativeList) {
+        return mode ? scheme === mode : nativeList.matches;
+    }
+
+    window.matchMedia = function(query) {
+        var nativeList = nativeMatchMedia(query);
+        var match = SCHEME_QUERY.exec(String(query));
+        if (!match) return nativeList;
+
+        var scheme = match[1].toLowerCase();
+        var listeners = [];
+        var list = {
+            media: nativeList.media,
+            onchange: null,
+            get matches() { return schemeMatches(scheme, nativeList); },
+            addListener: function(listener) { if (listener) listeners.push(listener); },
+            removeListener: function(listener) {
+                var index = listeners.indexOf(listener);
+                if (index !== -1) listeners.splice(index, 1);
+            },
+            addEventListener: function(type, listener) { if (type === 'change') list.addListener(listener); },
+            removeEventListener: function(type, listener) { if (type === 'change') list.removeListener(listener); },
+            dispatchEvent: function() { return true; },
+        };
+        var notify = function() {
+            var event = { matches: list.matches, media: list.media };
+            if (typeof list.onchange === 'function') list.onchange(event);
+            listeners.slice().forEach(function(listener) {
+                if (typeof listener === 'function') listener(event);
+                else if (listener && typeof listener.handleEvent === 'function') listener.handleEvent(event);
+            });
+        };
+        nativeList.addListener(function() { if (!mode) notify(); });
+        emulated.push(notify);
+        return list;
+    };
+
+    function luminance(color) {
+        var parts = color.match(/[\d.]+/g);
+        if (!parts || parts.length < 3 || (parts.length > 3 && Number(parts[3]) === 0)) return null;
+        return (0.2126 * parts[0] + 0.7152 * parts[1] + 0.0722 * parts[2]) / 255;
+    }
+
+    function pageLooksLight() {
+        var candidates = [document.body, document.documentElement];
+        for (var i = 0; i < candidates.length; i++) {
+            if (!candidates[i]) continue;
+            var value = luminance(getComputedStyle(candidates[i]).backgroundColor);
+            if (value !== null) return value > 0.6;
+        }
+        return true;
+    }
+
+    function updateInversion() {
+        var existing = document.getElementById(INVERT_STYLE_ID);
+        if (existing) existing.remove();
+        if (mode !== 'dark' || !document.documentElement || !pageLooksLight()) return;
+
+        var style = document.createElement('style');
+        style.id = INVERT_STYLE_ID;
+        style.textContent = 'html { filter: invert(1) hue-rotate(180deg) !important; background: #fff; } ' +
+            'img, video, picture, canvas, iframe, svg image, [style*="background-image"] { filter: invert(1) hue-rotate(180deg) !important; }';
+        (document.head || document.documentElement).appendChild(style);
+    }
+
+    function apply() {
+        if (document.documentElement) {
+            document.documentElement.style.colorScheme = mode || '';
+        }
+        rewriteStyleSheets();
+    }
+
+    var observer = new MutationObserver(function(mutations) {
+        for (var i = 0; i < mutations.length; i++) {
+            var nodes = mutations[i].addedNodes;
+            for (var j = 0; j < nodes.length; j++) {
+                var node = nodes[j];
+                if (node.tagName === 'LINK') node.addEventListener('load', rewriteStyleSheets, { once: true });
+                if (node.tagName === 'STYLE' || node.tagName === 'LINK') {
+                    rewriteStyleSheets();
+                    return;
+                }
+            }
+        }
+    });
+
+    function start() {
+        observer.observe(document.documentElement, { childList: true, subtree: true });
+        apply();
+    }
+
+    if (document.documentElement) start();
+    else document.addEventListener('DOMContentLoaded', start, { once: true });
+    document.addEventListener('DOMContentLoaded', apply, { once: true });
+    window.addEventListener('load', function() { apply(); updateInversion(); }, { once: true });
+
+    window.__anychatSetTheme = function(next, remember) {
+        mode = next === 'dark' || next === 'light' ? next : null;
+        if (remember) {
+            try { sessionStorage.setItem('__anychatTheme', mode || 'system'); } catch (_) {}
+        }
+        apply();
+        var existing = document.getElementById(INVERT_STYLE_ID);
+        if (existing) existing.remove();
+        emulated.forEach(function(notify) { notify(); });
+        if (document.readyState === 'complete') {
+            // Let the page react to the scheme change before judging its background.
+            setTimeout(updateInversion, 300);
+        }
+    };
+})();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        effective_scheme, initialization_script, scheme_name, update_script, AppTheme, ColorScheme,
+        ServiceTheme,
+    };
+
+    #[test]
+    fn forced_themes_win_and_follow_app_mirrors_the_app_theme() {
+        assert_eq!(
+            effective_scheme(ServiceTheme::ForceDark, AppTheme::Light),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            effective_scheme(ServiceTheme::ForceLight, AppTheme::System),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(
+            effective_scheme(ServiceTheme::FollowApp, AppTheme::Dark),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            effective_scheme(ServiceTheme::FollowApp, AppTheme::System),
+            None
+        );
+    }
+
+    #[test]
+    fn scripts_carry_the_scheme_as_a_js_literal() {
+        assert!(initialization_script(Some(ColorScheme::Dark)).contains("var mode = 'dark';"));
+        assert!(initialization_script(None).contains("var mode = null;"));
+        assert!(update_script(Some(ColorScheme::Light))
+            .ends_with("window.__anychatSetTheme('light', true);"));
+        assert_eq!(
+            update_script(None),
+            "if (window.__anychatSetTheme) window.__anychatSetTheme(null, true);"
+        );
+        assert_eq!(scheme_name(None), "system");
+    }
+
+    #[test]
+    fn service_theme_serializes_in_camel_case() {
+        assert_eq!(
+            serde_json::to_value(ServiceTheme::ForceDark).unwrap(),
+            "forceDark"
+        );
+        assert_eq!(
+            serde_json::from_str::<AppTheme>(r#""system""#).unwrap(),
+            AppTheme::System
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use crate::pages::{escape_html, render_error_page};
+use sha2::{Digest, Sha256};
+
+/// What a service webview does when a page fails certificate validation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub(crate) enum TlsErrorPolicy {
+    /// Show an explanation page instead of the site.
+    #[default]
+    Block,
+    /// Accept exactly one certificate, identified by its SHA-256 fingerprint.
+    AllowPinned { fingerprint: String },
+}
+
+/// Only WebKitGTK reports the failing certificate, so pins cannot be honoured elsewhere.
+pub(crate) const CERTIFICATE_PINNING_SUPPORTED: bool = cfg!(target_os = "linux");
+
+impl TlsErrorPolicy {
+    /// Normalizes a pinned fingerprint, and rejects pins where they would be silently ignored.
+    pub fn validated(self, pinning_supported: bool) -> Result<Self, String> {
+        match self {
+            TlsErrorPolicy::Block => Ok(TlsErrorPolicy::Block),
+            TlsErrorPolicy::AllowPinned { .. } if !pinning_supported => Err(
+                "Pinning a certificate is only supported on Linux; this platform always shows its native certificate error page"
+                    .to_string(),
+            ),
+            TlsErrorPolicy::AllowPinned { fingerprint } => Ok(TlsErrorPolicy::AllowPinned {
+                fingerprint: normalize_fingerprint(&fingerprint)?,
+            }),
+        }
+    }
+
+    pub fn allows(&self, certificate_fingerprint: &str) -> bool {
+        match self {
+            TlsErrorPolicy::Block => false,
+            TlsErrorPolicy::AllowPinned { fingerprint } => normalize_fingerprint(fingerprint)
+                .is_ok_and(|pinned| pinned == certificate_fingerprint),
+        }
+    }
+}
+
+/// Accepts `AB:CD:..`, `abcd..` or `sha256/AB:CD..` and returns the canonical `AB:CD:..` form.
+pub(crate) fn normalize_fingerprint(value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    let without_prefix = trimmed
+        .strip_prefix("sha256/")
+        .or_else(|| trimmed.strip_prefix("SHA256/"))
+        .unwrap_or(trimmed);
+    let hex_digits = without_prefix
+        .chars()
+        .filter(|ch| *ch != ':' && !ch.is_whitespace())
+        .collect::<String>();
+
+    if hex_digits.len() != 64 || !hex_digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(format!("Invalid SHA-256 fingerprint: {}", value));
+    }
+
+    Ok(hex_digits
+        .to_ascii_uppercase()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+pub(crate) fn certificate_fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub(crate) fn tls_error_page_html(host: &str, reasons: &[String], fingerprint: &str) -> String {
+    let extra_html = if fingerprint.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<p>If this is an internal host you trust, pin this certificate in the service's TLS settings:</p><p><code>{}</code></p>",
+            escape_html(fingerprint)
+        )
+    };
+
+    render_error_page(
+        "This connection is not trusted",
+        &format!(
+            "AnyChat blocked {} because its certificate could not be verified.",
+            if host.is_empty() { "this page" } else { host }
+        ),
+        reasons,
+        &extra_html,
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn describe_tls_errors(errors: webkit2gtk::gio::TlsCertificateFlags) -> Vec<String> {
+    use webkit2gtk::gio::TlsCertificateFlags;
+
+    let descriptions = [
+        (
+            TlsCertificateFlags::UNKNOWN_CA,
+            "The certificate is signed by an unknown authority.",
+        ),
+        (
+            TlsCertificateFlags::BAD_IDENTITY,
+            "The certificate does not match the host name.",
+        ),
+        (
+            TlsCertificateFlags::NOT_ACTIVATED,
+            "The certificate is not valid yet.",
+        ),
+        (TlsCertificateFlags::EXPIRED, "The certificate has expired."),
+        (
+            TlsCertificateFlags::REVOKED,
+            "The certificate has been revoked.",
+        ),
+        (
+            TlsCertificateFlags::INSECURE,
+            "The certificate uses an insecure algorithm.",
+        ),
+        (
+            TlsCertificateFlags::GENERIC_ERROR,
+            "The certificate could not be validated.",
+        ),
+    ];
+
+    descriptions
+        .iter()
+        .filter(|(flag, _)| errors.contains(*flag))
+        .map(|(_, description)| description.to_string())
+        .collect()
+}
+
+/// Replaces WebKitGTK's silent blank page on certificate errors with the service's TLS policy.
+/// Other platforms keep their native error page.
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_tls_error_handler(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use tauri::Manager;
+    use webkit2gtk::gio::prelude::TlsCertificateExt;
+    use webkit2gtk::{WebContextExt, WebViewExt};
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        platform_webview
+            .inner()
+            .connect_load_failed_with_tls_errors(
+                move |webview, failing_uri, certificate, errors| {
+                    let fingerprint = certificate
+                        .certificate()
+                        .map(|der| certificate_fingerprint(&der))
+                        .unwrap_or_default();
+                    let host = tauri::Url::parse(failing_uri)
+                        .ok()
+                        .and_then(|url| url.host_str().map(str::to_string))
+                        .unwrap_or_default();
+                    let policy = app
+                        .state::<crate::AppState>()
+                        .service_registry
+                        .lock()
+                        .unwrap()
+                        .settings(&service_id)
+                        .tls_error_policy;
+
+                    if policy.allows(&fingerprint) {
+                        if let Some(context) = webview.context() {
+                            println!(
+                                "[AnyChat] Accepting pinned certificate for {} ({})",
+                                host, service_id
+                            );
+                            context.allow_tls_certificate_for_host(certificate, &host);
+                            webview.load_uri(failing_uri);
+                            return true;
+                        }
+                    }
+
+                    println!(
+                        "[AnyChat] Blocked TLS error for {} ({}): {:?}",
+                        failing_uri, service_id, errors
+                    );
+                    let html =
+                        tls_error_page_html(&host, &describe_tls_errors(errors), &fingerprint);
+                    webview.load_alternate_html(&html, failing_uri, None);
+                    true
+                },
+            );
+    });
+}
+
+/// A pin can still arrive here through sync or a backup made on Linux; it is logged, not applied.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_tls_error_handler(
+    app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use tauri::Manager;
+
+    let policy = app
+        .state::<crate::AppState>()
+        .service_registry
+        .lock()
+        .unwrap()
+        .settings(service_id)
+        .tls_error_policy;
+    if matches!(policy, TlsErrorPolicy::AllowPinned { .. }) {
+        println!(
+            "[AnyChat] Ignoring pinned certificate for {}: pinning is only supported on Linux",
+            service_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        certificate_fingerprint, normalize_fingerprint, tls_error_page_html, TlsErrorPolicy,
+    };
+
+    const FINGERPRINT: &str = "AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89";
+
+    #[test]
+    fn fingerprints_normalize_from_common_notations() {
+        assert_eq!(normalize_fingerprint(FINGERPRINT).unwrap(), FINGERPRINT);
+        assert_eq!(
+            normalize_fingerprint(&FINGERPRINT.replace(':', "").to_lowercase()).unwrap(),
+            FINGERPRINT
+        );
+        assert_eq!(
+            normalize_fingerprint(&format!("sha256/{}", FINGERPRINT)).unwrap(),
+            FINGERPRINT
+        );
+        assert!(normalize_fingerprint("AB:CD").is_err());
+        assert!(normalize_fingerprint(&FINGERPRINT.replace('A', "Z")).is_err());
+    }
+
+    #[test]
+    fn certificate_fingerprint_is_uppercase_colon_separated_sha256() {
+        assert_eq!(
+            certificate_fingerprint(b""),
+            "E3:B0:C4:42:98:FC:1C:14:9A:FB:F4:C8:99:6F:B9:24:27:AE:41:E4:64:9B:93:4C:A4:95:99:1B:78:52:B8:55"
+        );
+    }
+
+    #[test]
+    fn block_policy_never_allows_and_pinned_policy_allows_only_its_certificate() {
+        assert!(!TlsErrorPolicy::Block.allows(FINGERPRINT));
+
+        let pinned = TlsErrorPolicy::AllowPinned {
+            fingerprint: FINGERPRINT.replace(':', "").to_lowercase(),
+        };
+        assert!(pinned.allows(FINGERPRINT));
+        assert!(!pinned.allows(&certificate_fingerprint(b"other")));
+    }
+
+    #[test]
+    fn pins_are_normalized_where_supported_and_rejected_elsewhere() {
+        let pinned = || TlsErrorPolicy::AllowPinned {
+            fingerprint: FINGERPRINT.replace(':', "").to_lowercase(),
+        };
+        assert_eq!(
+            pinned().validated(true).unwrap(),
+            TlsErrorPolicy::AllowPinned {
+                fingerprint: FINGERPRINT.to_string()
+            }
+        );
+        assert!(pinned().validated(false).is_err());
+        assert_eq!(
+            TlsErrorPolicy::Block.validated(false).unwrap(),
+            TlsErrorPolicy::Block
+        );
+        assert!(TlsErrorPolicy::AllowPinned {
+            fingerprint: "AB:CD".to_string()
+        }
+        .validated(true)
+        .is_err());
+    }
+
+    #[test]
+    fn tls_policy_serializes_with_mode_tag() {
+        let json = serde_json::to_value(TlsErrorPolicy::AllowPinned {
+            fingerprint: FINGERPRINT.to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["mode"], "allowPinned");
+        assert_eq!(
+            serde_json::from_str::<TlsErrorPolicy>(r#"{"mode":"block"}"#).unwrap(),
+            TlsErrorPolicy::Block
+        );
+    }
+
+    #[test]
+    fn tls_error_page_explains_host_and_offers_fingerprint() {
+        let html = tls_error_page_html(
+            "chat.internal",
+            &["The certificate has expired.".to_string()],
+            FINGERPRINT,
+        );
+        assert!(html.contains("chat.internal"));
+        assert!(html.contains("The certificate has expired."));
+        assert!(html.contains(FINGERPRINT));
+    }
+}
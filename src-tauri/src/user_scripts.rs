@@ -0,0 +1,521 @@
+//! Per-service user scripts and stylesheets.
+//!
+//! Scripts are stored in `user_scripts/<service_id>.json`. They are baked into the webview as
+//! initialization scripts when it is created, and edits are pushed into the live page with
+//! `eval`. Because initialization scripts cannot be removed from a running webview, every file
+//! carries a revision: the live update stores it in `sessionStorage` and baked scripts from an
+//! older revision skip themselves on later navigations, leaving the page-load hook to apply the
+//! current set instead.
+
+use crate::storage::{is_safe_file_stem, Vault};
+use std::path::{Path, PathBuf};
+
+pub(crate) const USER_SCRIPTS_DIR: &str = "user_scripts";
+pub(crate) const USER_SCRIPTS_LOG: &str = "user_scripts.log";
+/// Page-supplied fields are cut to these lengths before they reach the log.
+const MAX_LOGGED_MESSAGE_CHARS: usize = 1000;
+const MAX_LOGGED_FIELD_CHARS: usize = 200;
+const REVISION_KEY: &str = "__anychatUserScripts";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum UserScriptKind {
+    #[default]
+    Script,
+    Style,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RunAt {
+    DocumentStart,
+    /// After `DOMContentLoaded`, like a userscript manager's `document-idle`.
+    #[default]
+    DocumentIdle,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UserScript {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub kind: UserScriptKind,
+    pub code: String,
+    /// URL globs where `*` matches anything, e.g. `https://chatgpt.com/c/*`. Empty matches all.
+    #[serde(default)]
+    pub matches: Vec<String>,
+    #[serde(default)]
+    pub run_at: RunAt,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct UserScriptSet {
+    pub revision: u64,
+    pub scripts: Vec<UserScript>,
+}
+
+fn scripts_path(data_dir: &Path, service_id: &str) -> Result<PathBuf, String> {
+    if !is_safe_file_stem(service_id) {
+        return Err(format!("Invalid service id: {}", service_id));
+    }
+
+    Ok(data_dir
+        .join(USER_SCRIPTS_DIR)
+        .join(format!("{}.json", service_id)))
+}
+
+pub(crate) fn load_scripts(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+) -> Result<UserScriptSet, String> {
+    crate::storage::read_json(vault, &scripts_path(data_dir, service_id)?)
+}
+
+fn validate_script(script: &UserScript) -> Result<(), String> {
+    if !is_safe_file_stem(&script.id) {
+        return Err(format!("Invalid script id: {}", script.id));
+    }
+    if script.name.trim().is_empty() {
+        return Err("Script name must not be empty".to_string());
+    }
+    if script
+        .matches
+        .iter()
+        .any(|pattern| pattern.trim().is_empty())
+    {
+        return Err("Match patterns must not be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Inserts or replaces `script` by id and bumps the revision. Returns the stored script and the
+/// new revision.
+pub(crate) fn save_script(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+    mut script: UserScript,
+) -> Result<(UserScript, u64), String> {
+    if script.id.is_empty() {
+        script.id = format!("script-{}", crate::native_chat::now_millis());
+    }
+    script.matches = script
+        .matches
+        .iter()
+        .map(|pattern| pattern.trim().to_string())
+        .collect();
+    validate_script(&script)?;
+
+    let path = scripts_path(data_dir, service_id)?;
+    let mut set: UserScriptSet = crate::storage::read_json(vault, &path)?;
+    match set.scripts.iter_mut().find(|stored| stored.id == script.id) {
+        Some(stored) => *stored = script.clone(),
+        None => set.scripts.push(script.clone()),
+    }
+    set.revision += 1;
+    crate::storage::write_json(vault, &path, &set)?;
+    Ok((script, set.revision))
+}
+
+/// Removes a script and returns the new revision, or `None` when it did not exist.
+pub(crate) fn delete_script(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+    script_id: &str,
+) -> Result<Option<u64>, String> {
+    let path = scripts_path(data_dir, service_id)?;
+    let mut set: UserScriptSet = crate::storage::read_json(vault, &path)?;
+    let count = set.scripts.len();
+    set.scripts.retain(|script| script.id != script_id);
+    if set.scripts.len() == count {
+        return Ok(None);
+    }
+
+    set.revision += 1;
+    crate::storage::write_json(vault, &path, &set)?;
+    Ok(Some(set.revision))
+}
+
+/// Every stored script set, keyed by service id.
+pub(crate) fn load_all_scripts(
+    data_dir: &Path,
+    vault: &Vault,
+) -> Result<Vec<(String, UserScriptSet)>, String> {
+    let dir = data_dir.join(USER_SCRIPTS_DIR);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut sets = Vec::new();
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        let Some(service_id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| is_safe_file_stem(stem))
+        else {
+            continue;
+        };
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            sets.push((
+                service_id.to_string(),
+                crate::storage::read_json(vault, &path)?,
+            ));
+        }
+    }
+    sets.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(sets)
+}
+
+/// Replaces a service's whole script list, e.g. with a synced copy, and returns the new revision.
+pub(crate) fn replace_scripts(
+    data_dir: &Path,
+    vault: &Vault,
+    service_id: &str,
+    scripts: Vec<UserScript>,
+) -> Result<u64, String> {
+    for script in &scripts {
+        validate_script(script)?;
+    }
+
+    let path = scripts_path(data_dir, service_id)?;
+    let mut set: UserScriptSet = crate::storage::read_json(vault, &path)?;
+    set.scripts = scripts;
+    set.revision += 1;
+    crate::storage::write_json(vault, &path, &set)?;
+    Ok(set.revision)
+}
+
+/// Translates a match glob into an anchored regex understood by both JavaScript and Rust.
+pub(crate) fn pattern_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    for ch in pattern.chars() {
+        match ch {
+            '*' => regex.push_str(".*"),
+            '\\' | '^' | '$' | '.' | '|' | '?' | '+' | '(' | ')' | '[' | ']' | '{' | '}' | '/' => {
+                regex.push('\\');
+                regex.push(ch);
+            }
+            _ => regex.push(ch),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+fn style_element_id(script_id: &str) -> String {
+    format!("anychat-user-style-{}", script_id)
+}
+
+fn url_test(script: &UserScript) -> String {
+    if script.matches.is_empty() {
+        return "true".to_string();
+    }
+
+    let patterns = script
+        .matches
+        .iter()
+        .map(|pattern| js_string(&pattern_to_regex(pattern)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "[{}].some(function(source) {{ return new RegExp(source).test(location.href); }})",
+        patterns
+    )
+}
+
+/// One log line for an error a page reported; line breaks and control characters in the
+/// page-supplied fields are replaced so a page cannot forge further entries.
+pub(crate) fn error_log_line(
+    now_millis: u64,
+    service_id: &str,
+    script_id: &str,
+    url: &str,
+    message: &str,
+) -> String {
+    format!(
+        "{} [{}] {} at {}: {}",
+        now_millis,
+        service_id,
+        log_field(script_id, MAX_LOGGED_FIELD_CHARS),
+        log_field(url, MAX_LOGGED_FIELD_CHARS),
+        log_field(message, MAX_LOGGED_MESSAGE_CHARS)
+    )
+}
+
+fn log_field(value: &str, max_chars: usize) -> String {
+    let mut field = value
+        .chars()
+        .take(max_chars)
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect::<String>();
+    if value.chars().nth(max_chars).is_some() {
+        field.push('…');
+    }
+    field
+}
+
+fn script_body(script: &UserScript) -> String {
+    match script.kind {
+        UserScriptKind::Script => format!("(function() {{\n{}\n}})();", script.code),
+        UserScriptKind::Style => format!(
+            "var style = document.getElementById({id}) || document.createElement('style'); \
+             style.id = {id}; style.textContent = {css}; \
+             (document.head || document.documentElement).appendChild(style);",
+            id = js_string(&style_element_id(&script.id)),
+            css = js_string(&script.code),
+        ),
+    }
+}
+
+fn guarded_run(script: &UserScript) -> String {
+    format!(
+        "var run = function() {{ try {{ {body} }} catch (error) {{ \
+         if (window.__anychatPost) window.__anychatPost({{ type: 'userScriptError', scriptId: {id}, \
+         message: String(error && error.message || error), url: location.href }}); \
+         console.error('[AnyChat] User script ' + {id} + ' failed:', error); }} }};",
+        body = script_body(script),
+        id = js_string(&script.id),
+    )
+}
+
+fn remember_revision(revision: u64) -> String {
+    format!(
+        "try {{ sessionStorage.setItem({}, {}); }} catch (_) {{}}",
+        js_string(REVISION_KEY),
+        js_string(&revision.to_string())
+    )
+}
+
+/// One initialization script per enabled user script, so a syntax error only breaks its own.
+pub(crate) fn initialization_scripts(set: &UserScriptSet) -> Vec<String> {
+    set.scripts
+        .iter()
+        .filter(|script| script.enabled)
+        .map(|script| {
+            let schedule = match (script.run_at, script.kind) {
+                (RunAt::DocumentStart, UserScriptKind::Script) => "run();",
+                (RunAt::DocumentStart, UserScriptKind::Style) => {
+                    "if (document.documentElement) run(); else document.addEventListener('DOMContentLoaded', run, { once: true });"
+                }
+                (RunAt::DocumentIdle, _) => {
+                    "if (document.readyState === 'loading') document.addEventListener('DOMContentLoaded', run, { once: true }); else run();"
+                }
+            };
+
+            format!(
+                "(function() {{\n\
+                 try {{ var revision = sessionStorage.getItem({key}); if (revision && revision !== {revision}) return; }} catch (_) {{}}\n\
+                 if (!({test})) return;\n\
+                 {run}\n\
+                 {schedule}\n\
+                 }})();",
+                key = js_string(REVISION_KEY),
+                revision = js_string(&set.revision.to_string()),
+                test = url_test(script),
+                run = guarded_run(script),
+                schedule = schedule,
+            )
+        })
+        .collect()
+}
+
+/// Applies one edited script to an already loaded page. A disabled stylesheet is removed; a
+/// disabled script cannot be undone and only stops running on later page loads.
+pub(crate) fn live_update_script(revision: u64, script: &UserScript) -> String {
+    let apply = if script.enabled {
+        format!(
+            "if ({test}) {{ {run} run(); }}",
+            test = url_test(script),
+            run = guarded_run(script)
+        )
+    } else {
+        remove_style(&script.id)
+    };
+
+    format!(
+        "(function() {{ {} {} }})();",
+        remember_revision(revision),
+        apply
+    )
+}
+
+pub(crate) fn live_remove_script(revision: u64, script_id: &str) -> String {
+    format!(
+        "(function() {{ {} {} }})();",
+        remember_revision(revision),
+        remove_style(script_id)
+    )
+}
+
+fn remove_style(script_id: &str) -> String {
+    format!(
+        "var stale = document.getElementById({}); if (stale) stale.remove();",
+        js_string(&style_element_id(script_id))
+    )
+}
+
+/// Runs the current set on a page whose baked initialization scripts are out of date.
+pub(crate) fn page_load_script(set: &UserScriptSet) -> String {
+    let scripts = set
+        .scripts
+        .iter()
+        .filter(|script| script.enabled)
+        .map(|script| {
+            format!(
+                "(function() {{ if ({test}) {{ {run} run(); }} }})();",
+                test = url_test(script),
+                run = guarded_run(script)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("{}\n{}", remember_revision(set.revision), scripts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        delete_script, error_log_line, initialization_scripts, live_update_script, load_scripts,
+        pattern_to_regex, save_script, RunAt, UserScript, UserScriptKind,
+    };
+    use crate::storage::Vault;
+    use regex::Regex;
+
+    fn script(id: &str, kind: UserScriptKind) -> UserScript {
+        UserScript {
+            id: id.to_string(),
+            name: "Wide chat".to_string(),
+            kind,
+            code: "main { max-width: none; }".to_string(),
+            matches: vec!["https://chatgpt.com/*".to_string()],
+            run_at: RunAt::DocumentIdle,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn match_patterns_are_globs_anchored_to_the_whole_url() {
+        let regex = Regex::new(&pattern_to_regex("https://chatgpt.com/c/*")).unwrap();
+        assert!(regex.is_match("https://chatgpt.com/c/123"));
+        assert!(!regex.is_match("https://chatgpt.com/"));
+        assert!(!regex.is_match("https://chatgptxcom/c/1"));
+        assert!(!regex.is_match("https://evil.example/?https://chatgpt.com/c/1"));
+
+        let any_subdomain = Regex::new(&pattern_to_regex("https://*.google.com/*")).unwrap();
+        assert!(any_subdomain.is_match("https://gemini.google.com/app"));
+    }
+
+    #[test]
+    fn scripts_persist_per_service_and_bump_revision() {
+        let dir = std::env::temp_dir().join(format!("anychat-user-scripts-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (stored, revision) = save_script(
+            &dir,
+            &Vault::Disabled,
+            "chatgpt",
+            script("", UserScriptKind::Style),
+        )
+        .unwrap();
+        assert!(stored.id.starts_with("script-"));
+        assert_eq!(revision, 1);
+
+        let mut edited = stored.clone();
+        edited.code = "main { max-width: 90%; }".to_string();
+        assert_eq!(
+            save_script(&dir, &Vault::Disabled, "chatgpt", edited)
+                .unwrap()
+                .1,
+            2
+        );
+
+        let set = load_scripts(&dir, &Vault::Disabled, "chatgpt").unwrap();
+        assert_eq!(set.scripts.len(), 1);
+        assert_eq!(set.scripts[0].code, "main { max-width: 90%; }");
+        assert!(load_scripts(&dir, &Vault::Disabled, "gemini")
+            .unwrap()
+            .scripts
+            .is_empty());
+
+        assert_eq!(
+            delete_script(&dir, &Vault::Disabled, "chatgpt", &stored.id).unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            delete_script(&dir, &Vault::Disabled, "chatgpt", &stored.id).unwrap(),
+            None
+        );
+        assert!(save_script(
+            &dir,
+            &Vault::Disabled,
+            "../chatgpt",
+            script("x", UserScriptKind::Script)
+        )
+        .is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn initialization_scripts_skip_disabled_entries_and_guard_revision() {
+        let mut disabled = script("off", UserScriptKind::Script);
+        disabled.enabled = false;
+        let set = super::UserScriptSet {
+            revision: 7,
+            scripts: vec![script("wide", UserScriptKind::Style), disabled],
+        };
+
+        let scripts = initialization_scripts(&set);
+        assert_eq!(scripts.len(), 1);
+        assert!(scripts[0].contains(r#"revision !== "7""#));
+        assert!(scripts[0].contains("anychat-user-style-wide"));
+        assert!(scripts[0].contains("userScriptError"));
+    }
+
+    #[test]
+    fn error_log_lines_are_single_line_and_capped() {
+        let line = error_log_line(
+            1,
+            "chatgpt",
+            "wide",
+            "https://chatgpt.com/",
+            &format!("boom\n2 [chatgpt] forged\r{}", "x".repeat(2000)),
+        );
+        assert!(!line.contains('\n') && !line.contains('\r'));
+        assert!(
+            line.starts_with("1 [chatgpt] wide at https://chatgpt.com/: boom 2 [chatgpt] forged ")
+        );
+        assert!(line.ends_with('…'));
+        assert!(line.chars().count() < 1100);
+    }
+
+    #[test]
+    fn live_update_of_disabled_style_removes_it() {
+        let mut style = script("wide", UserScriptKind::Style);
+        style.enabled = false;
+        let update = live_update_script(3, &style);
+        assert!(update.contains("stale.remove()"));
+        assert!(update.contains(r#""3""#));
+    }
+}
@@ -0,0 +1,156 @@
+//! Detects crashed or hung service webviews so they can be rebuilt.
+//!
+//! Crashes of the web content process are reported by WebKitGTK directly. Hangs are detected
+//! through a heartbeat the page posts over the script bridge: a visible page that stops
+//! beating is considered unresponsive. Webviews that never sent a heartbeat (no bridge on this
+//! platform) are left alone.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub(crate) const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const HANG_TIMEOUT: Duration = Duration::from_secs(45);
+/// Keeps a page that crashes on load from being rebuilt in a tight loop.
+const MIN_RECOVERY_INTERVAL: Duration = Duration::from_secs(60);
+pub(crate) const WATCHDOG_LOG: &str = "watchdog.log";
+
+pub(crate) const HEARTBEAT_SCRIPT: &str = r#"
+(function() {
+    if (window.top !== window || window.__anychatHeartbeat) return;
+    window.__anychatHeartbeat = setInterval(function() {
+        if (window.__anychatPost) window.__anychatPost({ type: 'heartbeat' });
+    }, 10000);
+})();
+"#;
+
+#[derive(Debug, Default)]
+pub(crate) struct Watchdog {
+    heartbeats: HashMap<String, Instant>,
+    recoveries: HashMap<String, Instant>,
+}
+
+impl Watchdog {
+    pub fn heartbeat(&mut self, service_id: &str, now: Instant) {
+        self.heartbeats.insert(service_id.to_string(), now);
+    }
+
+    /// Restarts the hang timer, e.g. when a throttled background page becomes visible again.
+    pub fn grace(&mut self, service_id: &str, now: Instant) {
+        if let Some(last) = self.heartbeats.get_mut(service_id) {
+            *last = now;
+        }
+    }
+
+    /// A rebuilt webview starts unmonitored until its first heartbeat.
+    pub fn forget(&mut self, service_id: &str) {
+        self.heartbeats.remove(service_id);
+    }
+
+    pub fn unresponsive(&self, now: Instant, is_watched: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut service_ids = self
+            .heartbeats
+            .iter()
+            .filter(|(service_id, last)| {
+                now.saturating_duration_since(**last) > HANG_TIMEOUT && is_watched(service_id)
+            })
+            .map(|(service_id, _)| service_id.clone())
+            .collect::<Vec<_>>();
+        service_ids.sort();
+        service_ids
+    }
+
+    /// Claims a recovery slot; `false` while the service was rebuilt too recently.
+    pub fn begin_recovery(&mut self, service_id: &str, now: Instant) -> bool {
+        if self
+            .recoveries
+            .get(service_id)
+            .is_some_and(|last| now.saturating_duration_since(*last) < MIN_RECOVERY_INTERVAL)
+        {
+            return false;
+        }
+        self.recoveries.insert(service_id.to_string(), now);
+        self.forget(service_id);
+        true
+    }
+}
+
+/// Rebuilds the webview when its web process dies for any reason other than an explicit stop.
+#[cfg(target_os = "linux")]
+pub(crate) fn attach_crash_detection(
+    app: &tauri::AppHandle,
+    webview: &tauri::Webview,
+    service_id: &str,
+) {
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+
+    let app = app.clone();
+    let service_id = service_id.to_string();
+    let _ = webview.with_webview(move |platform_webview| {
+        platform_webview
+            .inner()
+            .connect_web_process_terminated(move |_, reason| {
+                let reason = match reason {
+                    WebProcessTerminationReason::TerminatedByApi => return,
+                    WebProcessTerminationReason::ExceededMemoryLimit => "exceeded memory limit",
+                    _ => "web process crashed",
+                };
+                let app = app.clone();
+                let service_id = service_id.clone();
+                tauri::async_runtime::spawn(async move {
+                    crate::recover_service_webview(&app, &service_id, reason);
+                });
+            });
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn attach_crash_detection(
+    _app: &tauri::AppHandle,
+    _webview: &tauri::Webview,
+    _service_id: &str,
+) {
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Watchdog;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn only_watched_services_with_stale_heartbeats_are_unresponsive() {
+        let start = Instant::now();
+        let mut watchdog = Watchdog::default();
+        watchdog.heartbeat("chatgpt", start);
+        watchdog.heartbeat("claude", start);
+        watchdog.heartbeat("gemini", start + Duration::from_secs(40));
+
+        let later = start + Duration::from_secs(60);
+        assert_eq!(
+            watchdog.unresponsive(later, |_| true),
+            vec!["chatgpt".to_string(), "claude".to_string()]
+        );
+        assert_eq!(
+            watchdog.unresponsive(later, |service_id| service_id == "claude"),
+            vec!["claude".to_string()]
+        );
+
+        watchdog.grace("chatgpt", later);
+        assert_eq!(
+            watchdog.unresponsive(later, |service_id| service_id != "claude"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn recoveries_are_rate_limited_and_reset_monitoring() {
+        let start = Instant::now();
+        let mut watchdog = Watchdog::default();
+        watchdog.heartbeat("chatgpt", start);
+        assert!(watchdog.begin_recovery("chatgpt", start));
+        assert!(!watchdog.begin_recovery("chatgpt", start + Duration::from_secs(30)));
+        assert!(watchdog.begin_recovery("chatgpt", start + Duration::from_secs(61)));
+        assert!(watchdog
+            .unresponsive(start + Duration::from_secs(600), |_| true)
+            .is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+//! Per-service page zoom, stored as a percentage in the service registry.
+
+pub(crate) const DEFAULT_ZOOM_PERCENT: u16 = 100;
+pub(crate) const ZOOM_EVENT: &str = "service://zoom";
+
+/// The same stops browsers use, so repeated zoom in/out lands on familiar values.
+const ZOOM_STEPS: [u16; 17] = [
+    25, 33, 50, 67, 75, 80, 90, 100, 110, 125, 150, 175, 200, 250, 300, 400, 500,
+];
+const MIN_ZOOM_PERCENT: u16 = ZOOM_STEPS[0];
+const MAX_ZOOM_PERCENT: u16 = ZOOM_STEPS[ZOOM_STEPS.len() - 1];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ZoomAction {
+    In,
+    Out,
+    Reset,
+    Set { percent: u16 },
+}
+
+pub(crate) fn next_zoom(current: u16, action: ZoomAction) -> u16 {
+    match action {
+        ZoomAction::In => ZOOM_STEPS
+            .iter()
+            .copied()
+            .find(|step| *step > current)
+            .unwrap_or(MAX_ZOOM_PERCENT),
+        ZoomAction::Out => ZOOM_STEPS
+            .iter()
+            .rev()
+            .copied()
+            .find(|step| *step < current)
+            .unwrap_or(MIN_ZOOM_PERCENT),
+        ZoomAction::Reset => DEFAULT_ZOOM_PERCENT,
+        ZoomAction::Set { percent } => percent.clamp(MIN_ZOOM_PERCENT, MAX_ZOOM_PERCENT),
+    }
+}
+
+pub(crate) fn zoom_factor(percent: u16) -> f64 {
+    f64::from(percent) / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_zoom, zoom_factor, ZoomAction};
+
+    #[test]
+    fn zoom_in_and_out_move_between_steps_and_stop_at_the_limits() {
+        assert_eq!(next_zoom(100, ZoomAction::In), 110);
+        assert_eq!(next_zoom(100, ZoomAction::Out), 90);
+        assert_eq!(next_zoom(115, ZoomAction::In), 125);
+        assert_eq!(next_zoom(115, ZoomAction::Out), 110);
+        assert_eq!(next_zoom(500, ZoomAction::In), 500);
+        assert_eq!(next_zoom(25, ZoomAction::Out), 25);
+    }
+
+    #[test]
+    fn set_clamps_and_reset_returns_to_default() {
+        assert_eq!(next_zoom(150, ZoomAction::Reset), 100);
+        assert_eq!(next_zoom(100, ZoomAction::Set { percent: 5 }), 25);
+        assert_eq!(next_zoom(100, ZoomAction::Set { percent: 130 }), 130);
+        assert_eq!(zoom_factor(125), 1.25);
+    }
+}
@@ -11,6 +11,7 @@ import {
   DialogFooter,
 } from '@/components/ui/dialog';
 import { findWorkingIconCandidate, normalizeServiceUrl } from '@/lib/icon';
+import { usePolicy } from '@/hooks/usePolicy';
 
 const PRESET_SERVICES = [
   {
@@ -52,6 +53,8 @@ export function AddServiceDialog() {
   const [url, setUrl] = useState('');
   const [iconUrl, setIconUrl] = useState('');
   const [autoIconUrl, setAutoIconUrl] = useState('');
+  const policy = usePolicy();
+  const addingDisabled = policy?.disableCustomServices ?? false;
 
   const existingUrls = services.map((s) => s.url.toLowerCase());
 
@@ -80,7 +83,7 @@ export function AddServiceDialog() {
 
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
-    if (!name.trim() || !url.trim()) return;
+    if (addingDisabled || !name.trim() || !url.trim()) return;
 
     const finalUrl = normalizeServiceUrl(url);
     if (!finalUrl) return;
@@ -96,7 +99,7 @@ export function AddServiceDialog() {
   };
 
   const handlePresetSelect = (preset: (typeof PRESET_SERVICES)[0]) => {
-    if (existingUrls.includes(preset.url.toLowerCase())) {
+    if (addingDisabled || existingUrls.includes(preset.url.toLowerCase())) {
       return;
     }
     addService({
@@ -129,6 +132,12 @@ export function AddServiceDialog() {
           <DialogDescription>Add a new AI chat service to your dashboard.</DialogDescription>
         </DialogHeader>
 
+        {addingDisabled && (
+          <p className="rounded-md bg-muted p-3 text-sm text-muted-foreground">
+            Adding services is disabled by your administrator's policy.
+          </p>
+        )}
+
         <div className="flex gap-2 border-b pb-3">
           <Button
             variant={mode === 'preset' ? 'default' : 'ghost'}
@@ -157,7 +166,8 @@ export function AddServiceDialog() {
                 <button
                   key={preset.id}
                   onClick={() => handlePresetSelect(preset)}
-                  className="flex w-full items-center gap-3 rounded-lg border p-3 text-left transition-colors hover:bg-accent"
+                  disabled={addingDisabled}
+                  className="flex w-full items-center gap-3 rounded-lg border p-3 text-left transition-colors hover:bg-accent disabled:pointer-events-none disabled:opacity-50"
                 >
                   <div className="flex h-8 w-8 items-center justify-center rounded-md bg-muted">
                     <img
@@ -221,7 +231,9 @@ export function AddServiceDialog() {
               <Button type="button" variant="outline" onClick={resetAndClose}>
                 Cancel
               </Button>
-              <Button type="submit">Add Service</Button>
+              <Button type="submit" disabled={addingDisabled}>
+                Add Service
+              </Button>
             </DialogFooter>
           </form>
         )}
@@ -4,7 +4,16 @@ import { Sidebar } from './Sidebar';
 import { WebViewContainer } from './WebViewContainer';
 import { AddServiceDialog } from './AddServiceDialog';
 import { SettingsPage } from './SettingsPage';
+import { LockScreen } from './LockScreen';
+import { DataUnlockScreen } from './DataUnlockScreen';
+import { NativeChatPanel } from './NativeChatPanel';
+import { useAppLock } from '@/hooks/useAppLock';
+import { useAppTheme } from '@/hooks/useAppTheme';
+import { useDataEncryption } from '@/hooks/useDataEncryption';
 import { useKeyboardShortcuts } from '@/hooks/useKeyboardShortcuts';
+import { usePolicy } from '@/hooks/usePolicy';
+import { useRestoredBackup } from '@/hooks/useRestoredBackup';
+import { useSync } from '@/hooks/useSync';
 import {
   activateServiceContent,
   hideAllServiceContent,
@@ -12,30 +21,55 @@ import {
   syncDockedContentLayout,
   usesDockedWindowContentHost,
 } from '@/services/content-host';
+import { enabledOverride } from '@/services/policy';
 import { useAppStore } from '@/stores/app-store';
 
 export function AppLayout() {
-  const { activeServiceId, settingsPageOpen, addServiceDialogOpen, services } = useAppStore();
+  const {
+    activeServiceId,
+    settingsPageOpen,
+    addServiceDialogOpen,
+    services,
+    applyEnabledOverrides,
+  } = useAppStore();
   const [useDockedWindowHost, setUseDockedWindowHost] = useState<boolean | null>(null);
+  const { status: lockStatus } = useAppLock();
+  const dataStatus = useDataEncryption();
+  const dataLocked = dataStatus?.state === 'locked' || dataStatus?.state === 'failed';
+  const locked = (lockStatus?.locked ?? false) || dataLocked;
+  const policy = usePolicy();
 
   const isAnyDialogOpen = settingsPageOpen || addServiceDialogOpen;
   const hostServicesSignature = services
-    .map(({ id, name, url, enabled }) => `${id}:${name}:${url}:${enabled}`)
+    .map(({ id, name, url, enabled, kind }) => `${id}:${name}:${url}:${enabled}:${kind ?? ''}`)
     .join('|');
   const hostServices = useMemo(
     () =>
-      services.map(({ id, name, url, enabled }) => ({
+      services.map(({ id, name, url, enabled, kind }) => ({
         id,
         name,
         url,
         enabled,
+        ...(kind ? { kind } : {}),
       })),
     [hostServicesSignature]
   );
+  const nativeChatService = services.find(
+    (service) => service.id === activeServiceId && service.kind === 'openAiCompatible'
+  );
   const activeService = hostServices.find((service) => service.id === activeServiceId);
-  const visibleHostServiceId = isAnyDialogOpen ? null : activeService?.id ?? null;
+  const visibleHostServiceId = isAnyDialogOpen || locked ? null : activeService?.id ?? null;
 
+  useAppTheme();
   useKeyboardShortcuts();
+  useSync(locked);
+  useRestoredBackup();
+
+  useEffect(() => {
+    if (policy) {
+      applyEnabledOverrides((service) => enabledOverride(policy, service));
+    }
+  }, [applyEnabledOverrides, policy, services]);
 
   useEffect(() => {
     let cancelled = false;
@@ -59,7 +93,7 @@ export function AppLayout() {
   }, []);
 
   const syncActiveContent = useCallback(() => {
-    if (isAnyDialogOpen) {
+    if (isAnyDialogOpen || locked) {
       hideAllServiceContent().catch(console.error);
       return;
     }
@@ -70,7 +104,7 @@ export function AppLayout() {
     }
 
     hideAllServiceContent().catch(console.error);
-  }, [activeService, hostServices, isAnyDialogOpen]);
+  }, [activeService, hostServices, isAnyDialogOpen, locked]);
 
   useEffect(() => {
     if (!useDockedWindowHost) {
@@ -131,10 +165,24 @@ export function AppLayout() {
     };
   }, [syncActiveContent, useDockedWindowHost]);
 
+  if (dataStatus && dataLocked) {
+    return <DataUnlockScreen status={dataStatus} />;
+  }
+
+  if (locked) {
+    return <LockScreen />;
+  }
+
   return (
     <div className="flex h-screen w-screen overflow-hidden bg-background">
       <Sidebar />
-      {settingsPageOpen ? <SettingsPage /> : <WebViewContainer />}
+      {settingsPageOpen ? (
+        <SettingsPage />
+      ) : nativeChatService ? (
+        <NativeChatPanel service={nativeChatService} />
+      ) : (
+        <WebViewContainer />
+      )}
       <AddServiceDialog />
     </div>
   );
@@ -0,0 +1,146 @@
+import { useEffect, useState } from 'react';
+import { Lock } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { SettingsCard } from '@/components/SettingsCard';
+import {
+  getAppLockStatus,
+  lockApp,
+  setAppLockOptions,
+  setAppLockPassword,
+  type AppLockStatus,
+} from '@/services/app-lock';
+
+const MIN_PASSWORD_LENGTH = 6;
+
+/** Lock password, idle time and screen-lock behaviour of the app lock. */
+export function AppLockSettings() {
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+  const [currentPassword, setCurrentPassword] = useState('');
+  const [newPassword, setNewPassword] = useState('');
+  const [idleMinutes, setIdleMinutes] = useState('');
+  const [lockOnScreenLock, setLockOnScreenLock] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const applyStatus = (next: AppLockStatus) => {
+    setStatus(next);
+    setIdleMinutes(next.idleMinutes?.toString() ?? '');
+    setLockOnScreenLock(next.lockOnScreenLock);
+  };
+
+  useEffect(() => {
+    getAppLockStatus()
+      .then(applyStatus)
+      .catch((reason) => setError(String(reason)));
+  }, []);
+
+  const run = (task: () => Promise<AppLockStatus>, done: string) => {
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+    task()
+      .then((next) => {
+        applyStatus(next);
+        setCurrentPassword('');
+        setNewPassword('');
+        setMessage(done);
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const enabled = status?.enabled ?? false;
+  const idleValue = idleMinutes.trim() === '' ? null : Number(idleMinutes);
+  const idleValid = idleValue === null || (Number.isInteger(idleValue) && idleValue > 0);
+  const canSetPassword =
+    !busy && newPassword.length >= MIN_PASSWORD_LENGTH && (!enabled || currentPassword !== '');
+
+  return (
+    <SettingsCard
+      title="应用锁"
+      description="设置密码后，空闲一段时间或系统锁屏时会隐藏所有服务，输入密码才能继续使用。"
+    >
+      {enabled && (
+        <Input
+          type="password"
+          placeholder="当前锁定密码"
+          value={currentPassword}
+          onChange={(event) => setCurrentPassword(event.target.value)}
+        />
+      )}
+      <Input
+        type="password"
+        placeholder={`${enabled ? '新的锁定密码' : '锁定密码'}，至少 ${MIN_PASSWORD_LENGTH} 个字符`}
+        value={newPassword}
+        onChange={(event) => setNewPassword(event.target.value)}
+      />
+      <div className="flex gap-2">
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!canSetPassword}
+          onClick={() =>
+            run(
+              () => setAppLockPassword(enabled ? currentPassword : null, newPassword),
+              enabled ? '锁定密码已修改。' : '应用锁已开启。'
+            )
+          }
+        >
+          {enabled ? '修改密码' : '设置密码'}
+        </Button>
+        {enabled && (
+          <>
+            <Button
+              variant="outline"
+              size="sm"
+              className="text-destructive hover:bg-destructive/10 hover:text-destructive"
+              disabled={busy || currentPassword === ''}
+              onClick={() => run(() => setAppLockPassword(currentPassword, null), '应用锁已关闭。')}
+            >
+              移除密码
+            </Button>
+            <Button variant="outline" size="sm" disabled={busy} onClick={() => void lockApp()}>
+              <Lock className="h-4 w-4" />
+              立即锁定
+            </Button>
+          </>
+        )}
+      </div>
+
+      {enabled && (
+        <div className="space-y-2">
+          <Input
+            type="number"
+            min={1}
+            placeholder="空闲多少分钟后锁定，留空则不自动锁定"
+            value={idleMinutes}
+            onChange={(event) => setIdleMinutes(event.target.value)}
+          />
+          <label className="flex items-center gap-2 text-sm">
+            <input
+              type="checkbox"
+              checked={lockOnScreenLock}
+              onChange={(event) => setLockOnScreenLock(event.target.checked)}
+            />
+            系统锁屏时同时锁定
+          </label>
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={busy || !idleValid}
+            onClick={() =>
+              run(() => setAppLockOptions(idleValue, lockOnScreenLock), '锁定选项已保存。')
+            }
+          >
+            保存锁定选项
+          </Button>
+        </div>
+      )}
+
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
@@ -0,0 +1,86 @@
+import { useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { SettingsCard } from '@/components/SettingsCard';
+import { createBackup, restoreBackup } from '@/services/backup';
+
+/** Creates a full backup of AnyChat's data and restores one. */
+export function BackupSettings() {
+  const [path, setPath] = useState('');
+  const [passphrase, setPassphrase] = useState('');
+  const [includeWebviewStorage, setIncludeWebviewStorage] = useState(false);
+  const [confirmRestore, setConfirmRestore] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const start = () => {
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+  };
+
+  const handleCreate = () => {
+    setConfirmRestore(false);
+    start();
+    createBackup(path.trim(), includeWebviewStorage, passphrase)
+      .then((manifest) => {
+        setPassphrase('');
+        setMessage(`已备份 ${manifest.files.length} 个文件${passphrase ? '，备份已加密' : ''}。`);
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const handleRestore = () => {
+    if (!confirmRestore) {
+      setConfirmRestore(true);
+      return;
+    }
+    setConfirmRestore(false);
+    start();
+    // Resolves only when the backup could not be restored; otherwise AnyChat relaunches.
+    restoreBackup(path.trim(), passphrase)
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const canUse = !busy && path.trim() !== '';
+
+  return (
+    <SettingsCard
+      title="备份与恢复"
+      description="备份服务列表、服务设置、用户脚本与对话记录；恢复时先校验备份，再重启 AnyChat。"
+    >
+      <Input
+        placeholder="备份文件的完整路径，例如：/home/me/anychat-backup.tar.gz"
+        value={path}
+        onChange={(event) => setPath(event.target.value)}
+      />
+      <Input
+        type="password"
+        placeholder="备份口令，留空则不加密；开启数据加密时必填"
+        value={passphrase}
+        onChange={(event) => setPassphrase(event.target.value)}
+      />
+      <label className="flex items-center gap-2 text-sm">
+        <input
+          type="checkbox"
+          checked={includeWebviewStorage}
+          onChange={(event) => setIncludeWebviewStorage(event.target.checked)}
+        />
+        包含网页的登录状态与站点存储
+      </label>
+      <div className="flex gap-2">
+        <Button variant="outline" size="sm" disabled={!canUse} onClick={handleCreate}>
+          创建备份
+        </Button>
+        <Button variant="outline" size="sm" disabled={!canUse} onClick={handleRestore}>
+          {confirmRestore ? '确认恢复（将覆盖当前数据并重启）' : '恢复备份'}
+        </Button>
+      </div>
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
@@ -0,0 +1,158 @@
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { cn } from '@/lib/utils';
+import {
+  importBrowserCookies,
+  listBrowserProfiles,
+  previewBrowserCookieImport,
+  type BrowserProfile,
+  type ImportPreview,
+} from '@/services/browser-import';
+import type { ChatService } from '@/types';
+
+const BROWSER_NAMES: Record<BrowserProfile['browser'], string> = {
+  chrome: 'Chrome',
+  chromium: 'Chromium',
+  brave: 'Brave',
+  edge: 'Edge',
+  firefox: 'Firefox',
+};
+
+interface BrowserImportDialogProps {
+  /** The service to import into; the dialog is closed while `null`. */
+  service: ChatService | null;
+  onClose: () => void;
+}
+
+/** Imports one service's sign-in from a local browser profile, after showing what is copied. */
+export function BrowserImportDialog({ service, onClose }: BrowserImportDialogProps) {
+  const [profiles, setProfiles] = useState<BrowserProfile[] | null>(null);
+  const [preview, setPreview] = useState<ImportPreview | null>(null);
+  const [imported, setImported] = useState<number | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setProfiles(null);
+    setPreview(null);
+    setImported(null);
+    setError(null);
+    if (!service) return;
+
+    listBrowserProfiles()
+      .then(setProfiles)
+      .catch((reason) => setError(String(reason)));
+  }, [service?.id]);
+
+  if (!service) {
+    return null;
+  }
+
+  const run = async (task: () => Promise<void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await task();
+    } catch (reason) {
+      setError(String(reason));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handlePreview = (profile: BrowserProfile) =>
+    run(async () => {
+      setPreview(await previewBrowserCookieImport(service, profile));
+    });
+
+  const handleImport = () =>
+    run(async () => {
+      if (!preview) return;
+      const summary = await importBrowserCookies(service, preview.profile);
+      setImported(summary.cookies);
+    });
+
+  return (
+    <Dialog open onOpenChange={(open) => !open && onClose()}>
+      <DialogContent className="sm:max-w-lg">
+        <DialogHeader>
+          <DialogTitle>从浏览器导入 {service.name} 的登录状态</DialogTitle>
+          <DialogDescription>
+            只复制属于该服务网站的 Cookie，导入前会列出全部条目，不显示 Cookie 的值。
+          </DialogDescription>
+        </DialogHeader>
+
+        {imported !== null ? (
+          <p className="text-sm">已导入 {imported} 个 Cookie，服务已重新加载。</p>
+        ) : preview ? (
+          <div className="space-y-3">
+            <p className="text-sm text-muted-foreground">
+              来自 {BROWSER_NAMES[preview.profile.browser]} · {preview.profile.name}：
+              {preview.cookies.length} 个 Cookie
+              {preview.expired > 0 && `，${preview.expired} 个已过期将跳过`}
+              {preview.undecryptable > 0 && `，${preview.undecryptable} 个无法解密`}
+            </p>
+            <ul className="max-h-64 space-y-1 overflow-y-auto rounded-md border p-2 text-xs">
+              {preview.cookies.map((cookie) => (
+                <li
+                  key={`${cookie.domain}|${cookie.path}|${cookie.name}`}
+                  className="flex justify-between gap-2"
+                >
+                  <span className="truncate font-mono">{cookie.name}</span>
+                  <span className="flex-none text-muted-foreground">
+                    {cookie.domain}
+                    {cookie.httpOnly && ' · HttpOnly'}
+                    {cookie.expires === null
+                      ? ' · 会话'
+                      : ` · ${new Date(cookie.expires * 1000).toLocaleDateString()}`}
+                  </span>
+                </li>
+              ))}
+            </ul>
+            <div className="flex justify-end gap-2">
+              <Button variant="ghost" onClick={() => setPreview(null)} disabled={busy}>
+                返回
+              </Button>
+              <Button onClick={handleImport} disabled={busy || preview.cookies.length === 0}>
+                导入 {preview.cookies.length} 个 Cookie
+              </Button>
+            </div>
+          </div>
+        ) : profiles === null ? (
+          <Loader2 className="h-5 w-5 animate-spin text-muted-foreground" />
+        ) : profiles.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            未找到浏览器配置。目前仅支持 Linux 上的 Chrome、Chromium、Brave、Edge 和 Firefox。
+          </p>
+        ) : (
+          <div className="space-y-2">
+            {profiles.map((profile) => (
+              <button
+                key={profile.cookieDb}
+                onClick={() => handlePreview(profile)}
+                disabled={busy}
+                className={cn(
+                  'flex w-full items-center justify-between rounded-lg border p-3 text-left text-sm',
+                  'transition-colors hover:bg-accent disabled:pointer-events-none disabled:opacity-50'
+                )}
+              >
+                <span className="font-medium">{BROWSER_NAMES[profile.browser]}</span>
+                <span className="text-muted-foreground">{profile.name}</span>
+              </button>
+            ))}
+          </div>
+        )}
+
+        {error && <p className="text-sm text-destructive">{error}</p>}
+      </DialogContent>
+    </Dialog>
+  );
+}
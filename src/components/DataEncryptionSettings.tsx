@@ -0,0 +1,169 @@
+import { useEffect, useState } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { SettingsCard } from '@/components/SettingsCard';
+import { cn } from '@/lib/utils';
+import {
+  disableDataEncryption,
+  enableDataEncryption,
+  exportDataRecoveryKey,
+  getDataEncryptionStatus,
+  type DataEncryptionStatus,
+  type DataKeySource,
+} from '@/services/data-encryption';
+
+const MIN_PASSPHRASE_LENGTH = 8;
+
+const KEY_SOURCES: Array<{ value: DataKeySource; label: string }> = [
+  { value: 'passphrase', label: '启动时输入口令' },
+  { value: 'keyring', label: '系统钥匙串' },
+];
+
+/** Turns encryption of AnyChat's data files on and off and manages the recovery key. */
+export function DataEncryptionSettings() {
+  const [status, setStatus] = useState<DataEncryptionStatus | null>(null);
+  const [keySource, setKeySource] = useState<DataKeySource>('passphrase');
+  const [passphrase, setPassphrase] = useState('');
+  const [recoveryKey, setRecoveryKey] = useState<string | null>(null);
+  const [recoveryPath, setRecoveryPath] = useState('');
+  const [confirmDisable, setConfirmDisable] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getDataEncryptionStatus()
+      .then(setStatus)
+      .catch((reason) => setError(String(reason)));
+  }, []);
+
+  const start = () => {
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+  };
+
+  const handleEnable = () => {
+    start();
+    enableDataEncryption(keySource, keySource === 'passphrase' ? passphrase : undefined)
+      .then((key) => {
+        setRecoveryKey(key);
+        setPassphrase('');
+        return getDataEncryptionStatus().then(setStatus);
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const handleExport = () => {
+    start();
+    exportDataRecoveryKey(recoveryPath.trim())
+      .then(() => setMessage('新的恢复密钥已写入文件，之前的恢复密钥已失效。'))
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const handleDisable = () => {
+    if (!confirmDisable) {
+      setConfirmDisable(true);
+      return;
+    }
+    setConfirmDisable(false);
+    start();
+    disableDataEncryption()
+      .then((next) => {
+        setStatus(next);
+        setRecoveryKey(null);
+        setMessage('数据加密已关闭。');
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const keySourceLabel = status?.keySource === 'keyring' ? '系统钥匙串' : '启动时输入的口令';
+  const canEnable =
+    !busy && (keySource === 'keyring' || passphrase.length >= MIN_PASSPHRASE_LENGTH);
+
+  return (
+    <SettingsCard
+      title="数据加密"
+      description="加密保存服务设置、对话记录与密钥等数据文件；网页的 Cookie 与缓存不在其中。"
+    >
+      {status?.state === 'disabled' && (
+        <>
+          <div className="flex gap-2">
+            {KEY_SOURCES.map(({ value, label }) => (
+              <Button
+                key={value}
+                variant="outline"
+                size="sm"
+                className={cn(keySource === value && 'border-primary bg-primary/10 text-primary')}
+                onClick={() => setKeySource(value)}
+              >
+                {label}
+              </Button>
+            ))}
+          </div>
+          {keySource === 'passphrase' && (
+            <Input
+              type="password"
+              placeholder={`数据口令，至少 ${MIN_PASSPHRASE_LENGTH} 个字符`}
+              value={passphrase}
+              onChange={(event) => setPassphrase(event.target.value)}
+            />
+          )}
+          <Button variant="outline" size="sm" disabled={!canEnable} onClick={handleEnable}>
+            开启加密
+          </Button>
+        </>
+      )}
+
+      {recoveryKey && (
+        <div className="space-y-2 rounded-xl border border-primary/40 bg-primary/5 p-3">
+          <p className="text-sm">
+            恢复密钥只显示这一次。忘记口令或钥匙串丢失时，只能用它打开数据，请立即妥善保存：
+          </p>
+          <code className="block select-all break-all rounded bg-muted p-2 text-xs">
+            {recoveryKey}
+          </code>
+          <Button variant="outline" size="sm" onClick={() => setRecoveryKey(null)}>
+            我已保存
+          </Button>
+        </div>
+      )}
+
+      {status?.state === 'unlocked' && (
+        <>
+          <p className="text-sm text-muted-foreground">数据已加密，密钥来自{keySourceLabel}。</p>
+          <Input
+            placeholder="恢复密钥文件的完整路径，例如：/home/me/anychat-recovery.txt"
+            value={recoveryPath}
+            onChange={(event) => setRecoveryPath(event.target.value)}
+          />
+          <div className="flex gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={busy || recoveryPath.trim() === ''}
+              onClick={handleExport}
+            >
+              导出新的恢复密钥
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              className="text-destructive hover:bg-destructive/10 hover:text-destructive"
+              disabled={busy}
+              onClick={handleDisable}
+            >
+              {confirmDisable ? '确认关闭（数据将以明文保存）' : '关闭加密'}
+            </Button>
+          </div>
+        </>
+      )}
+
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
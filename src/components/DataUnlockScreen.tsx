@@ -0,0 +1,81 @@
+import { useState, type FormEvent } from 'react';
+import { KeyRound } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { recoverData, unlockData, type DataEncryptionStatus } from '@/services/data-encryption';
+
+interface DataUnlockScreenProps {
+  status: DataEncryptionStatus;
+}
+
+/**
+ * Shown while encrypted data is not open. A failed keyring lookup or a damaged file can only be
+ * fixed with the recovery key, which also sets a new passphrase.
+ */
+export function DataUnlockScreen({ status }: DataUnlockScreenProps) {
+  const [recovering, setRecovering] = useState(status.state === 'failed');
+  const [passphrase, setPassphrase] = useState('');
+  const [recoveryKey, setRecoveryKey] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!passphrase || busy) return;
+
+    setBusy(true);
+    setError(null);
+    try {
+      if (recovering) {
+        await recoverData(recoveryKey, 'passphrase', passphrase);
+      } else {
+        await unlockData(passphrase);
+      }
+    } catch (reason) {
+      setError(String(reason));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="flex h-screen w-screen items-center justify-center bg-background">
+      <form onSubmit={handleSubmit} className="flex w-96 flex-col items-center gap-4">
+        <KeyRound className="h-10 w-10 text-muted-foreground" />
+        <h1 className="text-lg font-medium">
+          {recovering ? '使用恢复密钥恢复数据' : 'AnyChat 数据已加密'}
+        </h1>
+        {status.error && <p className="text-center text-sm text-destructive">{status.error}</p>}
+        {recovering && (
+          <Input
+            autoFocus
+            placeholder="恢复密钥"
+            value={recoveryKey}
+            onChange={(event) => setRecoveryKey(event.target.value)}
+            disabled={busy}
+          />
+        )}
+        <Input
+          type="password"
+          autoFocus={!recovering}
+          placeholder={recovering ? '设置新的数据密码' : '输入数据密码'}
+          value={passphrase}
+          onChange={(event) => setPassphrase(event.target.value)}
+          disabled={busy}
+        />
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        <Button type="submit" className="w-full" disabled={!passphrase || busy}>
+          {recovering ? '恢复' : '解锁'}
+        </Button>
+        <Button
+          type="button"
+          variant="link"
+          onClick={() => setRecovering((current) => !current)}
+          disabled={busy}
+        >
+          {recovering ? '使用数据密码解锁' : '忘记密码？使用恢复密钥'}
+        </Button>
+      </form>
+    </div>
+  );
+}
@@ -0,0 +1,204 @@
+import { useEffect, useState } from 'react';
+import { RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { AppLockSettings } from '@/components/AppLockSettings';
+import { BackupSettings } from '@/components/BackupSettings';
+import { DataEncryptionSettings } from '@/components/DataEncryptionSettings';
+import { NetworkSettings } from '@/components/NetworkSettings';
+import { SettingsCard } from '@/components/SettingsCard';
+import { SyncSettings } from '@/components/SyncSettings';
+import {
+  getContentFilterStats,
+  reloadContentFilters,
+  type ContentFilterStats,
+} from '@/services/content-blocking';
+import {
+  exportServiceCookies,
+  importServiceCookies,
+  type ServiceCookieSummary,
+} from '@/services/cookie-backup';
+import { applyAppTheme } from '@/hooks/useAppTheme';
+import { cn } from '@/lib/utils';
+import { getAppTheme, setAppTheme, type AppTheme } from '@/services/theme';
+import { useAppStore } from '@/stores/app-store';
+
+const APP_THEMES: Array<{ value: AppTheme; label: string }> = [
+  { value: 'system', label: '跟随系统' },
+  { value: 'light', label: '浅色' },
+  { value: 'dark', label: '深色' },
+];
+
+/** App-wide settings that are not tied to one service. */
+export function GeneralSettings() {
+  const { services } = useAppStore();
+  const [filterStats, setFilterStats] = useState<ContentFilterStats | null>(null);
+  const [reloading, setReloading] = useState(false);
+  const [filterError, setFilterError] = useState<string | null>(null);
+  const [theme, setTheme] = useState<AppTheme | null>(null);
+  const [themeError, setThemeError] = useState<string | null>(null);
+  const [backupPath, setBackupPath] = useState('');
+  const [backupPassword, setBackupPassword] = useState('');
+  const [backupBusy, setBackupBusy] = useState(false);
+  const [backupResult, setBackupResult] = useState<{
+    exported: boolean;
+    summaries: ServiceCookieSummary[];
+  } | null>(null);
+  const [backupError, setBackupError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getAppTheme()
+      .then(setTheme)
+      .catch((reason) => setThemeError(String(reason)));
+    getContentFilterStats()
+      .then(setFilterStats)
+      .catch((reason) => setFilterError(String(reason)));
+  }, []);
+
+  const handleReloadFilters = () => {
+    setReloading(true);
+    setFilterError(null);
+    reloadContentFilters(services.filter((service) => service.enabled))
+      .then(setFilterStats)
+      .catch((reason) => setFilterError(String(reason)))
+      .finally(() => setReloading(false));
+  };
+
+  const handleTheme = (next: AppTheme) => {
+    setThemeError(null);
+    setAppTheme(next)
+      .then(() => {
+        setTheme(next);
+        applyAppTheme(next);
+      })
+      .catch((reason) => setThemeError(String(reason)));
+  };
+
+  const canUseBackup = backupPath.trim() !== '' && backupPassword.length >= 8 && !backupBusy;
+
+  const runBackup = (exported: boolean) => {
+    setBackupBusy(true);
+    setBackupResult(null);
+    setBackupError(null);
+    const task = exported
+      ? exportServiceCookies(
+          services.filter((service) => service.kind !== 'openAiCompatible'),
+          backupPath.trim(),
+          backupPassword
+        )
+      : importServiceCookies(backupPath.trim(), backupPassword);
+    task
+      .then((summaries) => {
+        setBackupResult({ exported, summaries });
+        setBackupPassword('');
+      })
+      .catch((reason) => setBackupError(String(reason)))
+      .finally(() => setBackupBusy(false));
+  };
+
+  return (
+    <div className="flex-1 overflow-y-auto p-8 max-w-3xl">
+      <h1 className="mb-6 text-2xl font-bold">通用</h1>
+
+      <div className="space-y-4">
+        <SettingsCard
+          title="外观"
+          description="设为“跟随应用”的服务会随之切换深浅色，无需重新加载页面。"
+        >
+          <div className="flex gap-2">
+            {APP_THEMES.map(({ value, label }) => (
+              <Button
+                key={value}
+                variant="outline"
+                size="sm"
+                className={cn(theme === value && 'border-primary bg-primary/10 text-primary')}
+                onClick={() => handleTheme(value)}
+              >
+                {label}
+              </Button>
+            ))}
+          </div>
+          {themeError && <p className="text-sm text-destructive">{themeError}</p>}
+        </SettingsCard>
+
+        <AppLockSettings />
+
+        <SyncSettings />
+
+        <DataEncryptionSettings />
+
+        <BackupSettings />
+
+        <NetworkSettings />
+
+        <SettingsCard
+          title="内容拦截规则"
+          description="从数据目录下的 content_filters 文件夹读取 EasyList 或 JSON 规则，无需联网。"
+        >
+          {filterStats && (
+            <p className="text-sm text-muted-foreground">
+              {filterStats.sources.length} 个规则文件：拦截 {filterStats.blockedHosts} 个域名，
+              隐藏 {filterStats.hiddenSelectors} 类元素
+              {filterStats.unsupportedRules > 0 &&
+                `，${filterStats.unsupportedRules} 条规则不受支持`}
+            </p>
+          )}
+          <Button variant="outline" size="sm" onClick={handleReloadFilters} disabled={reloading}>
+            <RefreshCw className={reloading ? 'h-4 w-4 animate-spin' : 'h-4 w-4'} />
+            重新加载规则
+          </Button>
+          {filterError && <p className="text-sm text-destructive">{filterError}</p>}
+        </SettingsCard>
+
+        <SettingsCard
+          title="登录状态备份"
+          description="把各服务的 Cookie 加密导出到文件，重装或换机后用同一密码导入即可免去重新登录。"
+        >
+          <Input
+            placeholder="备份文件的完整路径，例如：/home/me/anychat-cookies.bak"
+            value={backupPath}
+            onChange={(event) => setBackupPath(event.target.value)}
+          />
+          <Input
+            type="password"
+            placeholder="密码，至少 8 个字符"
+            value={backupPassword}
+            onChange={(event) => setBackupPassword(event.target.value)}
+          />
+          <div className="flex gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={!canUseBackup}
+              onClick={() => runBackup(true)}
+            >
+              导出
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={!canUseBackup}
+              onClick={() => runBackup(false)}
+            >
+              导入
+            </Button>
+          </div>
+          {backupResult && (
+            <ul className="space-y-1 text-sm">
+              {backupResult.summaries.map((summary) => (
+                <li key={summary.serviceId} className="flex justify-between gap-2">
+                  <span>{summary.name}</span>
+                  <span className="text-muted-foreground">
+                    {backupResult.exported ? '已导出' : '已恢复'} {summary.cookies} 个 Cookie
+                    {summary.expired > 0 && `，跳过 ${summary.expired} 个过期`}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          )}
+          {backupError && <p className="text-sm text-destructive">{backupError}</p>}
+        </SettingsCard>
+      </div>
+    </div>
+  );
+}
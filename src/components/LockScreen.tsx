@@ -0,0 +1,49 @@
+import { useState, type FormEvent } from 'react';
+import { Lock } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { unlockApp } from '@/services/app-lock';
+
+/** Covers the whole window while the app is locked; service webviews stay hidden meanwhile. */
+export function LockScreen() {
+  const [password, setPassword] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [unlocking, setUnlocking] = useState(false);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!password || unlocking) return;
+
+    setUnlocking(true);
+    setError(null);
+    try {
+      await unlockApp(password);
+      setPassword('');
+    } catch {
+      setError('密码错误');
+    } finally {
+      setUnlocking(false);
+    }
+  };
+
+  return (
+    <div className="flex h-screen w-screen items-center justify-center bg-background">
+      <form onSubmit={handleSubmit} className="flex w-72 flex-col items-center gap-4">
+        <Lock className="h-10 w-10 text-muted-foreground" />
+        <h1 className="text-lg font-medium">AnyChat 已锁定</h1>
+        <Input
+          type="password"
+          autoFocus
+          placeholder="输入密码解锁"
+          value={password}
+          onChange={(event) => setPassword(event.target.value)}
+          disabled={unlocking}
+        />
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        <Button type="submit" className="w-full" disabled={!password || unlocking}>
+          {unlocking ? '正在解锁…' : '解锁'}
+        </Button>
+      </form>
+    </div>
+  );
+}
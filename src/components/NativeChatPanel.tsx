@@ -0,0 +1,353 @@
+import { useCallback, useEffect, useMemo, useRef, useState, type FormEvent } from 'react';
+import { KeyRound, Loader2, MessageSquare, Plus, Send, Square, Trash2 } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { cn } from '@/lib/utils';
+import {
+  cancelNativeChatRequest,
+  deleteNativeChatConversation,
+  listNativeChatConversations,
+  listNativeChatModels,
+  listenNativeChatStream,
+  loadNativeChatConversation,
+  sendNativeChatMessage,
+  setNativeChatApiKey,
+  type NativeChatConversationSummary,
+  type NativeChatMessage,
+  type NativeChatStreamEvent,
+} from '@/services/native-chat';
+import type { ChatService } from '@/types';
+
+interface NativeChatPanelProps {
+  service: ChatService;
+}
+
+interface PendingReply {
+  requestId: string;
+  text: string;
+}
+
+/** Chat UI for OpenAI-compatible endpoints, rendered in the main webview instead of a site. */
+export function NativeChatPanel({ service: storedService }: NativeChatPanelProps) {
+  // Only the fields sent to Rust, so icon or order updates do not reset the panel.
+  const service = useMemo(
+    () => ({
+      id: storedService.id,
+      name: storedService.name,
+      url: storedService.url,
+      enabled: storedService.enabled,
+      kind: storedService.kind,
+    }),
+    [
+      storedService.id,
+      storedService.name,
+      storedService.url,
+      storedService.enabled,
+      storedService.kind,
+    ]
+  );
+  const [models, setModels] = useState<string[]>([]);
+  const [model, setModel] = useState('');
+  const [conversations, setConversations] = useState<NativeChatConversationSummary[]>([]);
+  const [conversationId, setConversationId] = useState<string | null>(null);
+  const [messages, setMessages] = useState<NativeChatMessage[]>([]);
+  const [pending, setPending] = useState<PendingReply | null>(null);
+  const [input, setInput] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [editingApiKey, setEditingApiKey] = useState(false);
+  const [apiKey, setApiKey] = useState('');
+  const pendingRef = useRef<PendingReply | null>(null);
+  pendingRef.current = pending;
+  /** Replies that finished before `sendNativeChatMessage` returned their request id. */
+  const settledRef = useRef(new Map<string, NativeChatStreamEvent>());
+
+  const refreshConversations = useCallback(() => {
+    listNativeChatConversations(service.id)
+      .then(setConversations)
+      .catch((reason) => setError(String(reason)));
+  }, [service.id]);
+
+  const refreshModels = useCallback(() => {
+    listNativeChatModels(service)
+      .then((available) => {
+        setModels(available);
+        setModel((current) => current || available[0] || '');
+      })
+      .catch((reason) => setError(String(reason)));
+  }, [service]);
+
+  useEffect(() => {
+    setConversationId(null);
+    setMessages([]);
+    setPending(null);
+    setError(null);
+    setModel('');
+    refreshModels();
+    refreshConversations();
+  }, [refreshConversations, refreshModels]);
+
+  const settle = useCallback(
+    (event: NativeChatStreamEvent) => {
+      const reply = event.message;
+      if (reply) {
+        setMessages((current) => [...current, reply]);
+      } else {
+        setError(event.error ?? '请求失败');
+      }
+      setPending(null);
+      refreshConversations();
+    },
+    [refreshConversations]
+  );
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+    const isPending = (requestId: string) => pendingRef.current?.requestId === requestId;
+    const onSettled = (event: NativeChatStreamEvent) => {
+      if (isPending(event.requestId)) {
+        settle(event);
+      } else {
+        settledRef.current.set(event.requestId, event);
+      }
+    };
+
+    listenNativeChatStream({
+      onDelta: (event) => {
+        if (!isPending(event.requestId)) return;
+        setPending((current) =>
+          current ? { ...current, text: current.text + (event.delta ?? '') } : current
+        );
+      },
+      onDone: onSettled,
+      onError: onSettled,
+    })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+          return;
+        }
+        unlisten = fn;
+      })
+      .catch(console.error);
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [settle]);
+
+  const openConversation = async (id: string) => {
+    if (pending) return;
+    try {
+      const conversation = await loadNativeChatConversation(service.id, id);
+      setConversationId(conversation.id);
+      setMessages(conversation.messages);
+      setModel(conversation.model);
+      setError(null);
+    } catch (reason) {
+      setError(String(reason));
+    }
+  };
+
+  const startConversation = () => {
+    if (pending) return;
+    setConversationId(null);
+    setMessages([]);
+    setError(null);
+  };
+
+  const removeConversation = async (id: string) => {
+    try {
+      await deleteNativeChatConversation(service.id, id);
+      if (id === conversationId) {
+        startConversation();
+      }
+      refreshConversations();
+    } catch (reason) {
+      setError(String(reason));
+    }
+  };
+
+  const handleSend = async (event: FormEvent) => {
+    event.preventDefault();
+    const content = input.trim();
+    if (!content || !model.trim() || pending) return;
+
+    setError(null);
+    settledRef.current.clear();
+    try {
+      const started = await sendNativeChatMessage(
+        service,
+        model.trim(),
+        content,
+        conversationId ?? undefined
+      );
+      setConversationId(started.conversationId);
+      setMessages((current) => [...current, { role: 'user', content }]);
+      setInput('');
+      const settled = settledRef.current.get(started.requestId);
+      if (settled) {
+        settle(settled);
+      } else {
+        setPending({ requestId: started.requestId, text: '' });
+        refreshConversations();
+      }
+    } catch (reason) {
+      setError(String(reason));
+    }
+  };
+
+  const handleStop = () => {
+    if (!pending) return;
+    cancelNativeChatRequest(pending.requestId).catch(console.error);
+    setPending(null);
+  };
+
+  const handleSaveApiKey = async (event: FormEvent) => {
+    event.preventDefault();
+    try {
+      await setNativeChatApiKey(service.id, service.url, apiKey.trim() || null);
+      setApiKey('');
+      setEditingApiKey(false);
+      refreshModels();
+    } catch (reason) {
+      setError(String(reason));
+    }
+  };
+
+  return (
+    <div className="flex h-full flex-1 overflow-hidden bg-background" data-testid="native-chat">
+      <aside className="flex w-56 flex-none flex-col border-r bg-muted/30">
+        <div className="p-2">
+          <Button variant="outline" className="w-full" onClick={startConversation}>
+            <Plus className="h-4 w-4" />
+            新对话
+          </Button>
+        </div>
+        <nav className="flex-1 space-y-1 overflow-y-auto p-2">
+          {conversations.map((conversation) => (
+            <div
+              key={conversation.id}
+              className={cn(
+                'group flex items-center gap-2 rounded-md px-2 py-1.5 text-sm',
+                conversation.id === conversationId
+                  ? 'bg-primary/10 text-primary'
+                  : 'text-muted-foreground hover:bg-muted hover:text-foreground'
+              )}
+            >
+              <button
+                className="flex flex-1 items-center gap-2 truncate text-left"
+                onClick={() => openConversation(conversation.id)}
+              >
+                <MessageSquare className="h-3.5 w-3.5 flex-none" />
+                <span className="truncate">{conversation.title || '未命名对话'}</span>
+              </button>
+              <button
+                className="opacity-0 transition-opacity group-hover:opacity-100"
+                onClick={() => removeConversation(conversation.id)}
+                title="删除对话"
+              >
+                <Trash2 className="h-3.5 w-3.5" />
+              </button>
+            </div>
+          ))}
+        </nav>
+      </aside>
+
+      <div className="flex flex-1 flex-col overflow-hidden">
+        <header className="flex items-center gap-2 border-b px-4 py-2">
+          <h1 className="flex-1 truncate text-sm font-medium">{service.name}</h1>
+          <Input
+            className="h-8 w-56"
+            list={`native-chat-models-${service.id}`}
+            placeholder="模型"
+            value={model}
+            onChange={(event) => setModel(event.target.value)}
+            disabled={pending !== null}
+          />
+          <datalist id={`native-chat-models-${service.id}`}>
+            {models.map((name) => (
+              <option key={name} value={name} />
+            ))}
+          </datalist>
+          <Button
+            variant="ghost"
+            size="icon"
+            className="h-8 w-8"
+            onClick={() => setEditingApiKey((current) => !current)}
+            title="API Key"
+          >
+            <KeyRound className="h-4 w-4" />
+          </Button>
+        </header>
+
+        {editingApiKey && (
+          <form onSubmit={handleSaveApiKey} className="flex gap-2 border-b px-4 py-2">
+            <Input
+              type="password"
+              autoFocus
+              className="h-8"
+              placeholder="新的 API Key，留空则清除"
+              value={apiKey}
+              onChange={(event) => setApiKey(event.target.value)}
+            />
+            <Button type="submit" size="sm">
+              保存
+            </Button>
+          </form>
+        )}
+
+        <div className="flex-1 space-y-4 overflow-y-auto p-4">
+          {messages.length === 0 && !pending && (
+            <p className="pt-16 text-center text-sm text-muted-foreground">
+              向 {service.name} 发送消息开始对话
+            </p>
+          )}
+          {messages.map((message, index) => (
+            <ChatBubble key={index} role={message.role} content={message.content} />
+          ))}
+          {pending &&
+            (pending.text ? (
+              <ChatBubble role="assistant" content={pending.text} />
+            ) : (
+              <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+            ))}
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <form onSubmit={handleSend} className="flex gap-2 border-t p-4">
+          <Input
+            placeholder={model.trim() ? '输入消息' : '请先选择模型'}
+            value={input}
+            onChange={(event) => setInput(event.target.value)}
+          />
+          {pending ? (
+            <Button type="button" variant="outline" onClick={handleStop} title="停止">
+              <Square className="h-4 w-4" />
+            </Button>
+          ) : (
+            <Button type="submit" disabled={!input.trim() || !model.trim()} title="发送">
+              <Send className="h-4 w-4" />
+            </Button>
+          )}
+        </form>
+      </div>
+    </div>
+  );
+}
+
+function ChatBubble({ role, content }: NativeChatMessage) {
+  return (
+    <div className={cn('flex', role === 'user' ? 'justify-end' : 'justify-start')}>
+      <div
+        className={cn(
+          'max-w-[75%] whitespace-pre-wrap rounded-lg px-3 py-2 text-sm',
+          role === 'user' ? 'bg-primary text-primary-foreground' : 'bg-muted'
+        )}
+      >
+        {content}
+      </div>
+    </div>
+  );
+}
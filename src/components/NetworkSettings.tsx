@@ -0,0 +1,101 @@
+import { useEffect, useState } from 'react';
+import { X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { SettingsCard } from '@/components/SettingsCard';
+import { getHttpSettings, setHttpSettings, type HttpSettings } from '@/services/http';
+
+/** Extra CA certificates and system roots for requests AnyChat makes itself. */
+export function NetworkSettings() {
+  const [settings, setSettings] = useState<HttpSettings | null>(null);
+  const [newBundlePath, setNewBundlePath] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getHttpSettings()
+      .then(setSettings)
+      .catch((reason) => setError(String(reason)));
+  }, []);
+
+  const update = (next: Partial<HttpSettings>) => {
+    setSettings((current) => current && { ...current, ...next });
+    setMessage(null);
+  };
+
+  const handleAddBundle = () => {
+    const path = newBundlePath.trim();
+    if (!settings || !path || settings.caBundlePaths.includes(path)) return;
+    update({ caBundlePaths: [...settings.caBundlePaths, path] });
+    setNewBundlePath('');
+  };
+
+  const handleSave = () => {
+    if (!settings) return;
+    setSaving(true);
+    setMessage(null);
+    setError(null);
+    setHttpSettings(settings)
+      .then(() => setMessage('证书设置已保存。'))
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setSaving(false));
+  };
+
+  return (
+    <SettingsCard
+      title="证书"
+      description="AnyChat 自身的请求（同步、原生对话、图标）额外信任这些证书，网页沿用系统设置。"
+    >
+      <label className="flex items-center gap-2 text-sm">
+        <input
+          type="checkbox"
+          checked={settings?.useSystemRoots ?? false}
+          disabled={!settings}
+          onChange={(event) => update({ useSystemRoots: event.target.checked })}
+        />
+        信任系统证书库
+      </label>
+      {settings && settings.caBundlePaths.length > 0 && (
+        <ul className="space-y-1 text-sm">
+          {settings.caBundlePaths.map((path) => (
+            <li key={path} className="flex items-center justify-between gap-2">
+              <span className="truncate font-mono text-xs">{path}</span>
+              <Button
+                variant="ghost"
+                size="icon"
+                className="h-7 w-7 shrink-0"
+                title={`移除 ${path}`}
+                onClick={() =>
+                  update({ caBundlePaths: settings.caBundlePaths.filter((item) => item !== path) })
+                }
+              >
+                <X className="h-4 w-4" />
+              </Button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <div className="flex gap-2">
+        <Input
+          placeholder="CA 证书文件（PEM）的完整路径，例如：/etc/ssl/corp-ca.pem"
+          value={newBundlePath}
+          onChange={(event) => setNewBundlePath(event.target.value)}
+        />
+        <Button
+          variant="outline"
+          size="sm"
+          disabled={!settings || newBundlePath.trim() === ''}
+          onClick={handleAddBundle}
+        >
+          添加
+        </Button>
+      </div>
+      <Button variant="outline" size="sm" disabled={!settings || saving} onClick={handleSave}>
+        保存证书设置
+      </Button>
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+    </SettingsCard>
+  );
+}
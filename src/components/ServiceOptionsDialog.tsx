@@ -0,0 +1,285 @@
+import { useEffect, useState, type ReactNode } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { cn } from '@/lib/utils';
+import { clearServiceBrowsingData } from '@/services/browsing-data';
+import {
+  getServiceContentBlocking,
+  setServiceContentBlocking,
+} from '@/services/content-blocking';
+import { closePrivateService, openPrivateService } from '@/services/private-session';
+import { getServiceTheme, setServiceTheme, type ServiceTheme } from '@/services/theme';
+import { getServiceTlsPolicy, setServiceTlsPolicy, type TlsErrorPolicy } from '@/services/tls';
+import type { ChatService } from '@/types';
+
+const CONTENT_BLOCKING_CHOICES: Array<{ value: boolean | null; label: string }> = [
+  { value: null, label: '按站点默认' },
+  { value: true, label: '开启' },
+  { value: false, label: '关闭' },
+];
+
+const THEME_CHOICES: Array<{ value: ServiceTheme; label: string }> = [
+  { value: 'followApp', label: '跟随应用' },
+  { value: 'forceLight', label: '浅色' },
+  { value: 'forceDark', label: '深色' },
+];
+
+interface ServiceOptionsDialogProps {
+  /** The service to configure; the dialog is closed while `null`. */
+  service: ChatService | null;
+  onClose: () => void;
+}
+
+/** Per-service webview options that live in the Rust service registry. */
+export function ServiceOptionsDialog({ service, onClose }: ServiceOptionsDialogProps) {
+  const [contentBlocking, setContentBlocking] = useState<boolean | null | undefined>(undefined);
+  const [contentBlockingActive, setContentBlockingActive] = useState<boolean | null>(null);
+  const [theme, setTheme] = useState<ServiceTheme | null>(null);
+  const [tlsPolicy, setTlsPolicy] = useState<TlsErrorPolicy | null>(null);
+  const [fingerprint, setFingerprint] = useState('');
+  const [confirmClear, setConfirmClear] = useState(false);
+  const [clearing, setClearing] = useState(false);
+  const [cleared, setCleared] = useState<string | null>(null);
+  const [privateOpen, setPrivateOpen] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    setContentBlocking(undefined);
+    setContentBlockingActive(null);
+    setTheme(null);
+    setTlsPolicy(null);
+    setFingerprint('');
+    setConfirmClear(false);
+    setCleared(null);
+    setPrivateOpen(false);
+    setError(null);
+    if (!service) return;
+
+    getServiceContentBlocking(service.id)
+      .then(setContentBlocking)
+      .catch((reason) => setError(String(reason)));
+    getServiceTheme(service.id)
+      .then(setTheme)
+      .catch((reason) => setError(String(reason)));
+    getServiceTlsPolicy(service.id)
+      .then((policy) => {
+        setTlsPolicy(policy);
+        setFingerprint(policy.mode === 'allowPinned' ? policy.fingerprint : '');
+      })
+      .catch((reason) => setError(String(reason)));
+  }, [service?.id]);
+
+  if (!service) {
+    return null;
+  }
+
+  const handleContentBlocking = (enabled: boolean | null) => {
+    setError(null);
+    setServiceContentBlocking(service, enabled)
+      .then((active) => {
+        setContentBlocking(enabled);
+        setContentBlockingActive(active);
+      })
+      .catch((reason) => setError(String(reason)));
+  };
+
+  const handleTheme = (next: ServiceTheme) => {
+    setError(null);
+    setServiceTheme(service.id, next)
+      .then(() => setTheme(next))
+      .catch((reason) => setError(String(reason)));
+  };
+
+  const handleTlsPolicy = (next: TlsErrorPolicy) => {
+    setError(null);
+    setServiceTlsPolicy(service.id, next)
+      .then(() => setTlsPolicy(next))
+      .catch((reason) => setError(String(reason)));
+  };
+
+  const handleClear = (cacheOnly: boolean) => {
+    if (!cacheOnly && !confirmClear) {
+      setConfirmClear(true);
+      return;
+    }
+    setClearing(true);
+    setConfirmClear(false);
+    setCleared(null);
+    setError(null);
+    clearServiceBrowsingData(service.id, cacheOnly)
+      .then((removed) =>
+        setCleared(
+          cacheOnly
+            ? '缓存已清除，页面已重新打开。'
+            : `已清除 ${removed} 条站点数据，页面已重新打开。`
+        )
+      )
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setClearing(false));
+  };
+
+  const handlePrivateSession = () => {
+    setError(null);
+    const task = privateOpen ? closePrivateService(service.id) : openPrivateService(service);
+    task.then(() => setPrivateOpen((open) => !open)).catch((reason) => setError(String(reason)));
+  };
+
+  return (
+    <Dialog open onOpenChange={(open) => !open && onClose()}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>{service.name} 设置</DialogTitle>
+          <DialogDescription>修改会立即应用到已打开的页面。</DialogDescription>
+        </DialogHeader>
+
+        <OptionSection
+          title="内容拦截"
+          description={
+            contentBlockingActive === null
+              ? '隐藏广告与升级弹窗，并拦截规则列表中的追踪域名。'
+              : contentBlockingActive
+                ? '内容拦截已对该服务生效。'
+                : '内容拦截已对该服务停用。'
+          }
+        >
+          {CONTENT_BLOCKING_CHOICES.map(({ value, label }) => (
+            <ChoiceButton
+              key={label}
+              selected={contentBlocking === value}
+              disabled={contentBlocking === undefined}
+              onClick={() => handleContentBlocking(value)}
+            >
+              {label}
+            </ChoiceButton>
+          ))}
+        </OptionSection>
+
+        <OptionSection title="主题" description="强制深色时，没有深色模式的网站会以反色显示。">
+          {THEME_CHOICES.map(({ value, label }) => (
+            <ChoiceButton
+              key={value}
+              selected={theme === value}
+              disabled={theme === null}
+              onClick={() => handleTheme(value)}
+            >
+              {label}
+            </ChoiceButton>
+          ))}
+        </OptionSection>
+
+        <OptionSection
+          title="证书错误"
+          description={
+            tlsPolicy?.mode === 'allowPinned'
+              ? '只放行下面这张证书，其他证书错误仍会拦截（仅 Linux 支持）。'
+              : '证书无效时显示说明页面；内网自签名服务可固定信任一张证书（仅 Linux 支持）。'
+          }
+        >
+          <ChoiceButton
+            selected={tlsPolicy?.mode === 'block'}
+            disabled={tlsPolicy === null}
+            onClick={() => handleTlsPolicy({ mode: 'block' })}
+          >
+            拦截
+          </ChoiceButton>
+          <Input
+            className="h-8 font-mono text-xs"
+            placeholder="证书的 SHA-256 指纹，例如：AB:CD:…"
+            value={fingerprint}
+            disabled={tlsPolicy === null}
+            onChange={(event) => setFingerprint(event.target.value)}
+          />
+          <ChoiceButton
+            selected={tlsPolicy?.mode === 'allowPinned'}
+            disabled={tlsPolicy === null || fingerprint.trim() === ''}
+            onClick={() =>
+              handleTlsPolicy({ mode: 'allowPinned', fingerprint: fingerprint.trim() })
+            }
+          >
+            信任此证书
+          </ChoiceButton>
+        </OptionSection>
+
+        <OptionSection
+          title="浏览数据"
+          description={cleared ?? '页面异常时可清除该服务的缓存，或清除全部 Cookie 与站点存储。'}
+        >
+          <Button variant="outline" size="sm" disabled={clearing} onClick={() => handleClear(true)}>
+            仅清除缓存
+          </Button>
+          <Button
+            variant="outline"
+            size="sm"
+            className="text-destructive hover:bg-destructive/10 hover:text-destructive"
+            disabled={clearing}
+            onClick={() => handleClear(false)}
+          >
+            {confirmClear ? '确认清除（需要重新登录）' : '清除全部数据'}
+          </Button>
+        </OptionSection>
+
+        <OptionSection
+          title="无痕会话"
+          description="在单独的窗口中打开该服务，关闭窗口或退出 AnyChat 后 Cookie 与存储全部丢弃。"
+        >
+          <Button variant="outline" size="sm" onClick={handlePrivateSession}>
+            {privateOpen ? '关闭无痕窗口' : '无痕打开'}
+          </Button>
+        </OptionSection>
+
+        {error && <p className="text-sm text-destructive">{error}</p>}
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+function OptionSection({
+  title,
+  description,
+  children,
+}: {
+  title: string;
+  description: string;
+  children: ReactNode;
+}) {
+  return (
+    <section className="space-y-2">
+      <div>
+        <h3 className="text-sm font-medium">{title}</h3>
+        <p className="text-xs text-muted-foreground">{description}</p>
+      </div>
+      <div className="flex flex-wrap gap-2">{children}</div>
+    </section>
+  );
+}
+
+function ChoiceButton({
+  selected,
+  disabled,
+  onClick,
+  children,
+}: {
+  selected: boolean;
+  disabled?: boolean;
+  onClick: () => void;
+  children: ReactNode;
+}) {
+  return (
+    <Button
+      variant="outline"
+      size="sm"
+      className={cn(selected && 'border-primary bg-primary/10 text-primary')}
+      disabled={disabled}
+      onClick={onClick}
+    >
+      {children}
+    </Button>
+  );
+}
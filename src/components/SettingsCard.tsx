@@ -0,0 +1,22 @@
+import type { ReactNode } from 'react';
+
+/** A titled block on the general settings page. */
+export function SettingsCard({
+  title,
+  description,
+  children,
+}: {
+  title: string;
+  description: string;
+  children: ReactNode;
+}) {
+  return (
+    <section className="space-y-3 rounded-2xl border bg-card p-5 text-card-foreground">
+      <div className="space-y-1">
+        <h3 className="text-base font-semibold">{title}</h3>
+        <p className="text-sm text-muted-foreground">{description}</p>
+      </div>
+      {children}
+    </section>
+  );
+}
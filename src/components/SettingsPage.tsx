@@ -27,22 +27,34 @@ import {
   GripVertical,
   Info,
   Loader2,
+  LogIn,
   MessageSquare,
   Plus,
+  Radar,
   Settings,
+  Settings2,
+  SlidersHorizontal,
   Sparkles,
   Trash2,
   Zap,
 } from 'lucide-react';
 import { motion } from 'framer-motion';
 import { useAppStore } from '@/stores/app-store';
+import { BrowserImportDialog } from '@/components/BrowserImportDialog';
+import { GeneralSettings } from '@/components/GeneralSettings';
+import { ServiceOptionsDialog } from '@/components/ServiceOptionsDialog';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Dialog, DialogContent, DialogHeader, DialogTitle } from '@/components/ui/dialog';
 import { cn } from '@/lib/utils';
 import { findWorkingIconCandidate, normalizeServiceUrl } from '@/lib/icon';
 import { useCachedIcon } from '@/hooks/useCachedIcon';
-import type { ChatService } from '@/types';
+import { usePolicy } from '@/hooks/usePolicy';
+import { supportsBasicAuthPrompt } from '@/services/content-host';
+import { discoverLocalServers, type DiscoveredServer } from '@/services/local-servers';
+import { setNativeChatApiKey } from '@/services/native-chat';
+import { enabledOverride } from '@/services/policy';
+import type { ChatService, ServiceKind } from '@/types';
 
 const ANYCHAT_REPO_URL = 'https://github.com/JS-banana/anychat';
 const AMBERKEEPER_REPO_URL = 'https://github.com/JS-banana/AmberKeeper';
@@ -57,13 +69,34 @@ const PRESET_ICONS = [
   { id: 'circle', Icon: CircleDot, label: '圆点' },
 ] as const;
 
+const SERVICE_KINDS: Array<{ kind: ServiceKind; label: string; placeholder: string }> = [
+  { kind: 'web', label: '网页', placeholder: '例如：https://chat.example.com' },
+  { kind: 'selfHosted', label: '自托管', placeholder: '例如：http://localhost:3000' },
+  {
+    kind: 'openAiCompatible',
+    label: 'OpenAI 兼容 API',
+    placeholder: '例如：http://localhost:11434',
+  },
+];
+
 interface SortableServiceItemProps {
   service: ChatService;
+  /** The administrator policy pins this service's visibility. */
+  pinned: boolean;
   onToggle: () => void;
   onRemove: () => void;
+  onImportSession: () => void;
+  onOpenOptions: () => void;
 }
 
-function SortableServiceItem({ service, onToggle, onRemove }: SortableServiceItemProps) {
+function SortableServiceItem({
+  service,
+  pinned,
+  onToggle,
+  onRemove,
+  onImportSession,
+  onOpenOptions,
+}: SortableServiceItemProps) {
   const updateService = useAppStore((state) => state.updateService);
   const {
     iconSrc: iconUrl,
@@ -118,7 +151,38 @@ function SortableServiceItem({ service, onToggle, onRemove }: SortableServiceIte
         <p className="truncate text-xs text-muted-foreground">{service.url}</p>
       </div>
 
-      <Button variant="ghost" size="icon" className="h-8 w-8" onClick={onToggle}>
+      {service.kind !== 'openAiCompatible' && (
+        <Button
+          variant="ghost"
+          size="icon"
+          className="h-8 w-8"
+          onClick={onImportSession}
+          title="从浏览器导入登录状态"
+        >
+          <LogIn className="h-4 w-4" />
+        </Button>
+      )}
+
+      {service.kind !== 'openAiCompatible' && (
+        <Button
+          variant="ghost"
+          size="icon"
+          className="h-8 w-8"
+          onClick={onOpenOptions}
+          title="服务设置"
+        >
+          <SlidersHorizontal className="h-4 w-4" />
+        </Button>
+      )}
+
+      <Button
+        variant="ghost"
+        size="icon"
+        className="h-8 w-8"
+        onClick={onToggle}
+        disabled={pinned}
+        title={pinned ? '由管理员策略设定' : undefined}
+      >
         {service.enabled ? <Eye className="h-4 w-4" /> : <EyeOff className="h-4 w-4" />}
       </Button>
 
@@ -177,14 +241,23 @@ export function SettingsPage() {
     addService,
   } = useAppStore();
 
+  const policy = usePolicy();
   const [showAddDialog, setShowAddDialog] = useState(false);
   const [newServiceName, setNewServiceName] = useState('');
   const [newServiceUrl, setNewServiceUrl] = useState('');
+  const [newServiceKind, setNewServiceKind] = useState<ServiceKind>('web');
+  const [newServiceApiKey, setNewServiceApiKey] = useState('');
+  const [basicAuthPrompt, setBasicAuthPrompt] = useState(true);
   const [fetchedLogoUrl, setFetchedLogoUrl] = useState<string | null>(null);
   const [selectedPresetIcon, setSelectedPresetIcon] = useState<string | null>(null);
   const [logoLoading, setLogoLoading] = useState(false);
+  const [importService, setImportService] = useState<ChatService | null>(null);
+  const [optionsService, setOptionsService] = useState<ChatService | null>(null);
+  const [discoveredServers, setDiscoveredServers] = useState<DiscoveredServer[] | null>(null);
+  const [discovering, setDiscovering] = useState(false);
 
   const sortedServices = [...services].sort((a, b) => a.order - b.order);
+  const urlPlaceholder = SERVICE_KINDS.find(({ kind }) => kind === newServiceKind)?.placeholder;
   const sensors = useSensors(
     useSensor(PointerSensor),
     useSensor(KeyboardSensor, {
@@ -218,6 +291,14 @@ export function SettingsPage() {
     return () => clearTimeout(timer);
   }, [newServiceUrl]);
 
+  useEffect(() => {
+    if (newServiceKind !== 'selfHosted') return;
+
+    supportsBasicAuthPrompt()
+      .then(setBasicAuthPrompt)
+      .catch(() => setBasicAuthPrompt(true));
+  }, [newServiceKind]);
+
   const handleDragEnd = (event: DragEndEvent) => {
     const { active, over } = event;
     if (!over || active.id === over.id) return;
@@ -230,23 +311,60 @@ export function SettingsPage() {
   const handleAddService = () => {
     if (!newServiceName.trim() || !newServiceUrl.trim()) return;
 
-    const normalizedUrl = normalizeServiceUrl(newServiceUrl);
+    // Local servers rarely have certificates, so their addresses default to http.
+    const rawUrl =
+      newServiceKind === 'web' || /^https?:\/\//i.test(newServiceUrl.trim())
+        ? newServiceUrl
+        : `http://${newServiceUrl.trim()}`;
+    const normalizedUrl = normalizeServiceUrl(rawUrl);
     if (!normalizedUrl) return;
 
-    addService({
+    const id = addService({
       name: newServiceName.trim(),
       url: normalizedUrl,
       enabled: true,
       iconUrl: fetchedLogoUrl || undefined,
+      ...(newServiceKind === 'web' ? {} : { kind: newServiceKind }),
     });
+    if (newServiceKind === 'openAiCompatible' && newServiceApiKey.trim()) {
+      setNativeChatApiKey(id, normalizedUrl, newServiceApiKey.trim()).catch(console.error);
+    }
 
     setNewServiceName('');
     setNewServiceUrl('');
+    setNewServiceKind('web');
+    setNewServiceApiKey('');
     setFetchedLogoUrl(null);
     setSelectedPresetIcon(null);
     setShowAddDialog(false);
   };
 
+  const existingServiceUrls = services.map((service) => normalizeServiceUrl(service.url));
+  const newDiscoveredServers = (discoveredServers ?? []).filter(
+    (server) => !existingServiceUrls.includes(normalizeServiceUrl(server.url))
+  );
+
+  const handleDiscover = () => {
+    setDiscovering(true);
+    discoverLocalServers()
+      .then(setDiscoveredServers)
+      .catch((error) => {
+        console.error(error);
+        setDiscoveredServers([]);
+      })
+      .finally(() => setDiscovering(false));
+  };
+
+  const handleAddDiscovered = (server: DiscoveredServer) => {
+    addService({
+      name: server.name,
+      url: normalizeServiceUrl(server.url) ?? server.url,
+      enabled: true,
+      iconUrl: server.iconUrl ?? undefined,
+      kind: server.hasWebUi ? 'selfHosted' : 'openAiCompatible',
+    });
+  };
+
   if (!settingsPageOpen) {
     return null;
   }
@@ -268,6 +386,19 @@ export function SettingsPage() {
             服务管理
           </button>
 
+          <button
+            onClick={() => setSettingsActiveTab('general')}
+            className={cn(
+              'flex w-full items-center gap-3 rounded-md px-3 py-2 text-sm font-medium transition-colors',
+              settingsActiveTab === 'general'
+                ? 'border-l-2 border-primary bg-primary/10 text-primary'
+                : 'text-muted-foreground hover:bg-muted hover:text-foreground'
+            )}
+          >
+            <Settings2 className="h-4 w-4" />
+            通用
+          </button>
+
           <button
             onClick={() => setSettingsActiveTab('about')}
             className={cn(
@@ -293,7 +424,10 @@ export function SettingsPage() {
                   拖拽排序服务。点击眼睛图标切换显示或隐藏。
                 </p>
               </div>
-              <Button onClick={() => setShowAddDialog(true)}>
+              <Button
+                onClick={() => setShowAddDialog(true)}
+                disabled={policy?.disableCustomServices ?? false}
+              >
                 <Plus className="mr-2 h-4 w-4" />
                 添加服务
               </Button>
@@ -313,8 +447,11 @@ export function SettingsPage() {
                     <SortableServiceItem
                       key={service.id}
                       service={service}
+                      pinned={policy !== null && enabledOverride(policy, service) !== null}
                       onToggle={() => toggleServiceEnabled(service.id)}
                       onRemove={() => removeService(service.id)}
+                      onImportSession={() => setImportService(service)}
+                      onOpenOptions={() => setOptionsService(service)}
                     />
                   ))}
                 </div>
@@ -323,6 +460,8 @@ export function SettingsPage() {
           </div>
         )}
 
+        {settingsActiveTab === 'general' && <GeneralSettings />}
+
         {settingsActiveTab === 'about' && (
           <div className="flex-1 overflow-y-auto p-8 max-w-3xl">
             <div className="space-y-6">
@@ -370,6 +509,9 @@ export function SettingsPage() {
         )}
       </div>
 
+      <BrowserImportDialog service={importService} onClose={() => setImportService(null)} />
+      <ServiceOptionsDialog service={optionsService} onClose={() => setOptionsService(null)} />
+
       <Dialog open={showAddDialog} onOpenChange={setShowAddDialog}>
         <DialogContent className="sm:max-w-md">
           <DialogHeader>
@@ -377,6 +519,57 @@ export function SettingsPage() {
           </DialogHeader>
 
           <div className="space-y-4 py-4">
+            <div className="space-y-2">
+              <Button
+                type="button"
+                variant="outline"
+                size="sm"
+                onClick={handleDiscover}
+                disabled={discovering}
+              >
+                {discovering ? (
+                  <Loader2 className="h-4 w-4 animate-spin" />
+                ) : (
+                  <Radar className="h-4 w-4" />
+                )}
+                扫描本机 AI 服务
+              </Button>
+              {discoveredServers !== null && newDiscoveredServers.length === 0 && (
+                <p className="text-xs text-muted-foreground">没有发现尚未添加的本机服务。</p>
+              )}
+              {newDiscoveredServers.map((server) => (
+                <div
+                  key={server.url}
+                  className="flex items-center justify-between gap-2 rounded-lg border p-2 text-sm"
+                >
+                  <div>
+                    <p className="font-medium">{server.name}</p>
+                    <p className="text-xs text-muted-foreground">{server.url}</p>
+                  </div>
+                  <Button size="sm" onClick={() => handleAddDiscovered(server)}>
+                    添加 {server.name}
+                  </Button>
+                </div>
+              ))}
+            </div>
+
+            <div className="space-y-2">
+              <label className="text-sm font-medium">服务类型</label>
+              <div className="flex gap-2">
+                {SERVICE_KINDS.map(({ kind, label }) => (
+                  <Button
+                    key={kind}
+                    type="button"
+                    size="sm"
+                    variant={newServiceKind === kind ? 'default' : 'outline'}
+                    onClick={() => setNewServiceKind(kind)}
+                  >
+                    {label}
+                  </Button>
+                ))}
+              </div>
+            </div>
+
             <div className="space-y-2">
               <label className="text-sm font-medium">服务名称</label>
               <Input
@@ -390,12 +583,32 @@ export function SettingsPage() {
             <div className="space-y-2">
               <label className="text-sm font-medium">服务地址</label>
               <Input
-                placeholder="例如：https://chat.example.com"
+                placeholder={urlPlaceholder}
                 value={newServiceUrl}
                 onChange={(event) => setNewServiceUrl(event.target.value)}
               />
             </div>
 
+            {newServiceKind === 'selfHosted' && (
+              <p className="text-xs text-muted-foreground">
+                仅允许本机或局域网地址使用 http。
+                {!basicAuthPrompt &&
+                  ' macOS 上无法弹出 HTTP Basic 认证窗口，受其保护的服务将无法加载，请改用服务自带的登录方式。'}
+              </p>
+            )}
+
+            {newServiceKind === 'openAiCompatible' && (
+              <div className="space-y-2">
+                <label className="text-sm font-medium">API Key</label>
+                <Input
+                  type="password"
+                  placeholder="可选，本地 Ollama 等无需填写"
+                  value={newServiceApiKey}
+                  onChange={(event) => setNewServiceApiKey(event.target.value)}
+                />
+              </div>
+            )}
+
             <div className="space-y-2">
               <label className="text-sm font-medium">图标预览</label>
               <div className="flex items-center gap-4">
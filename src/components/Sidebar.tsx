@@ -5,6 +5,8 @@ import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from '@/comp
 import { SIDEBAR_WIDTH } from '@/lib/layout';
 import { cn } from '@/lib/utils';
 import { useCachedIcon } from '@/hooks/useCachedIcon';
+import { useKeepAliveStatuses } from '@/hooks/useKeepAliveStatuses';
+import { useLoginStates } from '@/hooks/useLoginStates';
 
 interface ServiceIconProps {
   serviceId: string;
@@ -49,6 +51,8 @@ function ServiceIcon({
 export function Sidebar() {
   const { services, activeServiceId, setActiveService, setSettingsPageOpen, updateService } =
     useAppStore();
+  const keepAliveStatuses = useKeepAliveStatuses();
+  const loginStates = useLoginStates();
 
   const enabledServices = services.filter((s) => s.enabled).sort((a, b) => a.order - b.order);
 
@@ -62,6 +66,17 @@ export function Sidebar() {
         <div className="flex flex-1 flex-col items-center gap-2">
           {enabledServices.map((service) => {
             const isActive = activeServiceId === service.id;
+            const loginState = loginStates[service.id];
+            const keepAlive = keepAliveStatuses[service.id]?.outcome;
+            // Detected sign-ins win over an older keep-alive result.
+            const signedOut =
+              loginState === 'signedOut' ||
+              (loginState !== 'signedIn' && keepAlive === 'signedOut');
+            const tooltip = signedOut
+              ? `${service.name}（已登出）`
+              : keepAlive === 'failed'
+                ? `${service.name}（会话检查失败）`
+                : service.name;
 
             return (
               <Tooltip key={service.id}>
@@ -88,10 +103,13 @@ export function Sidebar() {
                         updateService(service.id, { iconUrl: resolvedIconUrl });
                       }}
                     />
+                    {signedOut && (
+                      <span className="absolute right-1 top-1 h-2.5 w-2.5 rounded-full bg-red-500 ring-2 ring-sidebar" />
+                    )}
                   </button>
                 </TooltipTrigger>
                 <TooltipContent side="right">
-                  <p>{service.name}</p>
+                  <p>{tooltip}</p>
                 </TooltipContent>
               </Tooltip>
             );
@@ -0,0 +1,236 @@
+import { useEffect, useState } from 'react';
+import { RefreshCw } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { SettingsCard } from '@/components/SettingsCard';
+import { cn } from '@/lib/utils';
+import {
+  getSyncConflicts,
+  getSyncStatus,
+  setSyncSettings,
+  syncNow,
+  type SyncConflict,
+  type SyncStatus,
+} from '@/services/sync';
+import { useAppStore } from '@/stores/app-store';
+
+type TargetType = 'off' | 'folder' | 'webDav';
+
+const TARGET_TYPES: Array<{ value: TargetType; label: string }> = [
+  { value: 'off', label: '关闭' },
+  { value: 'folder', label: '文件夹' },
+  { value: 'webDav', label: 'WebDAV' },
+];
+
+/** Newest conflicts shown; older ones stay in the log file. */
+const MAX_LISTED_CONFLICTS = 20;
+
+/** Where synced data goes, its secrets, and the log of merge conflicts. */
+export function SyncSettings() {
+  const { services, replaceServices } = useAppStore();
+  const [status, setStatus] = useState<SyncStatus | null>(null);
+  const [targetType, setTargetType] = useState<TargetType>('off');
+  const [folderPath, setFolderPath] = useState('');
+  const [webDavUrl, setWebDavUrl] = useState('');
+  const [webDavUsername, setWebDavUsername] = useState('');
+  const [webDavPassword, setWebDavPassword] = useState('');
+  const [passphrase, setPassphrase] = useState('');
+  const [encrypt, setEncrypt] = useState(false);
+  const [conflicts, setConflicts] = useState<SyncConflict[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const applyStatus = (next: SyncStatus) => {
+    setStatus(next);
+    setEncrypt(next.settings.encrypt);
+    const target = next.settings.target;
+    setTargetType(target?.type ?? 'off');
+    if (target?.type === 'folder') {
+      setFolderPath(target.path);
+    } else if (target?.type === 'webDav') {
+      setWebDavUrl(target.url);
+      setWebDavUsername(target.username);
+    }
+  };
+
+  const loadConflicts = () =>
+    getSyncConflicts()
+      .then(setConflicts)
+      .catch((reason) => setError(String(reason)));
+
+  useEffect(() => {
+    getSyncStatus()
+      .then(applyStatus)
+      .catch((reason) => setError(String(reason)));
+    void loadConflicts();
+  }, []);
+
+  const handleSave = () => {
+    const target =
+      targetType === 'folder'
+        ? { type: 'folder' as const, path: folderPath.trim() }
+        : targetType === 'webDav'
+          ? { type: 'webDav' as const, url: webDavUrl.trim(), username: webDavUsername.trim() }
+          : null;
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+    setSyncSettings({ target, encrypt }, webDavPassword || null, passphrase || null)
+      .then((next) => {
+        applyStatus(next);
+        setWebDavPassword('');
+        setPassphrase('');
+        setMessage(target ? '同步设置已保存。' : '同步已关闭。');
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const handleSyncNow = () => {
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+    syncNow(services)
+      .then((report) => {
+        if (report.services) {
+          replaceServices(report.services);
+        }
+        setMessage(
+          `同步完成：应用了 ${report.applied} 项远端更改` +
+            (report.conflicts.length > 0 ? `，${report.conflicts.length} 项冲突` : '') +
+            '。'
+        );
+        return Promise.all([getSyncStatus().then(applyStatus), loadConflicts()]);
+      })
+      .catch((reason) => setError(String(reason)))
+      .finally(() => setBusy(false));
+  };
+
+  const canSave =
+    !busy &&
+    (targetType === 'off' ||
+      (targetType === 'folder' && folderPath.trim() !== '') ||
+      (targetType === 'webDav' && webDavUrl.trim() !== ''));
+  const listedConflicts = [...conflicts]
+    .sort((a, b) => b.detectedAt - a.detectedAt)
+    .slice(0, MAX_LISTED_CONFLICTS);
+
+  return (
+    <SettingsCard
+      title="同步"
+      description="把服务列表与各服务的设置同步到文件夹（如网盘目录）或 WebDAV，供多台设备共用。"
+    >
+      <div className="flex gap-2">
+        {TARGET_TYPES.map(({ value, label }) => (
+          <Button
+            key={value}
+            variant="outline"
+            size="sm"
+            className={cn(targetType === value && 'border-primary bg-primary/10 text-primary')}
+            disabled={status === null}
+            onClick={() => setTargetType(value)}
+          >
+            {label}
+          </Button>
+        ))}
+      </div>
+
+      {targetType === 'folder' && (
+        <Input
+          placeholder="同步文件夹的完整路径，例如：/home/me/Nextcloud/anychat"
+          value={folderPath}
+          onChange={(event) => setFolderPath(event.target.value)}
+        />
+      )}
+      {targetType === 'webDav' && (
+        <>
+          <Input
+            placeholder="WebDAV 目录地址，例如：https://dav.example.com/anychat/"
+            value={webDavUrl}
+            onChange={(event) => setWebDavUrl(event.target.value)}
+          />
+          <Input
+            placeholder="WebDAV 用户名"
+            value={webDavUsername}
+            onChange={(event) => setWebDavUsername(event.target.value)}
+          />
+          <Input
+            type="password"
+            placeholder={
+              status?.hasWebdavPassword ? 'WebDAV 密码（已保存，留空则不修改）' : 'WebDAV 密码'
+            }
+            value={webDavPassword}
+            onChange={(event) => setWebDavPassword(event.target.value)}
+          />
+        </>
+      )}
+
+      {targetType !== 'off' && (
+        <>
+          <label className="flex items-center gap-2 text-sm">
+            <input
+              type="checkbox"
+              checked={encrypt}
+              onChange={(event) => setEncrypt(event.target.checked)}
+            />
+            加密同步文件
+          </label>
+          {encrypt && (
+            <Input
+              type="password"
+              placeholder={
+                status?.hasPassphrase
+                  ? '同步口令（已保存，留空则不修改）'
+                  : '同步口令，至少 8 个字符，其他设备需填写同一口令'
+              }
+              value={passphrase}
+              onChange={(event) => setPassphrase(event.target.value)}
+            />
+          )}
+        </>
+      )}
+
+      <div className="flex gap-2">
+        <Button variant="outline" size="sm" disabled={!canSave} onClick={handleSave}>
+          保存同步设置
+        </Button>
+        {status?.settings.target && (
+          <Button variant="outline" size="sm" disabled={busy} onClick={handleSyncNow}>
+            <RefreshCw className={busy ? 'h-4 w-4 animate-spin' : 'h-4 w-4'} />
+            立即同步
+          </Button>
+        )}
+      </div>
+      {status?.settings.target && (
+        <p className="text-sm text-muted-foreground">
+          {status.lastSyncedAt
+            ? `上次同步：${new Date(status.lastSyncedAt).toLocaleString()}`
+            : '尚未同步'}
+        </p>
+      )}
+      {message && <p className="text-sm text-muted-foreground">{message}</p>}
+      {error && <p className="text-sm text-destructive">{error}</p>}
+
+      {listedConflicts.length > 0 && (
+        <div className="space-y-1">
+          <h4 className="text-sm font-medium">冲突记录</h4>
+          <ul className="space-y-1 text-sm">
+            {listedConflicts.map((conflict) => (
+              <li
+                key={`${conflict.key}-${conflict.detectedAt}`}
+                className="flex justify-between gap-2"
+              >
+                <span className="truncate">{conflict.key}</span>
+                <span className="shrink-0 text-muted-foreground">
+                  {conflict.winner === 'local' ? '保留本机' : '采用远端'} ·{' '}
+                  {new Date(conflict.detectedAt).toLocaleString()}
+                </span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </SettingsCard>
+  );
+}
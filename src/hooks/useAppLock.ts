@@ -0,0 +1,46 @@
+import { useEffect, useState } from 'react';
+import {
+  getAppLockStatus,
+  listenAppLock,
+  reportAppActivity,
+  type AppLockStatus,
+} from '@/services/app-lock';
+
+const ACTIVITY_REPORT_INTERVAL_MS = 30_000;
+const ACTIVITY_EVENTS = ['keydown', 'pointerdown', 'wheel'] as const;
+
+/** Current app lock status; also reports input in the AnyChat UI for the idle timer. */
+export function useAppLock() {
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+
+  useEffect(() => {
+    let disposed = false;
+    const unlisten = listenAppLock(setStatus);
+    getAppLockStatus()
+      .then((initial) => {
+        if (!disposed) setStatus((current) => current ?? initial);
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load app lock status:', error));
+
+    let lastReport = 0;
+    const onActivity = () => {
+      const now = Date.now();
+      if (now - lastReport < ACTIVITY_REPORT_INTERVAL_MS) return;
+      lastReport = now;
+      reportAppActivity().catch(console.error);
+    };
+    ACTIVITY_EVENTS.forEach((type) =>
+      window.addEventListener(type, onActivity, { capture: true, passive: true })
+    );
+
+    return () => {
+      disposed = true;
+      void unlisten.then((fn) => fn());
+      ACTIVITY_EVENTS.forEach((type) =>
+        window.removeEventListener(type, onActivity, { capture: true })
+      );
+    };
+  }, []);
+
+  return { status, setStatus };
+}
@@ -0,0 +1,32 @@
+import { useEffect } from 'react';
+import { getAppTheme, type AppTheme } from '@/services/theme';
+
+const DARK_SCHEME_QUERY = '(prefers-color-scheme: dark)';
+
+/** Applies `theme` to the AnyChat UI; service webviews are updated by `setAppTheme`. */
+export function applyAppTheme(theme: AppTheme) {
+  const root = document.documentElement;
+  const dark =
+    theme === 'dark' || (theme === 'system' && window.matchMedia?.(DARK_SCHEME_QUERY).matches);
+  root.dataset.theme = theme;
+  root.classList.toggle('dark', Boolean(dark));
+}
+
+/** Loads the saved app theme and keeps the `system` theme in step with the OS. */
+export function useAppTheme() {
+  useEffect(() => {
+    getAppTheme()
+      .then((theme) => applyAppTheme(theme ?? 'system'))
+      .catch((error) => console.error('[AnyChat] Failed to load app theme:', error));
+
+    const query = window.matchMedia?.(DARK_SCHEME_QUERY);
+    if (!query) return;
+    const onChange = () => {
+      if (document.documentElement.dataset.theme === 'system') {
+        applyAppTheme('system');
+      }
+    };
+    query.addEventListener('change', onChange);
+    return () => query.removeEventListener('change', onChange);
+  }, []);
+}
@@ -0,0 +1,28 @@
+import { useEffect, useState } from 'react';
+import {
+  getDataEncryptionStatus,
+  listenDataEncryption,
+  type DataEncryptionStatus,
+} from '@/services/data-encryption';
+
+/** Encryption state of AnyChat's data files, kept current by backend events. */
+export function useDataEncryption() {
+  const [status, setStatus] = useState<DataEncryptionStatus | null>(null);
+
+  useEffect(() => {
+    let disposed = false;
+    const unlisten = listenDataEncryption(setStatus);
+    getDataEncryptionStatus()
+      .then((initial) => {
+        if (!disposed) setStatus((current) => current ?? initial);
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load data encryption status:', error));
+
+    return () => {
+      disposed = true;
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return status;
+}
@@ -0,0 +1,26 @@
+import { useEffect, useState } from 'react';
+import { getKeepAliveStatuses, listenKeepAlive, type KeepAliveStatus } from '@/services/keep-alive';
+
+/** Latest keep-alive result per service, kept current by backend events. */
+export function useKeepAliveStatuses() {
+  const [statuses, setStatuses] = useState<Record<string, KeepAliveStatus>>({});
+
+  useEffect(() => {
+    let disposed = false;
+    const unlisten = listenKeepAlive(({ serviceId, status }) => {
+      setStatuses((current) => ({ ...current, [serviceId]: status }));
+    });
+    getKeepAliveStatuses()
+      .then((initial) => {
+        if (!disposed) setStatuses((current) => ({ ...initial, ...current }));
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load keep-alive statuses:', error));
+
+    return () => {
+      disposed = true;
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return statuses;
+}
@@ -1,13 +1,49 @@
 import { useEffect } from 'react';
 import { useAppStore } from '@/stores/app-store';
+import { openFindInService } from '@/services/find';
+import { serviceGoBack, serviceGoForward } from '@/services/navigation';
+import { zoomServiceIn, zoomServiceOut, zoomServiceReset } from '@/services/zoom';
 
 export function useKeyboardShortcuts() {
-  const { services, setActiveService } = useAppStore();
+  const { services, activeServiceId, setActiveService } = useAppStore();
 
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.altKey && !(e.metaKey || e.ctrlKey) && (e.key === 'ArrowLeft' || e.key === 'ArrowRight')) {
+        e.preventDefault();
+        if (activeServiceId) {
+          const navigate = e.key === 'ArrowLeft' ? serviceGoBack : serviceGoForward;
+          navigate(activeServiceId).catch((error) => console.error('Failed to navigate service:', error));
+        }
+        return;
+      }
+
       if (!(e.metaKey || e.ctrlKey)) return;
 
+      if (e.key === 'f' || e.key === 'F') {
+        e.preventDefault();
+        if (activeServiceId) {
+          openFindInService(activeServiceId).catch((error) => console.error('Failed to open find bar:', error));
+        }
+        return;
+      }
+
+      const zoom =
+        e.key === '=' || e.key === '+'
+          ? zoomServiceIn
+          : e.key === '-'
+            ? zoomServiceOut
+            : e.key === '0'
+              ? zoomServiceReset
+              : null;
+      if (zoom) {
+        e.preventDefault();
+        if (activeServiceId) {
+          zoom(activeServiceId).catch((error) => console.error('Failed to zoom service:', error));
+        }
+        return;
+      }
+
       const enabledServices = services.filter((s) => s.enabled).sort((a, b) => a.order - b.order);
 
       const num = parseInt(e.key);
@@ -22,5 +58,5 @@ export function useKeyboardShortcuts() {
 
     window.addEventListener('keydown', handleKeyDown);
     return () => window.removeEventListener('keydown', handleKeyDown);
-  }, [services, setActiveService]);
+  }, [services, activeServiceId, setActiveService]);
 }
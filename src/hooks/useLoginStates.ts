@@ -0,0 +1,30 @@
+import { useEffect, useState } from 'react';
+import {
+  getServiceLoginStates,
+  listenServiceLoginState,
+  type LoginState,
+} from '@/services/login-state';
+
+/** Latest login state per service, kept current by backend events. */
+export function useLoginStates() {
+  const [states, setStates] = useState<Record<string, LoginState>>({});
+
+  useEffect(() => {
+    let disposed = false;
+    const unlisten = listenServiceLoginState(({ serviceId, state }) => {
+      setStates((current) => ({ ...current, [serviceId]: state }));
+    });
+    getServiceLoginStates()
+      .then((initial) => {
+        if (!disposed) setStates((current) => ({ ...initial, ...current }));
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load login states:', error));
+
+    return () => {
+      disposed = true;
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return states;
+}
@@ -0,0 +1,22 @@
+import { useEffect, useState } from 'react';
+import { getEffectivePolicy, type EffectivePolicy } from '@/services/policy';
+
+/** The administrator policy, or `null` until it has loaded. */
+export function usePolicy() {
+  const [policy, setPolicy] = useState<EffectivePolicy | null>(null);
+
+  useEffect(() => {
+    let disposed = false;
+    getEffectivePolicy()
+      .then((loaded) => {
+        if (!disposed) setPolicy(loaded);
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load policy:', error));
+
+    return () => {
+      disposed = true;
+    };
+  }, []);
+
+  return policy;
+}
@@ -0,0 +1,18 @@
+import { useEffect } from 'react';
+import { importIconCache } from '@/lib/icon-cache';
+import { takeRestoredFrontendState } from '@/services/backup';
+import { useAppStore } from '@/stores/app-store';
+
+/** Applies the service list and icon cache from a backup restored during the last launch. */
+export function useRestoredBackup() {
+  useEffect(() => {
+    takeRestoredFrontendState()
+      .then(async (state) => {
+        if (!state) return;
+        useAppStore.getState().replaceServices(state.services);
+        await importIconCache(state.icons);
+        console.log('[AnyChat] Restored services and icons from backup');
+      })
+      .catch((error) => console.error('[AnyChat] Failed to apply restored backup:', error));
+  }, []);
+}
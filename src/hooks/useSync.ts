@@ -0,0 +1,60 @@
+import { useEffect, useRef } from 'react';
+import { getSyncStatus, syncNow } from '@/services/sync';
+import { useAppStore } from '@/stores/app-store';
+import type { ChatService } from '@/types';
+
+const SYNC_INTERVAL_MS = 10 * 60_000;
+const CHANGE_DEBOUNCE_MS = 5_000;
+
+function signature(services: ChatService[]) {
+  return JSON.stringify(services);
+}
+
+/** Syncs on start, shortly after the service list changes and every ten minutes. */
+export function useSync(paused: boolean) {
+  const services = useAppStore((state) => state.services);
+  const configured = useRef(false);
+  const lastSynced = useRef<string | null>(null);
+
+  const run = useRef(async () => {
+    if (!configured.current) return;
+    const { services: current, replaceServices } = useAppStore.getState();
+    try {
+      const report = await syncNow(current);
+      if (report.services) {
+        replaceServices(report.services);
+      }
+      lastSynced.current = signature(report.services ?? current);
+      if (report.conflicts.length > 0) {
+        console.warn('[AnyChat] Sync conflicts:', report.conflicts);
+      }
+    } catch (error) {
+      console.error('[AnyChat] Sync failed:', error);
+    }
+  });
+
+  useEffect(() => {
+    if (paused) return;
+
+    let disposed = false;
+    getSyncStatus()
+      .then((status) => {
+        configured.current = status.settings.target !== null;
+        if (!disposed) void run.current();
+      })
+      .catch((error) => console.error('[AnyChat] Failed to load sync status:', error));
+    const timer = setInterval(() => void run.current(), SYNC_INTERVAL_MS);
+
+    return () => {
+      disposed = true;
+      clearInterval(timer);
+    };
+  }, [paused]);
+
+  useEffect(() => {
+    if (paused || !configured.current || signature(services) === lastSynced.current) return;
+
+    const timer = setTimeout(() => void run.current(), CHANGE_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [paused, services]);
+}
@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  /** Minutes without input before locking; `null` when idle locking is off. */
+  idleMinutes: number | null;
+  lockOnScreenLock: boolean;
+}
+
+export async function getAppLockStatus() {
+  return invoke<AppLockStatus>('get_app_lock_status');
+}
+
+/** Pass `newPassword: null` to remove the lock; changing a password needs the current one. */
+export async function setAppLockPassword(
+  currentPassword: string | null,
+  newPassword: string | null
+) {
+  return invoke<AppLockStatus>('set_app_lock_password', { currentPassword, newPassword });
+}
+
+export async function setAppLockOptions(idleMinutes: number | null, lockOnScreenLock: boolean) {
+  return invoke<AppLockStatus>('set_app_lock_options', { idleMinutes, lockOnScreenLock });
+}
+
+export async function lockApp() {
+  return invoke<void>('lock_app');
+}
+
+/** Rejects with an error message when the password is wrong. */
+export async function unlockApp(password: string) {
+  return invoke<void>('unlock_app', { password });
+}
+
+export async function reportAppActivity() {
+  return invoke<void>('report_app_activity');
+}
+
+export async function listenAppLock(
+  handler: (status: AppLockStatus) => void
+): Promise<UnlistenFn> {
+  return listen<AppLockStatus>('app://lock', (event) => handler(event.payload));
+}
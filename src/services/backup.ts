@@ -0,0 +1,57 @@
+import { invoke } from '@tauri-apps/api/core';
+import { exportIconCache, type IconCacheEntry } from '@/lib/icon-cache';
+import { useAppStore } from '@/stores/app-store';
+import type { ChatService } from '@/types';
+
+export interface BackupFile {
+  /** Path inside the archive. */
+  path: string;
+  size: number;
+  sha256: string;
+}
+
+export interface BackupManifest {
+  format: string;
+  version: number;
+  /** Unix time in milliseconds. */
+  createdAt: number;
+  appVersion: string;
+  includesWebviewStorage: boolean;
+  files: BackupFile[];
+}
+
+/** Frontend state carried in a backup next to the Rust-side data files. */
+export interface FrontendBackupState {
+  services: ChatService[];
+  icons: IconCacheEntry[];
+}
+
+/**
+ * Writes a backup to `path`; webview storage (cookies, site data) is optional. A passphrase
+ * encrypts the backup and is required while data encryption is on.
+ */
+export async function createBackup(
+  path: string,
+  includeWebviewStorage: boolean,
+  passphrase?: string
+) {
+  const frontend: FrontendBackupState = {
+    services: useAppStore.getState().services,
+    icons: await exportIconCache(),
+  };
+  return invoke<BackupManifest>('create_backup', {
+    path,
+    includeWebviewStorage,
+    frontend,
+    passphrase: passphrase || null,
+  });
+}
+
+/** Verifies the backup and relaunches AnyChat to restore it; only returns on failure. */
+export async function restoreBackup(path: string, passphrase?: string) {
+  return invoke<void>('restore_backup', { path, passphrase: passphrase || null });
+}
+
+export async function takeRestoredFrontendState() {
+  return invoke<FrontendBackupState | null>('take_restored_frontend_state');
+}
@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+import { toServicePayload, type HostService } from './content-host';
+import type { ServiceCookieSummary } from './cookie-backup';
+
+export interface BrowserProfile {
+  browser: 'chrome' | 'chromium' | 'brave' | 'edge' | 'firefox';
+  /** Profile directory name, e.g. `Default`. */
+  name: string;
+  cookieDb: string;
+}
+
+export interface CookiePreview {
+  name: string;
+  domain: string | null;
+  path: string | null;
+  /** Unix seconds; null for session cookies. */
+  expires: number | null;
+  secure: boolean;
+  httpOnly: boolean;
+}
+
+export interface ImportPreview {
+  serviceId: string;
+  profile: BrowserProfile;
+  cookies: CookiePreview[];
+  expired: number;
+  /** Cookies that could not be decrypted, e.g. with a locked keyring. */
+  undecryptable: number;
+}
+
+/** Local browser profiles to import from; always empty outside Linux. */
+export async function listBrowserProfiles() {
+  return invoke<BrowserProfile[]>('list_browser_profiles');
+}
+
+/** Shows which cookies an import would copy, without their values. */
+export async function previewBrowserCookieImport(service: HostService, profile: BrowserProfile) {
+  return invoke<ImportPreview>('preview_browser_cookie_import', {
+    service: toServicePayload(service),
+    profile,
+  });
+}
+
+export async function importBrowserCookies(service: HostService, profile: BrowserProfile) {
+  return invoke<ServiceCookieSummary>('import_browser_cookies', {
+    service: toServicePayload(service),
+    profile,
+  });
+}
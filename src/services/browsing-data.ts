@@ -0,0 +1,10 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Clears the service's cookies, storage, IndexedDB, caches and service workers, or only its
+ * caches, and recreates its webview. Resolves to the number of site records removed where the
+ * platform reports it.
+ */
+export async function clearServiceBrowsingData(serviceId: string, cacheOnly = false) {
+  return invoke<number>('clear_service_browsing_data', { serviceId, cacheOnly });
+}
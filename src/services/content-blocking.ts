@@ -0,0 +1,35 @@
+import { invoke } from '@tauri-apps/api/core';
+import { toServicePayload, type HostService } from './content-host';
+
+export interface ContentFilterStats {
+  sources: string[];
+  blockedHosts: number;
+  hiddenSelectors: number;
+  unsupportedRules: number;
+}
+
+export async function getContentFilterStats() {
+  return invoke<ContentFilterStats>('get_content_filter_stats');
+}
+
+export async function reloadContentFilters(services: HostService[]) {
+  return invoke<ContentFilterStats>('reload_content_filters', {
+    services: services.map(toServicePayload),
+  });
+}
+
+/** Pass `null` to return the service to the per-host default. Resolves to the effective state. */
+export async function setServiceContentBlocking(service: HostService, enabled: boolean | null) {
+  return invoke<boolean>('set_service_content_blocking', {
+    service: toServicePayload(service),
+    enabled,
+  });
+}
+
+/** The stored override; `null` follows the per-host default. */
+export async function getServiceContentBlocking(serviceId: string) {
+  const settings = await invoke<{ contentBlocking: boolean | null }>('get_service_settings', {
+    serviceId,
+  });
+  return settings.contentBlocking;
+}
@@ -1,7 +1,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { ChatService } from '@/types';
 
-export type HostService = Pick<ChatService, 'id' | 'name' | 'url' | 'enabled'>;
+export type HostService = Pick<ChatService, 'id' | 'name' | 'url' | 'enabled' | 'kind'>;
 
 type HostPlatform = 'windows' | 'macos' | 'linux' | 'unknown';
 
@@ -16,20 +16,22 @@ async function resolveHostPlatform(): Promise<HostPlatform> {
 }
 
 function toManagedServices(services: HostService[]) {
-  return services.map(({ id, name, url, enabled }) => ({
+  return services.map(({ id, name, url, enabled, kind }) => ({
     id,
     name,
     url,
     enabled,
+    ...(kind ? { kind } : {}),
   }));
 }
 
-function toServicePayload(service: HostService) {
+export function toServicePayload(service: HostService) {
   return {
     id: service.id,
     name: service.name,
     url: service.url,
     enabled: service.enabled,
+    ...(service.kind ? { kind: service.kind } : {}),
   };
 }
 
@@ -37,6 +39,11 @@ export async function usesDockedWindowContentHost() {
   return (await resolveHostPlatform()) === 'windows';
 }
 
+/** Whether self-hosted services can answer HTTP basic-auth challenges; WKWebView has no prompt. */
+export async function supportsBasicAuthPrompt() {
+  return (await resolveHostPlatform()) !== 'macos';
+}
+
 export async function activateServiceContent(service: HostService, services: HostService[]) {
   await invoke('activate_service_content', {
     service: toServicePayload(service),
@@ -0,0 +1,25 @@
+import { invoke } from '@tauri-apps/api/core';
+import { toServicePayload, type HostService } from './content-host';
+
+export interface ServiceCookieSummary {
+  serviceId: string;
+  name: string;
+  /** Cookies written to the backup, or restored into the webview store. */
+  cookies: number;
+  /** Cookies skipped because they had expired. */
+  expired: number;
+}
+
+/** Writes the services' cookies to an encrypted backup; the password needs 8+ characters. */
+export async function exportServiceCookies(services: HostService[], path: string, password: string) {
+  return invoke<ServiceCookieSummary[]>('export_service_cookies', {
+    services: services.map(toServicePayload),
+    path,
+    password,
+  });
+}
+
+/** Restores unexpired cookies from a backup and reloads the affected services. */
+export async function importServiceCookies(path: string, password: string) {
+  return invoke<ServiceCookieSummary[]>('import_service_cookies', { path, password });
+}
@@ -0,0 +1,56 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type DataKeySource = 'passphrase' | 'keyring';
+export type DataState = 'disabled' | 'locked' | 'unlocked' | 'failed';
+
+export interface DataEncryptionStatus {
+  state: DataState;
+  keySource: DataKeySource | null;
+  /** Why encrypted data could not be opened, when `state` is `failed`. */
+  error: string | null;
+}
+
+export async function getDataEncryptionStatus() {
+  return invoke<DataEncryptionStatus>('get_data_encryption_status');
+}
+
+/**
+ * Encrypts AnyChat's data files and resolves with the recovery key. It is shown once and not
+ * stored, so the user has to save it.
+ */
+export async function enableDataEncryption(keySource: DataKeySource, passphrase?: string) {
+  return invoke<string>('enable_data_encryption', { keySource, passphrase: passphrase ?? null });
+}
+
+export async function unlockData(passphrase: string) {
+  return invoke<DataEncryptionStatus>('unlock_data', { passphrase });
+}
+
+/** Opens the data with the recovery key and protects it with a new passphrase or the keyring. */
+export async function recoverData(
+  recoveryKey: string,
+  keySource: DataKeySource,
+  passphrase?: string
+) {
+  return invoke<DataEncryptionStatus>('recover_data', {
+    recoveryKey,
+    keySource,
+    passphrase: passphrase ?? null,
+  });
+}
+
+/** Writes a new recovery key file to the absolute `path`; the previous key stops working. */
+export async function exportDataRecoveryKey(path: string) {
+  return invoke<void>('export_data_recovery_key', { path });
+}
+
+export async function disableDataEncryption() {
+  return invoke<DataEncryptionStatus>('disable_data_encryption');
+}
+
+export async function listenDataEncryption(
+  handler: (status: DataEncryptionStatus) => void
+): Promise<UnlistenFn> {
+  return listen<DataEncryptionStatus>('app://data-encryption', (event) => handler(event.payload));
+}
@@ -0,0 +1,22 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type FindDirection = 'next' | 'previous';
+
+/** `current` is 1-based; both are null on platforms that cannot read page results back. */
+export interface FindResult {
+  matches: number | null;
+  current: number | null;
+}
+
+export async function findInService(serviceId: string, query: string, direction: FindDirection = 'next') {
+  return invoke<FindResult>('find_in_service', { serviceId, query, direction });
+}
+
+/** Opens the find bar inside the service page and focuses it. */
+export async function openFindInService(serviceId: string) {
+  await invoke('open_find_in_service', { serviceId });
+}
+
+export async function stopFindInService(serviceId: string) {
+  await invoke('stop_find_in_service', { serviceId });
+}
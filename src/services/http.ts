@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Trust settings for requests AnyChat itself makes (sync, native chat, icons). */
+export interface HttpSettings {
+  /** PEM files with extra CA certificates, e.g. of a TLS-inspecting proxy or an internal CA. */
+  caBundlePaths: string[];
+  /** Trust the operating system certificate store in addition to the bundled roots. */
+  useSystemRoots: boolean;
+  proxy: string | null;
+}
+
+export async function getHttpSettings() {
+  return invoke<HttpSettings>('get_http_settings');
+}
+
+/** Rejects when a CA bundle cannot be read or holds no certificates. */
+export async function setHttpSettings(settings: HttpSettings) {
+  await invoke('set_http_settings', { settings });
+}
@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface KeepAliveSettings {
+  /** Same-origin path fetched with the service's cookies, e.g. `/api/auth/session`. */
+  path: string;
+  /** Between 5 and 1440 minutes. */
+  intervalMinutes: number;
+}
+
+export interface KeepAliveStatus {
+  outcome: 'ok' | 'signedOut' | 'failed';
+  /** 0 when the request did not complete. */
+  httpStatus: number;
+  error: string | null;
+  checkedAt: number;
+}
+
+export interface ServiceKeepAliveResult {
+  serviceId: string;
+  status: KeepAliveStatus;
+}
+
+/** Pass `null` to turn keep-alive off. */
+export async function setServiceKeepAlive(serviceId: string, keepAlive: KeepAliveSettings | null) {
+  return invoke<void>('set_service_keep_alive', { serviceId, keepAlive });
+}
+
+export async function getKeepAliveStatuses() {
+  return invoke<Record<string, KeepAliveStatus>>('get_keep_alive_statuses');
+}
+
+export async function listenKeepAlive(
+  handler: (result: ServiceKeepAliveResult) => void
+): Promise<UnlistenFn> {
+  return listen<ServiceKeepAliveResult>('service://keep-alive', (event) => handler(event.payload));
+}
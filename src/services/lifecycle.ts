@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** `private` is set for events of the service's ephemeral session window. */
+export type ServiceLifecycleEvent = { serviceId: string; private?: true } & (
+  | { type: 'created' | 'destroyed' | 'shown' | 'hidden' }
+  | { type: 'navigationStarted'; url: string }
+  | { type: 'pageLoaded'; url: string; title: string | null }
+  | { type: 'loadFailed'; url: string; error: string }
+  | { type: 'titleChanged'; title: string }
+  | { type: 'crashed'; reason: string }
+);
+
+export interface ServiceStatus {
+  created: boolean;
+  visible: boolean;
+  loading: boolean;
+  /** Last successfully loaded URL. */
+  url: string | null;
+  title: string | null;
+  error: string | null;
+  /** Consecutive failed loads; the in-page error page backs off its retries accordingly. */
+  failedAttempts: number;
+}
+
+export async function getServiceStatuses() {
+  return invoke<Record<string, ServiceStatus>>('get_service_statuses');
+}
+
+export async function listenServiceLifecycle(
+  handler: (event: ServiceLifecycleEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ServiceLifecycleEvent>('service://lifecycle', (event) => handler(event.payload));
+}
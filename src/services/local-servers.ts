@@ -0,0 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DiscoveredServer {
+  product: 'ollama' | 'lmStudio' | 'llamaCpp' | 'openWebUi' | 'jan' | 'openAiCompatible';
+  name: string;
+  /** Base URL, e.g. `http://127.0.0.1:11434`. */
+  url: string;
+  port: number;
+  iconUrl: string | null;
+  /** Whether the server has its own chat UI; API-only servers use the native chat panel. */
+  hasWebUi: boolean;
+}
+
+/** Probes the configured and well-known local ports for AI servers. */
+export async function discoverLocalServers() {
+  return invoke<DiscoveredServer[]>('discover_local_servers');
+}
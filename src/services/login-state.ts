@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type LoginState = 'unknown' | 'signedIn' | 'signedOut';
+
+/** Replaces the built-in rules for the service's host. */
+export interface LoginRules {
+  /** Cookie that is only present while signed in. */
+  sessionCookie?: string | null;
+  /** URL substrings of sign-in pages. */
+  signedOutUrlPatterns?: string[];
+  signedInSelector?: string | null;
+  signedOutSelector?: string | null;
+}
+
+export interface ServiceLoginStateChange {
+  serviceId: string;
+  state: LoginState;
+}
+
+export async function getServiceLoginStates() {
+  return invoke<Record<string, LoginState>>('get_service_login_states');
+}
+
+/** Pass `null` to go back to the built-in rules. */
+export async function setServiceLoginRules(serviceId: string, rules: LoginRules | null) {
+  return invoke<void>('set_service_login_rules', { serviceId, rules });
+}
+
+export async function listenServiceLoginState(
+  handler: (change: ServiceLoginStateChange) => void
+): Promise<UnlistenFn> {
+  return listen<ServiceLoginStateChange>('service://login-state', (event) =>
+    handler(event.payload)
+  );
+}
@@ -0,0 +1,106 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { HostService } from './content-host';
+
+export interface NativeChatMessage {
+  role: 'system' | 'user' | 'assistant';
+  content: string;
+}
+
+export interface NativeChatConversation {
+  id: string;
+  serviceId: string;
+  model: string;
+  title: string;
+  createdAt: number;
+  updatedAt: number;
+  messages: NativeChatMessage[];
+}
+
+export type NativeChatConversationSummary = Pick<
+  NativeChatConversation,
+  'id' | 'title' | 'model' | 'updatedAt'
+>;
+
+export interface NativeChatStreamEvent {
+  requestId: string;
+  serviceId: string;
+  conversationId: string;
+  delta?: string;
+  message?: NativeChatMessage;
+  error?: string;
+}
+
+function toServicePayload(service: HostService) {
+  return {
+    id: service.id,
+    name: service.name,
+    url: service.url,
+    enabled: service.enabled,
+    kind: 'openAiCompatible',
+  };
+}
+
+export async function sendNativeChatMessage(
+  service: HostService,
+  model: string,
+  content: string,
+  conversationId?: string
+) {
+  return invoke<NativeChatStreamEvent>('native_chat_send', {
+    service: toServicePayload(service),
+    model,
+    content,
+    conversationId: conversationId ?? null,
+  });
+}
+
+export async function cancelNativeChatRequest(requestId: string) {
+  await invoke('native_chat_cancel', { requestId });
+}
+
+export async function listNativeChatModels(service: HostService) {
+  return invoke<string[]>('native_chat_list_models', { service: toServicePayload(service) });
+}
+
+export async function listNativeChatConversations(serviceId: string) {
+  return invoke<NativeChatConversationSummary[]>('native_chat_list_conversations', { serviceId });
+}
+
+export async function loadNativeChatConversation(serviceId: string, conversationId: string) {
+  return invoke<NativeChatConversation>('native_chat_load_conversation', {
+    serviceId,
+    conversationId,
+  });
+}
+
+export async function deleteNativeChatConversation(serviceId: string, conversationId: string) {
+  await invoke('native_chat_delete_conversation', { serviceId, conversationId });
+}
+
+/** Saves the key for `baseUrl` only; it is not sent once the service points elsewhere. */
+export async function setNativeChatApiKey(
+  serviceId: string,
+  baseUrl: string,
+  apiKey: string | null
+) {
+  await invoke('native_chat_set_api_key', { serviceId, baseUrl, apiKey });
+}
+
+export async function hasNativeChatApiKey(serviceId: string) {
+  return invoke<boolean>('native_chat_has_api_key', { serviceId });
+}
+
+export async function listenNativeChatStream(handlers: {
+  onDelta: (event: NativeChatStreamEvent) => void;
+  onDone: (event: NativeChatStreamEvent) => void;
+  onError: (event: NativeChatStreamEvent) => void;
+}): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all([
+    listen<NativeChatStreamEvent>('native-chat://delta', (event) => handlers.onDelta(event.payload)),
+    listen<NativeChatStreamEvent>('native-chat://done', (event) => handlers.onDone(event.payload)),
+    listen<NativeChatStreamEvent>('native-chat://error', (event) => handlers.onError(event.payload)),
+  ]);
+
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
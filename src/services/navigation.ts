@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core';
+import { toServicePayload, type HostService } from './content-host';
+
+/** Emitted with `{ serviceId, canGoBack, canGoForward }` when a service's history changes. */
+export const SERVICE_NAVIGATION_EVENT = 'service://navigation';
+
+export interface NavigationState {
+  canGoBack: boolean;
+  canGoForward: boolean;
+}
+
+export async function serviceGoBack(serviceId: string) {
+  await invoke('service_go_back', { serviceId });
+}
+
+export async function serviceGoForward(serviceId: string) {
+  await invoke('service_go_forward', { serviceId });
+}
+
+/** Navigates back to the service's configured URL. */
+export async function serviceGoHome(service: HostService) {
+  await invoke('service_go_home', { service: toServicePayload(service) });
+}
+
+export async function getServiceNavigationState(serviceId: string) {
+  return invoke<NavigationState>('get_service_navigation_state', { serviceId });
+}
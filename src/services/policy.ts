@@ -0,0 +1,48 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ChatService } from '@/types';
+
+/** Administrator policy; it is read once at startup and cannot be changed from the app. */
+export interface EffectivePolicy {
+  /** Hosts services may load, each also allowing its subdomains; `null` allows any host. */
+  allowedHosts: string[] | null;
+  disableCustomServices: boolean;
+  forceEnabledServices: string[];
+  forceDisabledServices: string[];
+  proxy: string | null;
+  disableDevtools: boolean;
+  blockDownloads: boolean;
+  /** Path of the policy file; `null` when no policy is installed. */
+  source: string | null;
+  /** Set when the policy file is broken and everything is locked down. */
+  error: string | null;
+}
+
+let policyPromise: Promise<EffectivePolicy> | null = null;
+
+export function getEffectivePolicy() {
+  policyPromise ??= invoke<EffectivePolicy>('get_effective_policy');
+  return policyPromise;
+}
+
+function hostAllowed(policy: EffectivePolicy, url: string) {
+  if (!policy.allowedHosts) return true;
+  let host: string;
+  try {
+    host = new URL(url).hostname.toLowerCase();
+  } catch {
+    return false;
+  }
+  return policy.allowedHosts.some((allowed) => {
+    const normalized = allowed.replace(/^\./, '').toLowerCase();
+    return host === normalized || host.endsWith(`.${normalized}`);
+  });
+}
+
+/** Forced state of a service's enabled switch, or `null` when the user may choose. */
+export function enabledOverride(policy: EffectivePolicy, service: ChatService): boolean | null {
+  if (policy.forceDisabledServices.includes(service.id)) return false;
+  if (policy.disableCustomServices && service.id.startsWith('custom-')) return false;
+  if (!hostAllowed(policy, service.url)) return false;
+  if (policy.forceEnabledServices.includes(service.id)) return true;
+  return null;
+}
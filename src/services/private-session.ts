@@ -0,0 +1,14 @@
+import { invoke } from '@tauri-apps/api/core';
+import { toServicePayload, type HostService } from './content-host';
+
+/**
+ * Opens the service in an ephemeral window whose cookies and storage are discarded on close.
+ * Its lifecycle events carry `private: true`. Resolves to the window label.
+ */
+export async function openPrivateService(service: HostService) {
+  return invoke<string>('open_private_service', { service: toServicePayload(service) });
+}
+
+export async function closePrivateService(serviceId: string) {
+  await invoke('close_private_service', { serviceId });
+}
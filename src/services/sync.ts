@@ -0,0 +1,64 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ChatService } from '@/types';
+
+export type SyncTarget =
+  | { type: 'folder'; path: string }
+  /** `url` is the WebDAV collection that holds the sync file. */
+  | { type: 'webDav'; url: string; username: string };
+
+export interface SyncSettings {
+  /** `null` turns sync off. */
+  target: SyncTarget | null;
+  /** Seal the sync file with the sync passphrase. */
+  encrypt: boolean;
+}
+
+export interface SyncStatus {
+  settings: SyncSettings;
+  hasWebdavPassword: boolean;
+  hasPassphrase: boolean;
+  /** Unix time in milliseconds. */
+  lastSyncedAt: number | null;
+}
+
+export interface SyncConflict {
+  /** `<kind>/<id>`, e.g. `service/chatgpt` or `userScripts/chatgpt`. */
+  key: string;
+  detectedAt: number;
+  winner: 'local' | 'remote';
+  localModifiedAt: number;
+  remoteModifiedAt: number;
+  remoteDeviceId: string;
+  /** The losing side's value, for manual recovery. */
+  discarded: unknown;
+}
+
+export interface SyncReport {
+  syncedAt: number;
+  uploaded: boolean;
+  applied: number;
+  conflicts: SyncConflict[];
+  /** The merged service list when it changed. */
+  services: ChatService[] | null;
+}
+
+export async function getSyncStatus() {
+  return invoke<SyncStatus>('get_sync_status');
+}
+
+/** Pass `null` to keep a stored secret and an empty string to remove it. */
+export async function setSyncSettings(
+  settings: SyncSettings,
+  webdavPassword: string | null,
+  passphrase: string | null
+) {
+  return invoke<SyncStatus>('set_sync_settings', { settings, webdavPassword, passphrase });
+}
+
+export async function syncNow(services: ChatService[]) {
+  return invoke<SyncReport>('sync_now', { services });
+}
+
+export async function getSyncConflicts() {
+  return invoke<SyncConflict[]>('get_sync_conflicts');
+}
@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type AppTheme = 'system' | 'light' | 'dark';
+
+export type ServiceTheme = 'followApp' | 'forceLight' | 'forceDark';
+
+export async function getAppTheme() {
+  return invoke<AppTheme>('get_app_theme');
+}
+
+/** Service webviews that follow the app theme update in place, without reloading. */
+export async function setAppTheme(theme: AppTheme) {
+  await invoke('set_app_theme', { theme });
+}
+
+export async function setServiceTheme(serviceId: string, theme: ServiceTheme) {
+  await invoke('set_service_theme', { serviceId, theme });
+}
+
+export async function getServiceTheme(serviceId: string) {
+  const settings = await invoke<{ theme: ServiceTheme }>('get_service_settings', { serviceId });
+  return settings.theme;
+}
@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** What a service page does on a certificate error. */
+export type TlsErrorPolicy =
+  | { mode: 'block' }
+  /** Accept exactly one certificate, by its SHA-256 fingerprint. */
+  | { mode: 'allowPinned'; fingerprint: string };
+
+export async function getServiceTlsPolicy(serviceId: string) {
+  const settings = await invoke<{ tlsErrorPolicy: TlsErrorPolicy }>('get_service_settings', {
+    serviceId,
+  });
+  return settings.tlsErrorPolicy;
+}
+
+/** Pinning is only supported on Linux; elsewhere it is rejected. */
+export async function setServiceTlsPolicy(serviceId: string, policy: TlsErrorPolicy) {
+  await invoke('set_service_tls_policy', { serviceId, policy });
+}
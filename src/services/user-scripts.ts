@@ -0,0 +1,32 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type UserScriptKind = 'script' | 'style';
+
+export type UserScriptRunAt = 'documentStart' | 'documentIdle';
+
+export interface UserScript {
+  id: string;
+  name: string;
+  kind: UserScriptKind;
+  code: string;
+  matches: string[];
+  runAt: UserScriptRunAt;
+  enabled: boolean;
+}
+
+export async function listUserScripts(serviceId: string) {
+  return invoke<UserScript[]>('list_user_scripts', { serviceId });
+}
+
+/** Saves the script (an empty id creates a new one) and applies it to the live page. */
+export async function saveUserScript(serviceId: string, script: UserScript) {
+  return invoke<UserScript>('save_user_script', { serviceId, script });
+}
+
+export async function deleteUserScript(serviceId: string, scriptId: string) {
+  await invoke('delete_user_script', { serviceId, scriptId });
+}
+
+export async function readUserScriptLog(limit?: number) {
+  return invoke<string[]>('read_user_script_log', { limit: limit ?? null });
+}
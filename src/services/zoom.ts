@@ -0,0 +1,30 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Emitted with `{ serviceId, percent }` whenever a service's zoom changes, including via native shortcuts. */
+export const SERVICE_ZOOM_EVENT = 'service://zoom';
+
+export interface ServiceZoomChange {
+  serviceId: string;
+  percent: number;
+}
+
+export async function getServiceZoom(serviceId: string) {
+  return invoke<number>('get_service_zoom', { serviceId });
+}
+
+export async function zoomServiceIn(serviceId: string) {
+  return invoke<number>('zoom_service_in', { serviceId });
+}
+
+export async function zoomServiceOut(serviceId: string) {
+  return invoke<number>('zoom_service_out', { serviceId });
+}
+
+export async function zoomServiceReset(serviceId: string) {
+  return invoke<number>('zoom_service_reset', { serviceId });
+}
+
+/** The percentage is clamped by the backend; the applied value is returned. */
+export async function setServiceZoom(serviceId: string, percent: number) {
+  return invoke<number>('zoom_service_set', { serviceId, percent });
+}
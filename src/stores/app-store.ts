@@ -6,17 +6,22 @@ interface AppState {
   services: ChatService[];
   activeServiceId: string | null;
   settingsPageOpen: boolean;
-  settingsActiveTab: 'services' | 'about';
+  settingsActiveTab: 'services' | 'general' | 'about';
   addServiceDialogOpen: boolean;
 
   setActiveService: (id: string) => void;
-  addService: (service: Omit<ChatService, 'id' | 'order'>) => void;
+  /** Returns the new service's id. */
+  addService: (service: Omit<ChatService, 'id' | 'order'>) => string;
   removeService: (id: string) => void;
   updateService: (id: string, updates: Partial<ChatService>) => void;
   toggleServiceEnabled: (id: string) => void;
+  /** Pins `enabled` wherever `override` returns a value, e.g. for the administrator policy. */
+  applyEnabledOverrides: (override: (service: ChatService) => boolean | null) => void;
   reorderServices: (startIndex: number, endIndex: number) => void;
+  /** Adopts a service list merged by sync. */
+  replaceServices: (services: ChatService[]) => void;
   setSettingsPageOpen: (open: boolean) => void;
-  setSettingsActiveTab: (tab: 'services' | 'about') => void;
+  setSettingsActiveTab: (tab: 'services' | 'general' | 'about') => void;
   setAddServiceDialogOpen: (open: boolean) => void;
 }
 
@@ -47,6 +52,7 @@ export const useAppStore = create<AppState>()(
           enabled: true,
         };
         set({ services: [...services, newService] });
+        return id;
       },
 
       removeService: (id) => {
@@ -80,6 +86,16 @@ export const useAppStore = create<AppState>()(
         set({ services, activeServiceId: newActiveId });
       },
 
+      applyEnabledOverrides: (override) => {
+        const current = get().services;
+        const services = current.map((s) => {
+          const enabled = override(s);
+          return enabled === null || enabled === s.enabled ? s : { ...s, enabled };
+        });
+        if (services.every((s, i) => s === current[i])) return;
+        get().replaceServices(services);
+      },
+
       reorderServices: (startIndex, endIndex) => {
         const services = [...get().services];
         const [removed] = services.splice(startIndex, 1);
@@ -89,6 +105,17 @@ export const useAppStore = create<AppState>()(
         });
       },
 
+      replaceServices: (services) => {
+        const activeId = get().activeServiceId;
+        const active = services.find((s) => s.id === activeId);
+        set({
+          services,
+          activeServiceId: active?.enabled
+            ? activeId
+            : (services.find((s) => s.enabled)?.id ?? null),
+        });
+      },
+
       setSettingsPageOpen: (open) => set({ settingsPageOpen: open }),
       setSettingsActiveTab: (tab) => set({ settingsActiveTab: tab }),
       setAddServiceDialogOpen: (open) => set({ addServiceDialogOpen: open }),
@@ -1,3 +1,5 @@
+export type ServiceKind = 'web' | 'selfHosted' | 'openAiCompatible';
+
 export interface ChatService {
   id: string;
   name: string;
@@ -7,6 +9,7 @@ export interface ChatService {
   enabled: boolean;
   order: number;
   isBuiltin?: boolean;
+  kind?: ServiceKind;
 }
 
 export const DEFAULT_SERVICES: ChatService[] = [
@@ -38,9 +38,9 @@ vi.mock('@/services/content-host', () => ({
 type StoreState = {
   activeServiceId: string | null;
   settingsPageOpen: boolean;
-  settingsActiveTab: 'services' | 'about';
+  settingsActiveTab: 'services' | 'general' | 'about';
   addServiceDialogOpen: boolean;
-  services: Array<{ id: string; name: string; url: string; enabled: boolean }>;
+  services: Array<{ id: string; name: string; url: string; enabled: boolean; kind?: string }>;
 };
 
 let storeState: StoreState = {
@@ -71,6 +71,10 @@ vi.mock('@/components/SettingsPage', () => ({
   SettingsPage: () => <div data-testid="settings" />,
 }));
 
+vi.mock('@/components/NativeChatPanel', () => ({
+  NativeChatPanel: () => <div data-testid="native-chat" />,
+}));
+
 describe('AppLayout', () => {
   beforeEach(() => {
     vi.clearAllMocks();
@@ -127,6 +131,25 @@ describe('AppLayout', () => {
     });
   });
 
+  it('renders the native chat panel for OpenAI-compatible services', async () => {
+    const ollama = {
+      id: 'custom-ollama',
+      name: 'Ollama',
+      url: 'http://localhost:11434/',
+      enabled: true,
+      kind: 'openAiCompatible',
+    };
+    storeState = { ...storeState, activeServiceId: ollama.id, services: [ollama] };
+
+    render(<AppLayout />);
+
+    expect(screen.getByTestId('native-chat')).toBeInTheDocument();
+    expect(screen.queryByTestId('webview')).not.toBeInTheDocument();
+    await waitFor(() => {
+      expect(mockActivateServiceContent).toHaveBeenCalledWith(ollama, [ollama]);
+    });
+  });
+
   it('hides all webviews while dialogs are open', async () => {
     storeState = {
       ...storeState,
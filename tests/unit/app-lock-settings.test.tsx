@@ -0,0 +1,98 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { AppLockSettings } from '@/components/AppLockSettings';
+
+const { appLock } = vi.hoisted(() => ({
+  appLock: {
+    getAppLockStatus: vi.fn(),
+    setAppLockPassword: vi.fn(),
+    setAppLockOptions: vi.fn(),
+    lockApp: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/app-lock', () => appLock);
+
+const unlocked = { enabled: false, locked: false, idleMinutes: null, lockOnScreenLock: false };
+const enabled = { enabled: true, locked: false, idleMinutes: 15, lockOnScreenLock: true };
+
+describe('AppLockSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    appLock.lockApp.mockResolvedValue(undefined);
+  });
+
+  it('sets a lock password once it is long enough', async () => {
+    appLock.getAppLockStatus.mockResolvedValue(unlocked);
+    appLock.setAppLockPassword.mockResolvedValue(enabled);
+    render(<AppLockSettings />);
+
+    fireEvent.change(screen.getByPlaceholderText(/锁定密码，至少 6 个字符/), {
+      target: { value: 'short' },
+    });
+    expect(screen.getByRole('button', { name: '设置密码' })).toBeDisabled();
+
+    fireEvent.change(screen.getByPlaceholderText(/锁定密码，至少 6 个字符/), {
+      target: { value: 'secret-1' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '设置密码' }));
+
+    await waitFor(() => {
+      expect(appLock.setAppLockPassword).toHaveBeenCalledWith(null, 'secret-1');
+    });
+    expect(await screen.findByText('应用锁已开启。')).toBeInTheDocument();
+    expect(screen.getByPlaceholderText('当前锁定密码')).toBeInTheDocument();
+  });
+
+  it('removes the password with the current one', async () => {
+    appLock.getAppLockStatus.mockResolvedValue(enabled);
+    appLock.setAppLockPassword.mockResolvedValue(unlocked);
+    render(<AppLockSettings />);
+
+    fireEvent.change(await screen.findByPlaceholderText('当前锁定密码'), {
+      target: { value: 'secret-1' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '移除密码' }));
+
+    await waitFor(() => {
+      expect(appLock.setAppLockPassword).toHaveBeenCalledWith('secret-1', null);
+    });
+    expect(await screen.findByText('应用锁已关闭。')).toBeInTheDocument();
+  });
+
+  it('saves the idle time and the screen lock option', async () => {
+    appLock.getAppLockStatus.mockResolvedValue(enabled);
+    appLock.setAppLockOptions.mockResolvedValue({
+      ...enabled,
+      idleMinutes: null,
+      lockOnScreenLock: false,
+    });
+    render(<AppLockSettings />);
+
+    const idle = await screen.findByPlaceholderText(/空闲多少分钟后锁定/);
+    expect(idle).toHaveValue(15);
+    fireEvent.change(idle, { target: { value: '' } });
+    fireEvent.click(screen.getByRole('checkbox', { name: '系统锁屏时同时锁定' }));
+    fireEvent.click(screen.getByRole('button', { name: '保存锁定选项' }));
+
+    await waitFor(() => {
+      expect(appLock.setAppLockOptions).toHaveBeenCalledWith(null, false);
+    });
+  });
+
+  it('shows errors from the backend', async () => {
+    appLock.getAppLockStatus.mockResolvedValue(enabled);
+    appLock.setAppLockPassword.mockRejectedValue('Wrong lock password');
+    render(<AppLockSettings />);
+
+    fireEvent.change(await screen.findByPlaceholderText('当前锁定密码'), {
+      target: { value: 'wrong' },
+    });
+    fireEvent.change(screen.getByPlaceholderText(/新的锁定密码/), {
+      target: { value: 'secret-2' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '修改密码' }));
+
+    expect(await screen.findByText('Wrong lock password')).toBeInTheDocument();
+  });
+});
@@ -77,6 +77,35 @@ describe('AppStore', () => {
         useAppStore.getState().services[useAppStore.getState().services.length - 1];
       expect(newService.id).toMatch(/^custom-/);
     });
+
+    it('should return the id of the new service', () => {
+      const { addService } = useAppStore.getState();
+
+      const id = addService({
+        name: 'Ollama',
+        url: 'http://localhost:11434/',
+        enabled: true,
+        kind: 'openAiCompatible',
+      });
+
+      const services = useAppStore.getState().services;
+      expect(services[services.length - 1].id).toBe(id);
+    });
+
+    it('should keep the service kind', () => {
+      const { addService } = useAppStore.getState();
+
+      addService({
+        name: 'Open WebUI',
+        url: 'http://localhost:3000/',
+        enabled: true,
+        kind: 'selfHosted',
+      });
+
+      const newService =
+        useAppStore.getState().services[useAppStore.getState().services.length - 1];
+      expect(newService.kind).toBe('selfHosted');
+    });
   });
 
   describe('removeService', () => {
@@ -131,6 +160,31 @@ describe('AppStore', () => {
     });
   });
 
+  describe('applyEnabledOverrides', () => {
+    it('should pin enabled where the override returns a value', () => {
+      const { applyEnabledOverrides } = useAppStore.getState();
+      const activeId = useAppStore.getState().activeServiceId;
+
+      applyEnabledOverrides((service) =>
+        service.id === activeId ? false : service.id === 'grok' ? true : null
+      );
+
+      const services = useAppStore.getState().services;
+      expect(services.find((s) => s.id === activeId)?.enabled).toBe(false);
+      expect(services.find((s) => s.id === 'grok')?.enabled).toBe(true);
+      expect(useAppStore.getState().activeServiceId).not.toBe(activeId);
+    });
+
+    it('should leave the store untouched when nothing changes', () => {
+      const { applyEnabledOverrides } = useAppStore.getState();
+      const services = useAppStore.getState().services;
+
+      applyEnabledOverrides((service) => service.enabled);
+
+      expect(useAppStore.getState().services).toBe(services);
+    });
+  });
+
   describe('reorderServices', () => {
     it('should reorder services correctly', () => {
       const { reorderServices } = useAppStore.getState();
@@ -157,6 +211,34 @@ describe('AppStore', () => {
     });
   });
 
+  describe('replaceServices', () => {
+    it('should adopt synced services and keep an active service that is still enabled', () => {
+      const { replaceServices } = useAppStore.getState();
+      const activeId = useAppStore.getState().activeServiceId;
+      const synced = useAppStore
+        .getState()
+        .services.map((s) => (s.id === activeId ? { ...s, name: 'Renamed' } : s));
+
+      replaceServices(synced);
+
+      expect(useAppStore.getState().services).toBe(synced);
+      expect(useAppStore.getState().activeServiceId).toBe(activeId);
+    });
+
+    it('should move off an active service that sync removed', () => {
+      const { replaceServices } = useAppStore.getState();
+      const activeId = useAppStore.getState().activeServiceId;
+
+      replaceServices(useAppStore.getState().services.filter((s) => s.id !== activeId));
+
+      const nextActiveId = useAppStore.getState().activeServiceId;
+      expect(nextActiveId).not.toBe(activeId);
+      expect(useAppStore.getState().services.find((s) => s.id === nextActiveId)?.enabled).toBe(
+        true
+      );
+    });
+  });
+
   describe('dialog states', () => {
     it('should toggle settings page', () => {
       const { setSettingsPageOpen } = useAppStore.getState();
@@ -184,6 +266,9 @@ describe('AppStore', () => {
       setSettingsActiveTab('services');
       expect(useAppStore.getState().settingsActiveTab).toBe('services');
 
+      setSettingsActiveTab('general');
+      expect(useAppStore.getState().settingsActiveTab).toBe('general');
+
       setSettingsActiveTab('about');
       expect(useAppStore.getState().settingsActiveTab).toBe('about');
     });
@@ -214,5 +299,28 @@ describe('AppStore', () => {
       expect(claudeService?.iconUrl).toBe('https://claude.ai/favicon.ico');
     });
 
+    it('should keep the kind of persisted custom services', async () => {
+      const customService = {
+        id: 'custom-1',
+        name: 'Ollama',
+        url: 'http://localhost:11434/',
+        enabled: true,
+        order: DEFAULT_SERVICES.length,
+        kind: 'openAiCompatible' as const,
+      };
+      localStorage.setItem(
+        'chat-box-app-storage',
+        JSON.stringify({
+          state: { services: [...DEFAULT_SERVICES, customService], activeServiceId: null },
+          version: 0,
+        })
+      );
+
+      await useAppStore.persist.rehydrate();
+
+      const restored = useAppStore.getState().services.find((service) => service.id === 'custom-1');
+      expect(restored?.kind).toBe('openAiCompatible');
+    });
+
   });
 });
@@ -0,0 +1,59 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { BackupSettings } from '@/components/BackupSettings';
+
+const { backup } = vi.hoisted(() => ({
+  backup: {
+    createBackup: vi.fn(),
+    restoreBackup: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/backup', () => backup);
+
+describe('BackupSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('creates an encrypted backup with webview storage', async () => {
+    backup.createBackup.mockResolvedValue({ files: [{}, {}, {}] });
+    render(<BackupSettings />);
+
+    expect(screen.getByRole('button', { name: '创建备份' })).toBeDisabled();
+    fireEvent.change(screen.getByPlaceholderText(/备份文件的完整路径/), {
+      target: { value: ' /tmp/anychat.tar.gz ' },
+    });
+    fireEvent.change(screen.getByPlaceholderText(/备份口令/), {
+      target: { value: 'correct horse' },
+    });
+    fireEvent.click(screen.getByRole('checkbox', { name: /登录状态与站点存储/ }));
+    fireEvent.click(screen.getByRole('button', { name: '创建备份' }));
+
+    await waitFor(() => {
+      expect(backup.createBackup).toHaveBeenCalledWith(
+        '/tmp/anychat.tar.gz',
+        true,
+        'correct horse'
+      );
+    });
+    expect(await screen.findByText('已备份 3 个文件，备份已加密。')).toBeInTheDocument();
+  });
+
+  it('asks for confirmation before restoring and shows failures', async () => {
+    backup.restoreBackup.mockRejectedValue('Wrong backup passphrase or damaged backup');
+    render(<BackupSettings />);
+
+    fireEvent.change(screen.getByPlaceholderText(/备份文件的完整路径/), {
+      target: { value: '/tmp/anychat.tar.gz' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '恢复备份' }));
+    expect(backup.restoreBackup).not.toHaveBeenCalled();
+    fireEvent.click(screen.getByRole('button', { name: /确认恢复/ }));
+
+    expect(backup.restoreBackup).toHaveBeenCalledWith('/tmp/anychat.tar.gz', '');
+    expect(
+      await screen.findByText('Wrong backup passphrase or damaged backup')
+    ).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,80 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { BrowserImportDialog } from '@/components/BrowserImportDialog';
+
+const { mocks } = vi.hoisted(() => ({
+  mocks: {
+    listBrowserProfiles: vi.fn(),
+    previewBrowserCookieImport: vi.fn(),
+    importBrowserCookies: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/browser-import', () => mocks);
+
+const service = {
+  id: 'chatgpt',
+  name: 'ChatGPT',
+  url: 'https://chatgpt.com',
+  enabled: true,
+  order: 0,
+};
+
+const profile = {
+  browser: 'firefox' as const,
+  name: 'abcd.default-release',
+  cookieDb: '/home/user/.mozilla/firefox/abcd.default-release/cookies.sqlite',
+};
+
+describe('BrowserImportDialog', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    mocks.listBrowserProfiles.mockResolvedValue([profile]);
+    mocks.previewBrowserCookieImport.mockResolvedValue({
+      serviceId: service.id,
+      profile,
+      cookies: [
+        {
+          name: '__Secure-next-auth.session-token',
+          domain: '.chatgpt.com',
+          path: '/',
+          expires: null,
+          secure: true,
+          httpOnly: true,
+        },
+      ],
+      expired: 2,
+      undecryptable: 0,
+    });
+    mocks.importBrowserCookies.mockResolvedValue({
+      serviceId: service.id,
+      name: service.name,
+      cookies: 1,
+      expired: 2,
+    });
+  });
+
+  it('imports only after previewing the cookies', async () => {
+    render(<BrowserImportDialog service={service} onClose={vi.fn()} />);
+
+    fireEvent.click(await screen.findByText('Firefox'));
+    expect(await screen.findByText('__Secure-next-auth.session-token')).toBeInTheDocument();
+    expect(screen.getByText(/2 个已过期将跳过/)).toBeInTheDocument();
+    expect(mocks.importBrowserCookies).not.toHaveBeenCalled();
+
+    fireEvent.click(screen.getByRole('button', { name: '导入 1 个 Cookie' }));
+
+    await waitFor(() => {
+      expect(mocks.importBrowserCookies).toHaveBeenCalledWith(service, profile);
+    });
+    expect(await screen.findByText(/已导入 1 个 Cookie/)).toBeInTheDocument();
+  });
+
+  it('explains when no browser profile is available', async () => {
+    mocks.listBrowserProfiles.mockResolvedValue([]);
+
+    render(<BrowserImportDialog service={service} onClose={vi.fn()} />);
+
+    expect(await screen.findByText(/未找到浏览器配置/)).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,94 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { DataEncryptionSettings } from '@/components/DataEncryptionSettings';
+
+const { dataEncryption } = vi.hoisted(() => ({
+  dataEncryption: {
+    getDataEncryptionStatus: vi.fn(),
+    enableDataEncryption: vi.fn(),
+    exportDataRecoveryKey: vi.fn(),
+    disableDataEncryption: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/data-encryption', () => dataEncryption);
+
+const disabled = { state: 'disabled' as const, keySource: null, error: null };
+const unlocked = { state: 'unlocked' as const, keySource: 'passphrase' as const, error: null };
+
+describe('DataEncryptionSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('shows the recovery key after enabling encryption', async () => {
+    dataEncryption.getDataEncryptionStatus
+      .mockResolvedValueOnce(disabled)
+      .mockResolvedValueOnce(unlocked);
+    dataEncryption.enableDataEncryption.mockResolvedValue('ANYCHAT-RECOVERY-1234');
+    render(<DataEncryptionSettings />);
+
+    const passphrase = await screen.findByPlaceholderText(/数据口令/);
+    fireEvent.change(passphrase, { target: { value: 'short' } });
+    expect(screen.getByRole('button', { name: '开启加密' })).toBeDisabled();
+    fireEvent.change(passphrase, { target: { value: 'long passphrase' } });
+    fireEvent.click(screen.getByRole('button', { name: '开启加密' }));
+
+    await waitFor(() => {
+      expect(dataEncryption.enableDataEncryption).toHaveBeenCalledWith(
+        'passphrase',
+        'long passphrase'
+      );
+    });
+    expect(await screen.findByText('ANYCHAT-RECOVERY-1234')).toBeInTheDocument();
+    expect(await screen.findByText(/数据已加密/)).toBeInTheDocument();
+
+    fireEvent.click(screen.getByRole('button', { name: '我已保存' }));
+    expect(screen.queryByText('ANYCHAT-RECOVERY-1234')).not.toBeInTheDocument();
+  });
+
+  it('uses the system keyring without a passphrase', async () => {
+    dataEncryption.getDataEncryptionStatus.mockResolvedValue(disabled);
+    dataEncryption.enableDataEncryption.mockResolvedValue('KEY');
+    render(<DataEncryptionSettings />);
+
+    fireEvent.click(await screen.findByRole('button', { name: '系统钥匙串' }));
+    fireEvent.click(screen.getByRole('button', { name: '开启加密' }));
+
+    await waitFor(() => {
+      expect(dataEncryption.enableDataEncryption).toHaveBeenCalledWith('keyring', undefined);
+    });
+  });
+
+  it('exports a new recovery key to a file', async () => {
+    dataEncryption.getDataEncryptionStatus.mockResolvedValue(unlocked);
+    dataEncryption.exportDataRecoveryKey.mockResolvedValue(undefined);
+    render(<DataEncryptionSettings />);
+
+    fireEvent.change(await screen.findByPlaceholderText(/恢复密钥文件的完整路径/), {
+      target: { value: '/home/me/recovery.txt' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '导出新的恢复密钥' }));
+
+    await waitFor(() => {
+      expect(dataEncryption.exportDataRecoveryKey).toHaveBeenCalledWith('/home/me/recovery.txt');
+    });
+    expect(await screen.findByText(/之前的恢复密钥已失效/)).toBeInTheDocument();
+  });
+
+  it('asks for confirmation before disabling encryption', async () => {
+    dataEncryption.getDataEncryptionStatus.mockResolvedValue(unlocked);
+    dataEncryption.disableDataEncryption.mockResolvedValue(disabled);
+    render(<DataEncryptionSettings />);
+
+    fireEvent.click(await screen.findByRole('button', { name: '关闭加密' }));
+    expect(dataEncryption.disableDataEncryption).not.toHaveBeenCalled();
+    fireEvent.click(screen.getByRole('button', { name: /确认关闭/ }));
+
+    await waitFor(() => {
+      expect(dataEncryption.disableDataEncryption).toHaveBeenCalled();
+    });
+    expect(await screen.findByText('数据加密已关闭。')).toBeInTheDocument();
+    expect(screen.getByRole('button', { name: '开启加密' })).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,137 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { GeneralSettings } from '@/components/GeneralSettings';
+
+const { contentBlocking, cookieBackup, theme } = vi.hoisted(() => ({
+  contentBlocking: {
+    getContentFilterStats: vi.fn(),
+    reloadContentFilters: vi.fn(),
+  },
+  cookieBackup: {
+    exportServiceCookies: vi.fn(),
+    importServiceCookies: vi.fn(),
+  },
+  theme: {
+    getAppTheme: vi.fn(),
+    setAppTheme: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/content-blocking', () => contentBlocking);
+vi.mock('@/services/cookie-backup', () => cookieBackup);
+vi.mock('@/services/theme', () => theme);
+
+vi.mock('@/components/AppLockSettings', () => ({
+  AppLockSettings: () => null,
+}));
+
+vi.mock('@/components/SyncSettings', () => ({
+  SyncSettings: () => null,
+}));
+
+vi.mock('@/components/DataEncryptionSettings', () => ({
+  DataEncryptionSettings: () => null,
+}));
+
+vi.mock('@/components/BackupSettings', () => ({
+  BackupSettings: () => null,
+}));
+
+vi.mock('@/components/NetworkSettings', () => ({
+  NetworkSettings: () => null,
+}));
+
+const services = [
+  { id: 'chatgpt', name: 'ChatGPT', url: 'https://chatgpt.com', enabled: true, order: 0 },
+  { id: 'grok', name: 'Grok', url: 'https://grok.com', enabled: false, order: 1 },
+  {
+    id: 'custom-ollama',
+    name: 'Ollama',
+    url: 'http://localhost:11434/',
+    enabled: true,
+    order: 2,
+    kind: 'openAiCompatible' as const,
+  },
+];
+
+vi.mock('@/stores/app-store', () => ({
+  useAppStore: () => ({ services }),
+}));
+
+const stats = {
+  sources: ['easylist.txt'],
+  blockedHosts: 12,
+  hiddenSelectors: 3,
+  unsupportedRules: 0,
+};
+
+describe('GeneralSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    contentBlocking.getContentFilterStats.mockResolvedValue(stats);
+    contentBlocking.reloadContentFilters.mockResolvedValue({ ...stats, blockedHosts: 20 });
+    theme.getAppTheme.mockResolvedValue('light');
+    theme.setAppTheme.mockResolvedValue(undefined);
+    cookieBackup.importServiceCookies.mockResolvedValue([
+      { serviceId: 'chatgpt', name: 'ChatGPT', cookies: 5, expired: 1 },
+    ]);
+  });
+
+  it('reloads content filters for the enabled services', async () => {
+    render(<GeneralSettings />);
+
+    expect(await screen.findByText(/拦截 12 个域名/)).toBeInTheDocument();
+    fireEvent.click(screen.getByRole('button', { name: /重新加载规则/ }));
+
+    await waitFor(() => {
+      expect(contentBlocking.reloadContentFilters).toHaveBeenCalledWith([services[0], services[2]]);
+    });
+    expect(await screen.findByText(/拦截 20 个域名/)).toBeInTheDocument();
+  });
+
+  it('switches the app theme and applies it to the UI', async () => {
+    render(<GeneralSettings />);
+
+    fireEvent.click(screen.getByRole('button', { name: '深色' }));
+
+    await waitFor(() => {
+      expect(theme.setAppTheme).toHaveBeenCalledWith('dark');
+    });
+    await waitFor(() => {
+      expect(document.documentElement).toHaveClass('dark');
+    });
+  });
+
+  it('exports cookies of web services only once a password is long enough', () => {
+    cookieBackup.exportServiceCookies.mockResolvedValue([]);
+    render(<GeneralSettings />);
+
+    fireEvent.change(screen.getByPlaceholderText(/备份文件的完整路径/), {
+      target: { value: '/tmp/cookies.bak' },
+    });
+    fireEvent.change(screen.getByPlaceholderText(/密码/), { target: { value: 'short' } });
+    expect(screen.getByRole('button', { name: '导出' })).toBeDisabled();
+
+    fireEvent.change(screen.getByPlaceholderText(/密码/), { target: { value: 'long enough' } });
+    fireEvent.click(screen.getByRole('button', { name: '导出' }));
+
+    expect(cookieBackup.exportServiceCookies).toHaveBeenCalledWith(
+      services.slice(0, 2),
+      '/tmp/cookies.bak',
+      'long enough'
+    );
+  });
+
+  it('lists the services restored from a cookie backup', async () => {
+    render(<GeneralSettings />);
+
+    fireEvent.change(screen.getByPlaceholderText(/备份文件的完整路径/), {
+      target: { value: '/tmp/cookies.bak' },
+    });
+    fireEvent.change(screen.getByPlaceholderText(/密码/), { target: { value: 'long enough' } });
+    fireEvent.click(screen.getByRole('button', { name: '导入' }));
+
+    expect(await screen.findByText(/已恢复 5 个 Cookie，跳过 1 个过期/)).toBeInTheDocument();
+    expect(screen.getByText('ChatGPT')).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,139 @@
+import { act, fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { NativeChatPanel } from '@/components/NativeChatPanel';
+import type { NativeChatStreamEvent } from '@/services/native-chat';
+
+type StreamHandlers = {
+  onDelta: (event: NativeChatStreamEvent) => void;
+  onDone: (event: NativeChatStreamEvent) => void;
+  onError: (event: NativeChatStreamEvent) => void;
+};
+
+const { mocks, stream } = vi.hoisted(() => ({
+  mocks: {
+    sendNativeChatMessage: vi.fn(),
+    cancelNativeChatRequest: vi.fn(() => Promise.resolve()),
+    listNativeChatModels: vi.fn(() => Promise.resolve(['llama3', 'qwen2'])),
+    listNativeChatConversations: vi.fn(() => Promise.resolve([])),
+    loadNativeChatConversation: vi.fn(),
+    deleteNativeChatConversation: vi.fn(() => Promise.resolve()),
+    setNativeChatApiKey: vi.fn(() => Promise.resolve()),
+  },
+  stream: { handlers: null as StreamHandlers | null },
+}));
+
+vi.mock('@/services/native-chat', () => ({
+  ...mocks,
+  listenNativeChatStream: vi.fn((handlers: StreamHandlers) => {
+    stream.handlers = handlers;
+    return Promise.resolve(() => undefined);
+  }),
+}));
+
+const service = {
+  id: 'custom-ollama',
+  name: 'Ollama',
+  url: 'http://localhost:11434/',
+  enabled: true,
+  order: 0,
+  kind: 'openAiCompatible' as const,
+};
+
+const started = {
+  requestId: 'conv-1-aa-2',
+  serviceId: service.id,
+  conversationId: 'conv-1-aa',
+};
+
+describe('NativeChatPanel', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    stream.handlers = null;
+    mocks.sendNativeChatMessage.mockResolvedValue(started);
+  });
+
+  it('streams a reply into the conversation', async () => {
+    render(<NativeChatPanel service={service} />);
+
+    await waitFor(() => {
+      expect(screen.getByPlaceholderText('模型')).toHaveValue('llama3');
+    });
+    fireEvent.change(screen.getByPlaceholderText('输入消息'), { target: { value: 'Hello' } });
+    fireEvent.click(screen.getByTitle('发送'));
+
+    await waitFor(() => {
+      expect(mocks.sendNativeChatMessage).toHaveBeenCalledWith(
+        expect.objectContaining({ id: service.id, kind: 'openAiCompatible' }),
+        'llama3',
+        'Hello',
+        undefined
+      );
+    });
+    expect(screen.getByText('Hello')).toBeInTheDocument();
+
+    act(() => stream.handlers?.onDelta({ ...started, delta: 'Hi ' }));
+    act(() => stream.handlers?.onDelta({ ...started, delta: 'there' }));
+    expect(screen.getByText('Hi there')).toBeInTheDocument();
+
+    act(() =>
+      stream.handlers?.onDone({ ...started, message: { role: 'assistant', content: 'Hi there!' } })
+    );
+    expect(screen.getByText('Hi there!')).toBeInTheDocument();
+    expect(screen.getByTitle('发送')).toBeInTheDocument();
+  });
+
+  it('keeps a reply that finished before the request id came back', async () => {
+    mocks.sendNativeChatMessage.mockImplementation(async () => {
+      stream.handlers?.onDone({ ...started, message: { role: 'assistant', content: 'Fast' } });
+      return started;
+    });
+    render(<NativeChatPanel service={service} />);
+
+    await waitFor(() => {
+      expect(screen.getByPlaceholderText('模型')).toHaveValue('llama3');
+    });
+    fireEvent.change(screen.getByPlaceholderText('输入消息'), { target: { value: 'Hello' } });
+    fireEvent.click(screen.getByTitle('发送'));
+
+    await waitFor(() => {
+      expect(screen.getByText('Fast')).toBeInTheDocument();
+    });
+    expect(screen.queryByTitle('停止')).not.toBeInTheDocument();
+  });
+
+  it('shows stream errors and cancels on stop', async () => {
+    render(<NativeChatPanel service={service} />);
+
+    await waitFor(() => {
+      expect(screen.getByPlaceholderText('模型')).toHaveValue('llama3');
+    });
+    fireEvent.change(screen.getByPlaceholderText('输入消息'), { target: { value: 'Hello' } });
+    fireEvent.click(screen.getByTitle('发送'));
+    await waitFor(() => {
+      expect(screen.getByTitle('停止')).toBeInTheDocument();
+    });
+
+    fireEvent.click(screen.getByTitle('停止'));
+    expect(mocks.cancelNativeChatRequest).toHaveBeenCalledWith(started.requestId);
+
+    fireEvent.change(screen.getByPlaceholderText('输入消息'), { target: { value: 'Again' } });
+    fireEvent.click(screen.getByTitle('发送'));
+    await waitFor(() => {
+      expect(screen.getByTitle('停止')).toBeInTheDocument();
+    });
+    act(() => stream.handlers?.onError({ ...started, error: 'connection refused' }));
+    expect(screen.getByText('connection refused')).toBeInTheDocument();
+  });
+
+  it('saves the API key for the service address', async () => {
+    render(<NativeChatPanel service={service} />);
+
+    fireEvent.click(screen.getByTitle('API Key'));
+    fireEvent.change(screen.getByPlaceholderText(/新的 API Key/), { target: { value: ' sk-1 ' } });
+    fireEvent.click(screen.getByRole('button', { name: '保存' }));
+
+    await waitFor(() => {
+      expect(mocks.setNativeChatApiKey).toHaveBeenCalledWith(service.id, service.url, 'sk-1');
+    });
+  });
+});
@@ -0,0 +1,62 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { NetworkSettings } from '@/components/NetworkSettings';
+
+const { http } = vi.hoisted(() => ({
+  http: {
+    getHttpSettings: vi.fn(),
+    setHttpSettings: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/http', () => http);
+
+const settings = {
+  caBundlePaths: ['/etc/ssl/old-ca.pem'],
+  useSystemRoots: false,
+  proxy: 'http://proxy.corp:3128',
+};
+
+describe('NetworkSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    http.getHttpSettings.mockResolvedValue(settings);
+    http.setHttpSettings.mockResolvedValue(undefined);
+  });
+
+  it('saves CA bundles and system roots and keeps the proxy', async () => {
+    render(<NetworkSettings />);
+
+    expect(await screen.findByText('/etc/ssl/old-ca.pem')).toBeInTheDocument();
+    fireEvent.click(screen.getByTitle('移除 /etc/ssl/old-ca.pem'));
+    fireEvent.change(screen.getByPlaceholderText(/CA 证书文件/), {
+      target: { value: ' /etc/ssl/corp-ca.pem ' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '添加' }));
+    fireEvent.click(screen.getByRole('checkbox', { name: '信任系统证书库' }));
+    fireEvent.click(screen.getByRole('button', { name: '保存证书设置' }));
+
+    await waitFor(() => {
+      expect(http.setHttpSettings).toHaveBeenCalledWith({
+        caBundlePaths: ['/etc/ssl/corp-ca.pem'],
+        useSystemRoots: true,
+        proxy: 'http://proxy.corp:3128',
+      });
+    });
+    expect(await screen.findByText('证书设置已保存。')).toBeInTheDocument();
+  });
+
+  it('shows why a CA bundle was rejected', async () => {
+    http.setHttpSettings.mockRejectedValue(
+      'CA bundle /etc/ssl/old-ca.pem contains no certificates'
+    );
+    render(<NetworkSettings />);
+
+    await screen.findByText('/etc/ssl/old-ca.pem');
+    fireEvent.click(screen.getByRole('button', { name: '保存证书设置' }));
+
+    expect(
+      await screen.findByText('CA bundle /etc/ssl/old-ca.pem contains no certificates')
+    ).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,124 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { ServiceOptionsDialog } from '@/components/ServiceOptionsDialog';
+
+const { browsingData, contentBlocking, privateSession, theme, tls } = vi.hoisted(() => ({
+  browsingData: {
+    clearServiceBrowsingData: vi.fn(),
+  },
+  contentBlocking: {
+    getServiceContentBlocking: vi.fn(),
+    setServiceContentBlocking: vi.fn(),
+  },
+  privateSession: {
+    openPrivateService: vi.fn(),
+    closePrivateService: vi.fn(),
+  },
+  theme: {
+    getServiceTheme: vi.fn(),
+    setServiceTheme: vi.fn(),
+  },
+  tls: {
+    getServiceTlsPolicy: vi.fn(),
+    setServiceTlsPolicy: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/browsing-data', () => browsingData);
+vi.mock('@/services/content-blocking', () => contentBlocking);
+vi.mock('@/services/private-session', () => privateSession);
+vi.mock('@/services/theme', () => theme);
+vi.mock('@/services/tls', () => tls);
+
+const service = {
+  id: 'chatgpt',
+  name: 'ChatGPT',
+  url: 'https://chatgpt.com',
+  enabled: true,
+  order: 0,
+};
+
+describe('ServiceOptionsDialog', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    contentBlocking.getServiceContentBlocking.mockResolvedValue(null);
+    contentBlocking.setServiceContentBlocking.mockResolvedValue(false);
+    theme.getServiceTheme.mockResolvedValue('followApp');
+    theme.setServiceTheme.mockResolvedValue(undefined);
+    tls.getServiceTlsPolicy.mockResolvedValue({ mode: 'block' });
+    tls.setServiceTlsPolicy.mockResolvedValue(undefined);
+    browsingData.clearServiceBrowsingData.mockResolvedValue(4);
+    privateSession.openPrivateService.mockResolvedValue('private-chatgpt');
+    privateSession.closePrivateService.mockResolvedValue(undefined);
+  });
+
+  it('switches content blocking and reports the effective state', async () => {
+    render(<ServiceOptionsDialog service={service} onClose={vi.fn()} />);
+
+    await waitFor(() => {
+      expect(screen.getByRole('button', { name: '关闭' })).toBeEnabled();
+    });
+    fireEvent.click(screen.getByRole('button', { name: '关闭' }));
+
+    await waitFor(() => {
+      expect(contentBlocking.setServiceContentBlocking).toHaveBeenCalledWith(service, false);
+    });
+    expect(await screen.findByText('内容拦截已对该服务停用。')).toBeInTheDocument();
+  });
+
+  it('forces a theme for the service', async () => {
+    render(<ServiceOptionsDialog service={service} onClose={vi.fn()} />);
+
+    await waitFor(() => {
+      expect(screen.getByRole('button', { name: '深色' })).toBeEnabled();
+    });
+    fireEvent.click(screen.getByRole('button', { name: '深色' }));
+
+    await waitFor(() => {
+      expect(theme.setServiceTheme).toHaveBeenCalledWith(service.id, 'forceDark');
+    });
+  });
+
+  it('pins a certificate for the service', async () => {
+    render(<ServiceOptionsDialog service={service} onClose={vi.fn()} />);
+
+    const fingerprint = await screen.findByPlaceholderText(/SHA-256 指纹/);
+    await waitFor(() => {
+      expect(fingerprint).toBeEnabled();
+    });
+    expect(screen.getByRole('button', { name: '信任此证书' })).toBeDisabled();
+    fireEvent.change(fingerprint, { target: { value: ' AB:CD ' } });
+    fireEvent.click(screen.getByRole('button', { name: '信任此证书' }));
+
+    await waitFor(() => {
+      expect(tls.setServiceTlsPolicy).toHaveBeenCalledWith(service.id, {
+        mode: 'allowPinned',
+        fingerprint: 'AB:CD',
+      });
+    });
+    expect(await screen.findByText(/只放行下面这张证书/)).toBeInTheDocument();
+  });
+
+  it('asks for confirmation before clearing all browsing data', async () => {
+    render(<ServiceOptionsDialog service={service} onClose={vi.fn()} />);
+
+    fireEvent.click(screen.getByRole('button', { name: '清除全部数据' }));
+    expect(browsingData.clearServiceBrowsingData).not.toHaveBeenCalled();
+    fireEvent.click(screen.getByRole('button', { name: /确认清除/ }));
+
+    await waitFor(() => {
+      expect(browsingData.clearServiceBrowsingData).toHaveBeenCalledWith(service.id, false);
+    });
+    expect(await screen.findByText(/已清除 4 条站点数据/)).toBeInTheDocument();
+  });
+
+  it('opens and closes a private session window', async () => {
+    render(<ServiceOptionsDialog service={service} onClose={vi.fn()} />);
+
+    fireEvent.click(screen.getByRole('button', { name: '无痕打开' }));
+    expect(privateSession.openPrivateService).toHaveBeenCalledWith(service);
+
+    fireEvent.click(await screen.findByRole('button', { name: '关闭无痕窗口' }));
+    expect(privateSession.closePrivateService).toHaveBeenCalledWith(service.id);
+  });
+});
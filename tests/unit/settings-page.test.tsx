@@ -1,15 +1,16 @@
-import { fireEvent, render, screen } from '@testing-library/react';
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
 import { vi } from 'vitest';
 import { SettingsPage } from '@/components/SettingsPage';
 
-const { mockOpenUrl } = vi.hoisted(() => ({
+const { mockOpenUrl, mockDiscoverLocalServers } = vi.hoisted(() => ({
   mockOpenUrl: vi.fn(() => Promise.resolve()),
+  mockDiscoverLocalServers: vi.fn(),
 }));
 
 type StoreState = {
   settingsPageOpen: boolean;
-  settingsActiveTab: 'services' | 'about';
-  setSettingsActiveTab: (tab: 'services' | 'about') => void;
+  settingsActiveTab: 'services' | 'general' | 'about';
+  setSettingsActiveTab: (tab: 'services' | 'general' | 'about') => void;
   services: Array<{
     id: string;
     name: string;
@@ -38,6 +39,10 @@ vi.mock('@tauri-apps/plugin-opener', () => ({
   openUrl: mockOpenUrl,
 }));
 
+vi.mock('@/services/local-servers', () => ({
+  discoverLocalServers: mockDiscoverLocalServers,
+}));
+
 vi.mock('@/stores/app-store', () => ({
   useAppStore: () => storeState,
 }));
@@ -79,4 +84,80 @@ describe('SettingsPage', () => {
     fireEvent.click(screen.getByRole('button', { name: /查看 AmberKeeper/i }));
     expect(mockOpenUrl).toHaveBeenCalledWith('https://github.com/JS-banana/AmberKeeper');
   });
+
+  it('switches to the general settings tab', () => {
+    render(<SettingsPage />);
+
+    fireEvent.click(screen.getByRole('button', { name: '通用' }));
+
+    expect(storeState.setSettingsActiveTab).toHaveBeenCalledWith('general');
+  });
+
+  it('adds self-hosted services with an http address by default', () => {
+    storeState = { ...storeState, settingsActiveTab: 'services' };
+    render(<SettingsPage />);
+
+    fireEvent.click(screen.getByRole('button', { name: /添加服务/ }));
+    fireEvent.click(screen.getByRole('button', { name: '自托管' }));
+    fireEvent.change(screen.getByPlaceholderText('例如：My AI Chat'), {
+      target: { value: 'Open WebUI' },
+    });
+    fireEvent.change(screen.getByPlaceholderText('例如：http://localhost:3000'), {
+      target: { value: 'localhost:3000' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '添加' }));
+
+    expect(storeState.addService).toHaveBeenCalledWith(
+      expect.objectContaining({
+        name: 'Open WebUI',
+        url: 'http://localhost:3000/',
+        kind: 'selfHosted',
+      })
+    );
+  });
+
+  it('offers to add discovered local servers that are not services yet', async () => {
+    mockDiscoverLocalServers.mockResolvedValue([
+      {
+        product: 'ollama',
+        name: 'Ollama',
+        url: 'http://127.0.0.1:11434',
+        port: 11434,
+        iconUrl: 'https://ollama.com/public/ollama.png',
+        hasWebUi: false,
+      },
+      {
+        product: 'openWebUi',
+        name: 'Open WebUI',
+        url: 'http://127.0.0.1:3000',
+        port: 3000,
+        iconUrl: null,
+        hasWebUi: true,
+      },
+    ]);
+    storeState = {
+      ...storeState,
+      settingsActiveTab: 'services',
+      services: [
+        { id: 'custom-1', name: 'WebUI', url: 'http://127.0.0.1:3000/', enabled: true, order: 0 },
+      ],
+    };
+    render(<SettingsPage />);
+
+    fireEvent.click(screen.getByRole('button', { name: /添加服务/ }));
+    fireEvent.click(screen.getByRole('button', { name: /扫描本机 AI 服务/ }));
+    await waitFor(() => {
+      expect(screen.getByRole('button', { name: '添加 Ollama' })).toBeInTheDocument();
+    });
+    expect(screen.queryByRole('button', { name: '添加 Open WebUI' })).not.toBeInTheDocument();
+
+    fireEvent.click(screen.getByRole('button', { name: '添加 Ollama' }));
+    expect(storeState.addService).toHaveBeenCalledWith(
+      expect.objectContaining({
+        name: 'Ollama',
+        url: 'http://127.0.0.1:11434/',
+        kind: 'openAiCompatible',
+      })
+    );
+  });
 });
@@ -29,6 +29,21 @@ vi.mock('@/stores/app-store', () => ({
   useAppStore: () => storeState,
 }));
 
+const { sessions } = vi.hoisted(() => ({
+  sessions: {
+    loginStates: {} as Record<string, 'unknown' | 'signedIn' | 'signedOut'>,
+    keepAliveStatuses: {} as Record<string, { outcome: 'ok' | 'signedOut' | 'failed' }>,
+  },
+}));
+
+vi.mock('@/hooks/useLoginStates', () => ({
+  useLoginStates: () => sessions.loginStates,
+}));
+
+vi.mock('@/hooks/useKeepAliveStatuses', () => ({
+  useKeepAliveStatuses: () => sessions.keepAliveStatuses,
+}));
+
 vi.mock('@/hooks/useCachedIcon', () => ({
   useCachedIcon: () => ({
     iconSrc: null,
@@ -47,6 +62,8 @@ vi.mock('@/components/ui/tooltip', () => ({
 describe('Sidebar', () => {
   beforeEach(() => {
     vi.clearAllMocks();
+    sessions.loginStates = {};
+    sessions.keepAliveStatuses = {};
     storeState = {
       services: [
         {
@@ -79,4 +96,31 @@ describe('Sidebar', () => {
     expect(storeState.setActiveService).toHaveBeenCalledWith('chatgpt');
     expect(storeState.setSettingsPageOpen).toHaveBeenCalledWith(false);
   });
+
+  it('marks services whose login state is signed out', () => {
+    sessions.loginStates = { chatgpt: 'signedOut', gemini: 'signedIn' };
+    render(<Sidebar />);
+
+    expect(screen.getByText('ChatGPT（已登出）')).toBeInTheDocument();
+    expect(screen.getByText('Gemini')).toBeInTheDocument();
+  });
+
+  it('falls back to keep-alive results until a sign-in is detected', () => {
+    sessions.loginStates = { gemini: 'signedIn' };
+    sessions.keepAliveStatuses = {
+      chatgpt: { outcome: 'signedOut' },
+      gemini: { outcome: 'signedOut' },
+    };
+    render(<Sidebar />);
+
+    expect(screen.getByText('ChatGPT（已登出）')).toBeInTheDocument();
+    expect(screen.getByText('Gemini')).toBeInTheDocument();
+  });
+
+  it('notes failed keep-alive checks without marking the service signed out', () => {
+    sessions.keepAliveStatuses = { chatgpt: { outcome: 'failed' } };
+    render(<Sidebar />);
+
+    expect(screen.getByText('ChatGPT（会话检查失败）')).toBeInTheDocument();
+  });
 });
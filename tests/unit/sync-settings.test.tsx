@@ -0,0 +1,124 @@
+import { fireEvent, render, screen, waitFor } from '@testing-library/react';
+import { vi } from 'vitest';
+import { SyncSettings } from '@/components/SyncSettings';
+
+const { sync, storeState } = vi.hoisted(() => ({
+  sync: {
+    getSyncStatus: vi.fn(),
+    setSyncSettings: vi.fn(),
+    syncNow: vi.fn(),
+    getSyncConflicts: vi.fn(),
+  },
+  storeState: {
+    services: [
+      { id: 'chatgpt', name: 'ChatGPT', url: 'https://chatgpt.com', enabled: true, order: 0 },
+    ],
+    replaceServices: vi.fn(),
+  },
+}));
+
+vi.mock('@/services/sync', () => sync);
+
+vi.mock('@/stores/app-store', () => ({
+  useAppStore: () => storeState,
+}));
+
+const off = {
+  settings: { target: null, encrypt: false },
+  hasWebdavPassword: false,
+  hasPassphrase: false,
+  lastSyncedAt: null,
+};
+
+const folder = {
+  ...off,
+  settings: { target: { type: 'folder' as const, path: '/home/me/sync' }, encrypt: true },
+  hasPassphrase: true,
+};
+
+describe('SyncSettings', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+    sync.getSyncConflicts.mockResolvedValue([]);
+  });
+
+  it('saves a WebDAV target with its password and passphrase', async () => {
+    sync.getSyncStatus.mockResolvedValue(off);
+    sync.setSyncSettings.mockResolvedValue(folder);
+    render(<SyncSettings />);
+
+    await waitFor(() => {
+      expect(screen.getByRole('button', { name: 'WebDAV' })).toBeEnabled();
+    });
+    fireEvent.click(screen.getByRole('button', { name: 'WebDAV' }));
+    fireEvent.change(screen.getByPlaceholderText(/WebDAV 目录地址/), {
+      target: { value: ' https://dav.example.com/anychat/ ' },
+    });
+    fireEvent.change(screen.getByPlaceholderText('WebDAV 用户名'), { target: { value: 'me' } });
+    fireEvent.change(screen.getByPlaceholderText('WebDAV 密码'), { target: { value: 'dav-pw' } });
+    fireEvent.click(screen.getByRole('checkbox', { name: '加密同步文件' }));
+    fireEvent.change(screen.getByPlaceholderText(/同步口令/), {
+      target: { value: 'long passphrase' },
+    });
+    fireEvent.click(screen.getByRole('button', { name: '保存同步设置' }));
+
+    await waitFor(() => {
+      expect(sync.setSyncSettings).toHaveBeenCalledWith(
+        {
+          target: { type: 'webDav', url: 'https://dav.example.com/anychat/', username: 'me' },
+          encrypt: true,
+        },
+        'dav-pw',
+        'long passphrase'
+      );
+    });
+    expect(await screen.findByText('同步设置已保存。')).toBeInTheDocument();
+  });
+
+  it('keeps stored secrets when their fields are left empty', async () => {
+    sync.getSyncStatus.mockResolvedValue(folder);
+    sync.setSyncSettings.mockResolvedValue(folder);
+    render(<SyncSettings />);
+
+    expect(await screen.findByDisplayValue('/home/me/sync')).toBeInTheDocument();
+    expect(screen.getByPlaceholderText(/同步口令（已保存/)).toBeInTheDocument();
+    fireEvent.click(screen.getByRole('button', { name: '保存同步设置' }));
+
+    await waitFor(() => {
+      expect(sync.setSyncSettings).toHaveBeenCalledWith(folder.settings, null, null);
+    });
+  });
+
+  it('syncs on demand and lists the conflict log', async () => {
+    sync.getSyncStatus.mockResolvedValue(folder);
+    sync.syncNow.mockResolvedValue({
+      syncedAt: 2,
+      uploaded: true,
+      applied: 3,
+      conflicts: [],
+      services: storeState.services,
+    });
+    sync.getSyncConflicts.mockResolvedValue([
+      {
+        key: 'service/chatgpt',
+        detectedAt: 1,
+        winner: 'remote',
+        localModifiedAt: 0,
+        remoteModifiedAt: 1,
+        remoteDeviceId: 'laptop',
+        discarded: null,
+      },
+    ]);
+    render(<SyncSettings />);
+
+    expect(await screen.findByText('service/chatgpt')).toBeInTheDocument();
+    expect(screen.getByText(/采用远端/)).toBeInTheDocument();
+    fireEvent.click(screen.getByRole('button', { name: /立即同步/ }));
+
+    await waitFor(() => {
+      expect(sync.syncNow).toHaveBeenCalledWith(storeState.services);
+    });
+    expect(storeState.replaceServices).toHaveBeenCalledWith(storeState.services);
+    expect(await screen.findByText('同步完成：应用了 3 项远端更改。')).toBeInTheDocument();
+  });
+});